@@ -0,0 +1,192 @@
+//! System-wide toggle hotkey, registered through `global-hotkey` on macOS,
+//! X11 and Windows.
+//!
+//! Hotkey strings come from settings written on any platform, so modifier
+//! names from each (`cmd`, `win`, `meta`, `option`, ...) are accepted.
+
+use crate::AppCommand;
+use global_hotkey::{
+    hotkey::{Code, HotKey, Modifiers},
+    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
+};
+
+pub(crate) fn default_toggle_hotkey() -> HotKey {
+    HotKey::new(Some(Modifiers::SUPER), Code::F4)
+}
+
+/// Rewrite `configured` with the modifier names `global-hotkey` parses:
+/// `super`, `control`, `alt` and `shift`. Whitespace is dropped and the key
+/// token is kept as written.
+pub(crate) fn normalize_hotkey(configured: &str) -> String {
+    let compact = configured
+        .chars()
+        .filter(|ch| !ch.is_whitespace())
+        .collect::<String>();
+    compact
+        .split('+')
+        .map(|token| match token.to_ascii_lowercase().as_str() {
+            "super" | "cmd" | "command" | "meta" | "win" | "windows" | "logo" => {
+                "super".to_string()
+            }
+            "ctrl" | "control" => "control".to_string(),
+            "alt" | "option" | "opt" => "alt".to_string(),
+            "shift" => "shift".to_string(),
+            _ => token.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("+")
+}
+
+pub(crate) fn parse_hotkey_or_fallback(
+    configured_hotkey: &str,
+    fallback: HotKey,
+    label: &str,
+) -> HotKey {
+    if let Some(alias_hotkey) = parse_reserved_or_alias_hotkey(configured_hotkey, label) {
+        return alias_hotkey;
+    }
+
+    match normalize_hotkey(configured_hotkey).parse::<HotKey>() {
+        Ok(hotkey) => hotkey,
+        Err(err) => {
+            log::warn!(
+                "invalid {} '{}': {err}; falling back to {}",
+                label,
+                configured_hotkey,
+                fallback
+            );
+            fallback
+        }
+    }
+}
+
+fn parse_reserved_or_alias_hotkey(configured_hotkey: &str, label: &str) -> Option<HotKey> {
+    if label != "global_hotkey" {
+        return None;
+    }
+
+    match normalize_hotkey(configured_hotkey)
+        .to_ascii_lowercase()
+        .as_str()
+    {
+        "super+f5" if cfg!(target_os = "macos") => {
+            let remapped = default_toggle_hotkey();
+            log::warn!(
+                "global_hotkey '{}' conflicts with macOS VoiceOver; remapping to {}",
+                configured_hotkey,
+                remapped
+            );
+            Some(remapped)
+        }
+        "super+r5" => Some(default_toggle_hotkey()),
+        _ => None,
+    }
+}
+
+/// Register `global_hotkey` and send `AppCommand::ToggleTerminal` whenever it
+/// is pressed. Dropping the returned manager unregisters the hotkey.
+pub(crate) fn install_toggle_hotkey(
+    global_hotkey: &str,
+    command_tx: smol::channel::Sender<AppCommand>,
+) -> Option<GlobalHotKeyManager> {
+    let manager = match GlobalHotKeyManager::new() {
+        Ok(manager) => manager,
+        Err(err) => {
+            log::warn!("failed to initialize global hotkey manager: {err}");
+            return None;
+        }
+    };
+
+    let toggle_hotkey =
+        parse_hotkey_or_fallback(global_hotkey, default_toggle_hotkey(), "global_hotkey");
+    if let Err(err) = manager.register(toggle_hotkey) {
+        log::warn!(
+            "failed to register global_hotkey '{}': {err}",
+            toggle_hotkey
+        );
+        return None;
+    }
+
+    let toggle_hotkey_id = toggle_hotkey.id();
+    let _ = std::thread::Builder::new()
+        .name("simple-term-hotkey-listener".to_string())
+        .spawn(move || {
+            let receiver = GlobalHotKeyEvent::receiver();
+            while let Ok(event) = receiver.recv() {
+                if event.state != HotKeyState::Pressed {
+                    continue;
+                }
+
+                if event.id == toggle_hotkey_id {
+                    let _ = command_tx.try_send(AppCommand::ToggleTerminal);
+                }
+            }
+        });
+
+    Some(manager)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_toggle_hotkey, normalize_hotkey, parse_hotkey_or_fallback};
+    use global_hotkey::hotkey::{Code, HotKey, Modifiers};
+
+    #[test]
+    fn normalize_hotkey_maps_each_platform_modifier_name() {
+        assert_eq!(normalize_hotkey("Cmd+Option+F4"), "super+alt+F4");
+        assert_eq!(
+            normalize_hotkey("Win + Ctrl + Backquote"),
+            "super+control+Backquote"
+        );
+        assert_eq!(normalize_hotkey("meta+shift+T"), "super+shift+T");
+        assert_eq!(normalize_hotkey("logo+opt+Space"), "super+alt+Space");
+        assert_eq!(
+            normalize_hotkey("command+control+alt+shift+K"),
+            "super+control+alt+shift+K"
+        );
+    }
+
+    #[test]
+    fn hotkeys_written_with_other_platform_modifiers_parse_to_the_same_combo() {
+        let expected = HotKey::new(Some(Modifiers::SUPER | Modifiers::ALT), Code::KeyT);
+        for configured in ["cmd+option+T", "win+alt+T", "meta+opt+t", "super+alt+T"] {
+            assert_eq!(
+                parse_hotkey_or_fallback(configured, default_toggle_hotkey(), "global_hotkey"),
+                expected,
+                "{configured}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_hotkey_falls_back() {
+        let fallback = HotKey::new(Some(Modifiers::CONTROL), Code::Backquote);
+        assert_eq!(
+            parse_hotkey_or_fallback("hyper+nothing", fallback, "pin_hotkey"),
+            fallback
+        );
+    }
+
+    #[test]
+    fn parse_r5_alias_hotkey_maps_to_default_toggle_hotkey() {
+        let expected = HotKey::new(Some(Modifiers::SUPER), Code::F4);
+        assert_eq!(
+            parse_hotkey_or_fallback("cmd+r5", expected, "global_hotkey"),
+            expected
+        );
+        assert_eq!(
+            parse_hotkey_or_fallback("Win+R5", expected, "global_hotkey"),
+            expected
+        );
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn parse_command_five_function_key_remaps_to_non_reserved_combo() {
+        let expected = HotKey::new(Some(Modifiers::SUPER), Code::F4);
+        assert_eq!(
+            parse_hotkey_or_fallback("command+F5", expected, "global_hotkey"),
+            expected
+        );
+    }
+}
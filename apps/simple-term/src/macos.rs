@@ -1,5 +1,8 @@
 #![allow(deprecated, unexpected_cfgs)]
 
+use crate::window_placement::{
+    monitor_key, panel_length, reconcile_window_placement, saved_placement_for_fractions,
+};
 use crate::AppCommand;
 use cocoa::{
     appkit::{
@@ -54,6 +57,18 @@ pub(crate) struct PanelPlacement {
     pub top_left_y: f64,
 }
 
+impl PanelPlacement {
+    /// This placement with the window moved to `bounds`, given in the same
+    /// monitor-local coordinates as `self.bounds`.
+    pub(crate) fn with_bounds(&self, bounds: Bounds<Pixels>) -> Self {
+        Self {
+            bounds,
+            top_left_x: self.top_left_x + f64::from(bounds.origin.x - self.bounds.origin.x),
+            top_left_y: self.top_left_y - f64::from(bounds.origin.y - self.bounds.origin.y),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub(crate) enum MoveWindowResult {
     ActivationHandledByApp,
@@ -102,8 +117,10 @@ pub(crate) fn install_status_item(enabled: bool) -> Option<StatusItemHandle> {
 }
 
 pub(crate) fn resolve_panel_placement(
-    desired_width: f32,
-    desired_height: f32,
+    default_width: u32,
+    default_height: u32,
+    width_fraction: Option<f32>,
+    height_fraction: Option<f32>,
     top_inset: f32,
     monitor_window_positions: &HashMap<String, MonitorWindowPlacement>,
 ) -> PanelPlacement {
@@ -113,46 +130,39 @@ pub(crate) fn resolve_panel_placement(
         let visible_frame = NSScreen::visibleFrame(screen);
         let monitor_key = monitor_key_for_screen(screen_frame);
 
-        let saved = monitor_window_positions.get(&monitor_key);
-        let width = saved
-            .and_then(|placement| placement.width)
-            .unwrap_or(desired_width)
-            .max(320.0)
-            .min(visible_frame.size.width as f32);
-        let height = saved
-            .and_then(|placement| placement.height)
-            .unwrap_or(desired_height)
-            .max(180.0)
-            .min(visible_frame.size.height as f32);
-
         let max_y = screen_frame.origin.y + screen_frame.size.height;
         let visible_max_y = visible_frame.origin.y + visible_frame.size.height;
-        let min_local_x = (visible_frame.origin.x - screen_frame.origin.x) as f32;
-        let max_local_x = ((visible_frame.origin.x + visible_frame.size.width)
-            - screen_frame.origin.x
-            - width as f64) as f32;
         let menubar_reserved = (max_y - visible_max_y).max(0.0) as f32;
-        let min_local_y = menubar_reserved;
-        let max_local_y = (max_y - visible_frame.origin.y - height as f64) as f32;
-
-        let fallback_local_x =
-            ((screen_frame.size.width as f32 - width) / 2.0).clamp(min_local_x, max_local_x);
-        let fallback_local_y =
-            (menubar_reserved + top_inset.max(0.0)).clamp(min_local_y, max_local_y);
-        let (local_x, local_y) = saved
-            .map(|saved| {
-                (
-                    saved.x.clamp(min_local_x, max_local_x),
-                    saved.y.clamp(min_local_y, max_local_y),
-                )
-            })
-            .unwrap_or((fallback_local_x, fallback_local_y));
-
-        let top_left_x = screen_frame.origin.x + local_x as f64;
-        let top_left_y = max_y - local_y as f64;
+        let visible = Bounds::new(
+            point(
+                (visible_frame.origin.x - screen_frame.origin.x) as f32,
+                menubar_reserved,
+            ),
+            size(
+                visible_frame.size.width as f32,
+                visible_frame.size.height as f32,
+            ),
+        );
+
+        let bounds = reconcile_window_placement(
+            panel_length(default_width, width_fraction, visible.size.width),
+            panel_length(default_height, height_fraction, visible.size.height),
+            screen_frame.size.width as f32,
+            visible,
+            Some(top_inset),
+            saved_placement_for_fractions(
+                monitor_window_positions.get(&monitor_key),
+                width_fraction,
+                height_fraction,
+            )
+            .as_ref(),
+        );
+
+        let top_left_x = screen_frame.origin.x + f64::from(bounds.origin.x);
+        let top_left_y = max_y - f64::from(bounds.origin.y);
 
         PanelPlacement {
-            bounds: Bounds::new(point(px(local_x), px(local_y)), size(px(width), px(height))),
+            bounds,
             top_left_x,
             top_left_y,
         }
@@ -210,6 +220,48 @@ pub(crate) fn capture_window_monitor_position(
     }
 }
 
+/// Placement of the window's current frame on the screen it is on.
+pub(crate) fn current_panel_placement(window: &mut Window) -> Option<PanelPlacement> {
+    let Ok(window_handle) = window.window_handle() else {
+        return None;
+    };
+
+    let RawWindowHandle::AppKit(handle) = window_handle.as_raw() else {
+        return None;
+    };
+
+    unsafe {
+        let ns_view = handle.ns_view.as_ptr() as id;
+        let ns_window: id = msg_send![ns_view, window];
+        if ns_window == nil {
+            return None;
+        }
+
+        let screen: id = msg_send![ns_window, screen];
+        if screen == nil {
+            return None;
+        }
+
+        let screen_frame = NSScreen::frame(screen);
+        let frame = NSWindow::frame(ns_window);
+        let max_y = screen_frame.origin.y + screen_frame.size.height;
+        let top_left_x = frame.origin.x;
+        let top_left_y = frame.origin.y + frame.size.height;
+
+        Some(PanelPlacement {
+            bounds: Bounds::new(
+                point(
+                    px((top_left_x - screen_frame.origin.x) as f32),
+                    px((max_y - top_left_y) as f32),
+                ),
+                size(px(frame.size.width as f32), px(frame.size.height as f32)),
+            ),
+            top_left_x,
+            top_left_y,
+        })
+    }
+}
+
 pub(crate) fn window_needs_frame_update(
     window: &mut Window,
     placement: &PanelPlacement,
@@ -351,9 +403,11 @@ fn point_in_rect(point: NSPoint, rect: NSRect) -> bool {
 }
 
 fn monitor_key_for_screen(frame: NSRect) -> String {
-    format!(
-        "{:.3}:{:.3}:{:.3}:{:.3}",
-        frame.origin.x, frame.origin.y, frame.size.width, frame.size.height
+    monitor_key(
+        frame.origin.x,
+        frame.origin.y,
+        frame.size.width,
+        frame.size.height,
     )
 }
 
@@ -380,6 +434,17 @@ struct DeferredWindowMove {
     activate_after_move: bool,
 }
 
+#[link(name = "AppKit", kind = "framework")]
+unsafe extern "C" {
+    fn NSBeep();
+}
+
+/// Play the user's alert sound.
+pub(crate) fn play_system_beep() {
+    // SAFETY: NSBeep takes no arguments and may be called from any thread.
+    unsafe { NSBeep() }
+}
+
 #[link(name = "System", kind = "dylib")]
 unsafe extern "C" {
     #[link_name = "_dispatch_main_q"]
@@ -2,7 +2,9 @@
 
 //! Simple Term - A standalone terminal application
 
+mod global_hotkeys;
 mod terminal_view;
+mod window_placement;
 
 #[cfg(target_os = "macos")]
 mod macos;
@@ -10,29 +12,42 @@ mod macos;
 use gpui::WindowHandle;
 use gpui::{point, px};
 #[cfg(not(target_os = "macos"))]
-use gpui::{size, Bounds};
+use gpui::{size, Bounds, Window};
 use gpui::{App, AppContext, Application, WindowBounds, WindowOptions};
 #[cfg(target_os = "macos")]
-use gpui::{TitlebarOptions, WindowKind};
+use gpui::{Task, TitlebarOptions, WindowKind};
+#[cfg(not(target_os = "macos"))]
+use simple_term::terminal_settings::MonitorWindowPlacement;
 use simple_term::TerminalSettings;
 use terminal_view::TerminalView;
+#[cfg(not(target_os = "macos"))]
+use window_placement::{
+    display_monitor_key, panel_length, placement_from_bounds, reconcile_window_placement,
+    saved_placement_for_fractions,
+};
+#[cfg(target_os = "macos")]
+use window_placement::{slid_out_bounds, slide_frames, SLIDE_FRAME_INTERVAL};
 
 #[cfg(target_os = "macos")]
-use global_hotkey::{
-    hotkey::{Code, HotKey, Modifiers},
-    GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState,
-};
+use global_hotkey::GlobalHotKeyManager;
+use std::sync::Arc;
 #[cfg(target_os = "macos")]
-use std::{cell::RefCell, rc::Rc, sync::Arc};
+use std::{cell::RefCell, rc::Rc};
 
 #[derive(Clone, Debug)]
 pub(crate) enum AppCommand {
     ToggleTerminal,
+    #[cfg(target_os = "macos")]
     TogglePinned,
+    #[cfg(target_os = "macos")]
     HideTerminal,
+    #[cfg(target_os = "macos")]
     ForceHideTerminal,
     UpdateHotkeys {
         global_hotkey: String,
+        /// Only the macOS panel keeps its own copy; the standard window
+        /// matches the pin hotkey from its settings.
+        #[cfg(target_os = "macos")]
         pin_hotkey: String,
     },
 }
@@ -49,31 +64,153 @@ fn main() {
 
         #[cfg(not(target_os = "macos"))]
         {
-            let _ = open_standard_window(cx, settings);
+            run_standard_app(cx, settings);
         }
     });
 }
 
+/// Standard window plus the global toggle hotkey, which minimizes the window
+/// when it is focused and brings it back otherwise.
 #[cfg(not(target_os = "macos"))]
-fn open_standard_window(cx: &mut App, settings: TerminalSettings) -> WindowHandle<TerminalView> {
-    let options = WindowOptions {
-        window_bounds: Some(WindowBounds::Windowed(Bounds {
+fn run_standard_app(cx: &mut App, settings: TerminalSettings) {
+    let (command_tx, command_rx) = smol::channel::unbounded::<AppCommand>();
+    let mut hotkey_manager =
+        global_hotkeys::install_toggle_hotkey(&settings.global_hotkey, command_tx.clone());
+    let window_handle = open_standard_window(cx, settings, command_tx.clone());
+
+    cx.spawn(async move |async_cx| {
+        while let Ok(command) = command_rx.recv().await {
+            match command {
+                AppCommand::ToggleTerminal => {
+                    let _ = window_handle.update(async_cx, |view, window, _| {
+                        if window.is_window_active() {
+                            window.minimize_window();
+                        } else {
+                            window.activate_window();
+                            view.focus_terminal(window);
+                        }
+                    });
+                }
+                AppCommand::UpdateHotkeys { global_hotkey } => {
+                    // Drop prior registrations before applying updated shortcut bindings.
+                    drop(hotkey_manager.take());
+                    hotkey_manager =
+                        global_hotkeys::install_toggle_hotkey(&global_hotkey, command_tx.clone());
+                }
+            }
+        }
+    })
+    .detach();
+}
+
+#[cfg(not(target_os = "macos"))]
+fn open_standard_window(
+    cx: &mut App,
+    settings: TerminalSettings,
+    command_tx: smol::channel::Sender<AppCommand>,
+) -> WindowHandle<TerminalView> {
+    let display = cx.primary_display();
+    let bounds = match display.as_ref() {
+        Some(display) => {
+            let screen = display.bounds();
+            let local = reconcile_window_placement(
+                panel_length(
+                    settings.default_width,
+                    settings.width_fraction,
+                    f32::from(screen.size.width),
+                ),
+                panel_length(
+                    settings.default_height,
+                    settings.height_fraction,
+                    f32::from(screen.size.height),
+                ),
+                f32::from(screen.size.width),
+                Bounds::new(
+                    point(0.0, 0.0),
+                    size(f32::from(screen.size.width), f32::from(screen.size.height)),
+                ),
+                None,
+                saved_placement_for_fractions(
+                    settings
+                        .monitor_window_positions
+                        .get(&display_monitor_key(screen)),
+                    settings.width_fraction,
+                    settings.height_fraction,
+                )
+                .as_ref(),
+            );
+            Bounds::new(
+                point(
+                    screen.origin.x + local.origin.x,
+                    screen.origin.y + local.origin.y,
+                ),
+                local.size,
+            )
+        }
+        None => Bounds {
             origin: point(px(0.), px(0.)),
             size: size(
                 px(settings.default_width as f32),
                 px(settings.default_height as f32),
             ),
-        })),
+        },
+    };
+    let options = WindowOptions {
+        window_bounds: Some(WindowBounds::Windowed(bounds)),
+        display_id: display.map(|display| display.id()),
         ..Default::default()
     };
 
+    let on_hotkeys_updated = Some(Arc::new(move |global_hotkey: String, _pin_hotkey: String| {
+        let _ = command_tx.try_send(AppCommand::UpdateHotkeys { global_hotkey });
+    }) as Arc<dyn Fn(String, String) + Send + Sync>);
+
     cx.open_window(options, move |window, cx| {
         let settings = settings.clone();
-        cx.new(move |cx| TerminalView::new(window, cx, settings, false, None, None, None, None))
+        let on_hotkeys_updated = on_hotkeys_updated.clone();
+        let view = cx.new(|cx| {
+            TerminalView::new(
+                window,
+                cx,
+                settings,
+                false,
+                None,
+                None,
+                None,
+                on_hotkeys_updated,
+                None,
+            )
+        });
+        let placement_view = view.downgrade();
+        window.on_window_should_close(cx, move |window, cx| {
+            if let Some((monitor_key, placement)) = capture_window_placement(window, cx) {
+                let _ = placement_view.update(cx, |view, _| {
+                    view.remember_window_placement(monitor_key, placement);
+                });
+            }
+            true
+        });
+        view
     })
     .expect("Failed to open window")
 }
 
+/// Where `window` sits relative to the display it is on. Wayland compositors
+/// do not report window positions, so only the size is meaningful there.
+#[cfg(not(target_os = "macos"))]
+fn capture_window_placement(window: &Window, cx: &App) -> Option<(String, MonitorWindowPlacement)> {
+    let screen = window.display(cx)?.bounds();
+    let bounds = window.window_bounds().get_bounds();
+    let local = Bounds::new(
+        point(
+            bounds.origin.x - screen.origin.x,
+            bounds.origin.y - screen.origin.y,
+        ),
+        bounds.size,
+    );
+    Some((display_monitor_key(screen), placement_from_bounds(local)))
+}
+
 #[cfg(target_os = "macos")]
 fn run_macos_app(cx: &mut App, settings: TerminalSettings) {
     let (command_tx, command_rx) = smol::channel::unbounded::<AppCommand>();
@@ -132,6 +269,8 @@ struct AppShellController {
     pinned: bool,
     status_item: Option<macos::StatusItemHandle>,
     hotkey_manager: Option<GlobalHotKeyManager>,
+    /// Slide animation in progress; dropping it stops the animation.
+    slide_task: Option<Task<()>>,
 }
 
 #[cfg(target_os = "macos")]
@@ -145,6 +284,7 @@ impl AppShellController {
             pinned: false,
             status_item: None,
             hotkey_manager: None,
+            slide_task: None,
         }
     }
 
@@ -158,101 +298,10 @@ impl AppShellController {
     fn install_global_hotkeys(&mut self) {
         // Drop prior registrations before applying updated shortcut bindings.
         self.hotkey_manager = None;
-
-        let manager = match GlobalHotKeyManager::new() {
-            Ok(manager) => manager,
-            Err(err) => {
-                log::warn!("failed to initialize global hotkey manager: {err}");
-                return;
-            }
-        };
-
-        let toggle_hotkey = Self::parse_hotkey_or_fallback(
+        self.hotkey_manager = global_hotkeys::install_toggle_hotkey(
             &self.settings.global_hotkey,
-            Self::default_toggle_hotkey(),
-            "global_hotkey",
+            self.command_tx.clone(),
         );
-        let toggle_hotkey_id = match manager.register(toggle_hotkey) {
-            Ok(_) => Some(toggle_hotkey.id()),
-            Err(err) => {
-                log::warn!(
-                    "failed to register global_hotkey '{}': {err}",
-                    toggle_hotkey
-                );
-                None
-            }
-        };
-
-        if toggle_hotkey_id.is_none() {
-            return;
-        }
-
-        let command_tx = self.command_tx.clone();
-        let _ = std::thread::Builder::new()
-            .name("simple-term-hotkey-listener".to_string())
-            .spawn(move || {
-                let receiver = GlobalHotKeyEvent::receiver();
-                while let Ok(event) = receiver.recv() {
-                    if event.state != HotKeyState::Pressed {
-                        continue;
-                    }
-
-                    if Some(event.id) == toggle_hotkey_id {
-                        let _ = command_tx.try_send(AppCommand::ToggleTerminal);
-                    }
-                }
-            });
-
-        self.hotkey_manager = Some(manager);
-    }
-
-    fn default_toggle_hotkey() -> HotKey {
-        HotKey::new(Some(Modifiers::SUPER), Code::F4)
-    }
-
-    fn parse_hotkey_or_fallback(configured_hotkey: &str, fallback: HotKey, label: &str) -> HotKey {
-        if let Some(alias_hotkey) = Self::parse_reserved_or_alias_hotkey(configured_hotkey, label) {
-            return alias_hotkey;
-        }
-
-        match configured_hotkey.parse::<HotKey>() {
-            Ok(hotkey) => hotkey,
-            Err(err) => {
-                log::warn!(
-                    "invalid {} '{}': {err}; falling back to {}",
-                    label,
-                    configured_hotkey,
-                    fallback
-                );
-                fallback
-            }
-        }
-    }
-
-    fn parse_reserved_or_alias_hotkey(configured_hotkey: &str, label: &str) -> Option<HotKey> {
-        if label != "global_hotkey" {
-            return None;
-        }
-
-        let normalized = configured_hotkey
-            .chars()
-            .filter(|ch| !ch.is_ascii_whitespace())
-            .collect::<String>()
-            .to_ascii_lowercase();
-
-        match normalized.as_str() {
-            "command+f5" | "cmd+f5" | "super+f5" | "meta+f5" => {
-                let remapped = Self::default_toggle_hotkey();
-                log::warn!(
-                    "global_hotkey '{}' conflicts with macOS VoiceOver; remapping to {}",
-                    configured_hotkey,
-                    remapped
-                );
-                Some(remapped)
-            }
-            "cmd+r5" | "command+r5" | "super+r5" | "meta+r5" => Some(Self::default_toggle_hotkey()),
-            _ => None,
-        }
     }
 
     fn handle_command(&mut self, command: AppCommand, cx: &mut App) {
@@ -283,7 +332,8 @@ impl AppShellController {
 
         if let Some(window_handle) = self.terminal_window {
             let pinned = self.pinned;
-            let should_activate = Self::should_activate_window_after_pin_toggle(pinned, self.visible);
+            let should_activate =
+                Self::should_activate_window_after_pin_toggle(pinned, self.visible);
             let _ = window_handle.update(cx, |view, window, cx| {
                 view.set_pinned(pinned, cx);
                 macos::set_window_pinned(window, pinned);
@@ -295,24 +345,37 @@ impl AppShellController {
     }
 
     fn show_terminal(&mut self, cx: &mut App) {
+        self.slide_task = None;
         let placement = macos::resolve_panel_placement(
-            self.settings.default_width as f32,
-            self.settings.default_height as f32,
+            self.settings.default_width,
+            self.settings.default_height,
+            self.settings.width_fraction,
+            self.settings.height_fraction,
             self.settings.panel_top_inset,
             &self.settings.monitor_window_positions,
         );
 
         if let Some(window_handle) = self.terminal_window {
             const FRAME_RESTORE_TOLERANCE: f32 = 0.5;
+            let slide_duration = self.settings.slide_animation();
+            let slides_in = !self.visible && !slide_duration.is_zero();
+            let first_frame = if slides_in {
+                placement.with_bounds(slid_out_bounds(placement.bounds))
+            } else {
+                placement.clone()
+            };
             let pinned_for_existing_window = self.pinned;
             let mut activation_deferred_to_native = false;
             let updated = window_handle
                 .update(cx, |view, window, cx| {
                     view.set_pinned(pinned_for_existing_window, cx);
-                    if macos::window_needs_frame_update(window, &placement, FRAME_RESTORE_TOLERANCE)
-                    {
+                    if macos::window_needs_frame_update(
+                        window,
+                        &first_frame,
+                        FRAME_RESTORE_TOLERANCE,
+                    ) {
                         activation_deferred_to_native = matches!(
-                            macos::move_window_to(window, &placement, true),
+                            macos::move_window_to(window, &first_frame, true),
                             macos::MoveWindowResult::ActivationDeferredToNative
                         );
                     }
@@ -325,6 +388,9 @@ impl AppShellController {
                 if !activation_deferred_to_native {
                     cx.activate(true);
                 }
+                if slides_in {
+                    self.start_slide(window_handle, &first_frame, &placement, false, cx);
+                }
                 self.visible = true;
                 return;
             }
@@ -390,6 +456,7 @@ impl AppShellController {
                     on_window_deactivated,
                     on_toggle_pin_requested,
                     on_hotkeys_updated,
+                    None,
                 )
             })
         }) {
@@ -429,10 +496,71 @@ impl AppShellController {
         ) {
             return;
         }
+        // Sliding out or already parked above the screen, where the frame is
+        // not a placement worth saving.
+        if !self.visible && self.slide_task.is_some() {
+            return;
+        }
 
         self.capture_and_persist_window_placement(cx);
-        cx.hide();
         self.visible = false;
+        if !self.start_slide_out(cx) {
+            cx.hide();
+        }
+    }
+
+    /// Slide the window up past the top edge, hiding the app once it is out
+    /// of sight. Returns false when sliding is off or the window is gone.
+    fn start_slide_out(&mut self, cx: &mut App) -> bool {
+        let Some(window_handle) = self.terminal_window else {
+            return false;
+        };
+        if self.settings.slide_animation().is_zero() {
+            return false;
+        }
+
+        let mut shown = None;
+        let _ = window_handle.update(cx, |_, window, _| {
+            shown = macos::current_panel_placement(window);
+        });
+        let Some(shown) = shown else {
+            return false;
+        };
+
+        let hidden = shown.with_bounds(slid_out_bounds(shown.bounds));
+        self.start_slide(window_handle, &shown, &hidden, true, cx);
+        true
+    }
+
+    /// Move the window from `from` to `to` one frame at a time, replacing any
+    /// slide already running.
+    fn start_slide(
+        &mut self,
+        window_handle: WindowHandle<TerminalView>,
+        from: &macos::PanelPlacement,
+        to: &macos::PanelPlacement,
+        hide_when_done: bool,
+        cx: &mut App,
+    ) {
+        let frames = slide_frames(from.bounds, to.bounds, self.settings.slide_animation())
+            .into_iter()
+            .map(|bounds| to.with_bounds(bounds))
+            .collect::<Vec<_>>();
+
+        self.slide_task = Some(cx.spawn(async move |async_cx| {
+            for frame in frames {
+                async_cx
+                    .background_executor()
+                    .timer(SLIDE_FRAME_INTERVAL)
+                    .await;
+                let _ = window_handle.update(async_cx, |_, window, _| {
+                    let _ = macos::move_window_to(window, &frame, false);
+                });
+            }
+            if hide_when_done {
+                let _ = async_cx.update(|cx| cx.hide());
+            }
+        }));
     }
 
     fn capture_and_persist_window_placement(&mut self, cx: &mut App) {
@@ -492,7 +620,6 @@ impl AppShellController {
 #[cfg(all(test, target_os = "macos"))]
 mod tests {
     use super::{with_try_borrow_mut, AppShellController};
-    use global_hotkey::hotkey::{Code, HotKey, Modifiers};
     use std::cell::{Cell, RefCell};
 
     #[test]
@@ -549,29 +676,7 @@ mod tests {
         assert!(AppShellController::should_activate_window_after_pin_toggle(
             true, true
         ));
-        assert!(!AppShellController::should_activate_window_after_pin_toggle(
-            true, false
-        ));
-        assert!(!AppShellController::should_activate_window_after_pin_toggle(
-            false, true
-        ));
-    }
-
-    #[test]
-    fn parse_r5_alias_hotkey_maps_to_default_toggle_hotkey() {
-        let expected = HotKey::new(Some(Modifiers::SUPER), Code::F4);
-        assert_eq!(
-            AppShellController::parse_hotkey_or_fallback("cmd+r5", expected, "global_hotkey"),
-            expected
-        );
-    }
-
-    #[test]
-    fn parse_command_five_function_key_remaps_to_non_reserved_combo() {
-        let expected = HotKey::new(Some(Modifiers::SUPER), Code::F4);
-        assert_eq!(
-            AppShellController::parse_hotkey_or_fallback("command+F5", expected, "global_hotkey"),
-            expected
-        );
+        assert!(!AppShellController::should_activate_window_after_pin_toggle(true, false));
+        assert!(!AppShellController::should_activate_window_after_pin_toggle(false, true));
     }
 }
@@ -1,51 +1,79 @@
 //! Terminal view - renders the terminal using GPUI
 
-use alacritty_terminal::event::WindowSize;
-use alacritty_terminal::grid::{Indexed, Scroll};
+use crate::global_hotkeys::normalize_hotkey;
+use alacritty_terminal::event::{EventListener, WindowSize};
+use alacritty_terminal::grid::Scroll;
 use alacritty_terminal::index::Side;
-use alacritty_terminal::term::cell::Flags;
+use alacritty_terminal::term::cell::{Cell, Flags};
 use alacritty_terminal::term::color::Colors as AlacColors;
+use alacritty_terminal::term::{Term, TermDamage};
 use alacritty_terminal::vte::ansi::{Color as AlacColor, CursorShape, NamedColor, Rgb as AlacRgb};
 use global_hotkey::hotkey::HotKey as GlobalHotKey;
 
 use gpui::prelude::FluentBuilder;
 use gpui::{
     canvas, div, fill, hsla, point, px, rgb, size, App, AppContext, AsyncWindowContext, Bounds,
-    ClipboardItem, ContentMask, Context, FocusHandle, Focusable, Font, FontFallbacks, FontFeatures,
-    FontStyle, FontWeight, Hsla, InteractiveElement, IntoElement, KeyDownEvent, Keystroke,
-    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, ParentElement, Pixels, Render, Rgba,
-    ScrollDelta, ScrollHandle, ScrollWheelEvent, SharedString, Size, StatefulInteractiveElement,
-    Styled, Subscription, TextRun, WeakEntity, Window, WindowControlArea,
+    ClipboardItem, ContentMask, Context, Corners, EventEmitter, FocusHandle, Focusable, Font,
+    FontFallbacks, FontFeatures, FontStyle, FontWeight, Hsla, InteractiveElement, IntoElement,
+    KeyDownEvent, Keystroke, MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent,
+    ParentElement, Pixels, Render, RenderImage, Rgba, ScrollDelta, ScrollHandle, ScrollWheelEvent,
+    SharedString, Size, StatefulInteractiveElement, Styled, Subscription, Task, TextRun,
+    WeakEntity, Window, WindowControlArea,
 };
 use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::BufWriter;
+use std::ops::Range;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use simple_term::alacritty_terminal::index::Boundary;
 use simple_term::alacritty_terminal::term::search::RegexSearch;
+use simple_term::error::TerminalError;
+use simple_term::images::{image_id_from_uri, TerminalImage};
+use simple_term::line_size::{strip_grid_markers, LineSize};
 use simple_term::mappings::mouse::{
-    alt_scroll, grid_point, grid_point_and_side, mouse_button_report, mouse_moved_report,
-    scroll_report,
+    alt_scroll, grid_point_and_side, mouse_button_report, mouse_moved_report, scroll_report,
 };
-use simple_term::terminal::{Terminal, TerminalEvent};
+use simple_term::shell_integration::{
+    adjacent_prompt, last_output_range, mark_lines, mark_points, MarkKind,
+};
+use simple_term::terminal::{sanitize_title, GridCell, GridCursor, Terminal, TerminalEvent};
 use simple_term::terminal_hyperlinks::{find_from_grid_point, RegexSearches};
 use simple_term::terminal_settings::{
-    Blinking, CursorShape as SettingsCursorShape, LineHeight, TerminalSettings, TerminalTheme,
+    merge_external_settings, BellMode, Blinking, CursorShape as SettingsCursorShape,
+    CustomThemeDefinition, LineHeight, ShellProfile, TabBarPosition, TerminalSettings,
+    TerminalTheme,
 };
 use simple_term::{
-    AlacDirection, AlacPoint, Column, Dimensions, Line, PathStyle, Selection, SelectionType,
-    TermMode, TerminalBounds,
+    AlacDirection, AlacPoint, Column, Dimensions, Event, Line, MaybeNavigationTarget,
+    PathLikeTarget, Selection, SelectionRange, SelectionType, TermMode, TerminalBounds,
 };
 
+mod copy_mode;
+mod find_history;
+mod keybindings;
+mod panes;
 mod utils;
 
+use copy_mode::{copy_mode_key, move_copy_cursor, CopyModeKey, CopyModeState};
+use find_history::FindHistory;
+use keybindings::{KeyAction, KeyBindings};
+use panes::{pane_at_cell, PaneGrid, PaneNode, PANE_DIVIDER_COLS};
 use utils::{
-    alternate_scroll_enabled, common_shortcut_action, consume_scroll_lines,
-    display_offset_from_pointer, effective_scroll_multiplier, file_path_to_file_url,
-    mouse_mode_enabled_for_scroll, point_in_bounds, prepare_for_terminal_input,
-    resolve_working_directory, scroll_delta_to_lines, scrollbar_layout, selection_copy_plan,
-    selection_type_for_click_count, should_ignore_scroll_event, strip_line_column_suffix,
-    text_to_insert, viewport_row_for_line, CommonShortcutAction, ScrollbarLayout,
+    alt_screen_indicator_visible, consume_scroll_lines, cwd_display_name, directed_scroll_lines,
+    display_offset_from_pointer, escape_clears_selection, file_open_url, focus_report,
+    grid_range_row_spans, hyperlink_modifier_held, new_tab_working_directory,
+    paste_needs_confirmation, point_in_bounds, prepare_for_terminal_input,
+    primary_selection_capture, resolve_path_target, resolve_working_directory,
+    scroll_delta_to_lines, scroll_multiplier_for_delta, scroll_position_label, scrollbar_layout,
+    scrollbar_track_click_pages_up, selection_changed, selection_copy_plan,
+    selection_type_for_click_count, should_ignore_scroll_event, snap_to_device_pixels,
+    split_line_column_suffix, text_entry_key, text_to_insert, typography_needs_refresh,
+    viewport_row_for_line, wheel_font_zoom_steps, wheel_scroll_target, ScrollbarLayout,
+    TextEntryKey, WheelScrollTarget,
 };
 
 const TAB_BAR_HEIGHT_PX: f32 = 40.0;
@@ -57,6 +85,25 @@ const TAB_ITEM_HEIGHT_PX: f32 = 28.0;
 const TAB_ITEM_INDICATOR_HEIGHT_PX: f32 = 3.0;
 const TAB_ITEM_INDICATOR_BOTTOM_GAP_PX: f32 = 2.0;
 const TAB_CLOSE_BUTTON_SIZE_PX: f32 = 20.0;
+const TAB_UNREAD_DOT_SIZE_PX: f32 = 6.0;
+const TAB_DROPDOWN_MENU_WIDTH_PX: f32 = 280.0;
+const TAB_DROPDOWN_MENU_MAX_HEIGHT_PX: f32 = 320.0;
+const TAB_DROPDOWN_MENU_RIGHT_OFFSET_PX: f32 = 12.0;
+const CONTEXT_MENU_WIDTH_PX: f32 = 180.0;
+const CONTEXT_MENU_ITEM_HEIGHT_PX: f32 = 24.0;
+const CONTEXT_MENU_PADDING_PX: f32 = 4.0;
+const CONTEXT_MENU_SWATCH_SIZE_PX: f32 = 10.0;
+/// Opacity of a tab tint over the terminal background.
+const TAB_TINT_ALPHA: f32 = 0.12;
+/// Tints offered by the tab right-click menu, in display order.
+const TAB_TINT_PRESETS: [(&str, Option<u32>); 6] = [
+    ("No Tint", None),
+    ("Red", Some(0xE5484D)),
+    ("Orange", Some(0xF76B15)),
+    ("Green", Some(0x30A46C)),
+    ("Blue", Some(0x0090FF)),
+    ("Purple", Some(0x8E4EC6)),
+];
 const PIN_INDICATOR_BUTTON_WIDTH_PX: f32 = 30.0;
 const SETTINGS_BUTTON_WIDTH_PX: f32 = 30.0;
 const FIND_PANEL_MAX_WIDTH_PX: f32 = 760.0;
@@ -73,6 +120,13 @@ const SETTINGS_DRAWER_SCROLLBAR_TRACK_INSET_PX: f32 = 2.0;
 const SETTINGS_DRAWER_SCROLLBAR_MIN_THUMB_HEIGHT_PX: f32 = 24.0;
 const SETTINGS_DRAWER_SCROLL_CONTENT_PADDING_RIGHT_PX: f32 = 14.0;
 const SETTINGS_OVERLAY_BACKDROP_ALPHA: f32 = 0.28;
+const PASTE_CONFIRMATION_MAX_WIDTH_PX: f32 = 560.0;
+const PASTE_PREVIEW_MAX_LINES: usize = 6;
+const PASTE_PREVIEW_MAX_COLUMNS: usize = 80;
+/// Largest write a paste makes at once; longer pastes are written a chunk per
+/// `PASTE_CHUNK_INTERVAL` so the UI keeps drawing.
+const PASTE_CHUNK_BYTES: usize = 4096;
+const PASTE_CHUNK_INTERVAL: Duration = Duration::from_millis(4);
 const SETTINGS_NUMERIC_BUTTON_WIDTH_PX: f32 = 24.0;
 const SETTINGS_CONTROL_HEIGHT_PX: f32 = 24.0;
 const SETTINGS_MIN_FONT_SIZE: f32 = 6.0;
@@ -84,16 +138,68 @@ const SETTINGS_LINE_HEIGHT_STEP: f32 = 0.1;
 const SETTINGS_MIN_SCROLL_MULTIPLIER: f32 = 0.01;
 const SETTINGS_MAX_SCROLL_MULTIPLIER: f32 = 10.0;
 const SETTINGS_SCROLL_MULTIPLIER_STEP: f32 = 0.25;
-const CURSOR_BLINK_INTERVAL: Duration = Duration::from_millis(530);
-const CURSOR_BLINK_SUPPRESSION_AFTER_INPUT: Duration = Duration::from_millis(800);
+const SETTINGS_SCROLLBACK_STEP: isize = 1_000;
+const SETTINGS_HYPERLINK_TIMEOUT_STEP_MS: i64 = 100;
+const SETTINGS_MAX_HYPERLINK_TIMEOUT_MS: u64 = 5_000;
+/// How often `settings.json` is checked for external edits. A change is applied
+/// once its mtime has been stable for one interval, which debounces bursts of writes.
+const SETTINGS_RELOAD_POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// How long after the user scrolls `scroll_on_output` leaves the viewport in
+/// history.
+const SCROLL_ON_OUTPUT_REVIEW_WINDOW: Duration = Duration::from_secs(2);
+/// How often tabs without a shell-set title check their foreground process.
+const PROCESS_TITLE_POLL_INTERVAL: Duration = Duration::from_secs(1);
 const SELECTION_TINT_ALPHA: f32 = 0.30;
-const THEME_PRESETS: [TerminalTheme; 6] = [
+/// How long the visual bell flashes the terminal.
+const BELL_FLASH_DURATION: Duration = Duration::from_millis(120);
+/// How long the find panel says a search wrapped around the buffer.
+const FIND_WRAP_INDICATOR_DURATION: Duration = Duration::from_millis(1500);
+/// Longest a synchronized update holds the previous frame, matching how long
+/// alacritty buffers one before drawing it anyway.
+const SYNC_UPDATE_TIMEOUT: Duration = Duration::from_millis(150);
+/// How long the scrollbar stays fully visible after the viewport last scrolled.
+const SCROLLBAR_FADE_DELAY: Duration = Duration::from_millis(1200);
+/// How long the scrollbar then takes to fade out.
+const SCROLLBAR_FADE_DURATION: Duration = Duration::from_millis(300);
+/// Gap between the scroll position label and the surface's top-right corner,
+/// leaving room for the scrollbar.
+const SCROLL_POSITION_LABEL_TOP_PX: f32 = 6.0;
+const SCROLL_POSITION_LABEL_RIGHT_PX: f32 = 18.0;
+/// Time allowed for counting matches; longer scans report a partial count.
+const FIND_SCAN_BUDGET: Duration = Duration::from_millis(200);
+/// Tooltip on the tab bar badge shown while the program uses the alternate screen.
+const ALT_SCREEN_INDICATOR_TOOLTIP: &str =
+    "Alternate screen: scrollback returns when the program exits";
+/// Tooltip on the tab bar badge shown while input goes to every tab.
+const BROADCAST_INPUT_INDICATOR_TOOLTIP: &str =
+    "Typing and pasting go to every tab. Click or press Cmd+Shift+I to stop";
+/// Name offered in the save dialog for exported scrollback.
+const SCROLLBACK_FILE_NAME: &str = "scrollback.txt";
+/// Dirty rows each shaping thread should get before another one is spawned.
+const ROWS_PER_SHAPING_THREAD: usize = 16;
+/// Fewest dirty rows shaped on more than one thread. Below this, spawning
+/// threads costs about as much as it saves, so incremental output such as a
+/// prompt redraw or a few new lines stays on the calling thread.
+const MIN_ROWS_FOR_PARALLEL_SHAPING: usize = 64;
+/// Upper bound on threads shaping rows for one frame.
+const MAX_SHAPING_THREADS: usize = 4;
+/// Opacity of the foreground-colored visual bell overlay.
+const BELL_FLASH_ALPHA: f32 = 0.18;
+/// Relative luminance above which a terminal background counts as light.
+const LIGHT_BACKGROUND_LUMINANCE: f32 = 0.5;
+/// Minimum contrast ratio between the selection tint and the background.
+const SELECTION_TINT_MIN_CONTRAST: f32 = 1.5;
+/// Bisection steps when adjusting text toward the minimum contrast.
+const CONTRAST_SEARCH_STEPS: usize = 12;
+const THEME_PRESETS: [TerminalTheme; 8] = [
     TerminalTheme::AtomOneDark,
     TerminalTheme::GruvboxDark,
     TerminalTheme::TokyoNight,
     TerminalTheme::CatppuccinMocha,
     TerminalTheme::Nord,
     TerminalTheme::SolarizedDark,
+    TerminalTheme::SolarizedLight,
+    TerminalTheme::GithubLight,
 ];
 const SETTINGS_FONT_FAMILY_CANDIDATES: [&str; 11] = [
     "JetBrains Mono",
@@ -126,8 +232,77 @@ struct ThemePalette {
     background: (u8, u8, u8),
 }
 
-fn theme_palette(theme: TerminalTheme) -> ThemePalette {
+impl ThemePalette {
+    fn is_light(&self) -> bool {
+        relative_luminance(rgb_u32_to_alac_rgb(self.terminal_bg)) > LIGHT_BACKGROUND_LUMINANCE
+    }
+
+    fn from_custom(theme: &CustomThemeDefinition) -> Self {
+        let rgb_tuple = |rgb: u32| {
+            let rgb = rgb_u32_to_alac_rgb(rgb);
+            (rgb.r, rgb.g, rgb.b)
+        };
+        ThemePalette {
+            ui_bg: theme.ui_bg,
+            terminal_bg: theme.terminal_bg,
+            cursor: theme.cursor,
+            ansi_colors: theme.ansi_colors.map(rgb_tuple),
+            foreground: rgb_tuple(theme.foreground),
+            background: rgb_tuple(theme.background),
+        }
+    }
+}
+
+/// Custom palettes discovered in `~/.simple-term/themes`, sorted by name.
+#[derive(Clone, Debug, Default)]
+struct ThemeRegistry {
+    custom: Vec<(String, ThemePalette)>,
+}
+
+impl ThemeRegistry {
+    fn load() -> Self {
+        Self::from_definitions(CustomThemeDefinition::load_dir(
+            &TerminalSettings::themes_dir(),
+        ))
+    }
+
+    fn from_definitions(definitions: Vec<(String, CustomThemeDefinition)>) -> Self {
+        Self {
+            custom: definitions
+                .iter()
+                .map(|(name, theme)| (name.clone(), ThemePalette::from_custom(theme)))
+                .collect(),
+        }
+    }
+
+    fn custom_palette(&self, name: &str) -> Option<ThemePalette> {
+        self.custom
+            .iter()
+            .find(|(custom_name, _)| custom_name == name)
+            .map(|(_, palette)| *palette)
+    }
+
+    /// Built-in presets followed by every discovered custom theme.
+    fn themes(&self) -> Vec<TerminalTheme> {
+        THEME_PRESETS
+            .iter()
+            .cloned()
+            .chain(
+                self.custom
+                    .iter()
+                    .map(|(name, _)| TerminalTheme::Custom(name.clone())),
+            )
+            .collect()
+    }
+}
+
+/// Palette for `theme`. Custom themes missing from `registry` fall back to
+/// the default preset.
+fn theme_palette(theme: &TerminalTheme, registry: &ThemeRegistry) -> ThemePalette {
     match theme {
+        TerminalTheme::Custom(name) => registry
+            .custom_palette(name)
+            .unwrap_or_else(|| theme_palette(&TerminalTheme::default(), registry)),
         TerminalTheme::AtomOneDark => ThemePalette {
             ui_bg: 0x101010,
             terminal_bg: 0x000000,
@@ -278,6 +453,57 @@ fn theme_palette(theme: TerminalTheme) -> ThemePalette {
             foreground: (0x83, 0x94, 0x96),
             background: (0x00, 0x2B, 0x36),
         },
+        // Light presets keep a dark chrome so tab bar and drawer text stay legible.
+        TerminalTheme::SolarizedLight => ThemePalette {
+            ui_bg: 0x073642,
+            terminal_bg: 0xFDF6E3,
+            cursor: 0x268BD2,
+            ansi_colors: [
+                (0x07, 0x36, 0x42),
+                (0xDC, 0x32, 0x2F),
+                (0x85, 0x99, 0x00),
+                (0xB5, 0x89, 0x00),
+                (0x26, 0x8B, 0xD2),
+                (0xD3, 0x36, 0x82),
+                (0x2A, 0xA1, 0x98),
+                (0xEE, 0xE8, 0xD5),
+                (0x00, 0x2B, 0x36),
+                (0xCB, 0x4B, 0x16),
+                (0x58, 0x6E, 0x75),
+                (0x65, 0x7B, 0x83),
+                (0x83, 0x94, 0x96),
+                (0x6C, 0x71, 0xC4),
+                (0x93, 0xA1, 0xA1),
+                (0xFD, 0xF6, 0xE3),
+            ],
+            foreground: (0x65, 0x7B, 0x83),
+            background: (0xFD, 0xF6, 0xE3),
+        },
+        TerminalTheme::GithubLight => ThemePalette {
+            ui_bg: 0x24292F,
+            terminal_bg: 0xFFFFFF,
+            cursor: 0x0969DA,
+            ansi_colors: [
+                (0x24, 0x29, 0x2F),
+                (0xCF, 0x22, 0x2E),
+                (0x11, 0x63, 0x29),
+                (0x4D, 0x2D, 0x00),
+                (0x09, 0x69, 0xDA),
+                (0x82, 0x50, 0xDF),
+                (0x1B, 0x7C, 0x83),
+                (0x6E, 0x77, 0x81),
+                (0x57, 0x60, 0x6A),
+                (0xA4, 0x0E, 0x26),
+                (0x1A, 0x7F, 0x37),
+                (0x63, 0x3C, 0x01),
+                (0x21, 0x8B, 0xFF),
+                (0xA4, 0x75, 0xF9),
+                (0x31, 0x92, 0xAA),
+                (0x8C, 0x95, 0x9F),
+            ],
+            foreground: (0x24, 0x29, 0x2F),
+            background: (0xFF, 0xFF, 0xFF),
+        },
     }
 }
 
@@ -285,7 +511,161 @@ struct TerminalTab {
     id: u64,
     number: usize,
     title: String,
+    /// Title the tab opened with, restored when the program drops its own.
+    opened_title: String,
+    /// User-chosen name that takes precedence over shell-reported titles.
+    custom_title: Option<String>,
+    /// Whether `title` came from the shell rather than from opening the tab.
+    title_from_shell: bool,
+    /// Foreground process of the focused pane, shown until the shell sets a title.
+    process_name: Option<String>,
+    /// Spawn directory, replaced by the shell's OSC 7 reports as it changes.
+    cwd: Option<PathBuf>,
+    panes: Vec<TerminalPane>,
+    layout: PaneNode,
+    focused_pane_id: u64,
+    /// Shell profile the tab was opened with; new panes reuse it.
+    profile: Option<ShellProfile>,
+    /// Exit status of the last command, as reported by `OSC 133 ; D`.
+    last_exit_status: Option<i32>,
+    /// Bells and title changes missed while the tab was in the background.
+    unread: TabUnread,
+    /// Color blended over the terminal background, set from the tab menu.
+    tint: Option<u32>,
+}
+
+struct TerminalPane {
+    id: u64,
     terminal: Terminal,
+    /// Exit code of a shell whose pane was kept open by `close_on_exit`.
+    exited: Option<i32>,
+}
+
+impl TerminalTab {
+    fn new(
+        id: u64,
+        number: usize,
+        title: String,
+        cwd: Option<PathBuf>,
+        pane_id: u64,
+        terminal: Terminal,
+    ) -> Self {
+        Self {
+            id,
+            number,
+            opened_title: title.clone(),
+            title,
+            custom_title: None,
+            title_from_shell: false,
+            process_name: None,
+            cwd,
+            panes: vec![TerminalPane {
+                id: pane_id,
+                terminal,
+                exited: None,
+            }],
+            layout: PaneNode::Leaf(pane_id),
+            focused_pane_id: pane_id,
+            profile: None,
+            last_exit_status: None,
+            unread: TabUnread::default(),
+            tint: None,
+        }
+    }
+
+    /// Drop a title the shell set, going back to the foreground process name
+    /// or the title the tab opened with. Returns whether anything changed.
+    fn reset_shell_title(&mut self) -> bool {
+        if !self.title_from_shell {
+            return false;
+        }
+        self.title = self.opened_title.clone();
+        self.title_from_shell = false;
+        self.process_name = self.focused_terminal().foreground_process_name();
+        true
+    }
+
+    fn pane_terminal(&self, pane_id: u64) -> Option<&Terminal> {
+        self.panes
+            .iter()
+            .find(|pane| pane.id == pane_id)
+            .map(|pane| &pane.terminal)
+    }
+
+    fn focused_terminal(&self) -> &Terminal {
+        self.pane_terminal(self.focused_pane_id)
+            .unwrap_or(&self.panes[0].terminal)
+    }
+
+    /// Swap in a new shell for a pane whose shell exited, keeping the tab's
+    /// id, number, title and layout.
+    fn respawn(&mut self, pane_id: u64, terminal: Terminal) -> bool {
+        let Some(pane) = self.panes.iter_mut().find(|pane| pane.id == pane_id) else {
+            return false;
+        };
+        pane.terminal = terminal;
+        pane.exited = None;
+        self.last_exit_status = None;
+        true
+    }
+
+    /// Remove a pane from a split, focusing the first remaining one if it
+    /// had focus. The last pane is never removed.
+    fn remove_pane(&mut self, pane_id: u64, grid_size: Size<u16>, cell_size: Size<Pixels>) -> bool {
+        if self.panes.len() <= 1 || !self.layout.remove_leaf(pane_id) {
+            return false;
+        }
+        self.panes.retain(|pane| pane.id != pane_id);
+        if self.focused_pane_id == pane_id {
+            if let Some(&first_pane_id) = self.layout.leaf_ids().first() {
+                self.focused_pane_id = first_pane_id;
+            }
+        }
+        self.resize_panes(grid_size, cell_size);
+        true
+    }
+
+    /// Whether any pane is running something other than its shell.
+    fn has_running_process(&self) -> bool {
+        self.panes.iter().any(|pane| {
+            should_confirm_close(
+                pane.terminal.foreground_process_name().as_deref(),
+                pane.terminal.shell_process_name().as_deref(),
+            )
+        })
+    }
+
+    fn pane_layout(&self, grid_size: Size<u16>) -> Vec<(u64, PaneGrid)> {
+        self.layout.layout(grid_size)
+    }
+
+    /// Resize every pane's PTY, which reaches the programs as `SIGWINCH`
+    /// whether or not the tab is active.
+    fn resize_panes(&self, grid_size: Size<u16>, cell_size: Size<Pixels>) {
+        for (pane_id, window_size) in pane_window_sizes(&self.layout, grid_size, cell_size) {
+            if let Some(terminal) = self.pane_terminal(pane_id) {
+                terminal.resize(window_size);
+            }
+        }
+    }
+}
+
+/// PTY size of each pane of a tab laid out in `grid_size`.
+fn pane_window_sizes(
+    layout: &PaneNode,
+    grid_size: Size<u16>,
+    cell_size: Size<Pixels>,
+) -> Vec<(u64, WindowSize)> {
+    layout
+        .layout(grid_size)
+        .into_iter()
+        .map(|(pane_id, pane_grid)| {
+            (
+                pane_id,
+                TerminalView::window_size_for_grid(pane_grid.size(), cell_size),
+            )
+        })
+        .collect()
 }
 
 #[derive(Clone, Debug)]
@@ -299,12 +679,220 @@ struct FindMatch {
     end: AlacPoint,
 }
 
+/// What happened in a background tab since it was last active.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct TabUnread {
+    has_activity: bool,
+    has_bell: bool,
+}
+
+impl TabUnread {
+    /// Record a bell or title change, unless the tab is the one on screen.
+    fn note(&mut self, bell: bool, tab_is_active: bool) {
+        if tab_is_active {
+            return;
+        }
+        if bell {
+            self.has_bell = true;
+        } else {
+            self.has_activity = true;
+        }
+    }
+
+    /// Dot shown on the tab: amber for a bell, which outranks plain activity.
+    fn dot_color(self) -> Option<Hsla> {
+        if self.has_bell {
+            Some(hsla(38.0 / 360.0, 0.92, 0.58, 1.0))
+        } else if self.has_activity {
+            Some(tab_brand_purple(0.9))
+        } else {
+            None
+        }
+    }
+}
+
+/// Chunks of a paste still to be written to one pane.
+struct PendingPaste {
+    tab_id: u64,
+    pane_id: u64,
+    chunks: VecDeque<Vec<u8>>,
+}
+
+/// Append typed `bytes` to the newest paste still pending for the pane, so
+/// they reach the program after the paste instead of inside it. Returns the
+/// bytes when nothing is pending for the pane.
+fn queue_behind_pending_paste(
+    pending_pastes: &mut [PendingPaste],
+    tab_id: u64,
+    pane_id: u64,
+    bytes: Vec<u8>,
+) -> Option<Vec<u8>> {
+    let Some(paste) = pending_pastes
+        .iter_mut()
+        .rev()
+        .find(|paste| paste.tab_id == tab_id && paste.pane_id == pane_id)
+    else {
+        return Some(bytes);
+    };
+    match paste.chunks.back_mut() {
+        Some(last) => last.extend_from_slice(&bytes),
+        None => paste.chunks.push_back(bytes),
+    }
+    None
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct TabRenameState {
+    tab_id: u64,
+    draft: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ContextMenuAction {
+    Copy,
+    Paste,
+    SelectAll,
+    ClearScrollback,
+    /// Index into `TAB_TINT_PRESETS`.
+    TabTint(usize),
+}
+
+impl ContextMenuAction {
+    fn label(self) -> &'static str {
+        match self {
+            ContextMenuAction::Copy => "Copy",
+            ContextMenuAction::Paste => "Paste",
+            ContextMenuAction::SelectAll => "Select All",
+            ContextMenuAction::ClearScrollback => "Clear Scrollback",
+            ContextMenuAction::TabTint(index) => TAB_TINT_PRESETS
+                .get(index)
+                .map_or("No Tint", |(label, _)| *label),
+        }
+    }
+
+    /// Color shown next to the label, for tint entries.
+    fn swatch(self) -> Option<u32> {
+        match self {
+            ContextMenuAction::TabTint(index) => TAB_TINT_PRESETS.get(index)?.1,
+            _ => None,
+        }
+    }
+}
+
+/// Link under the pointer while the secondary modifier is held.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct HoveredHyperlink {
+    start: AlacPoint,
+    end: AlacPoint,
+    target: MaybeNavigationTarget,
+}
+
 #[derive(Clone, Debug, Default)]
 struct FindState {
     query: String,
     last_match: Option<FindMatch>,
     match_count: usize,
     active_match_index: Option<usize>,
+    /// End of the "wrapped" hint after a search passed the end of the buffer.
+    wrapped_until: Option<Instant>,
+    cache: Option<FindCache>,
+}
+
+/// Compiled query and its matches, kept across navigations until the query
+/// or the terminal output changes.
+#[derive(Clone, Debug)]
+struct FindCache {
+    query: String,
+    regex: RegexSearch,
+    /// Every match from the top of the scrollback in grid order; `None` until
+    /// the next count.
+    matches: Option<Vec<FindMatch>>,
+    /// The last scan ran out of `FIND_SCAN_BUDGET` before the end.
+    truncated: bool,
+}
+
+impl FindCache {
+    fn new(query: &str) -> Option<Self> {
+        let escaped = TerminalView::regex_escape_literal(query);
+        match RegexSearch::new(&escaped) {
+            Ok(regex) => Some(Self {
+                query: query.to_string(),
+                regex,
+                matches: None,
+                truncated: false,
+            }),
+            Err(err) => {
+                log::warn!("failed to build find regex for '{}': {err}", query);
+                None
+            }
+        }
+    }
+
+    /// Drop the match list after new output; the regex stays compiled.
+    fn invalidate(&mut self) {
+        self.matches = None;
+    }
+
+    /// Match count and the 1-based index of `active`. The buffer is only
+    /// scanned when no match list is cached.
+    fn stats<T: EventListener>(
+        &mut self,
+        term: &alacritty_terminal::term::Term<T>,
+        active: Option<FindMatch>,
+    ) -> (usize, Option<usize>) {
+        if self.matches.is_none() {
+            let (matches, truncated) = scan_find_matches(term, &mut self.regex, FIND_SCAN_BUDGET);
+            self.matches = Some(matches);
+            self.truncated = truncated;
+        }
+
+        let matches = self.matches.as_deref().unwrap_or_default();
+        let active_index = active.and_then(|active| {
+            matches
+                .binary_search_by(|found| found.start.cmp(&active.start))
+                .ok()
+                .filter(|&index| matches[index] == active)
+                .map(|index| index + 1)
+        });
+        (matches.len(), active_index)
+    }
+}
+
+/// All matches from the top of the scrollback, and whether `budget` ran out
+/// first.
+fn scan_find_matches<T: EventListener>(
+    term: &alacritty_terminal::term::Term<T>,
+    regex: &mut RegexSearch,
+    budget: Duration,
+) -> (Vec<FindMatch>, bool) {
+    let started = Instant::now();
+    let mut origin = AlacPoint::new(term.topmost_line(), Column(0));
+    let mut matches = Vec::<FindMatch>::new();
+
+    while let Some(found_range) =
+        term.search_next(regex, origin, AlacDirection::Right, Side::Right, None)
+    {
+        let found = FindMatch {
+            start: *found_range.start(),
+            end: *found_range.end(),
+        };
+        // `search_next` wraps; meeting the first match again ends the pass.
+        if matches.first() == Some(&found) {
+            break;
+        }
+        matches.push(found);
+
+        let next_origin = found.end.add(term, Boundary::None, 1);
+        if next_origin == origin {
+            break;
+        }
+        origin = next_origin;
+        if started.elapsed() > budget {
+            return (matches, true);
+        }
+    }
+
+    (matches, false)
 }
 
 pub struct TerminalView {
@@ -312,31 +900,95 @@ pub struct TerminalView {
     active_tab_id: u64,
     hovered_tab_id: Option<u64>,
     next_tab_id: u64,
+    next_pane_id: u64,
     pinned: bool,
     on_hide_terminal_requested: Option<Arc<dyn Fn() + Send + Sync>>,
     on_toggle_pin_requested: Option<Arc<dyn Fn() + Send + Sync>>,
     on_hotkeys_updated: Option<Arc<dyn Fn(String, String) + Send + Sync>>,
+    /// Opens clicked links; `cx.open_url` is used when unset.
+    on_open_requested: Option<Arc<dyn Fn(MaybeNavigationTarget) + Send + Sync>>,
     regex_searches: RegexSearches,
+    keybindings: KeyBindings,
+    theme_registry: ThemeRegistry,
+    theme_before_light_dark_toggle: Option<TerminalTheme>,
+    /// Settings as last loaded from or saved to disk; the base for merging external edits.
+    settings_on_disk: TerminalSettings,
     settings: TerminalSettings,
     focus_handle: FocusHandle,
-    font: Font,
+    fonts: TerminalFonts,
     font_size: Pixels,
     cell_size: Size<Pixels>,
+    /// Display scale factor the cell size was snapped for.
+    scale_factor: f32,
     grid_size: Size<u16>,
     pending_scroll_lines: f32,
+    /// Wheel movement toward the next font zoom step while Cmd is held.
+    pending_font_zoom_lines: f32,
+    /// When the user last moved the viewport through history.
+    last_user_scroll_at: Option<Instant>,
     suppress_precise_scroll_until: Option<Instant>,
     suppress_precise_scroll_until_ended: bool,
     selection_anchor: Option<(AlacPoint, Side)>,
+    hovered_hyperlink: Option<HoveredHyperlink>,
+    /// Last completed mouse selection, pasted on middle click.
+    primary_selection: Option<String>,
+    /// Selection last announced with `Event::SelectionsChanged`.
+    reported_selection: Option<SelectionRange>,
     find_state: Option<FindState>,
+    /// Queries from earlier searches, shared by every tab.
+    find_history: FindHistory,
+    /// Keyboard selection mode entered with Cmd+Shift+Space.
+    copy_mode: Option<CopyModeState>,
+    /// Whether typed and pasted input goes to every tab, toggled with Cmd+Shift+I.
+    broadcast_input: bool,
+    tab_rename: Option<TabRenameState>,
+    /// Tab waiting for the user to confirm closing it over a running process.
+    close_confirmation_tab_id: Option<u64>,
+    /// Multi-line paste waiting for the user to confirm it.
+    paste_confirmation: Option<String>,
+    /// Large pastes still being written, oldest first.
+    pending_pastes: Vec<PendingPaste>,
+    /// Writes the next chunk of each pending paste.
+    paste_chunk_timer: Option<Task<()>>,
     settings_panel_open: bool,
+    tab_dropdown_open: bool,
+    profile_picker_open: bool,
+    hovered_dropdown_tab_id: Option<u64>,
+    /// Window position of an open right-click menu.
+    context_menu_position: Option<gpui::Point<Pixels>>,
+    /// Tab whose tint menu is open, instead of the terminal menu.
+    context_menu_tab_id: Option<u64>,
     recording_global_hotkey: bool,
     window_has_been_active: bool,
+    /// Whether the window is key. Inactive windows show a steady hollow cursor.
+    window_is_active: bool,
     cursor_blink_visible: bool,
     suppress_cursor_blink_until: Option<Instant>,
+    /// End of the visual bell flash, if one is showing.
+    bell_flash_until: Option<Instant>,
     settings_drawer_scroll_handle: ScrollHandle,
     scrollbar_drag_offset: Option<Pixels>,
-    row_text_cache: Vec<CachedRow>,
+    /// Whether the pointer is over the scrollbar track, which brightens the thumb.
+    scrollbar_hovered: bool,
+    /// When the viewport last scrolled; the scrollbar fades out after it.
+    scrollbar_scrolled_at: Option<Instant>,
+    /// Wakes the view once the scrollbar should start fading.
+    scrollbar_fade_timer: Option<Task<()>>,
+    /// Redraw once a synchronized update stops holding the frame.
+    sync_update_timer: Option<Task<()>>,
+    /// Shaped rows of the active pane, shared with the paint closure.
+    row_text_cache: Arc<Vec<CachedRow>>,
     previous_frame: Option<FrameCache>,
+    /// Last snapshot of the active pane, drawn again while the program is in
+    /// a synchronized update.
+    last_snapshot: Option<TerminalSnapshot>,
+    /// Cells of the active pane copied under the terminal lock, reused across
+    /// frames.
+    snapshot_buffer: SnapshotBuffer,
+    /// Row caches of the panes drawn without focus, by pane id.
+    pane_row_caches: HashMap<u64, PaneRowCache>,
+    /// GPU frames of the inline images painted last frame, by image id.
+    rendered_images: HashMap<u64, Arc<RenderImage>>,
     perf: PerfInstrumentation,
     _resize_subscription: Subscription,
     _activation_subscription: Subscription,
@@ -348,13 +1000,25 @@ struct SnapshotTiming {
     lock_hold: Duration,
 }
 
+/// What the focused pane keeps in `previous_frame`, `snapshot_buffer` and
+/// `row_text_cache`, for a pane drawn without focus.
+#[derive(Default)]
+struct PaneRowCache {
+    previous_frame: Option<FrameCache>,
+    snapshot_buffer: SnapshotBuffer,
+    rows: Arc<Vec<CachedRow>>,
+}
+
 #[derive(Clone)]
 struct FrameCache {
-    rows: Vec<Vec<CellSnapshot>>,
+    rows: Vec<Arc<Vec<GridCell>>>,
+    line_sizes: Vec<LineSize>,
     colors: ColorsSnapshot,
     num_cols: usize,
     num_lines: usize,
     display_offset: usize,
+    selection: Option<SelectionRange>,
+    scroll_region: Option<Range<usize>>,
     cursor_row: Option<usize>,
     cursor_col: usize,
     cursor_shape: CursorShape,
@@ -366,10 +1030,13 @@ impl FrameCache {
     fn from_snapshot(snapshot: &TerminalSnapshot) -> Self {
         Self {
             rows: snapshot.rows.clone(),
+            line_sizes: snapshot.line_sizes.clone(),
             colors: snapshot.colors.clone(),
             num_cols: snapshot.num_cols,
             num_lines: snapshot.num_lines,
             display_offset: snapshot.display_offset,
+            selection: snapshot.selection,
+            scroll_region: snapshot.scroll_region.clone(),
             cursor_row: snapshot.cursor_row,
             cursor_col: snapshot.cursor_col,
             cursor_shape: snapshot.cursor_shape,
@@ -425,20 +1092,75 @@ struct PerfCounters {
     background_row_cache_misses: u64,
 }
 
+/// Averages over every frame recorded since the view was created.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PerfSnapshot {
+    pub frames: u64,
+    pub avg_snapshot_ms: f32,
+    pub avg_lock_hold_ms: f32,
+    pub avg_paint_ms: f32,
+    /// Share of drawn rows that had to be rebuilt.
+    pub dirty_row_ratio: f32,
+    pub text_row_cache_hit_ratio: f32,
+    pub background_row_cache_hit_ratio: f32,
+}
+
+fn ratio(part: u64, total: u64) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f32 / total as f32
+    }
+}
+
+impl PerfCounters {
+    fn snapshot(&self) -> PerfSnapshot {
+        if self.frames == 0 {
+            return PerfSnapshot::default();
+        }
+
+        let frames = self.frames as f32;
+        let avg_ms = |total: Duration| total.as_secs_f32() * 1000.0 / frames;
+        PerfSnapshot {
+            frames: self.frames,
+            avg_snapshot_ms: avg_ms(self.snapshot_total),
+            avg_lock_hold_ms: avg_ms(self.snapshot_lock_hold),
+            avg_paint_ms: avg_ms(self.paint_total),
+            dirty_row_ratio: ratio(self.dirty_rows, self.total_rows),
+            text_row_cache_hit_ratio: ratio(
+                self.text_row_cache_hits,
+                self.text_row_cache_hits + self.text_row_cache_misses,
+            ),
+            background_row_cache_hit_ratio: ratio(
+                self.background_row_cache_hits,
+                self.background_row_cache_hits + self.background_row_cache_misses,
+            ),
+        }
+    }
+}
+
+/// Frames between two perf log lines.
+const PERF_LOG_EVERY_FRAMES: u64 = 120;
+
+/// Frame counters, always collected. `SIMPLE_TERM_PERF` only turns on the
+/// periodic log line.
 #[derive(Clone)]
 struct PerfInstrumentation {
-    enabled: bool,
+    log_enabled: bool,
     counters: Arc<Mutex<PerfCounters>>,
+    /// Frame count at the last log line.
+    logged_frames: u64,
 }
 
 impl PerfInstrumentation {
     fn from_env() -> Self {
-        let enabled = std::env::var("SIMPLE_TERM_PERF")
+        let log_enabled = std::env::var("SIMPLE_TERM_PERF")
             .map(|v| matches!(v.as_str(), "1" | "true" | "TRUE" | "yes" | "YES"))
             .unwrap_or(false);
         Self {
-            enabled,
-            counters: Arc::new(Mutex::new(PerfCounters::default())),
+            log_enabled,
+            counters: Arc::default(),
+            logged_frames: 0,
         }
     }
 
@@ -450,10 +1172,6 @@ impl PerfInstrumentation {
         total_rows: usize,
         row_cache_stats: RowCacheStats,
     ) {
-        if !self.enabled {
-            return;
-        }
-
         let mut counters = self.counters.lock();
         counters.frames += 1;
         counters.snapshot_total += snapshot_timing.total;
@@ -465,43 +1183,41 @@ impl PerfInstrumentation {
         counters.text_row_cache_misses += row_cache_stats.text_misses as u64;
         counters.background_row_cache_hits += row_cache_stats.background_hits as u64;
         counters.background_row_cache_misses += row_cache_stats.background_misses as u64;
+    }
 
-        const LOG_EVERY_FRAMES: u64 = 120;
-        if counters.frames.is_multiple_of(LOG_EVERY_FRAMES) {
-            let frames = counters.frames as f32;
-            let avg_snapshot_ms = counters.snapshot_total.as_secs_f32() * 1000.0 / frames;
-            let avg_lock_ms = counters.snapshot_lock_hold.as_secs_f32() * 1000.0 / frames;
-            let avg_paint_ms = counters.paint_total.as_secs_f32() * 1000.0 / frames;
-            let dirty_ratio = if counters.total_rows == 0 {
-                0.0
-            } else {
-                counters.dirty_rows as f32 / counters.total_rows as f32
-            };
-            let text_cache_total = counters.text_row_cache_hits + counters.text_row_cache_misses;
-            let text_cache_hit_ratio = if text_cache_total == 0 {
-                0.0
-            } else {
-                counters.text_row_cache_hits as f32 / text_cache_total as f32
-            };
-            let background_cache_total =
-                counters.background_row_cache_hits + counters.background_row_cache_misses;
-            let background_cache_hit_ratio = if background_cache_total == 0 {
-                0.0
-            } else {
-                counters.background_row_cache_hits as f32 / background_cache_total as f32
-            };
+    fn snapshot(&self) -> PerfSnapshot {
+        self.counters.lock().snapshot()
+    }
+}
 
-            log::info!(
-                "terminal perf: frames={} avg_snapshot_ms={:.3} avg_lock_hold_ms={:.3} avg_paint_ms={:.3} avg_dirty_row_ratio={:.3} text_row_cache_hit_ratio={:.3} background_row_cache_hit_ratio={:.3}",
-                counters.frames,
-                avg_snapshot_ms,
-                avg_lock_ms,
-                avg_paint_ms,
-                dirty_ratio,
-                text_cache_hit_ratio,
-                background_cache_hit_ratio,
-            );
+impl TerminalView {
+    /// Averaged frame metrics, for embedders that want them without parsing
+    /// the perf log.
+    pub fn perf_snapshot(&self) -> PerfSnapshot {
+        self.perf.snapshot()
+    }
+
+    /// Log the frame averages every `PERF_LOG_EVERY_FRAMES` painted frames
+    /// when `SIMPLE_TERM_PERF` is set.
+    fn log_perf(&mut self) {
+        if !self.perf.log_enabled {
+            return;
+        }
+        let perf = self.perf_snapshot();
+        if perf.frames < self.perf.logged_frames + PERF_LOG_EVERY_FRAMES {
+            return;
         }
+        self.perf.logged_frames = perf.frames;
+        log::info!(
+                "terminal perf: frames={} avg_snapshot_ms={:.3} avg_lock_hold_ms={:.3} avg_paint_ms={:.3} avg_dirty_row_ratio={:.3} text_row_cache_hit_ratio={:.3} background_row_cache_hit_ratio={:.3}",
+                perf.frames,
+                perf.avg_snapshot_ms,
+                perf.avg_lock_hold_ms,
+                perf.avg_paint_ms,
+                perf.dirty_row_ratio,
+                perf.text_row_cache_hit_ratio,
+            perf.background_row_cache_hit_ratio,
+        );
     }
 }
 
@@ -529,16 +1245,22 @@ impl RowCacheStats {
 enum ViewUpdateAction {
     Notify,
     SetTitleAndNotify(String),
-    Ignore,
-    Exit,
+    ResetTitle,
+    SetCwd(PathBuf),
+    SetExitStatus(Option<i32>),
+    Bell,
+    Exit(i32),
 }
 
 fn update_action_for_terminal_event(event: TerminalEvent) -> ViewUpdateAction {
     match event {
         TerminalEvent::Wakeup => ViewUpdateAction::Notify,
         TerminalEvent::TitleChanged(title) => ViewUpdateAction::SetTitleAndNotify(title),
-        TerminalEvent::Bell => ViewUpdateAction::Ignore,
-        TerminalEvent::Exit(_) => ViewUpdateAction::Exit,
+        TerminalEvent::TitleReset => ViewUpdateAction::ResetTitle,
+        TerminalEvent::CwdChanged(cwd) => ViewUpdateAction::SetCwd(cwd),
+        TerminalEvent::CommandFinished(status) => ViewUpdateAction::SetExitStatus(status),
+        TerminalEvent::Bell => ViewUpdateAction::Bell,
+        TerminalEvent::Exit(code) => ViewUpdateAction::Exit(code),
     }
 }
 
@@ -547,19 +1269,6 @@ impl TerminalView {
         self.focus_handle.focus(window);
     }
 
-    fn sanitize_tab_title(raw: &str) -> String {
-        let cleaned: String = raw
-            .chars()
-            .filter(|ch| *ch == '\t' || !ch.is_control())
-            .collect();
-        let trimmed = cleaned.trim();
-        if trimmed.is_empty() {
-            "shell".to_string()
-        } else {
-            trimmed.chars().take(60).collect()
-        }
-    }
-
     fn next_tab_number_from_numbers(existing_numbers: &[usize]) -> usize {
         let mut candidate = 1usize;
         loop {
@@ -639,6 +1348,7 @@ impl TerminalView {
         }
     }
 
+    #[cfg(target_os = "macos")]
     pub(crate) fn set_pinned(&mut self, pinned: bool, cx: &mut Context<Self>) {
         if self.pinned == pinned {
             return;
@@ -648,15 +1358,46 @@ impl TerminalView {
         cx.notify();
     }
 
-    fn terminal_grid_for_viewport(viewport: Size<Pixels>, cell_size: Size<Pixels>) -> Size<u16> {
-        let content_height = if viewport.height > px(TAB_BAR_HEIGHT_PX) {
-            viewport.height - px(TAB_BAR_HEIGHT_PX)
+    /// Save where the standard window sits on `monitor_key`, skipping the
+    /// write when it has not moved since the last save.
+    #[cfg(not(target_os = "macos"))]
+    pub(crate) fn remember_window_placement(
+        &mut self,
+        monitor_key: String,
+        placement: simple_term::terminal_settings::MonitorWindowPlacement,
+    ) {
+        let changed = self
+            .settings
+            .monitor_window_positions
+            .get(&monitor_key)
+            .map(|saved| !saved.approximately_equals(&placement, 0.5))
+            .unwrap_or(true);
+        if !changed {
+            return;
+        }
+
+        self.settings
+            .monitor_window_positions
+            .insert(monitor_key, placement);
+        self.persist_settings();
+    }
+
+    fn terminal_grid_for_viewport(
+        viewport: Size<Pixels>,
+        cell_size: Size<Pixels>,
+        padding: Size<Pixels>,
+        tab_bar_position: TabBarPosition,
+    ) -> Size<u16> {
+        let chrome_height = tab_bar_height(tab_bar_position) + padding.height * 2.0;
+        let content_height = if viewport.height > chrome_height {
+            viewport.height - chrome_height
         } else {
             px(1.0)
         };
+        let content_width = (viewport.width - padding.width * 2.0).max(px(1.0));
 
         let cols = std::cmp::max(
-            (f32::from(viewport.width) / f32::from(cell_size.width)) as u16,
+            (f32::from(content_width) / f32::from(cell_size.width)) as u16,
             1,
         );
         let lines = std::cmp::max(
@@ -670,35 +1411,114 @@ impl TerminalView {
         }
     }
 
+    /// The cell size is rounded so the pixel size reported to the PTY
+    /// (`TIOCSWINSZ`), which image protocols use, stays close to the window.
     fn window_size_for_grid(grid_size: Size<u16>, cell_size: Size<Pixels>) -> WindowSize {
         WindowSize {
             num_lines: grid_size.height,
             num_cols: grid_size.width,
-            cell_width: f32::from(cell_size.width) as u16,
-            cell_height: f32::from(cell_size.height) as u16,
+            cell_width: f32::from(cell_size.width).round() as u16,
+            cell_height: f32::from(cell_size.height).round() as u16,
         }
     }
 
     fn spawn_terminal(
         settings: &TerminalSettings,
+        profile: Option<&ShellProfile>,
+        working_directory: Option<PathBuf>,
         window_size: WindowSize,
-    ) -> std::io::Result<Terminal> {
-        let scrollback_lines = settings
-            .max_scroll_history_lines
-            .unwrap_or(simple_term::config::DEFAULT_SCROLL_HISTORY_LINES);
-        let working_directory = resolve_working_directory(&settings.working_directory);
-        Terminal::new(
-            settings.shell.to_shell(),
-            working_directory,
-            window_size,
-            scrollback_lines,
-            settings.env.clone(),
-            settings.default_cursor_style(),
-        )
+    ) -> Result<Terminal, TerminalError> {
+        let scrollback_lines = Self::scrollback_lines(settings);
+        // A tracked or configured directory may have been removed since; the
+        // shell then starts where the app runs instead of failing to open.
+        let working_directory = working_directory.filter(|directory| directory.is_dir());
+        let (shell, env) = match profile {
+            Some(profile) => (
+                profile.shell.to_shell(false),
+                profile.effective_env(&settings.env),
+            ),
+            None => (
+                settings.shell.to_shell(settings.login_shell),
+                settings.env.clone(),
+            ),
+        };
+        Terminal::builder(window_size)
+            .shell(shell)
+            .working_directory(working_directory)
+            .scrollback_lines(scrollback_lines)
+            .environment(env)
+            .default_cursor_style(settings.default_cursor_style())
+            .word_separators(&settings.word_separators)
+            .initial_command(profile.and_then(|profile| profile.initial_command.clone()))
+            .build()
+    }
+
+    fn tab_display_title(tab: &TerminalTab, show_cwd: bool) -> String {
+        let title = tab_title_text(
+            tab.custom_title.as_deref(),
+            &tab.title,
+            tab.title_from_shell,
+            tab.process_name.as_deref(),
+        );
+        if tab.custom_title.is_some() {
+            format!("{}: {}", tab.number, title)
+        } else {
+            Self::tab_title_label(tab.number, title, tab.cwd.as_deref(), show_cwd)
+        }
+    }
+
+    /// Rows for the tab overflow dropdown: `(tab_id, title, is_active)` in tab order.
+    fn tab_dropdown_entries(
+        tabs: impl IntoIterator<Item = (u64, String)>,
+        active_tab_id: u64,
+    ) -> Vec<(u64, String, bool)> {
+        tabs.into_iter()
+            .map(|(tab_id, title)| (tab_id, title, tab_id == active_tab_id))
+            .collect()
+    }
+
+    fn select_tab_from_dropdown(
+        &mut self,
+        tab_id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.tab_dropdown_open = false;
+        self.hovered_dropdown_tab_id = None;
+        self.set_active_tab(tab_id, window, cx);
+        cx.notify();
+    }
+
+    /// Title to store for a shell title update, or `None` when nothing changes.
+    /// A user-chosen custom title pins the tab name until it is cleared.
+    fn shell_title_update(
+        current_title: &str,
+        custom_title: Option<&str>,
+        raw_title: &str,
+    ) -> Option<String> {
+        if custom_title.is_some() {
+            return None;
+        }
+        let title = sanitize_title(raw_title);
+        (title != current_title).then_some(title)
+    }
+
+    /// Custom title committed from the rename editor; an empty draft clears it.
+    fn custom_title_from_draft(draft: &str) -> Option<String> {
+        let trimmed = draft.trim();
+        (!trimmed.is_empty()).then(|| sanitize_title(trimmed))
     }
 
-    fn tab_display_title(tab: &TerminalTab) -> String {
-        format!("{}: {}", tab.number, tab.title)
+    fn tab_title_label(
+        number: usize,
+        title: &str,
+        cwd: Option<&std::path::Path>,
+        show_cwd: bool,
+    ) -> String {
+        match cwd.filter(|_| show_cwd) {
+            Some(cwd) => format!("{}: {}", number, cwd_display_name(cwd)),
+            None => format!("{}: {}", number, title),
+        }
     }
 
     fn push_unique_font_family(options: &mut Vec<String>, family: &str) {
@@ -746,7 +1566,7 @@ impl TerminalView {
         options[next_index as usize].clone()
     }
 
-    fn theme_label(theme: TerminalTheme) -> &'static str {
+    fn theme_label(theme: &TerminalTheme) -> &str {
         match theme {
             TerminalTheme::AtomOneDark => "Atom One Dark",
             TerminalTheme::GruvboxDark => "Gruvbox Dark",
@@ -754,19 +1574,48 @@ impl TerminalView {
             TerminalTheme::CatppuccinMocha => "Catppuccin",
             TerminalTheme::Nord => "Nord",
             TerminalTheme::SolarizedDark => "Solarized Dark",
+            TerminalTheme::SolarizedLight => "Solarized Light",
+            TerminalTheme::GithubLight => "GitHub Light",
+            TerminalTheme::Custom(name) => name,
         }
     }
 
-    fn next_theme(current: TerminalTheme, direction: isize) -> TerminalTheme {
-        let current_index = THEME_PRESETS
+    fn next_theme(
+        current: &TerminalTheme,
+        registry: &ThemeRegistry,
+        direction: isize,
+    ) -> TerminalTheme {
+        let themes = registry.themes();
+        let current_index = themes
             .iter()
-            .position(|theme| *theme == current)
+            .position(|theme| theme == current)
             .unwrap_or(0);
-        let next_index =
-            (current_index as isize + direction).rem_euclid(THEME_PRESETS.len() as isize);
-        THEME_PRESETS[next_index as usize]
-    }
-
+        let next_index = (current_index as isize + direction).rem_euclid(themes.len() as isize);
+        themes[next_index as usize].clone()
+    }
+
+    /// Theme of the opposite brightness. Returns to `previous` when it has the
+    /// opposite brightness, so toggling twice restores the original theme.
+    fn light_dark_counterpart(
+        current: &TerminalTheme,
+        previous: Option<&TerminalTheme>,
+        registry: &ThemeRegistry,
+    ) -> TerminalTheme {
+        let current_is_light = theme_palette(current, registry).is_light();
+        if let Some(previous) = previous {
+            if theme_palette(previous, registry).is_light() != current_is_light {
+                return previous.clone();
+            }
+        }
+
+        match current {
+            TerminalTheme::SolarizedDark => TerminalTheme::SolarizedLight,
+            TerminalTheme::SolarizedLight => TerminalTheme::SolarizedDark,
+            _ if current_is_light => TerminalTheme::default(),
+            _ => TerminalTheme::GithubLight,
+        }
+    }
+
     fn toggled_settings_panel_open(is_open: bool) -> bool {
         !is_open
     }
@@ -853,6 +1702,35 @@ impl TerminalView {
         }
     }
 
+    /// Scrollback kept per terminal, capped like the emulator caps it.
+    fn scrollback_lines(settings: &TerminalSettings) -> usize {
+        settings
+            .max_scroll_history_lines
+            .unwrap_or(simple_term::config::DEFAULT_SCROLL_HISTORY_LINES)
+            .min(simple_term::config::MAX_SCROLL_HISTORY_LINES)
+    }
+
+    fn stepped_scrollback_lines(current: usize, delta: isize) -> usize {
+        current
+            .saturating_add_signed(delta)
+            .min(simple_term::config::MAX_SCROLL_HISTORY_LINES)
+    }
+
+    fn stepped_hyperlink_timeout_ms(current: u64, delta: i64) -> u64 {
+        current
+            .saturating_add_signed(delta)
+            .min(SETTINGS_MAX_HYPERLINK_TIMEOUT_MS)
+    }
+
+    /// Drawer label for the path hyperlink timeout; zero turns path links off.
+    fn hyperlink_timeout_display(timeout_ms: u64) -> String {
+        if timeout_ms == 0 {
+            "off".to_string()
+        } else {
+            format!("{timeout_ms}ms")
+        }
+    }
+
     fn global_hotkey_key_token(key: &str) -> Option<String> {
         let normalized = key.trim().to_ascii_lowercase();
         if normalized.is_empty() {
@@ -937,33 +1815,88 @@ impl TerminalView {
 
         match (
             candidate.parse::<GlobalHotKey>(),
-            pin_hotkey.parse::<GlobalHotKey>(),
+            normalize_hotkey(pin_hotkey).parse::<GlobalHotKey>(),
         ) {
             (Ok(candidate), Ok(configured)) => candidate == configured,
             _ => false,
         }
     }
 
-    fn persist_settings(&self) {
+    fn persist_settings(&mut self) {
         let config_path = TerminalSettings::config_path();
         if let Err(err) = self.settings.save(&config_path) {
             log::warn!(
                 "failed to save settings to {}: {err}",
                 config_path.display()
             );
+            return;
+        }
+        self.settings_on_disk = self.settings.clone();
+    }
+
+    /// Merge settings edited outside the app and re-apply what changed.
+    fn apply_external_settings(
+        &mut self,
+        disk: TerminalSettings,
+        window: &Window,
+        cx: &mut Context<Self>,
+    ) {
+        let merged = merge_external_settings(&self.settings, &self.settings_on_disk, &disk);
+        self.settings_on_disk = disk;
+        let previous = std::mem::replace(&mut self.settings, merged);
+
+        if previous.font_family != self.settings.font_family
+            || previous.font_size != self.settings.font_size
+            || previous.font_fallbacks != self.settings.font_fallbacks
+            || previous.line_height != self.settings.line_height
+        {
+            self.apply_typography_settings(window, cx);
+        }
+        if previous.padding_x != self.settings.padding_x
+            || previous.padding_y != self.settings.padding_y
+            || previous.tab_bar_position != self.settings.tab_bar_position
+            || previous.hide_tab_bar_with_single_tab != self.settings.hide_tab_bar_with_single_tab
+        {
+            self.sync_grid_to_viewport(window, cx, true);
+        }
+        if previous.keybindings != self.settings.keybindings {
+            self.keybindings = KeyBindings::from_settings(&self.settings.keybindings);
+        }
+        if previous.max_scroll_history_lines != self.settings.max_scroll_history_lines {
+            self.apply_scrollback_setting();
+        }
+        if previous.path_hyperlink_regexes != self.settings.path_hyperlink_regexes
+            || previous.path_hyperlink_timeout_ms != self.settings.path_hyperlink_timeout_ms
+        {
+            self.rebuild_regex_searches();
         }
+        if previous.global_hotkey != self.settings.global_hotkey
+            || previous.pin_hotkey != self.settings.pin_hotkey
+        {
+            if let Some(on_hotkeys_updated) = &self.on_hotkeys_updated {
+                on_hotkeys_updated(
+                    self.settings.global_hotkey.clone(),
+                    self.settings.pin_hotkey.clone(),
+                );
+            }
+        }
+
+        self.previous_frame = None;
+        self.pane_row_caches.clear();
+        cx.notify();
     }
 
     fn resolve_font_and_cell_size(
         window: &Window,
         settings: &TerminalSettings,
-    ) -> (Font, Pixels, Size<Pixels>) {
+    ) -> (TerminalFonts, Pixels, Size<Pixels>) {
         let text_system = window.text_system().clone();
+        let weight = font_weight_from_setting(settings.font_weight);
         let mut font = Font {
             family: SharedString::from(settings.font_family.clone()),
             features: FontFeatures::default(),
             fallbacks: Some(FontFallbacks::from_fonts(settings.font_fallbacks.clone())),
-            weight: FontWeight::NORMAL,
+            weight,
             style: FontStyle::Normal,
         };
         let font_size = px(settings.font_size);
@@ -974,7 +1907,7 @@ impl TerminalView {
                     family: SharedString::from(family.clone()),
                     features: FontFeatures::default(),
                     fallbacks: None,
-                    weight: FontWeight::NORMAL,
+                    weight,
                     style: FontStyle::Normal,
                 };
                 is_monospace_font(&text_system, &candidate, font_size).then_some(candidate)
@@ -993,6 +1926,30 @@ impl TerminalView {
             }
         }
 
+        let regular_bold = Font {
+            weight: FontWeight::BOLD,
+            ..font.clone()
+        };
+        let bold = match settings.bold_font_family.as_ref() {
+            Some(family) => {
+                let candidate = Font {
+                    family: SharedString::from(family.clone()),
+                    ..regular_bold.clone()
+                };
+                if is_monospace_font(&text_system, &candidate, font_size) {
+                    candidate
+                } else {
+                    log::warn!(
+                        "Bold font '{}' appears non-monospace; using '{}' for bold text",
+                        family,
+                        font.family
+                    );
+                    regular_bold
+                }
+            }
+            None => regular_bold,
+        };
+
         let font_id = text_system.resolve_font(&font);
         let cell_advance = text_system
             .advance(font_id, font_size, 'm')
@@ -1000,12 +1957,23 @@ impl TerminalView {
                 width: px(8.4),
                 height: px(17.0),
             });
+        let scale_factor = window.scale_factor();
         let cell_size = Size {
-            width: cell_advance.width,
-            height: font_size * settings.line_height.to_ratio(),
+            width: snap_to_device_pixels(cell_advance.width, scale_factor),
+            height: snap_to_device_pixels(
+                font_size * settings.line_height.to_ratio(),
+                scale_factor,
+            ),
         };
 
-        (font, font_size, cell_size)
+        (
+            TerminalFonts {
+                regular: font,
+                bold,
+            },
+            font_size,
+            cell_size,
+        )
     }
 
     fn sync_grid_to_viewport(
@@ -1014,8 +1982,12 @@ impl TerminalView {
         cx: &mut Context<Self>,
         force_resize: bool,
     ) {
-        let new_grid_size =
-            Self::terminal_grid_for_viewport(window.viewport_size(), self.cell_size);
+        let new_grid_size = Self::terminal_grid_for_viewport(
+            window.viewport_size(),
+            self.cell_size,
+            content_padding(&self.settings),
+            self.tab_bar_layout(),
+        );
         if new_grid_size.width == 0 || new_grid_size.height == 0 {
             return;
         }
@@ -1026,9 +1998,8 @@ impl TerminalView {
             return;
         }
 
-        let window_size = Self::window_size_for_grid(new_grid_size, self.cell_size);
         for tab in &self.tabs {
-            tab.terminal.resize(window_size);
+            tab.resize_panes(new_grid_size, self.cell_size);
         }
         self.grid_size = new_grid_size;
         self.reset_active_tab_frame_state();
@@ -1036,14 +2007,15 @@ impl TerminalView {
     }
 
     fn apply_typography_settings(&mut self, window: &Window, cx: &mut Context<Self>) {
-        let (font, font_size, cell_size) = Self::resolve_font_and_cell_size(window, &self.settings);
-        self.font = font;
+        let (fonts, font_size, cell_size) =
+            Self::resolve_font_and_cell_size(window, &self.settings);
+        self.fonts = fonts;
         self.font_size = font_size;
         self.cell_size = cell_size;
         self.sync_grid_to_viewport(window, cx, true);
     }
 
-    fn persist_and_notify(&self, cx: &mut Context<Self>) {
+    fn persist_and_notify(&mut self, cx: &mut Context<Self>) {
         self.persist_settings();
         cx.notify();
     }
@@ -1061,8 +2033,11 @@ impl TerminalView {
     }
 
     fn adjust_font_size(&mut self, delta: f32, window: &Window, cx: &mut Context<Self>) {
-        let next_size =
-            (self.settings.font_size + delta).clamp(SETTINGS_MIN_FONT_SIZE, SETTINGS_MAX_FONT_SIZE);
+        self.set_font_size(self.settings.font_size + delta, window, cx);
+    }
+
+    fn set_font_size(&mut self, size: f32, window: &Window, cx: &mut Context<Self>) {
+        let next_size = clamp_font_size(size);
         if (next_size - self.settings.font_size).abs() < f32::EPSILON {
             return;
         }
@@ -1073,7 +2048,7 @@ impl TerminalView {
     }
 
     fn cycle_theme(&mut self, direction: isize, cx: &mut Context<Self>) {
-        let next_theme = Self::next_theme(self.settings.theme, direction);
+        let next_theme = Self::next_theme(&self.settings.theme, &self.theme_registry, direction);
         if next_theme == self.settings.theme {
             return;
         }
@@ -1082,6 +2057,17 @@ impl TerminalView {
         self.persist_and_notify(cx);
     }
 
+    fn toggle_light_dark_theme(&mut self, cx: &mut Context<Self>) {
+        let next_theme = Self::light_dark_counterpart(
+            &self.settings.theme,
+            self.theme_before_light_dark_toggle.as_ref(),
+            &self.theme_registry,
+        );
+        self.theme_before_light_dark_toggle =
+            Some(std::mem::replace(&mut self.settings.theme, next_theme));
+        self.persist_and_notify(cx);
+    }
+
     fn line_height_display(line_height: &LineHeight) -> String {
         match line_height {
             LineHeight::Comfortable => "comfortable".to_string(),
@@ -1139,6 +2125,44 @@ impl TerminalView {
         self.persist_and_notify(cx);
     }
 
+    fn adjust_scrollback(&mut self, delta: isize, cx: &mut Context<Self>) {
+        let current = Self::scrollback_lines(&self.settings);
+        let next = Self::stepped_scrollback_lines(current, delta);
+        if next == current {
+            return;
+        }
+        self.settings.max_scroll_history_lines = Some(next);
+        self.apply_scrollback_setting();
+        self.persist_and_notify(cx);
+    }
+
+    fn adjust_hyperlink_timeout(&mut self, delta: i64, cx: &mut Context<Self>) {
+        let current = self.settings.path_hyperlink_timeout_ms;
+        let next = Self::stepped_hyperlink_timeout_ms(current, delta);
+        if next == current {
+            return;
+        }
+        self.settings.path_hyperlink_timeout_ms = next;
+        self.rebuild_regex_searches();
+        self.persist_and_notify(cx);
+    }
+
+    /// Recompile the path hyperlink patterns with the configured timeout.
+    fn rebuild_regex_searches(&mut self) {
+        self.regex_searches = RegexSearches::new(
+            &self.settings.path_hyperlink_regexes,
+            self.settings.path_hyperlink_timeout_ms,
+        );
+    }
+
+    /// Resize the history of every open terminal to the configured scrollback.
+    fn apply_scrollback_setting(&self) {
+        let lines = Self::scrollback_lines(&self.settings);
+        for pane in self.tabs.iter().flat_map(|tab| tab.panes.iter()) {
+            pane.terminal.set_scrollback_lines(lines);
+        }
+    }
+
     fn apply_global_hotkey_setting(&mut self, hotkey: String, cx: &mut Context<Self>) {
         if self.settings.global_hotkey == hotkey {
             cx.notify();
@@ -1242,8 +2266,8 @@ impl TerminalView {
 
     fn apply_cursor_settings(&mut self, cx: &mut Context<Self>) {
         let escape = Self::cursor_style_escape(self.settings.cursor_shape, self.settings.blinking);
-        for tab in &self.tabs {
-            tab.terminal.write_str(escape);
+        for pane in self.tabs.iter().flat_map(|tab| tab.panes.iter()) {
+            pane.terminal.write_str(escape);
         }
         self.previous_frame = None;
         self.persist_and_notify(cx);
@@ -1290,25 +2314,285 @@ impl TerminalView {
     }
 
     fn active_terminal(&self) -> &Terminal {
-        &self.active_tab().terminal
+        self.active_tab().focused_terminal()
+    }
+
+    /// Grid placement of the focused pane; a single-pane tab fills the whole grid.
+    fn focused_pane_grid(&self) -> PaneGrid {
+        let tab = self.active_tab();
+        tab.pane_layout(self.grid_size)
+            .into_iter()
+            .find(|(pane_id, _)| *pane_id == tab.focused_pane_id)
+            .map(|(_, pane_grid)| pane_grid)
+            .unwrap_or(PaneGrid {
+                col_offset: 0,
+                line_offset: 0,
+                cols: self.grid_size.width,
+                lines: self.grid_size.height,
+            })
+    }
+
+    /// Cell under a window position, relative to the tab's pane grid.
+    fn grid_cell_for_position(
+        position: gpui::Point<Pixels>,
+        cell_size: Size<Pixels>,
+        padding: Size<Pixels>,
+        tab_bar_position: TabBarPosition,
+    ) -> Option<(u16, u16)> {
+        let x = position.x - padding.width;
+        let y = position.y - tab_bar_top_inset(tab_bar_position) - padding.height;
+        if x < px(0.0) || y < px(0.0) {
+            return None;
+        }
+        Some(((x / cell_size.width) as u16, (y / cell_size.height) as u16))
+    }
+
+    /// Move focus to the pane under `position`. Returns `true` when focus changed.
+    fn focus_pane_at_position(&mut self, position: gpui::Point<Pixels>) -> bool {
+        let Some((col, line)) = Self::grid_cell_for_position(
+            position,
+            self.cell_size,
+            content_padding(&self.settings),
+            self.tab_bar_layout(),
+        ) else {
+            return false;
+        };
+        let layout = self.active_tab().pane_layout(self.grid_size);
+        let Some(pane_id) = pane_at_cell(&layout, col, line) else {
+            return false;
+        };
+        self.focus_pane(pane_id)
+    }
+
+    fn focus_pane(&mut self, pane_id: u64) -> bool {
+        let index = self.active_tab_index();
+        let tab = &mut self.tabs[index];
+        if tab.focused_pane_id == pane_id || tab.pane_terminal(pane_id).is_none() {
+            return false;
+        }
+        tab.focused_pane_id = pane_id;
+        self.reset_active_tab_frame_state();
+        true
+    }
+
+    /// Split the focused pane left/right, starting the new shell in the tab's cwd.
+    fn split_active_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.active_tab_index();
+        let working_directory = new_tab_working_directory(
+            self.tabs[index].cwd.as_deref(),
+            &self.settings.working_directory,
+        );
+        let pane_id = self.next_pane_id;
+        let tab = &mut self.tabs[index];
+        if !tab.layout.split_leaf(tab.focused_pane_id, pane_id) {
+            return;
+        }
+        let pane_grid = tab
+            .pane_layout(self.grid_size)
+            .into_iter()
+            .find(|(id, _)| *id == pane_id)
+            .map(|(_, pane_grid)| pane_grid.size())
+            .unwrap_or(self.grid_size);
+        let terminal = match Self::spawn_terminal(
+            &self.settings,
+            tab.profile.as_ref(),
+            working_directory,
+            Self::window_size_for_grid(pane_grid, self.cell_size),
+        ) {
+            Ok(terminal) => terminal,
+            Err(_) => {
+                tab.layout.remove_leaf(pane_id);
+                return;
+            }
+        };
+        self.next_pane_id += 1;
+
+        let events = terminal.events.clone();
+        tab.panes.push(TerminalPane {
+            id: pane_id,
+            terminal,
+            exited: None,
+        });
+        tab.focused_pane_id = pane_id;
+        tab.resize_panes(self.grid_size, self.cell_size);
+        let tab_id = tab.id;
+        self.reset_active_tab_frame_state();
+
+        Self::spawn_terminal_event_loop(tab_id, pane_id, events, window, cx);
+        cx.notify();
+    }
+
+    /// Close the focused pane, or the whole tab when it is the last pane.
+    fn close_active_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let index = self.active_tab_index();
+        let tab = &mut self.tabs[index];
+        if tab.panes.len() <= 1 {
+            self.close_tab(self.active_tab_id, window, cx);
+            return;
+        }
+
+        let closing_pane_id = tab.focused_pane_id;
+        if !tab.remove_pane(closing_pane_id, self.grid_size, self.cell_size) {
+            return;
+        }
+        self.reset_active_tab_frame_state();
+        cx.notify();
+    }
+
+    /// Close the pane or keep it open with a notice, per `close_on_exit`.
+    fn handle_pane_exit(
+        &mut self,
+        tab_id: u64,
+        pane_id: u64,
+        exit_code: i32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.settings.close_on_exit.closes_after(exit_code) {
+            self.close_exited_pane(tab_id, pane_id, window, cx);
+            return;
+        }
+
+        let Some(pane) = self
+            .tabs
+            .iter_mut()
+            .find(|tab| tab.id == tab_id)
+            .and_then(|tab| tab.panes.iter_mut().find(|pane| pane.id == pane_id))
+        else {
+            return;
+        };
+        pane.exited = Some(exit_code);
+        pane.terminal.show_notice(&exit_notice(exit_code));
+        cx.notify();
+    }
+
+    /// Close a pane whose shell has exited. Nothing is left to confirm, and
+    /// the last tab is replaced by a fresh shell before the window hides.
+    fn close_exited_pane(
+        &mut self,
+        tab_id: u64,
+        pane_id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        if tab.remove_pane(pane_id, self.grid_size, self.cell_size) {
+            if self.active_tab_id == tab_id {
+                self.reset_active_tab_frame_state();
+            }
+            cx.notify();
+            return;
+        }
+
+        if self.close_confirmation_tab_id == Some(tab_id) {
+            self.close_confirmation_tab_id = None;
+        }
+        if Self::should_hide_window_when_closing_tab(self.tabs.len()) {
+            self.create_tab(window, cx);
+            self.request_hide_terminal_window(cx);
+        }
+        self.remove_tab(tab_id, window, cx);
+    }
+
+    /// Start a new shell in an exited pane with the tab's profile and cwd.
+    fn respawn_exited_pane(
+        &mut self,
+        tab_id: u64,
+        pane_id: u64,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        let Some((_, window_size)) = pane_window_sizes(&tab.layout, self.grid_size, self.cell_size)
+            .into_iter()
+            .find(|(id, _)| *id == pane_id)
+        else {
+            return;
+        };
+        let working_directory =
+            new_tab_working_directory(tab.cwd.as_deref(), &self.settings.working_directory);
+        let terminal = match Self::spawn_terminal(
+            &self.settings,
+            tab.profile.as_ref(),
+            working_directory,
+            window_size,
+        ) {
+            Ok(terminal) => terminal,
+            Err(error) => {
+                log::warn!("failed to restart shell: {error}");
+                return;
+            }
+        };
+        let events = terminal.events.clone();
+        if !tab.respawn(pane_id, terminal) {
+            return;
+        }
+        if self.active_tab_id == tab_id {
+            self.reset_active_tab_frame_state();
+        }
+
+        Self::spawn_terminal_event_loop(tab_id, pane_id, events, window, cx);
+        cx.notify();
+    }
+
+    /// In a pane kept open after its shell exited, Enter starts a new shell
+    /// and other input is dropped.
+    fn handle_exited_pane_keystroke(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let tab = self.active_tab();
+        let pane_id = tab.focused_pane_id;
+        let exited = tab
+            .panes
+            .iter()
+            .any(|pane| pane.id == pane_id && pane.exited.is_some());
+        if !exited {
+            return false;
+        }
+
+        if matches!(
+            text_entry_key(&event.keystroke),
+            Some(TextEntryKey::Submit { .. })
+        ) {
+            self.respawn_exited_pane(self.active_tab_id, pane_id, window, cx);
+        }
+        true
+    }
+
+    /// Where the tab bar is laid out right now, which can differ from the
+    /// setting while a lone tab hides it.
+    fn tab_bar_layout(&self) -> TabBarPosition {
+        tab_bar_layout_position(&self.settings, self.tabs.len())
     }
 
     fn active_window_title(&self) -> String {
-        Self::tab_display_title(self.active_tab())
+        Self::tab_display_title(self.active_tab(), self.settings.show_cwd_in_tab_title)
     }
 
     fn reset_active_tab_frame_state(&mut self) {
         self.pending_scroll_lines = 0.0;
+        self.pending_font_zoom_lines = 0.0;
         self.suppress_precise_scroll_until = None;
         self.suppress_precise_scroll_until_ended = false;
         self.hovered_tab_id = None;
         self.selection_anchor = None;
-        self.find_state = None;
+        self.hovered_hyperlink = None;
+        self.close_find();
+        self.copy_mode = None;
+        self.paste_confirmation = None;
         self.cursor_blink_visible = true;
         self.suppress_cursor_blink_until = None;
         self.scrollbar_drag_offset = None;
-        self.row_text_cache.clear();
+        self.row_text_cache = Arc::default();
         self.previous_frame = None;
+        self.pane_row_caches.clear();
     }
 
     fn set_active_tab(&mut self, tab_id: u64, window: &mut Window, cx: &mut Context<Self>) {
@@ -1320,6 +2604,8 @@ impl TerminalView {
         }
 
         self.active_tab_id = tab_id;
+        let index = self.active_tab_index();
+        self.tabs[index].unread = TabUnread::default();
         self.reset_active_tab_frame_state();
         window.set_window_title(&self.active_window_title());
         cx.notify();
@@ -1343,28 +2629,106 @@ impl TerminalView {
     }
 
     fn create_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
-        let tab_id = self.next_tab_id;
-        self.next_tab_id += 1;
+        let working_directory = new_tab_working_directory(
+            self.active_tab().cwd.as_deref(),
+            &self.settings.working_directory,
+        );
+        self.open_tab(working_directory, None, None, window, cx);
+    }
+
+    /// "+" opens a tab directly when no profiles are configured, and the
+    /// profile picker otherwise.
+    fn handle_add_tab_click(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.settings.profiles.is_empty() {
+            self.create_tab(window, cx);
+        } else {
+            self.profile_picker_open = !self.profile_picker_open;
+            cx.notify();
+        }
+    }
+
+    /// Open a tab with the profile at `profile_index`. A profile without its own
+    /// working directory follows the global strategy like a plain new tab.
+    fn create_tab_with_profile(
+        &mut self,
+        profile_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.profile_picker_open = false;
+        let Some(profile) = self.settings.profiles.get(profile_index).cloned() else {
+            cx.notify();
+            return;
+        };
+        let working_directory = match &profile.working_directory {
+            Some(strategy) => resolve_working_directory(strategy),
+            None => new_tab_working_directory(
+                self.active_tab().cwd.as_deref(),
+                &self.settings.working_directory,
+            ),
+        };
+        self.open_tab(
+            working_directory,
+            Some(profile.name.clone()),
+            Some(profile),
+            window,
+            cx,
+        );
+    }
+
+    /// Open a new tab in the active tab's working directory, carrying over its
+    /// title and shell profile.
+    fn duplicate_active_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let source = self.active_tab();
+        let working_directory =
+            new_tab_working_directory(source.cwd.as_deref(), &self.settings.working_directory);
+        let title = Self::duplicated_tab_title(source.number, &source.title);
+        let profile = source.profile.clone();
+        self.open_tab(working_directory, title, profile, window, cx);
+    }
+
+    /// Titles that are still the tab-number placeholder are not carried over.
+    fn duplicated_tab_title(source_number: usize, source_title: &str) -> Option<String> {
+        (source_title != source_number.to_string()).then(|| source_title.to_string())
+    }
 
+    fn open_tab(
+        &mut self,
+        working_directory: Option<PathBuf>,
+        title: Option<String>,
+        profile: Option<ShellProfile>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         let number = Self::next_tab_number(&self.tabs);
-        let terminal = Self::spawn_terminal(
+        let terminal = match Self::spawn_terminal(
             &self.settings,
+            profile.as_ref(),
+            working_directory.clone(),
             Self::window_size_for_grid(self.grid_size, self.cell_size),
-        )
-        .expect("Failed to spawn terminal");
+        ) {
+            Ok(terminal) => terminal,
+            Err(error) => {
+                log::warn!("failed to open tab: {error}");
+                return;
+            }
+        };
+        let tab_id = self.next_tab_id;
+        self.next_tab_id += 1;
         let events = terminal.events.clone();
-        let title = number.to_string();
+        let title = title.unwrap_or_else(|| number.to_string());
 
-        self.tabs.push(TerminalTab {
-            id: tab_id,
-            number,
-            title,
-            terminal,
-        });
+        let pane_id = self.next_pane_id;
+        self.next_pane_id += 1;
+        let mut tab = TerminalTab::new(tab_id, number, title, working_directory, pane_id, terminal);
+        tab.profile = profile;
+        self.tabs.push(tab);
         self.active_tab_id = tab_id;
         self.reset_active_tab_frame_state();
+        // A second tab brings back a bar hidden for a single tab.
+        self.sync_grid_to_viewport(window, cx, false);
 
-        Self::spawn_terminal_event_loop(tab_id, events, window, cx);
+        Self::spawn_terminal_event_loop(tab_id, pane_id, events, window, cx);
         window.set_window_title(&self.active_window_title());
         cx.notify();
     }
@@ -1375,22 +2739,74 @@ impl TerminalView {
             return;
         }
 
-        let Some(closing_index) = self.tabs.iter().position(|tab| tab.id == tab_id) else {
+        let has_running_process = self
+            .tabs
+            .iter()
+            .find(|tab| tab.id == tab_id)
+            .is_some_and(TerminalTab::has_running_process);
+        if self.settings.confirm_close && has_running_process {
+            self.close_confirmation_tab_id = Some(tab_id);
+            cx.notify();
             return;
-        };
-        let was_active = self.active_tab_id == tab_id;
-        if self.hovered_tab_id == Some(tab_id) {
-            self.hovered_tab_id = None;
         }
 
-        self.tabs.remove(closing_index);
-        if was_active {
-            let next_active_index =
-                Self::next_active_index_after_close(closing_index, self.tabs.len());
-            self.active_tab_id = self.tabs[next_active_index].id;
-            self.reset_active_tab_frame_state();
-            window.set_window_title(&self.active_window_title());
-        }
+        self.remove_tab(tab_id, window, cx);
+    }
+
+    fn confirm_close_tab(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(tab_id) = self.close_confirmation_tab_id.take() {
+            self.remove_tab(tab_id, window, cx);
+        }
+    }
+
+    fn handle_close_confirmation_keybinding(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.close_confirmation_tab_id.is_none() {
+            return false;
+        }
+
+        match text_entry_key(&event.keystroke) {
+            Some(TextEntryKey::Cancel) => {
+                self.close_confirmation_tab_id = None;
+                cx.notify();
+            }
+            Some(TextEntryKey::Submit { .. }) => self.confirm_close_tab(window, cx),
+            _ => {}
+        }
+
+        true
+    }
+
+    fn remove_tab(&mut self, tab_id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(closing_index) = self.tabs.iter().position(|tab| tab.id == tab_id) else {
+            return;
+        };
+        let was_active = self.active_tab_id == tab_id;
+        if self.hovered_tab_id == Some(tab_id) {
+            self.hovered_tab_id = None;
+        }
+        if self
+            .tab_rename
+            .as_ref()
+            .is_some_and(|rename| rename.tab_id == tab_id)
+        {
+            self.tab_rename = None;
+        }
+
+        self.tabs.remove(closing_index);
+        if was_active {
+            let next_active_index =
+                Self::next_active_index_after_close(closing_index, self.tabs.len());
+            self.active_tab_id = self.tabs[next_active_index].id;
+            self.tabs[next_active_index].unread = TabUnread::default();
+            self.reset_active_tab_frame_state();
+            window.set_window_title(&self.active_window_title());
+        }
+        self.sync_grid_to_viewport(window, cx, false);
 
         cx.notify();
     }
@@ -1406,12 +2822,53 @@ impl TerminalView {
             return;
         };
 
-        let title = Self::sanitize_tab_title(raw_title);
-        if tab.title == title {
+        let Some(title) =
+            Self::shell_title_update(&tab.title, tab.custom_title.as_deref(), raw_title)
+        else {
             return;
-        }
+        };
         tab.title = title;
+        tab.title_from_shell = true;
+        tab.unread.note(false, self.active_tab_id == tab_id);
+
+        if self.active_tab_id == tab_id {
+            window.set_window_title(&self.active_window_title());
+        }
+        cx.notify();
+    }
+
+    fn reset_tab_title(&mut self, tab_id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        if !tab.reset_shell_title() {
+            return;
+        }
+
+        if self.active_tab_id == tab_id {
+            window.set_window_title(&self.active_window_title());
+        }
+        cx.notify();
+    }
+
+    fn update_tab_cwd(
+        &mut self,
+        tab_id: u64,
+        cwd: PathBuf,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        if tab.cwd.as_ref() == Some(&cwd) {
+            return;
+        }
+        tab.cwd = Some(cwd);
 
+        if !self.settings.show_cwd_in_tab_title {
+            return;
+        }
         if self.active_tab_id == tab_id {
             window.set_window_title(&self.active_window_title());
         }
@@ -1420,6 +2877,7 @@ impl TerminalView {
 
     fn spawn_terminal_event_loop(
         tab_id: u64,
+        pane_id: u64,
         events: smol::channel::Receiver<TerminalEvent>,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -1433,6 +2891,15 @@ impl TerminalView {
                             let _ = cx.update(|_window, cx| {
                                 let _ = this.update(cx, |this, cx| {
                                     if this.active_tab_id == tab_id {
+                                        if let Some(cache) = this
+                                            .find_state
+                                            .as_mut()
+                                            .and_then(|state| state.cache.as_mut())
+                                        {
+                                            cache.invalidate();
+                                        }
+                                        this.follow_output_with_grid_anchors();
+                                        this.scroll_on_output();
                                         cx.notify();
                                     }
                                 });
@@ -1445,8 +2912,46 @@ impl TerminalView {
                                 });
                             });
                         }
-                        ViewUpdateAction::Ignore => {}
-                        ViewUpdateAction::Exit => break,
+                        ViewUpdateAction::ResetTitle => {
+                            let _ = cx.update(|window, cx| {
+                                let _ = this.update(cx, |this, cx| {
+                                    this.reset_tab_title(tab_id, window, cx);
+                                });
+                            });
+                        }
+                        ViewUpdateAction::SetCwd(cwd) => {
+                            let _ = cx.update(|window, cx| {
+                                let _ = this.update(cx, |this, cx| {
+                                    this.update_tab_cwd(tab_id, cwd, window, cx);
+                                });
+                            });
+                        }
+                        ViewUpdateAction::SetExitStatus(status) => {
+                            let _ = cx.update(|_window, cx| {
+                                let _ = this.update(cx, |this, _cx| {
+                                    if let Some(tab) =
+                                        this.tabs.iter_mut().find(|tab| tab.id == tab_id)
+                                    {
+                                        tab.last_exit_status = status;
+                                    }
+                                });
+                            });
+                        }
+                        ViewUpdateAction::Bell => {
+                            let _ = cx.update(|window, cx| {
+                                let _ = this.update(cx, |this, cx| {
+                                    this.ring_bell(tab_id, window, cx);
+                                });
+                            });
+                        }
+                        ViewUpdateAction::Exit(code) => {
+                            let _ = cx.update(|window, cx| {
+                                let _ = this.update(cx, |this, cx| {
+                                    this.handle_pane_exit(tab_id, pane_id, code, window, cx);
+                                });
+                            });
+                            break;
+                        }
                     }
                 }
             },
@@ -1454,14 +2959,53 @@ impl TerminalView {
         .detach();
     }
 
-    fn spawn_cursor_blink_loop(window: &mut Window, cx: &mut Context<Self>) {
+    /// React to a bell from `tab_id` according to `bell_mode`. Bells from
+    /// background tabs are ignored.
+    fn ring_bell(&mut self, tab_id: u64, window: &mut Window, cx: &mut Context<Self>) {
+        if self.active_tab_id != tab_id {
+            if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == tab_id) {
+                tab.unread.note(true, false);
+                cx.notify();
+            }
+            return;
+        }
+
+        match self.settings.bell_mode {
+            BellMode::Off => {}
+            BellMode::Audible => play_system_beep(),
+            BellMode::Visual => {
+                self.bell_flash_until = Some(bell_flash_deadline(Instant::now()));
+                cx.notify();
+                // One more frame once the flash is over to clear it.
+                cx.spawn_in(
+                    window,
+                    async move |this: WeakEntity<TerminalView>, cx: &mut AsyncWindowContext| {
+                        smol::Timer::after(BELL_FLASH_DURATION).await;
+                        let _ = cx.update(|_window, cx| {
+                            let _ = this.update(cx, |_this, cx| cx.notify());
+                        });
+                    },
+                )
+                .detach();
+            }
+        }
+    }
+
+    /// Toggle the cursor every `cursor_blink_interval_ms`, read again on each
+    /// tick so reloaded settings apply without a restart.
+    fn spawn_cursor_blink_loop(
+        mut interval: Duration,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         cx.spawn_in(
             window,
             async move |this: WeakEntity<TerminalView>, cx: &mut AsyncWindowContext| loop {
-                smol::Timer::after(CURSOR_BLINK_INTERVAL).await;
+                smol::Timer::after(interval).await;
 
                 let updated = cx.update(|_window, cx| {
-                    let _ = this.update(cx, |this, cx| {
+                    this.update(cx, |this, cx| {
+                        interval = this.settings.cursor_blink_interval();
                         let terminal_blinking = {
                             let term = this.active_terminal().term.lock();
                             term.cursor_style().blinking
@@ -1470,7 +3014,8 @@ impl TerminalView {
                         let suppress_blink = this.cursor_blink_suppressed(now);
                         let should_blink =
                             cursor_should_blink(this.settings.blinking, terminal_blinking)
-                                && !suppress_blink;
+                                && !suppress_blink
+                                && this.window_is_active;
                         if should_blink {
                             this.cursor_blink_visible = !this.cursor_blink_visible;
                             this.previous_frame = None;
@@ -1480,9 +3025,32 @@ impl TerminalView {
                             this.previous_frame = None;
                             cx.notify();
                         }
-                    });
+                    })
                 });
 
+                if !matches!(updated, Ok(Ok(()))) {
+                    break;
+                }
+            },
+        )
+        .detach();
+    }
+
+    /// Track the foreground process of tabs whose title the shell never set.
+    fn spawn_process_title_loop(window: &mut Window, cx: &mut Context<Self>) {
+        cx.spawn_in(
+            window,
+            async move |this: WeakEntity<TerminalView>, cx: &mut AsyncWindowContext| loop {
+                smol::Timer::after(PROCESS_TITLE_POLL_INTERVAL).await;
+
+                let updated = cx.update(|window, cx| {
+                    let _ = this.update(cx, |this, cx| {
+                        if this.refresh_process_titles() {
+                            window.set_window_title(&this.active_window_title());
+                            cx.notify();
+                        }
+                    });
+                });
                 if updated.is_err() {
                     break;
                 }
@@ -1491,6 +3059,68 @@ impl TerminalView {
         .detach();
     }
 
+    /// Returns whether any tab's process name changed.
+    fn refresh_process_titles(&mut self) -> bool {
+        let mut changed = false;
+        for tab in &mut self.tabs {
+            if tab.custom_title.is_some() || tab.title_from_shell {
+                continue;
+            }
+            let name = tab.focused_terminal().foreground_process_name();
+            if tab.process_name != name {
+                tab.process_name = name;
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    fn spawn_settings_reload_loop(window: &mut Window, cx: &mut Context<Self>) {
+        let config_path = TerminalSettings::config_path();
+        let modified_at = move || {
+            std::fs::metadata(&config_path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+        };
+
+        cx.spawn_in(
+            window,
+            async move |this: WeakEntity<TerminalView>, cx: &mut AsyncWindowContext| {
+                let mut applied = modified_at();
+                let mut pending = None;
+                loop {
+                    smol::Timer::after(SETTINGS_RELOAD_POLL_INTERVAL).await;
+
+                    let observed = modified_at();
+                    if observed == applied {
+                        pending = None;
+                        continue;
+                    }
+                    if pending != Some(observed) {
+                        pending = Some(observed);
+                        continue;
+                    }
+                    applied = observed;
+                    pending = None;
+
+                    let Some(disk) = TerminalSettings::try_load(&TerminalSettings::config_path())
+                    else {
+                        continue;
+                    };
+                    let updated = cx.update(|window, cx| {
+                        let _ = this.update(cx, |this, cx| {
+                            this.apply_external_settings(disk, window, cx);
+                        });
+                    });
+                    if updated.is_err() {
+                        break;
+                    }
+                }
+            },
+        )
+        .detach();
+    }
+
     pub fn new(
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -1500,19 +3130,32 @@ impl TerminalView {
         on_window_deactivated: Option<Arc<dyn Fn() + Send + Sync>>,
         on_toggle_pin_requested: Option<Arc<dyn Fn() + Send + Sync>>,
         on_hotkeys_updated: Option<Arc<dyn Fn(String, String) + Send + Sync>>,
+        on_open_requested: Option<Arc<dyn Fn(MaybeNavigationTarget) + Send + Sync>>,
     ) -> Self {
-        let (font, font_size, cell_size) = Self::resolve_font_and_cell_size(window, &settings);
+        let (fonts, font_size, cell_size) = Self::resolve_font_and_cell_size(window, &settings);
 
-        let grid_size = Self::terminal_grid_for_viewport(window.viewport_size(), cell_size);
+        let grid_size = Self::terminal_grid_for_viewport(
+            window.viewport_size(),
+            cell_size,
+            content_padding(&settings),
+            tab_bar_layout_position(&settings, 1),
+        );
         let window_size = Self::window_size_for_grid(grid_size, cell_size);
-        let first_terminal =
-            Self::spawn_terminal(&settings, window_size).expect("Failed to spawn terminal");
+        let first_working_directory = resolve_working_directory(&settings.working_directory);
+        let first_terminal = Self::spawn_terminal(
+            &settings,
+            None,
+            first_working_directory.clone(),
+            window_size,
+        )
+        .expect("Failed to spawn terminal");
         let first_events = first_terminal.events.clone();
 
         let regex_searches = RegexSearches::new(
             &settings.path_hyperlink_regexes,
             settings.path_hyperlink_timeout_ms,
         );
+        let keybindings = KeyBindings::from_settings(&settings.keybindings);
         let focus_handle = cx.focus_handle();
 
         let resize_subscription =
@@ -1524,6 +3167,15 @@ impl TerminalView {
             cx.observe_window_activation(window, move |this, window, cx| {
                 let window_is_active = window.is_window_active();
                 this.window_has_been_active = this.window_has_been_active || window_is_active;
+                if this.window_is_active != window_is_active {
+                    this.window_is_active = window_is_active;
+                    this.cursor_blink_visible = true;
+                    let mode = *this.active_terminal().term.lock().mode();
+                    if let Some(report) = focus_report(mode, window_is_active) {
+                        this.active_terminal().write(report);
+                    }
+                    cx.notify();
+                }
                 Self::schedule_window_deactivation_hide(
                     auto_hide_on_outside_click,
                     window_is_active,
@@ -1538,47 +3190,86 @@ impl TerminalView {
             });
 
         let view = TerminalView {
-            tabs: vec![TerminalTab {
-                id: 1,
-                number: 1,
-                title: "1".to_string(),
-                terminal: first_terminal,
-            }],
+            tabs: vec![TerminalTab::new(
+                1,
+                1,
+                "1".to_string(),
+                first_working_directory,
+                1,
+                first_terminal,
+            )],
             active_tab_id: 1,
             hovered_tab_id: None,
             next_tab_id: 2,
+            next_pane_id: 2,
             pinned,
             on_hide_terminal_requested,
             on_toggle_pin_requested,
             on_hotkeys_updated,
+            on_open_requested,
             regex_searches,
+            keybindings,
+            theme_registry: ThemeRegistry::load(),
+            theme_before_light_dark_toggle: None,
+            settings_on_disk: settings.clone(),
             settings,
             focus_handle,
-            font,
+            fonts,
             font_size,
             cell_size,
+            scale_factor: window.scale_factor(),
             grid_size,
             pending_scroll_lines: 0.0,
+            pending_font_zoom_lines: 0.0,
+            last_user_scroll_at: None,
             suppress_precise_scroll_until: None,
             suppress_precise_scroll_until_ended: false,
             selection_anchor: None,
+            hovered_hyperlink: None,
+            primary_selection: None,
+            reported_selection: None,
             find_state: None,
+            find_history: FindHistory::default(),
+            copy_mode: None,
+            broadcast_input: false,
+            tab_rename: None,
+            close_confirmation_tab_id: None,
+            paste_confirmation: None,
             settings_panel_open: false,
+            tab_dropdown_open: false,
+            profile_picker_open: false,
+            hovered_dropdown_tab_id: None,
+            context_menu_position: None,
+            context_menu_tab_id: None,
             recording_global_hotkey: false,
             window_has_been_active: false,
+            window_is_active: window.is_window_active(),
             cursor_blink_visible: true,
             suppress_cursor_blink_until: None,
+            bell_flash_until: None,
             settings_drawer_scroll_handle: ScrollHandle::new(),
             scrollbar_drag_offset: None,
-            row_text_cache: Vec::new(),
+            scrollbar_hovered: false,
+            scrollbar_scrolled_at: None,
+            scrollbar_fade_timer: None,
+            sync_update_timer: None,
+            pending_pastes: Vec::new(),
+            paste_chunk_timer: None,
+            row_text_cache: Arc::default(),
             previous_frame: None,
+            last_snapshot: None,
+            snapshot_buffer: SnapshotBuffer::default(),
+            pane_row_caches: HashMap::new(),
+            rendered_images: HashMap::new(),
             perf: PerfInstrumentation::from_env(),
             _resize_subscription: resize_subscription,
             _activation_subscription: activation_subscription,
         };
         window.set_window_title(&view.active_window_title());
-        Self::spawn_terminal_event_loop(1, first_events, window, cx);
-        Self::spawn_cursor_blink_loop(window, cx);
+        Self::spawn_terminal_event_loop(1, 1, first_events, window, cx);
+        Self::spawn_cursor_blink_loop(view.settings.cursor_blink_interval(), window, cx);
+        Self::spawn_process_title_loop(window, cx);
+        Self::spawn_settings_reload_loop(window, cx);
 
         view
     }
@@ -1589,19 +3280,42 @@ impl TerminalView {
     }
 
     fn terminal_bounds(&self) -> TerminalBounds {
-        TerminalBounds::new(
-            self.cell_size.height,
-            self.cell_size.width,
-            Bounds {
-                origin: point(px(0.), px(TAB_BAR_HEIGHT_PX)),
-                size: size(
-                    self.cell_size.width * self.grid_size.width as f32,
-                    self.cell_size.height * self.grid_size.height as f32,
-                ),
-            },
+        pane_terminal_bounds(
+            &self.focused_pane_grid(),
+            self.cell_size,
+            content_padding(&self.settings),
+            self.tab_bar_layout(),
         )
     }
 
+    /// Grid point under `position` in the focused pane, accounting for rows
+    /// drawn at double width.
+    fn grid_point_and_side_at(
+        &self,
+        position: gpui::Point<Pixels>,
+        display_offset: usize,
+    ) -> (AlacPoint, Side) {
+        let bounds = self.terminal_bounds();
+        let (point, side) = grid_point_and_side(position, bounds, display_offset);
+        let line_size = usize::try_from(point.line.0 + display_offset as i32)
+            .ok()
+            .and_then(|row| self.previous_frame.as_ref()?.line_sizes.get(row).copied())
+            .unwrap_or_default();
+        if !line_size.is_double_width() {
+            return (point, side);
+        }
+        let (col, side) = double_width_column(
+            position.x - bounds.bounds.origin.x,
+            bounds.cell_width,
+            bounds.num_columns(),
+        );
+        (AlacPoint::new(point.line, Column(col)), side)
+    }
+
+    fn grid_point_at(&self, position: gpui::Point<Pixels>, display_offset: usize) -> AlacPoint {
+        self.grid_point_and_side_at(position, display_offset).0
+    }
+
     fn scrollbar_layout(&self) -> Option<ScrollbarLayout> {
         let term = self.active_terminal().term.lock();
         if term.mode().contains(TermMode::ALT_SCREEN) {
@@ -1616,6 +3330,59 @@ impl TerminalView {
         )
     }
 
+    /// Take a snapshot of the active pane, or repeat the last one while the
+    /// program is inside a synchronized update. The last snapshot is only
+    /// reused while `previous_frame` is, so switching panes or tabs always
+    /// takes a fresh one.
+    fn active_snapshot(
+        &mut self,
+        palette: ThemePalette,
+        cx: &mut Context<Self>,
+    ) -> (TerminalSnapshot, SnapshotTiming) {
+        let hold = synchronized_update_hold(
+            self.active_terminal().synchronized_update_started(),
+            Instant::now(),
+            SYNC_UPDATE_TIMEOUT,
+        );
+        if let (Some(remaining), Some(last), Some(_)) =
+            (hold, &self.last_snapshot, &self.previous_frame)
+        {
+            let snapshot = last.clone();
+            self.sync_update_timer = Some(cx.spawn(
+                async move |this: WeakEntity<TerminalView>, cx: &mut gpui::AsyncApp| {
+                    smol::Timer::after(remaining).await;
+                    let _ = this.update(cx, |_this, cx| cx.notify());
+                },
+            ));
+            return (snapshot, SnapshotTiming::default());
+        }
+
+        let mut snapshot_buffer = std::mem::take(&mut self.snapshot_buffer);
+        let (snapshot, snapshot_timing) = take_snapshot(
+            self.active_terminal(),
+            palette,
+            &self.settings,
+            self.color_dim(),
+            self.previous_frame.as_ref(),
+            &mut snapshot_buffer,
+        );
+        self.snapshot_buffer = snapshot_buffer;
+        self.last_snapshot = Some(snapshot.clone());
+        (snapshot, snapshot_timing)
+    }
+
+    /// Show the scrollbar again and schedule the frame that starts its fade.
+    fn note_viewport_scrolled(&mut self, now: Instant, cx: &mut Context<Self>) {
+        self.scrollbar_scrolled_at = Some(now);
+        // Replacing the timer cancels the one from the previous scroll.
+        self.scrollbar_fade_timer = Some(cx.spawn(
+            async move |this: WeakEntity<TerminalView>, cx: &mut gpui::AsyncApp| {
+                smol::Timer::after(SCROLLBAR_FADE_DELAY).await;
+                let _ = this.update(cx, |_this, cx| cx.notify());
+            },
+        ));
+    }
+
     fn set_display_offset(&mut self, target_offset: usize) -> bool {
         let mut term = self.active_terminal().term.lock();
         let max_offset = term.history_size();
@@ -1627,10 +3394,18 @@ impl TerminalView {
         }
 
         term.scroll_display(Scroll::Delta(delta));
+        drop(term);
+        self.last_user_scroll_at = Some(Instant::now());
         true
     }
 
     fn handle_resize(&mut self, window: &Window, cx: &mut Context<Self>) {
+        let scale_factor = window.scale_factor();
+        if typography_needs_refresh(self.scale_factor, scale_factor) {
+            self.scale_factor = scale_factor;
+            self.apply_typography_settings(window, cx);
+            return;
+        }
         self.sync_grid_to_viewport(window, cx, false);
     }
 
@@ -1643,7 +3418,40 @@ impl TerminalView {
         was_scrolled
     }
 
+    /// alacritty moves its own selection and vi mode cursor as output
+    /// scrolls the grid; the copy mode cursor and anchor are read back from
+    /// them. The drag anchor is a plain grid point, so it is dropped once
+    /// alacritty has dropped the selection it started.
+    fn follow_output_with_grid_anchors(&mut self) {
+        let mut copy_mode = self.copy_mode;
+        let selection_dropped = {
+            let term = self.active_terminal().term.lock();
+            if let Some(state) = copy_mode.as_mut() {
+                state.follow_output(&term);
+            }
+            term.selection.is_none()
+        };
+        self.copy_mode = copy_mode;
+        if selection_dropped {
+            self.selection_anchor = None;
+        }
+    }
+
+    /// Follow new output back to the bottom when `scroll_on_output` is on.
+    fn scroll_on_output(&mut self) {
+        let display_offset = self.active_terminal().term.lock().grid().display_offset();
+        if should_scroll_on_output(
+            self.settings.scroll_on_output,
+            display_offset,
+            self.last_user_scroll_at,
+            Instant::now(),
+        ) {
+            self.scroll_to_bottom();
+        }
+    }
+
     fn begin_terminal_input(&mut self, cx: &mut Context<Self>) {
+        self.copy_mode = None;
         let was_scrolled = self.scroll_to_bottom();
         let now = Instant::now();
         prepare_for_terminal_input(
@@ -1653,7 +3461,8 @@ impl TerminalView {
             &mut self.suppress_precise_scroll_until_ended,
             now,
         );
-        self.suppress_cursor_blink_until = Some(now + CURSOR_BLINK_SUPPRESSION_AFTER_INPUT);
+        self.suppress_cursor_blink_until =
+            cursor_blink_suppressed_until(now, self.settings.cursor_blink_suppression());
         if !self.cursor_blink_visible {
             self.cursor_blink_visible = true;
             self.previous_frame = None;
@@ -1661,6 +3470,10 @@ impl TerminalView {
         }
     }
 
+    fn color_dim(&self) -> f32 {
+        unfocused_color_dim(self.window_is_active, self.settings.unfocused_dim)
+    }
+
     fn cursor_blink_suppressed(&mut self, now: Instant) -> bool {
         if cursor_blink_is_suppressed(self.suppress_cursor_blink_until, now) {
             return true;
@@ -1675,7 +3488,11 @@ impl TerminalView {
 
     fn copy_selection_to_clipboard(&mut self, cx: &mut Context<Self>) -> bool {
         let mut term = self.active_terminal().term.lock();
-        let Some(text) = term.selection_to_string().filter(|text| !text.is_empty()) else {
+        let Some(text) = term
+            .selection_to_string()
+            .map(|text| strip_grid_markers(&text).into_owned())
+            .filter(|text| !text.is_empty())
+        else {
             return false;
         };
 
@@ -1693,102 +3510,348 @@ impl TerminalView {
         true
     }
 
-    fn select_all_terminal_content(&mut self) -> bool {
-        let mut term = self.active_terminal().term.lock();
-        if term.columns() == 0 || term.screen_lines() == 0 {
-            return false;
+    /// Copy the output of the last finished command, found through the shell
+    /// integration marks. Does nothing when the shell sends no marks.
+    fn copy_last_command_output(&mut self, cx: &mut Context<Self>) -> bool {
+        {
+            let mut term = self.active_terminal().term.lock();
+            let Some((start, end)) = last_output_range(
+                &mark_points(&term, MarkKind::Output),
+                &mark_points(&term, MarkKind::CommandEnd),
+                term.last_column(),
+            ) else {
+                return false;
+            };
+            let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+            selection.update(end, Side::Right);
+            term.selection = Some(selection);
         }
-
-        let start = AlacPoint::new(term.topmost_line(), Column(0));
-        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
-        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
-        selection.update(end, Side::Right);
-        term.selection = Some(selection);
-        drop(term);
         self.selection_anchor = None;
+        cx.notify();
 
-        true
+        self.copy_selection_to_clipboard(cx)
     }
 
-    fn normalize_find_query(selection: &str) -> Option<String> {
-        selection
-            .lines()
-            .next()
-            .map(str::trim)
-            .filter(|text| !text.is_empty())
-            .map(ToString::to_string)
+    fn hyperlink_at_position(
+        &mut self,
+        position: gpui::Point<Pixels>,
+    ) -> Option<(String, bool, std::ops::RangeInclusive<AlacPoint>)> {
+        let (_, display_offset) = self.mode_and_display_offset();
+        let point = self.grid_point_at(position, display_offset);
+        let term_handle = self.active_terminal().term.clone();
+        let term = term_handle.lock();
+        find_from_grid_point(
+            &term,
+            point,
+            &mut self.regex_searches,
+            self.settings.path_style,
+        )
     }
 
-    fn regex_escape_literal(query: &str) -> String {
-        let mut escaped = String::with_capacity(query.len());
-        for ch in query.chars() {
-            match ch {
-                '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '^' | '$'
-                | '|' => {
-                    escaped.push('\\');
-                    escaped.push(ch);
-                }
-                _ => escaped.push(ch),
-            }
+    /// Track the link under `position` while the secondary modifier is held;
+    /// `None` clears the highlight.
+    fn update_hovered_hyperlink(
+        &mut self,
+        position: Option<gpui::Point<Pixels>>,
+        cx: &mut Context<Self>,
+    ) {
+        let next = position
+            .filter(|position| point_in_bounds(&self.terminal_bounds().bounds, *position))
+            .and_then(|position| self.hyperlink_at_position(position))
+            .and_then(|(target, is_url, range)| {
+                Some(HoveredHyperlink {
+                    start: *range.start(),
+                    end: *range.end(),
+                    target: navigation_target(target, is_url, self.active_tab().cwd.clone())?,
+                })
+            });
+        if next == self.hovered_hyperlink {
+            return;
         }
-        escaped
-    }
 
-    fn collect_find_match_stats(
-        term: &alacritty_terminal::term::Term<simple_term::terminal::EventProxy>,
-        query: &str,
-        active: Option<FindMatch>,
-    ) -> (usize, Option<usize>) {
-        if query.is_empty() {
-            return (0, None);
+        cx.emit(Event::NewNavigationTarget(
+            next.as_ref().map(|hyperlink| hyperlink.target.clone()),
+        ));
+        self.hovered_hyperlink = next;
+        cx.notify();
+    }
+
+    /// Hand a clicked link to the host app, or open it with the system handler.
+    fn open_navigation_target(&self, target: MaybeNavigationTarget, cx: &mut Context<Self>) {
+        cx.emit(Event::Open(target.clone()));
+        if let Some(on_open_requested) = self.on_open_requested.as_ref() {
+            on_open_requested(target);
+            return;
         }
 
-        let escaped = Self::regex_escape_literal(query);
-        let mut regex = match RegexSearch::new(&escaped) {
-            Ok(regex) => regex,
-            Err(err) => {
-                log::warn!("failed to build find regex for '{}': {err}", query);
-                return (0, None);
+        match target {
+            MaybeNavigationTarget::Url(url) => cx.open_url(&url),
+            MaybeNavigationTarget::PathLike(path) => {
+                if let Some(file_path) = resolve_path_target(&path) {
+                    let (_, line, column) = split_line_column_suffix(&path.maybe_path);
+                    cx.open_url(&file_open_url(
+                        self.settings.file_open_template.as_deref(),
+                        &file_path.to_string_lossy(),
+                        line,
+                        column,
+                    ));
+                }
             }
-        };
+        }
+    }
 
-        let mut origin = AlacPoint::new(term.topmost_line(), Column(0));
-        let mut count = 0usize;
-        let mut active_index = None;
-        let mut first_seen = None::<FindMatch>;
+    fn paste_from_clipboard(&mut self, cx: &mut Context<Self>) {
+        if let Some(item) = cx.read_from_clipboard() {
+            if let Some(text) = item.text() {
+                self.paste_text(text, cx);
+            }
+        }
+    }
 
-        loop {
-            let Some(found_range) =
-                term.search_next(&mut regex, origin, AlacDirection::Right, Side::Right, None)
-            else {
-                break;
-            };
+    /// Paste `text`, first asking for confirmation when `paste_guard` is on
+    /// and the text could run commands.
+    fn paste_text(&mut self, text: String, cx: &mut Context<Self>) {
+        let mode = *self.active_terminal().term.lock().mode();
+        if self.settings.paste_guard && paste_needs_confirmation(&text, mode) {
+            self.paste_confirmation = Some(text);
+            cx.notify();
+            return;
+        }
 
-            let found = FindMatch {
-                start: *found_range.start(),
-                end: *found_range.end(),
-            };
-            if first_seen.is_some() && first_seen == Some(found) {
-                break;
+        self.begin_terminal_input(cx);
+        self.paste_into_input_terminals(&text, cx);
+    }
+
+    fn confirm_paste(&mut self, cx: &mut Context<Self>) {
+        if let Some(text) = self.paste_confirmation.take() {
+            self.begin_terminal_input(cx);
+            self.paste_into_input_terminals(&text, cx);
+            cx.notify();
+        }
+    }
+
+    /// Paste `text` into every pane receiving input. The first chunk is
+    /// written right away and the rest on a timer, so a paste of megabytes
+    /// does not stall the UI.
+    fn paste_into_input_terminals(&mut self, text: &str, cx: &mut Context<Self>) {
+        let pastes = input_target_tabs(
+            self.broadcast_input,
+            self.tabs.len(),
+            self.active_tab_index(),
+        )
+        .map(|index| {
+            let tab = &self.tabs[index];
+            PendingPaste {
+                tab_id: tab.id,
+                pane_id: tab.focused_pane_id,
+                chunks: tab
+                    .focused_terminal()
+                    .paste_chunks(text, PASTE_CHUNK_BYTES)
+                    .into(),
             }
+        })
+        .collect::<Vec<_>>();
+        self.pending_pastes.extend(pastes);
+        if !self.write_pending_paste_chunks() {
+            return;
+        }
+
+        // Replacing the timer cancels one left running by an earlier paste.
+        self.paste_chunk_timer = Some(cx.spawn(
+            async move |this: WeakEntity<TerminalView>, cx: &mut gpui::AsyncApp| loop {
+                smol::Timer::after(PASTE_CHUNK_INTERVAL).await;
+                let pending = this
+                    .update(cx, |this, _cx| this.write_pending_paste_chunks())
+                    .unwrap_or(false);
+                if !pending {
+                    break;
+                }
+            },
+        ));
+    }
+
+    /// Write typed input to a pane, behind any paste still being written to it.
+    fn write_typed_input(&mut self, tab_id: u64, pane_id: u64, bytes: Vec<u8>) {
+        let Some(bytes) =
+            queue_behind_pending_paste(&mut self.pending_pastes, tab_id, pane_id, bytes)
+        else {
+            return;
+        };
+        if let Some(terminal) = self
+            .tabs
+            .iter()
+            .find(|tab| tab.id == tab_id)
+            .and_then(|tab| tab.pane_terminal(pane_id))
+        {
+            terminal.write(bytes);
+        }
+    }
 
-            if first_seen.is_none() {
-                first_seen = Some(found);
+    /// Write the next chunk of the oldest pending paste for each pane, so
+    /// pastes into the same pane never interleave. Pastes into closed panes
+    /// are dropped. Returns whether any chunks are left.
+    fn write_pending_paste_chunks(&mut self) -> bool {
+        let tabs = &self.tabs;
+        let mut written_panes = Vec::new();
+        self.pending_pastes.retain_mut(|paste| {
+            let target = (paste.tab_id, paste.pane_id);
+            if written_panes.contains(&target) {
+                return true;
+            }
+            written_panes.push(target);
+            let Some(terminal) = tabs
+                .iter()
+                .find(|tab| tab.id == paste.tab_id)
+                .and_then(|tab| tab.pane_terminal(paste.pane_id))
+            else {
+                return false;
+            };
+            if let Some(chunk) = paste.chunks.pop_front() {
+                terminal.write(chunk);
             }
+            !paste.chunks.is_empty()
+        });
+        !self.pending_pastes.is_empty()
+    }
+
+    fn handle_paste_confirmation_keybinding(
+        &mut self,
+        event: &KeyDownEvent,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.paste_confirmation.is_none() {
+            return false;
+        }
 
-            count += 1;
-            if active == Some(found) {
-                active_index = Some(count);
+        match text_entry_key(&event.keystroke) {
+            Some(TextEntryKey::Cancel) => {
+                self.paste_confirmation = None;
+                cx.notify();
             }
+            Some(TextEntryKey::Submit { .. }) => self.confirm_paste(cx),
+            _ => {}
+        }
+
+        true
+    }
+
+    fn clear_scrollback(&mut self) {
+        let mut term = self.active_terminal().term.lock();
+        term.selection = None;
+        term.grid_mut().clear_history();
+        drop(term);
+        self.selection_anchor = None;
+    }
+
+    /// Entries for the right-click menu as `(action, enabled)`, in display order.
+    fn context_menu_items(has_selection: bool) -> Vec<(ContextMenuAction, bool)> {
+        vec![
+            (ContextMenuAction::Copy, has_selection),
+            (ContextMenuAction::Paste, true),
+            (ContextMenuAction::SelectAll, true),
+            (ContextMenuAction::ClearScrollback, true),
+        ]
+    }
+
+    /// Entries for the tab right-click menu, one per tint preset.
+    fn tab_context_menu_items() -> Vec<(ContextMenuAction, bool)> {
+        (0..TAB_TINT_PRESETS.len())
+            .map(|index| (ContextMenuAction::TabTint(index), true))
+            .collect()
+    }
+
+    /// Keep the menu inside the viewport by flipping it left/up at the edges.
+    fn context_menu_origin(
+        position: gpui::Point<Pixels>,
+        menu_size: Size<Pixels>,
+        viewport: Size<Pixels>,
+    ) -> gpui::Point<Pixels> {
+        let x = if position.x + menu_size.width > viewport.width {
+            position.x - menu_size.width
+        } else {
+            position.x
+        };
+        let y = if position.y + menu_size.height > viewport.height {
+            position.y - menu_size.height
+        } else {
+            position.y
+        };
+        point(x.max(px(0.0)), y.max(px(0.0)))
+    }
 
-            let next_origin = found.end.add(term, Boundary::None, 1);
-            if next_origin == origin {
-                break;
+    fn run_context_menu_action(&mut self, action: ContextMenuAction, cx: &mut Context<Self>) {
+        self.context_menu_position = None;
+        let menu_tab_id = self.context_menu_tab_id.take();
+        match action {
+            ContextMenuAction::Copy => {
+                let _ = self.copy_selection_to_clipboard(cx);
+            }
+            ContextMenuAction::Paste => self.paste_from_clipboard(cx),
+            ContextMenuAction::SelectAll => {
+                let _ = self.select_all_terminal_content(cx);
+            }
+            ContextMenuAction::ClearScrollback => self.clear_scrollback(),
+            ContextMenuAction::TabTint(index) => {
+                let tint = TAB_TINT_PRESETS.get(index).and_then(|(_, tint)| *tint);
+                if let Some(tab) =
+                    menu_tab_id.and_then(|tab_id| self.tabs.iter_mut().find(|tab| tab.id == tab_id))
+                {
+                    tab.tint = tint;
+                }
             }
-            origin = next_origin;
         }
+        cx.notify();
+    }
+
+    fn select_all_terminal_content(&mut self, cx: &mut Context<Self>) -> bool {
+        let mut term = self.active_terminal().term.lock();
+        if term.columns() == 0 || term.screen_lines() == 0 {
+            return false;
+        }
+
+        let start = AlacPoint::new(term.topmost_line(), Column(0));
+        let end = AlacPoint::new(term.bottommost_line(), term.last_column());
+        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+        selection.update(end, Side::Right);
+        let range = selection.to_range(&*term);
+        term.selection = Some(selection);
+        drop(term);
+        self.selection_anchor = None;
+        self.report_selection(range, cx);
+
+        true
+    }
+
+    /// Let embedders know a selection was completed; the text is available
+    /// from `Terminal::current_selection_text`.
+    fn report_selection(&mut self, range: Option<SelectionRange>, cx: &mut Context<Self>) {
+        if selection_changed(&mut self.reported_selection, range) {
+            cx.emit(Event::SelectionsChanged);
+        }
+    }
+
+    fn normalize_find_query(selection: &str) -> Option<String> {
+        selection
+            .lines()
+            .next()
+            .map(str::trim)
+            .filter(|text| !text.is_empty())
+            .map(ToString::to_string)
+    }
 
-        (count, active_index)
+    fn regex_escape_literal(query: &str) -> String {
+        let mut escaped = String::with_capacity(query.len());
+        for ch in query.chars() {
+            match ch {
+                '\\' | '.' | '+' | '*' | '?' | '(' | ')' | '[' | ']' | '{' | '}' | '^' | '$'
+                | '|' => {
+                    escaped.push('\\');
+                    escaped.push(ch);
+                }
+                _ => escaped.push(ch),
+            }
+        }
+        escaped
     }
 
     fn start_find(&mut self, cx: &mut Context<Self>) {
@@ -1796,7 +3859,8 @@ impl TerminalView {
             let term = self.active_terminal().term.lock();
             term.selection_to_string()
                 .as_deref()
-                .and_then(Self::normalize_find_query)
+                .map(strip_grid_markers)
+                .and_then(|text| Self::normalize_find_query(&text))
         };
 
         if let Some(query) = selected_query {
@@ -1805,6 +3869,8 @@ impl TerminalView {
                 last_match: None,
                 match_count: 0,
                 active_match_index: None,
+                wrapped_until: None,
+                cache: None,
             });
         } else if self.find_state.is_none() {
             self.find_state = Some(FindState::default());
@@ -1827,228 +3893,578 @@ impl TerminalView {
         cx.notify();
     }
 
-    fn find_next_match(&mut self, direction: AlacDirection, cx: &mut Context<Self>) -> bool {
-        let query = match self
-            .find_state
-            .as_ref()
-            .map(|state| state.query.as_str())
-            .filter(|query| !query.is_empty())
-        {
-            Some(query) => query,
-            None => return false,
-        };
-
-        let escaped = Self::regex_escape_literal(query);
-        let mut regex = match RegexSearch::new(&escaped) {
-            Ok(regex) => regex,
-            Err(err) => {
-                log::warn!("failed to build find regex for '{}': {err}", query);
-                return false;
-            }
-        };
-
-        let previous_match = self.find_state.as_ref().and_then(|state| state.last_match);
+    /// Next match after `previous` in `direction`, wrapping past either end of
+    /// the buffer. Without a previous match the search starts at the top of
+    /// the viewport.
+    fn next_find_match<T: EventListener>(
+        term: &alacritty_terminal::term::Term<T>,
+        regex: &mut RegexSearch,
+        previous: Option<FindMatch>,
+        direction: AlacDirection,
+    ) -> Option<FindMatch> {
         let side = if matches!(direction, AlacDirection::Right) {
             Side::Right
         } else {
             Side::Left
         };
-
-        let mut term = self.active_terminal().term.lock();
-        if term.columns() == 0 || term.screen_lines() == 0 {
-            return false;
-        }
-
-        let origin = match previous_match {
+        let origin = match previous {
             Some(found) if matches!(direction, AlacDirection::Right) => {
-                found.end.add(&*term, Boundary::None, 1)
+                found.end.add(term, Boundary::None, 1)
             }
-            Some(found) => found.start.sub(&*term, Boundary::None, 1),
+            Some(found) => found.start.sub(term, Boundary::None, 1),
             None => {
                 let display_offset = term.grid().display_offset() as i32;
                 AlacPoint::new(Line(-display_offset), Column(0))
             }
         };
 
-        let Some(found) = term.search_next(&mut regex, origin, direction, side, None) else {
-            drop(term);
-            if let Some(state) = self.find_state.as_mut() {
-                state.last_match = None;
-                state.match_count = 0;
-                state.active_match_index = None;
-            }
+        term.search_next(regex, origin, direction, side, None)
+            .map(|found| FindMatch {
+                start: *found.start(),
+                end: *found.end(),
+            })
+    }
+
+    fn find_next_match(&mut self, direction: AlacDirection, cx: &mut Context<Self>) -> bool {
+        let term_handle = self.active_terminal().term.clone();
+        let mut term = term_handle.lock();
+        if term.columns() == 0 || term.screen_lines() == 0 {
+            return false;
+        }
+
+        let Some(state) = self
+            .find_state
+            .as_mut()
+            .filter(|state| !state.query.is_empty())
+        else {
+            return false;
+        };
+        if state
+            .cache
+            .as_ref()
+            .is_none_or(|cache| cache.query != state.query)
+        {
+            state.cache = FindCache::new(&state.query);
+        }
+        let previous_match = state.last_match;
+        let Some(cache) = state.cache.as_mut() else {
             return false;
         };
 
-        let start = *found.start();
-        let end = *found.end();
-        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
-        selection.update(end, Side::Right);
+        let Some(matched) =
+            Self::next_find_match(&term, &mut cache.regex, previous_match, direction)
+        else {
+            state.last_match = None;
+            state.match_count = 0;
+            state.active_match_index = None;
+            return false;
+        };
+        let (match_count, active_match_index) = cache.stats(&term, Some(matched));
+        let wrapped =
+            previous_match.is_some_and(|previous| find_wrapped(direction, previous, matched));
+        state.last_match = Some(matched);
+        state.match_count = match_count;
+        state.active_match_index = active_match_index;
+        if wrapped {
+            state.wrapped_until = Some(Instant::now() + FIND_WRAP_INDICATOR_DURATION);
+        }
+
+        let mut selection = Selection::new(SelectionType::Simple, matched.start, Side::Left);
+        selection.update(matched.end, Side::Right);
         term.selection = Some(selection);
 
-        let display_offset = term.grid().display_offset() as i32;
-        let screen_lines = term.screen_lines() as i32;
-        let line = start.line.0;
-        let matched = FindMatch { start, end };
-        let (match_count, active_match_index) =
-            Self::collect_find_match_stats(&term, query, Some(matched));
         drop(term);
 
-        if let Some(state) = self.find_state.as_mut() {
-            state.last_match = Some(matched);
-            state.match_count = match_count;
-            state.active_match_index = active_match_index;
+        if wrapped {
+            // One more frame once the hint expires to clear it.
+            cx.spawn(
+                async move |this: WeakEntity<TerminalView>, cx: &mut gpui::AsyncApp| {
+                    smol::Timer::after(FIND_WRAP_INDICATOR_DURATION).await;
+                    let _ = this.update(cx, |_this, cx| cx.notify());
+                },
+            )
+            .detach();
         }
         self.selection_anchor = None;
-
-        let target_offset = if line + display_offset < 0 {
-            (-line) as usize
-        } else if line + display_offset >= screen_lines {
-            (screen_lines - 1 - line).max(0) as usize
-        } else {
-            display_offset.max(0) as usize
-        };
-        let _ = self.set_display_offset(target_offset);
+        self.active_terminal()
+            .scroll_line_into_view(matched.start.line);
 
         cx.notify();
         true
     }
 
-    fn handle_find_keybinding(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
-        if self.find_state.is_none() {
-            return false;
+    /// Close the find panel, remembering its query for recall.
+    fn close_find(&mut self) {
+        if let Some(state) = self.find_state.take() {
+            self.find_history.push(&state.query);
         }
+    }
 
-        let modifiers = event.keystroke.modifiers;
-        let key = event.keystroke.key.as_str();
-        if key == "escape" && !modifiers.platform && !modifiers.control && !modifiers.alt {
-            self.find_state = None;
+    /// Replace the find query with one recalled from history and search for it.
+    fn set_find_query(&mut self, query: String, cx: &mut Context<Self>) {
+        let Some(state) = self.find_state.as_mut() else {
+            return;
+        };
+        state.query = query;
+        state.last_match = None;
+        state.active_match_index = None;
+        if state.query.is_empty() {
+            state.match_count = 0;
+        }
+        if !self.find_next_match(AlacDirection::Right, cx) {
             cx.notify();
-            return true;
         }
+    }
 
-        if key == "enter" && !modifiers.platform && !modifiers.control && !modifiers.alt {
-            let direction = if modifiers.shift {
-                AlacDirection::Left
-            } else {
-                AlacDirection::Right
-            };
-            let _ = self.find_next_match(direction, cx);
-            return true;
-        }
+    fn handle_find_keybinding(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        let Some(state) = self.find_state.as_ref() else {
+            return false;
+        };
 
-        if key == "backspace" && !modifiers.platform && !modifiers.control && !modifiers.alt {
-            let should_search = if let Some(state) = self.find_state.as_mut() {
-                if state.query.pop().is_some() {
-                    state.last_match = None;
-                    state.active_match_index = None;
-                    !state.query.is_empty()
-                } else {
-                    state.match_count = 0;
-                    state.active_match_index = None;
-                    false
-                }
-            } else {
-                false
+        let modifiers = event.keystroke.modifiers;
+        if !modifiers.modified() {
+            let recalled = match event.keystroke.key.as_str() {
+                "up" => Some(self.find_history.previous(&state.query)),
+                "down" => Some(self.find_history.next()),
+                _ => None,
             };
-
-            if should_search {
-                if !self.find_next_match(AlacDirection::Right, cx) {
-                    cx.notify();
+            if let Some(recalled) = recalled {
+                if let Some(query) = recalled {
+                    self.set_find_query(query, cx);
                 }
-            } else {
-                cx.notify();
-            }
-            return true;
-        }
-
-        if let Some(text) = text_to_insert(&event.keystroke) {
-            if let Some(state) = self.find_state.as_mut() {
-                state.query.push_str(&text);
-                state.last_match = None;
-                state.active_match_index = None;
-            }
-            if !self.find_next_match(AlacDirection::Right, cx) {
-                cx.notify();
+                return true;
             }
-            return true;
         }
 
-        false
-    }
-
-    fn handle_common_shortcut(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
-        let Some(action) = common_shortcut_action(&event.keystroke) else {
+        let Some(entry_key) = text_entry_key(&event.keystroke) else {
             return false;
         };
 
-        match action {
-            CommonShortcutAction::CopySelection => {
-                let _ = self.copy_selection_to_clipboard(cx);
+        match entry_key {
+            TextEntryKey::Cancel => {
+                self.close_find();
+                cx.notify();
+            }
+            TextEntryKey::Submit { shift } => {
+                if let Some(state) = self.find_state.as_ref() {
+                    self.find_history.push(&state.query);
+                }
+                let direction = if shift {
+                    AlacDirection::Left
+                } else {
+                    AlacDirection::Right
+                };
+                let _ = self.find_next_match(direction, cx);
             }
-            CommonShortcutAction::Paste => {
-                if let Some(item) = cx.read_from_clipboard() {
-                    if let Some(text) = item.text() {
-                        self.begin_terminal_input(cx);
-                        self.active_terminal().write_str(&text);
+            TextEntryKey::Backspace => {
+                self.find_history.reset_recall();
+                let should_search = if let Some(state) = self.find_state.as_mut() {
+                    if state.query.pop().is_some() {
+                        state.last_match = None;
+                        state.active_match_index = None;
+                        !state.query.is_empty()
+                    } else {
+                        state.match_count = 0;
+                        state.active_match_index = None;
+                        false
+                    }
+                } else {
+                    false
+                };
+
+                if should_search {
+                    if !self.find_next_match(AlacDirection::Right, cx) {
+                        cx.notify();
                     }
+                } else {
+                    cx.notify();
                 }
             }
-            CommonShortcutAction::SelectAll => {
-                if self.select_all_terminal_content() {
+            TextEntryKey::Insert(text) => {
+                self.find_history.reset_recall();
+                if let Some(state) = self.find_state.as_mut() {
+                    state.query.push_str(&text);
+                    state.last_match = None;
+                    state.active_match_index = None;
+                }
+                if !self.find_next_match(AlacDirection::Right, cx) {
                     cx.notify();
                 }
             }
-            CommonShortcutAction::Find => self.start_find(cx),
         }
 
         true
     }
 
-    fn handle_pin_keybinding(&mut self, event: &KeyDownEvent) -> bool {
-        if !Self::pin_hotkey_matches_keystroke(&self.settings.pin_hotkey, &event.keystroke) {
-            return false;
-        }
+    fn begin_tab_rename(&mut self, tab_id: u64, cx: &mut Context<Self>) {
+        let Some(tab) = self.tabs.iter().find(|tab| tab.id == tab_id) else {
+            return;
+        };
+        let draft = tab
+            .custom_title
+            .clone()
+            .unwrap_or_else(|| tab.title.clone());
+        self.tab_rename = Some(TabRenameState { tab_id, draft });
+        cx.notify();
+    }
 
-        self.request_toggle_pin();
-        true
+    fn commit_tab_rename(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(rename) = self.tab_rename.take() else {
+            return;
+        };
+        if let Some(tab) = self.tabs.iter_mut().find(|tab| tab.id == rename.tab_id) {
+            tab.custom_title = Self::custom_title_from_draft(&rename.draft);
+        }
+        if self.active_tab_id == rename.tab_id {
+            window.set_window_title(&self.active_window_title());
+        }
+        cx.notify();
     }
 
-    fn handle_tab_keybinding(
+    fn handle_tab_rename_keybinding(
         &mut self,
         event: &KeyDownEvent,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> bool {
-        let modifiers = event.keystroke.modifiers;
-        let key = event.keystroke.key.as_str();
-        if key == "tab" && modifiers.control {
-            let direction = if modifiers.shift { -1 } else { 1 };
-            self.set_active_tab_relative(direction, window, cx);
-            return true;
+        if self.tab_rename.is_none() {
+            return false;
         }
 
-        if !modifiers.platform {
+        match text_entry_key(&event.keystroke) {
+            Some(TextEntryKey::Cancel) => {
+                self.tab_rename = None;
+                cx.notify();
+            }
+            Some(TextEntryKey::Submit { .. }) => self.commit_tab_rename(window, cx),
+            Some(TextEntryKey::Backspace) => {
+                if let Some(rename) = self.tab_rename.as_mut() {
+                    rename.draft.pop();
+                }
+                cx.notify();
+            }
+            Some(TextEntryKey::Insert(text)) => {
+                if let Some(rename) = self.tab_rename.as_mut() {
+                    rename.draft.push_str(&text);
+                }
+                cx.notify();
+            }
+            // Leave platform shortcuts (tab switching, copy/paste) working while editing.
+            None => return !event.keystroke.modifiers.platform,
+        }
+
+        true
+    }
+
+    fn handle_common_shortcut(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        match self.keybindings.action_for(&event.keystroke) {
+            Some(KeyAction::Copy) => {
+                let _ = self.copy_selection_to_clipboard(cx);
+            }
+            Some(KeyAction::CopyLastOutput) => {
+                let _ = self.copy_last_command_output(cx);
+            }
+            Some(KeyAction::Paste) => self.paste_from_clipboard(cx),
+            Some(KeyAction::SelectAll) => {
+                if self.select_all_terminal_content(cx) {
+                    cx.notify();
+                }
+            }
+            Some(KeyAction::Find) => self.start_find(cx),
+            Some(KeyAction::CopyMode) => self.toggle_copy_mode(cx),
+            Some(KeyAction::SaveScrollback) => self.save_scrollback(cx),
+            Some(KeyAction::BroadcastInput) => {
+                self.broadcast_input = !self.broadcast_input;
+                cx.notify();
+            }
+            Some(KeyAction::Clear) => {
+                self.clear_scrollback();
+                cx.notify();
+            }
+            Some(
+                action @ (KeyAction::ScrollToTop
+                | KeyAction::ScrollToBottom
+                | KeyAction::ScrollPageUp
+                | KeyAction::ScrollPageDown),
+            ) => return self.scroll_by_keybinding(action, cx),
+            Some(action @ (KeyAction::PreviousPrompt | KeyAction::NextPrompt)) => {
+                return self.jump_to_prompt(action == KeyAction::PreviousPrompt, cx)
+            }
+            _ => return false,
+        }
+
+        true
+    }
+
+    /// Ask where to save the focused pane's scrollback and write it there.
+    fn save_scrollback(&mut self, cx: &mut Context<Self>) {
+        let tab = self.active_tab();
+        let (tab_id, pane_id) = (tab.id, tab.focused_pane_id);
+        let directory = tab.cwd.clone().or_else(dirs::home_dir).unwrap_or_default();
+        let path = cx.prompt_for_new_path(&directory, Some(SCROLLBACK_FILE_NAME));
+        cx.spawn(
+            async move |this: WeakEntity<TerminalView>, cx: &mut gpui::AsyncApp| {
+                let path = match path.await {
+                    Ok(Ok(Some(path))) => path,
+                    Ok(Err(err)) => {
+                        log::warn!("failed to ask where to save the scrollback: {err}");
+                        return;
+                    }
+                    _ => return,
+                };
+                let _ = this.update(cx, |this, _cx| {
+                    let Some(terminal) = this
+                        .tabs
+                        .iter()
+                        .find(|tab| tab.id == tab_id)
+                        .and_then(|tab| tab.pane_terminal(pane_id))
+                    else {
+                        return;
+                    };
+                    let result = File::create(&path)
+                        .and_then(|file| terminal.export_scrollback(BufWriter::new(file)));
+                    if let Err(err) = result {
+                        log::warn!("failed to save scrollback to {}: {err}", path.display());
+                    }
+                });
+            },
+        )
+        .detach();
+    }
+
+    /// The first Escape after selecting clears the selection; the next one
+    /// reaches the program.
+    fn handle_selection_escape(&mut self, event: &KeyDownEvent, cx: &mut Context<Self>) -> bool {
+        let has_selection = self
+            .active_terminal()
+            .term
+            .lock()
+            .selection
+            .as_ref()
+            .is_some_and(|selection| !selection.is_empty());
+        if !escape_clears_selection(&event.keystroke, has_selection) {
             return false;
         }
 
+        self.active_terminal().term.lock().selection = None;
+        self.selection_anchor = None;
+        self.report_selection(None, cx);
+        cx.notify();
+        true
+    }
+
+    /// Enter copy mode with its cursor on the terminal cursor, moved onto the
+    /// viewport when scrolled back, or leave it.
+    fn toggle_copy_mode(&mut self, cx: &mut Context<Self>) {
+        if self.copy_mode.take().is_none() {
+            let mut term = self.active_terminal().term.lock();
+            let display_offset = term.grid().display_offset() as i32;
+            let top = Line(-display_offset);
+            let bottom = Line(term.screen_lines() as i32 - 1 - display_offset);
+            let cursor = term.grid().cursor.point;
+            let start = AlacPoint::new(cursor.line.max(top).min(bottom), cursor.column);
+            let state = CopyModeState::new(start);
+            state.store_cursor_in(&mut term);
+            drop(term);
+            self.copy_mode = Some(state);
+            self.close_find();
+            self.selection_anchor = None;
+        }
+        cx.notify();
+    }
+
+    /// Keys in copy mode move its cursor and selection instead of reaching
+    /// the shell. Shortcuts with Cmd or Ctrl still go through.
+    fn handle_copy_mode_keybinding(
+        &mut self,
+        event: &KeyDownEvent,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(mut state) = self.copy_mode else {
+            return false;
+        };
+        let Some(key) = copy_mode_key(&event.keystroke) else {
+            let modifiers = event.keystroke.modifiers;
+            return !modifiers.platform && !modifiers.control;
+        };
+
         match key {
-            "t" => {
-                self.create_tab(window, cx);
-                true
+            CopyModeKey::Move(motion) => {
+                let mut term = self.active_terminal().term.lock();
+                state.cursor = move_copy_cursor(
+                    state.cursor,
+                    motion,
+                    Line(-(term.history_size() as i32)),
+                    Line(term.screen_lines() as i32 - 1),
+                    term.last_column(),
+                );
+                state.store_cursor_in(&mut term);
+                if state.anchor.is_some() {
+                    term.selection = state.selection();
+                }
+                drop(term);
+                self.copy_mode = Some(state);
+                self.active_terminal()
+                    .scroll_line_into_view(state.cursor.line);
+            }
+            CopyModeKey::ToggleSelection => {
+                state.toggle_selection();
+                self.active_terminal().term.lock().selection = state.selection();
+                self.copy_mode = Some(state);
+            }
+            CopyModeKey::Copy => {
+                self.copy_mode = None;
+                let _ = self.copy_selection_to_clipboard(cx);
+            }
+            CopyModeKey::Exit => {
+                self.copy_mode = None;
+                if state.anchor.is_some() {
+                    self.active_terminal().term.lock().selection = None;
+                }
+            }
+        }
+
+        cx.notify();
+        true
+    }
+
+    /// Move through scrollback for a scroll action. Returns `false`, leaving
+    /// the key to the program, when it handles the mouse or uses the
+    /// alternate screen.
+    fn scroll_by_keybinding(&mut self, action: KeyAction, cx: &mut Context<Self>) -> bool {
+        let (mode, display_offset, history_size, screen_lines) = {
+            let term = self.active_terminal().term.lock();
+            (
+                *term.mode(),
+                term.grid().display_offset(),
+                term.history_size(),
+                term.screen_lines(),
+            )
+        };
+        if mode.intersects(TermMode::MOUSE_MODE | TermMode::ALT_SCREEN) {
+            return false;
+        }
+
+        let changed = match action {
+            KeyAction::ScrollToTop => self.set_display_offset(history_size),
+            KeyAction::ScrollToBottom => self.scroll_to_bottom(),
+            KeyAction::ScrollPageUp => self.set_display_offset(page_scroll_offset(
+                display_offset,
+                history_size,
+                screen_lines,
+                true,
+            )),
+            KeyAction::ScrollPageDown => self.set_display_offset(page_scroll_offset(
+                display_offset,
+                history_size,
+                screen_lines,
+                false,
+            )),
+            _ => false,
+        };
+        if changed {
+            cx.notify();
+        }
+        true
+    }
+
+    /// Scroll the closest shell prompt above or below the viewport's top row
+    /// to the top. Returns `false` on the alternate screen, which has no
+    /// prompts.
+    fn jump_to_prompt(&mut self, up: bool, cx: &mut Context<Self>) -> bool {
+        let target = {
+            let term = self.active_terminal().term.lock();
+            if term.mode().contains(TermMode::ALT_SCREEN) {
+                return false;
+            }
+            let top = Line(-(term.grid().display_offset() as i32));
+            adjacent_prompt(&mark_lines(&term, MarkKind::Prompt), top, up)
+        };
+        if let Some(line) = target {
+            if self.set_display_offset(line.0.min(0).unsigned_abs() as usize) {
+                cx.notify();
+            }
+        }
+        true
+    }
+
+    fn handle_pin_keybinding(&mut self, event: &KeyDownEvent) -> bool {
+        if !Self::pin_hotkey_matches_keystroke(&self.settings.pin_hotkey, &event.keystroke) {
+            return false;
+        }
+
+        self.request_toggle_pin();
+        true
+    }
+
+    /// Zoom shortcuts work everywhere, including while overlays are open, so
+    /// they are checked before any key reaches the shell.
+    fn handle_font_size_keybinding(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        match self.keybindings.action_for(&event.keystroke) {
+            Some(KeyAction::IncreaseFontSize) => {
+                self.adjust_font_size(SETTINGS_FONT_SIZE_STEP, window, cx)
+            }
+            Some(KeyAction::DecreaseFontSize) => {
+                self.adjust_font_size(-SETTINGS_FONT_SIZE_STEP, window, cx)
+            }
+            Some(KeyAction::ResetFontSize) => self.set_font_size(reset_font_size(), window, cx),
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn handle_tab_keybinding(
+        &mut self,
+        event: &KeyDownEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        match self.keybindings.action_for(&event.keystroke) {
+            // A hidden tab bar keeps the window to a single tab.
+            Some(KeyAction::NewTab) => {
+                if self.settings.tab_bar_position != TabBarPosition::Hidden {
+                    self.create_tab(window, cx);
+                }
+                return true;
             }
-            "w" => {
-                self.close_tab(self.active_tab_id, window, cx);
-                true
+            Some(KeyAction::DuplicateTab) => {
+                if self.settings.tab_bar_position != TabBarPosition::Hidden {
+                    self.duplicate_active_tab(window, cx);
+                }
+                return true;
+            }
+            Some(KeyAction::CloseTab) => {
+                self.close_active_pane(window, cx);
+                return true;
             }
-            "[" => {
+            Some(KeyAction::SplitPane) => {
+                self.split_active_pane(window, cx);
+                return true;
+            }
+            Some(KeyAction::PreviousTab) => {
                 self.set_active_tab_relative(-1, window, cx);
-                true
+                return true;
             }
-            "]" => {
+            Some(KeyAction::NextTab) => {
                 self.set_active_tab_relative(1, window, cx);
-                true
+                return true;
             }
+            _ => {}
+        }
+
+        let modifiers = event.keystroke.modifiers;
+        let key = event.keystroke.key.as_str();
+        if !modifiers.platform {
+            return false;
+        }
+
+        match key {
             "1" | "2" | "3" | "4" | "5" | "6" | "7" | "8" | "9" => {
                 let index = key.parse::<usize>().ok().and_then(|n| n.checked_sub(1));
                 let Some(index) = index else {
@@ -2072,33 +4488,159 @@ impl TerminalView {
         dirty_rows: &[bool],
         text_system: &gpui::WindowTextSystem,
     ) -> RowCacheStats {
-        let mut stats = RowCacheStats::default();
+        refresh_row_cache(
+            &mut self.row_text_cache,
+            snapshot,
+            dirty_rows,
+            text_system,
+            &self.fonts,
+            self.font_size,
+            self.cell_size.width,
+        )
+    }
 
-        if self.row_text_cache.len() != snapshot.num_lines {
-            self.row_text_cache = vec![CachedRow::default(); snapshot.num_lines];
+    /// Shape the character a visible block cursor covers so it can be drawn
+    /// on top of the cursor.
+    fn shape_cursor_glyph(
+        &self,
+        snapshot: &TerminalSnapshot,
+        text_system: &gpui::WindowTextSystem,
+    ) -> Option<gpui::ShapedLine> {
+        if !snapshot.cursor_draw_visible || snapshot.cursor_shape != CursorShape::Block {
+            return None;
+        }
+        let cell = snapshot.cursor_cell()?;
+        if matches!(cell.c, ' ' | '\0') || cell.flags.contains(Flags::WIDE_CHAR_SPACER) {
+            return None;
         }
 
-        for (row_idx, row) in snapshot.rows.iter().enumerate() {
-            if !row_cache_rebuild_required(
-                dirty_rows.get(row_idx).copied().unwrap_or(true),
-                &self.row_text_cache[row_idx],
-            ) {
-                stats.record_hit();
-                continue;
-            }
+        let line_size = snapshot
+            .cursor_row
+            .and_then(|row| snapshot.line_sizes.get(row).copied())
+            .unwrap_or_default();
+        let font_size = if line_size.is_double_height() {
+            self.font_size * 2.0
+        } else {
+            self.font_size
+        };
+        let text = cell.c.to_string();
+        let color = cursor_text_rgb(self.settings.cursor_text_color, cell, &snapshot.colors);
+        Some(text_system.shape_line(
+            SharedString::from(text.clone()),
+            font_size,
+            &[TextRun {
+                len: text.len(),
+                font: if cell.flags.contains(Flags::BOLD) {
+                    self.fonts.bold.clone()
+                } else {
+                    self.fonts.regular.clone()
+                },
+                color: alac_rgb_to_hsla(color),
+                background_color: None,
+                underline: None,
+                strikethrough: None,
+            }],
+            Some(line_cell_width(self.cell_size.width, line_size)),
+        ))
+    }
 
-            stats.record_miss();
-            self.row_text_cache[row_idx] = build_cached_row(
-                row,
-                &snapshot.colors,
-                text_system,
-                &self.font,
-                self.font_size,
-                self.cell_size.width,
-            );
-        }
+    /// Divider strips sit in the column left of every pane that does not start at column 0.
+    fn pane_divider_grids(layout: &[(u64, PaneGrid)]) -> Vec<PaneGrid> {
+        layout
+            .iter()
+            .filter(|(_, pane_grid)| pane_grid.col_offset >= PANE_DIVIDER_COLS)
+            .map(|(_, pane_grid)| PaneGrid {
+                col_offset: pane_grid.col_offset - PANE_DIVIDER_COLS,
+                cols: PANE_DIVIDER_COLS,
+                ..*pane_grid
+            })
+            .collect()
+    }
+
+    /// Rows for the panes that do not have focus. Each keeps its own row
+    /// cache, so only the rows its terminal changed are shaped again.
+    fn unfocused_pane_frames(
+        &mut self,
+        layout: &[(u64, PaneGrid)],
+        text_system: &gpui::WindowTextSystem,
+    ) -> Vec<(PaneGrid, TerminalSnapshot, Arc<Vec<CachedRow>>)> {
+        let palette = theme_palette(&self.settings.theme, &self.theme_registry);
+        let dim = self.color_dim();
+        let mut caches = std::mem::take(&mut self.pane_row_caches);
+        let tab = self.active_tab();
+        let frames = layout
+            .iter()
+            .filter(|(pane_id, _)| *pane_id != tab.focused_pane_id)
+            .filter_map(|(pane_id, pane_grid)| {
+                let terminal = tab.pane_terminal(*pane_id)?;
+                let cache = caches.entry(*pane_id).or_default();
+                let (mut snapshot, _) = take_snapshot(
+                    terminal,
+                    palette,
+                    &self.settings,
+                    dim,
+                    cache.previous_frame.as_ref(),
+                    &mut cache.snapshot_buffer,
+                );
+                snapshot.cursor_shape = CursorShape::HollowBlock;
+                snapshot.cursor_draw_visible = snapshot.show_cursor;
+                let previous_view = cache
+                    .previous_frame
+                    .as_ref()
+                    .map(PreviousFrameView::from_frame);
+                shift_row_cache_for_display_offset(
+                    Arc::make_mut(&mut cache.rows).as_mut_slice(),
+                    previous_view,
+                    &snapshot,
+                );
+                let dirty_rows =
+                    dirty_rows_for_snapshot(&snapshot, cache.previous_frame.as_ref(), None);
+                refresh_row_cache(
+                    &mut cache.rows,
+                    &snapshot,
+                    &dirty_rows,
+                    text_system,
+                    &self.fonts,
+                    self.font_size,
+                    self.cell_size.width,
+                );
+                cache.previous_frame = Some(FrameCache::from_snapshot(&snapshot));
+                Some((*pane_grid, snapshot, cache.rows.clone()))
+            })
+            .collect();
+        // Panes that closed, gained focus or belong to another tab start over.
+        caches.retain(|pane_id, _| {
+            *pane_id != tab.focused_pane_id && tab.pane_terminal(*pane_id).is_some()
+        });
+        self.pane_row_caches = caches;
+        frames
+    }
 
-        stats
+    /// Attach GPU frames to the images in `snapshots`. Frames of images that
+    /// left the screen are released so the sprite atlas does not grow.
+    fn prepare_snapshot_images<'a>(
+        &mut self,
+        snapshots: impl IntoIterator<Item = &'a mut TerminalSnapshot>,
+        window: &mut Window,
+    ) {
+        let mut previous = std::mem::take(&mut self.rendered_images);
+        for snapshot in snapshots {
+            for placed in snapshot.images.iter_mut() {
+                let rendered = match self.rendered_images.get(&placed.id) {
+                    Some(rendered) => Some(rendered.clone()),
+                    None => previous
+                        .remove(&placed.id)
+                        .or_else(|| render_image_frame(&placed.image)),
+                };
+                if let Some(rendered) = rendered {
+                    self.rendered_images.insert(placed.id, rendered.clone());
+                    placed.rendered = Some(rendered);
+                }
+            }
+        }
+        for (_, stale) in previous {
+            let _ = window.drop_image(stale);
+        }
     }
 }
 
@@ -2122,6 +4664,148 @@ fn is_monospace_font(text_system: &gpui::WindowTextSystem, font: &Font, font_siz
     (m - i).abs() <= tolerance && (m - w).abs() <= tolerance
 }
 
+/// Line printed in a pane kept open after its shell exited.
+fn exit_notice(exit_code: i32) -> String {
+    format!("[Process exited with code {exit_code} — press Enter to restart]")
+}
+
+/// Whether output should pull a viewport scrolled `display_offset` lines into
+/// history back to the bottom. A scroll within the review window means the
+/// user is reading and stays put.
+fn should_scroll_on_output(
+    enabled: bool,
+    display_offset: usize,
+    last_user_scroll_at: Option<Instant>,
+    now: Instant,
+) -> bool {
+    enabled
+        && display_offset != 0
+        && last_user_scroll_at.is_none_or(|scrolled_at| {
+            now.saturating_duration_since(scrolled_at) >= SCROLL_ON_OUTPUT_REVIEW_WINDOW
+        })
+}
+
+/// Indices of the tabs that receive input: all of them while broadcasting.
+fn input_target_tabs(
+    broadcast: bool,
+    tab_count: usize,
+    active_tab: usize,
+) -> std::ops::Range<usize> {
+    if broadcast {
+        0..tab_count
+    } else {
+        active_tab..active_tab + 1
+    }
+}
+
+fn clamp_font_size(size: f32) -> f32 {
+    size.clamp(SETTINGS_MIN_FONT_SIZE, SETTINGS_MAX_FONT_SIZE)
+}
+
+/// Size Cmd+0 restores: the built-in default, not whatever was last zoomed to.
+fn reset_font_size() -> f32 {
+    clamp_font_size(TerminalSettings::default().font_size)
+}
+
+/// Whether moving from `previous` to `found` passed the end of the buffer.
+/// A lone match wraps onto itself.
+fn find_wrapped(direction: AlacDirection, previous: FindMatch, found: FindMatch) -> bool {
+    match direction {
+        AlacDirection::Right => found.start <= previous.start,
+        AlacDirection::Left => found.start >= previous.start,
+    }
+}
+
+/// Classify a link found in the grid. `file://` URLs have already been turned
+/// into paths by `find_from_grid_point`. A path that does not resolve from
+/// `terminal_dir` is not a link.
+fn navigation_target(
+    target: String,
+    is_url: bool,
+    terminal_dir: Option<PathBuf>,
+) -> Option<MaybeNavigationTarget> {
+    if is_url {
+        return Some(MaybeNavigationTarget::Url(target));
+    }
+    let path = PathLikeTarget {
+        maybe_path: target,
+        terminal_dir,
+    };
+    resolve_path_target(&path)?;
+    Some(MaybeNavigationTarget::PathLike(path))
+}
+
+/// First lines of a paste awaiting confirmation, shortened for the preview,
+/// and how many lines it has.
+fn paste_preview(text: &str) -> (Vec<String>, usize) {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut preview: Vec<String> = lines
+        .iter()
+        .take(PASTE_PREVIEW_MAX_LINES)
+        .map(|line| {
+            let mut shown: String = line.chars().take(PASTE_PREVIEW_MAX_COLUMNS).collect();
+            if line.chars().count() > PASTE_PREVIEW_MAX_COLUMNS {
+                shown.push('…');
+            }
+            shown
+        })
+        .collect();
+    if lines.len() > PASTE_PREVIEW_MAX_LINES {
+        preview.push(format!("… {} more", lines.len() - PASTE_PREVIEW_MAX_LINES));
+    }
+    (preview, lines.len())
+}
+
+/// Closing needs confirmation when the foreground program is known and is not
+/// the shell, e.g. `vim` or a build running in `zsh`.
+fn should_confirm_close(foreground: Option<&str>, shell_name: Option<&str>) -> bool {
+    match (foreground, shell_name) {
+        (Some(foreground), Some(shell_name)) => foreground != shell_name,
+        _ => false,
+    }
+}
+
+/// Text after a tab's number: the user's custom title, else a title the shell
+/// set, else the foreground process, else the title the tab opened with.
+fn tab_title_text<'a>(
+    custom_title: Option<&'a str>,
+    title: &'a str,
+    title_from_shell: bool,
+    process_name: Option<&'a str>,
+) -> &'a str {
+    match (custom_title, process_name) {
+        (Some(custom_title), _) => custom_title,
+        (None, _) if title_from_shell => title,
+        (None, Some(process_name)) => process_name,
+        (None, None) => title,
+    }
+}
+
+/// Tab tooltip: the title, followed by the last command's exit status when
+/// the shell reports one.
+fn tab_tooltip_text(title: &str, last_exit_status: Option<i32>) -> String {
+    match last_exit_status {
+        Some(status) => format!("{title}\nLast command exited with status {status}"),
+        None => title.to_string(),
+    }
+}
+
+/// Display offset one page up or down from `display_offset`. A page keeps
+/// one line of overlap and stays within the scrollback.
+fn page_scroll_offset(
+    display_offset: usize,
+    history_size: usize,
+    screen_lines: usize,
+    up: bool,
+) -> usize {
+    let page = screen_lines.saturating_sub(1).max(1);
+    if up {
+        display_offset.saturating_add(page).min(history_size)
+    } else {
+        display_offset.saturating_sub(page)
+    }
+}
+
 fn cursor_should_blink(blinking: Blinking, terminal_blinking: bool) -> bool {
     match blinking {
         Blinking::Off => false,
@@ -2130,10 +4814,75 @@ fn cursor_should_blink(blinking: Blinking, terminal_blinking: bool) -> bool {
     }
 }
 
+/// Cursor shape and blinking for the window's activation state. Inactive
+/// windows draw a steady hollow block; a hidden cursor stays hidden.
+fn cursor_style_for_focus(
+    window_is_active: bool,
+    shape: CursorShape,
+    should_blink: bool,
+) -> (CursorShape, bool) {
+    if window_is_active || shape == CursorShape::Hidden {
+        (shape, should_blink)
+    } else {
+        (CursorShape::HollowBlock, false)
+    }
+}
+
+/// End of the still period after input, or `None` when the window is zero.
+fn cursor_blink_suppressed_until(now: Instant, window: Duration) -> Option<Instant> {
+    (!window.is_zero()).then(|| now + window)
+}
+
+/// How far colors fade toward the background: `unfocused_dim` while the
+/// window is inactive, otherwise none.
+fn unfocused_color_dim(window_is_active: bool, unfocused_dim: f32) -> f32 {
+    if window_is_active {
+        0.0
+    } else {
+        unfocused_dim
+    }
+}
+
 fn cursor_blink_is_suppressed(suppress_until: Option<Instant>, now: Instant) -> bool {
     matches!(suppress_until, Some(until) if now < until)
 }
 
+fn bell_flash_deadline(now: Instant) -> Instant {
+    now + BELL_FLASH_DURATION
+}
+
+fn bell_flash_active(flash_until: Option<Instant>, now: Instant) -> bool {
+    matches!(flash_until, Some(until) if now < until)
+}
+
+/// Scrollbar opacity for a viewport that last scrolled at `scrolled_at`: fully
+/// visible for `SCROLLBAR_FADE_DELAY`, then fading linearly to hidden.
+fn scrollbar_opacity(scrolled_at: Option<Instant>, now: Instant) -> f32 {
+    let Some(scrolled_at) = scrolled_at else {
+        return 0.0;
+    };
+    let fading_for = now
+        .saturating_duration_since(scrolled_at)
+        .saturating_sub(SCROLLBAR_FADE_DELAY);
+    (1.0 - fading_for.as_secs_f32() / SCROLLBAR_FADE_DURATION.as_secs_f32()).clamp(0.0, 1.0)
+}
+
+#[cfg(target_os = "macos")]
+fn play_system_beep() {
+    crate::macos::play_system_beep();
+}
+
+/// Without a system sound API, ring the bell of the terminal the app was
+/// started from, if any.
+#[cfg(not(target_os = "macos"))]
+fn play_system_beep() {
+    use std::io::Write;
+
+    let mut stderr = std::io::stderr();
+    let _ = stderr.write_all(b"\x07");
+    let _ = stderr.flush();
+}
+
 fn beam_cursor_width(cell_width: Pixels) -> Pixels {
     px((f32::from(cell_width) * 0.14).clamp(1.0, 2.0))
 }
@@ -2148,121 +4897,609 @@ fn hollow_cursor_thickness(cell_size: Size<Pixels>) -> Pixels {
     px((f32::from(cell_size.width) * 0.1).clamp(1.0, max_thickness))
 }
 
-impl Focusable for TerminalView {
-    fn focus_handle(&self, _cx: &App) -> FocusHandle {
-        self.focus_handle.clone()
-    }
+/// Cursor color from the settings, falling back to the theme's cursor.
+fn cursor_color(settings: &TerminalSettings, palette: ThemePalette) -> u32 {
+    settings.cursor_color.unwrap_or(palette.cursor)
 }
 
-/// Snapshot of a single cell.
-#[derive(Clone, PartialEq, Eq)]
-struct CellSnapshot {
-    c: char,
-    fg: AlacColor,
-    bg: AlacColor,
-    flags: Flags,
+/// Gap between the terminal surface edges and the grid.
+fn content_padding(settings: &TerminalSettings) -> Size<Pixels> {
+    size(px(settings.padding_x), px(settings.padding_y))
+}
+
+/// Tab bar position used for layout: hidden while `hide_tab_bar_with_single_tab`
+/// applies to a lone tab.
+fn tab_bar_layout_position(settings: &TerminalSettings, tab_count: usize) -> TabBarPosition {
+    if settings.hide_tab_bar_with_single_tab && tab_count == 1 {
+        TabBarPosition::Hidden
+    } else {
+        settings.tab_bar_position
+    }
+}
+
+/// Height of the tab bar, or zero when it is hidden.
+fn tab_bar_height(position: TabBarPosition) -> Pixels {
+    match position {
+        TabBarPosition::Top | TabBarPosition::Bottom => px(TAB_BAR_HEIGHT_PX),
+        TabBarPosition::Hidden => px(0.0),
+    }
+}
+
+/// Space the tab bar takes above the terminal grid.
+fn tab_bar_top_inset(position: TabBarPosition) -> Pixels {
+    match position {
+        TabBarPosition::Top => px(TAB_BAR_HEIGHT_PX),
+        TabBarPosition::Bottom | TabBarPosition::Hidden => px(0.0),
+    }
+}
+
+/// Window-space bounds of a pane's grid.
+fn pane_terminal_bounds(
+    pane_grid: &PaneGrid,
+    cell_size: Size<Pixels>,
+    padding: Size<Pixels>,
+    tab_bar_position: TabBarPosition,
+) -> TerminalBounds {
+    TerminalBounds::new(
+        cell_size.height,
+        cell_size.width,
+        Bounds {
+            origin: point(
+                padding.width + cell_size.width * pane_grid.col_offset as f32,
+                tab_bar_top_inset(tab_bar_position)
+                    + padding.height
+                    + cell_size.height * pane_grid.line_offset as f32,
+            ),
+            size: size(
+                cell_size.width * pane_grid.cols as f32,
+                cell_size.height * pane_grid.lines as f32,
+            ),
+        },
+    )
+}
+
+/// Top of the text line and its height for a row starting at `row_y`.
+/// Double-height rows draw the full glyph and show the half belonging to
+/// the row.
+fn text_line_metrics(row_y: Pixels, line_size: LineSize, cell_height: Pixels) -> (Pixels, Pixels) {
+    match line_size {
+        LineSize::DoubleHeightTop => (row_y, cell_height * 2.0),
+        LineSize::DoubleHeightBottom => (row_y - cell_height, cell_height * 2.0),
+        _ => (row_y, cell_height),
+    }
+}
+
+/// Width of one cell on a row of the given line size.
+fn line_cell_width(cell_width: Pixels, line_size: LineSize) -> Pixels {
+    if line_size.is_double_width() {
+        cell_width * 2.0
+    } else {
+        cell_width
+    }
+}
+
+/// Column and side under `x` (relative to the pane's left edge) on a
+/// double-width row, where only the first half of the columns is visible.
+fn double_width_column(x: Pixels, cell_width: Pixels, num_cols: usize) -> (usize, Side) {
+    let double_width = cell_width * 2.0;
+    let x = x.max(px(0.0));
+    let last_col = num_cols.div_ceil(2).saturating_sub(1);
+    let col = (x / double_width) as usize;
+    if col > last_col {
+        return (last_col, Side::Right);
+    }
+    let side = if x % double_width > cell_width {
+        Side::Right
+    } else {
+        Side::Left
+    };
+    (col, side)
+}
+
+/// Paint one pane's cached rows, cursor and scrollbar, clipped to `bounds`.
+fn paint_terminal_pane(
+    bounds: Bounds<Pixels>,
+    snapshot: &TerminalSnapshot,
+    rows: &[CachedRow],
+    cell_size: Size<Pixels>,
+    palette: ThemePalette,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let content_bounds = Bounds {
+        origin: bounds.origin,
+        size: size(
+            cell_size.width * snapshot.num_cols as f32,
+            cell_size.height * snapshot.num_lines as f32,
+        ),
+    };
+    let scrollbar = scrollbar_layout(
+        content_bounds,
+        snapshot.num_lines,
+        snapshot.history_size,
+        snapshot.display_offset,
+    );
+
+    window.with_content_mask(Some(ContentMask { bounds }), |window| {
+        window.paint_quad(fill(content_bounds, rgb(palette.terminal_bg)));
+        let line_size = |row_idx: usize| {
+            snapshot
+                .line_sizes
+                .get(row_idx)
+                .copied()
+                .unwrap_or_default()
+        };
+        for (row_idx, cached_row) in rows.iter().enumerate() {
+            let row_cell_width = line_cell_width(cell_size.width, line_size(row_idx));
+            for span in cached_row.background_spans.iter() {
+                let span_bounds = Bounds {
+                    origin: point(
+                        bounds.origin.x + row_cell_width * span.start_col as f32,
+                        bounds.origin.y + cell_size.height * row_idx as f32,
+                    ),
+                    size: size(row_cell_width * span.len as f32, cell_size.height),
+                };
+                window.paint_quad(fill(span_bounds, span.color));
+            }
+        }
+
+        for (row_idx, cached_row) in rows.iter().enumerate() {
+            let line_size = line_size(row_idx);
+            let row_cell_width = line_cell_width(cell_size.width, line_size);
+            let row_y = bounds.origin.y + cell_size.height * row_idx as f32;
+            let (text_y, line_height) = text_line_metrics(row_y, line_size, cell_size.height);
+            let row_mask = ContentMask {
+                bounds: Bounds {
+                    origin: point(bounds.origin.x, row_y),
+                    size: size(content_bounds.size.width, cell_size.height),
+                },
+            };
+            window.with_content_mask(Some(row_mask), |window| {
+                for run in cached_row.text_runs.iter() {
+                    let origin = point(
+                        bounds.origin.x + row_cell_width * run.start_col as f32,
+                        text_y,
+                    );
+                    let _ = run.shaped.paint(origin, line_height, window, cx);
+                }
+            });
+        }
+
+        for placed in snapshot.images.iter() {
+            if let Some(rendered) = placed.rendered.clone() {
+                let image_bounds = image_paint_bounds(bounds.origin, placed, cell_size);
+                let _ = window.paint_image(image_bounds, Corners::default(), rendered, 0, false);
+            }
+        }
+
+        if snapshot.cursor_draw_visible && snapshot.cursor_col < snapshot.num_cols {
+            if let Some(cursor_row) = snapshot.cursor_row {
+                let cursor_cell_width = line_cell_width(cell_size.width, line_size(cursor_row));
+                let cell_bounds = Bounds {
+                    origin: point(
+                        bounds.origin.x + cursor_cell_width * snapshot.cursor_col as f32,
+                        bounds.origin.y + cell_size.height * cursor_row as f32,
+                    ),
+                    size: size(cursor_cell_width, cell_size.height),
+                };
+                match snapshot.cursor_shape {
+                    CursorShape::Beam => {
+                        let width = beam_cursor_width(cell_size.width);
+                        let cursor_bounds = Bounds {
+                            origin: cell_bounds.origin,
+                            size: size(width, cell_size.height),
+                        };
+                        window.paint_quad(fill(cursor_bounds, rgb(palette.cursor)));
+                    }
+                    CursorShape::Underline => {
+                        let height = underline_cursor_height(cell_size.height);
+                        let cursor_bounds = Bounds {
+                            origin: point(
+                                cell_bounds.origin.x,
+                                cell_bounds.origin.y + cell_size.height - height,
+                            ),
+                            size: size(cell_bounds.size.width, height),
+                        };
+                        window.paint_quad(fill(cursor_bounds, rgb(palette.cursor)));
+                    }
+                    CursorShape::HollowBlock => {
+                        let stroke = hollow_cursor_thickness(cell_size);
+                        let color = rgb(palette.cursor);
+                        let top = Bounds {
+                            origin: cell_bounds.origin,
+                            size: size(cell_bounds.size.width, stroke),
+                        };
+                        let bottom = Bounds {
+                            origin: point(
+                                cell_bounds.origin.x,
+                                cell_bounds.origin.y + cell_bounds.size.height - stroke,
+                            ),
+                            size: size(cell_bounds.size.width, stroke),
+                        };
+                        let left = Bounds {
+                            origin: cell_bounds.origin,
+                            size: size(stroke, cell_bounds.size.height),
+                        };
+                        let right = Bounds {
+                            origin: point(
+                                cell_bounds.origin.x + cell_bounds.size.width - stroke,
+                                cell_bounds.origin.y,
+                            ),
+                            size: size(stroke, cell_bounds.size.height),
+                        };
+                        window.paint_quad(fill(top, color));
+                        window.paint_quad(fill(bottom, color));
+                        window.paint_quad(fill(left, color));
+                        window.paint_quad(fill(right, color));
+                    }
+                    _ => {
+                        window.paint_quad(fill(cell_bounds, rgb(palette.cursor)));
+                        if let Some(glyph) = &snapshot.cursor_glyph {
+                            let (text_y, line_height) = text_line_metrics(
+                                cell_bounds.origin.y,
+                                line_size(cursor_row),
+                                cell_size.height,
+                            );
+                            let mask = ContentMask {
+                                bounds: cell_bounds,
+                            };
+                            window.with_content_mask(Some(mask), |window| {
+                                let origin = point(cell_bounds.origin.x, text_y);
+                                let _ = glyph.paint(origin, line_height, window, cx);
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        if let (Some(layout), Some(thumb_color)) = (&scrollbar, snapshot.scrollbar_thumb) {
+            window.paint_quad(fill(layout.thumb, thumb_color));
+        }
+    });
+}
+
+impl EventEmitter<Event> for TerminalView {}
+
+impl Focusable for TerminalView {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
 }
 
 /// Snapshot of the terminal state taken while holding the lock.
+#[derive(Clone)]
 struct TerminalSnapshot {
-    rows: Vec<Vec<CellSnapshot>>,
+    /// Viewport rows. Rows alacritty did not damage share their cells with the
+    /// previous frame.
+    rows: Vec<Arc<Vec<GridCell>>>,
+    /// DEC line size of each viewport row, read from the row's markers.
+    line_sizes: Vec<LineSize>,
     num_cols: usize,
     num_lines: usize,
     history_size: usize,
     display_offset: usize,
+    selection: Option<SelectionRange>,
+    /// Viewport rows of the program's scroll region, when it does not cover
+    /// the whole screen.
+    scroll_region: Option<Range<usize>>,
     cursor_row: Option<usize>,
     cursor_col: usize,
+    /// Style last set by the program with DECSCUSR (`CSI Ps SP q`), or the
+    /// configured default.
     cursor_shape: CursorShape,
+    /// Whether that style blinks; only used with `Blinking::TerminalControlled`.
     cursor_blinking: bool,
     show_cursor: bool,
     cursor_draw_visible: bool,
     colors: ColorsSnapshot,
+    images: Vec<SnapshotImage>,
+    /// Glyph under a block cursor, shaped in the cursor text color by the
+    /// view before painting.
+    cursor_glyph: Option<gpui::ShapedLine>,
+    /// Scrollbar thumb color chosen by the view; `None` hides the scrollbar.
+    scrollbar_thumb: Option<Hsla>,
+}
+
+impl TerminalSnapshot {
+    /// Cell under the cursor, when the cursor is in the viewport.
+    fn cursor_cell(&self) -> Option<&GridCell> {
+        self.rows.get(self.cursor_row?)?.get(self.cursor_col)
+    }
+}
+
+/// An inline image anchored at a viewport cell. `row` is negative when the
+/// anchor has scrolled above the viewport but the image still reaches into it.
+#[derive(Clone)]
+struct SnapshotImage {
+    id: u64,
+    row: isize,
+    col: usize,
+    image: Arc<TerminalImage>,
+    /// GPU frame, attached by the view before painting.
+    rendered: Option<Arc<RenderImage>>,
+}
+
+/// Lines above the viewport searched for anchors of images reaching into it.
+const IMAGE_ANCHOR_LOOKBEHIND_LINES: usize = 128;
+
+/// Convert a decoded image to the BGRA frame GPUI paints.
+fn render_image_frame(image: &TerminalImage) -> Option<Arc<RenderImage>> {
+    let mut bgra = image.rgba.clone();
+    for pixel in bgra.chunks_exact_mut(4) {
+        pixel.swap(0, 2);
+    }
+    let buffer = image::RgbaImage::from_raw(image.width, image.height, bgra)?;
+    Some(Arc::new(RenderImage::new(vec![image::Frame::new(buffer)])))
+}
+
+/// Bounds of an anchored image, scaled from the cell size it was drawn for to
+/// the current one so it keeps covering the same cells after a font change.
+fn image_paint_bounds(
+    origin: gpui::Point<Pixels>,
+    placed: &SnapshotImage,
+    cell_size: Size<Pixels>,
+) -> Bounds<Pixels> {
+    let image = &placed.image;
+    let cols = image.display_width as f32 / f32::from(image.cell_width.max(1));
+    let lines = image.display_height as f32 / f32::from(image.cell_height.max(1));
+    Bounds {
+        origin: point(
+            origin.x + cell_size.width * placed.col as f32,
+            origin.y + cell_size.height * placed.row as f32,
+        ),
+        size: size(cell_size.width * cols, cell_size.height * lines),
+    }
 }
 
-fn take_snapshot(terminal: &Terminal, theme: TerminalTheme) -> (TerminalSnapshot, SnapshotTiming) {
+fn take_snapshot(
+    terminal: &Terminal,
+    palette: ThemePalette,
+    settings: &TerminalSettings,
+    dim: f32,
+    previous: Option<&FrameCache>,
+    buffer: &mut SnapshotBuffer,
+) -> (TerminalSnapshot, SnapshotTiming) {
     let total_start = Instant::now();
-    let term = terminal.term.lock();
+    let mut term = terminal.term.lock();
     let lock_acquired_at = Instant::now();
-    let content = term.renderable_content();
-    let colors = ColorsSnapshot::from_colors(content.colors, theme);
-    let selection_tint = selection_tint_rgb(theme);
-    let cursor = content.cursor;
-    let selection = content.selection;
+    let track_images = !terminal.images.is_empty();
+    let (mut snapshot, image_anchors) = snapshot_term(
+        &mut term,
+        palette,
+        settings,
+        dim,
+        previous,
+        track_images,
+        buffer,
+    );
+    let lock_hold = lock_acquired_at.elapsed();
+    drop(term);
+
+    fill_snapshot_rows(&mut snapshot, buffer, previous);
+    snapshot.scroll_region = terminal.scroll_region(snapshot.num_lines);
+    snapshot.images = image_anchors
+        .into_iter()
+        .filter_map(|(id, row, col)| {
+            let image = terminal.images.get(id)?;
+            (row + image.lines() as isize > 0).then_some(SnapshotImage {
+                id,
+                row,
+                col,
+                image,
+                rendered: None,
+            })
+        })
+        .collect();
+
+    (
+        snapshot,
+        SnapshotTiming {
+            total: total_start.elapsed(),
+            lock_hold,
+        },
+    )
+}
+
+/// Grid cells copied while the terminal lock is held, kept across frames so
+/// the copy only allocates when the grid size changes.
+#[derive(Default)]
+struct SnapshotBuffer {
+    /// Row-major cells, `num_cols` per row.
+    cells: Vec<GridCell>,
+    line_sizes: Vec<LineSize>,
+    /// Rows copied this frame. The others are unchanged since the previous
+    /// frame.
+    copied: Vec<bool>,
+    num_cols: usize,
+}
+
+impl SnapshotBuffer {
+    fn resize(&mut self, num_cols: usize, num_lines: usize) {
+        self.num_cols = num_cols;
+        self.cells.resize(num_cols * num_lines, GridCell::default());
+        self.line_sizes.resize(num_lines, LineSize::Single);
+        self.copied.resize(num_lines, true);
+    }
+
+    fn row(&self, row: usize) -> &[GridCell] {
+        &self.cells[row * self.num_cols..(row + 1) * self.num_cols]
+    }
+}
+
+/// Mark the viewport rows alacritty reported as damaged since the last call.
+/// Returns `true` when the whole screen is damaged, leaving `damaged` as is.
+/// The damage is reset either way.
+fn take_damaged_rows<T: EventListener>(term: &mut Term<T>, damaged: &mut [bool]) -> bool {
+    let full = match term.damage() {
+        TermDamage::Full => true,
+        TermDamage::Partial(lines) => {
+            damaged.fill(false);
+            for bounds in lines {
+                mark_row_dirty(damaged, Some(bounds.line));
+            }
+            false
+        }
+    };
+    term.reset_damage();
+    full
+}
+
+/// Snapshot the viewport of `term` along with the image anchors it contains.
+///
+/// Cells are copied into `buffer`; the returned snapshot has no rows until
+/// `fill_snapshot_rows` runs after the lock is released. Rows alacritty did
+/// not damage are left out of the copy when `previous` shows the same
+/// viewport with the same colors and selection, since neither of those is
+/// part of alacritty's damage.
+fn snapshot_term<T: EventListener>(
+    term: &mut Term<T>,
+    palette: ThemePalette,
+    settings: &TerminalSettings,
+    dim: f32,
+    previous: Option<&FrameCache>,
+    track_images: bool,
+    buffer: &mut SnapshotBuffer,
+) -> (TerminalSnapshot, Vec<(u64, isize, usize)>) {
     let num_cols = term.columns();
     let num_lines = term.screen_lines();
+    buffer.resize(num_cols, num_lines);
+    let fully_damaged = take_damaged_rows(term, &mut buffer.copied);
+    let content = term.renderable_content();
+    let colors = ColorsSnapshot::from_colors(content.colors, palette, settings, dim);
+    let selection_tint = selection_tint_rgb(palette);
+    let selection = content.selection;
     let history_size = term.history_size();
     let display_offset = term.grid().display_offset();
 
-    let default_cell = CellSnapshot {
-        c: ' ',
-        fg: AlacColor::Named(NamedColor::Foreground),
-        bg: AlacColor::Named(NamedColor::Background),
-        flags: Flags::empty(),
-    };
-
-    let mut rows: Vec<Vec<CellSnapshot>> = (0..num_lines)
-        .map(|_| vec![default_cell.clone(); num_cols])
-        .collect();
+    let reusable = !fully_damaged
+        && previous.is_some_and(|previous| {
+            previous.num_cols == num_cols
+                && previous.num_lines == num_lines
+                && previous.display_offset == display_offset
+                && previous.colors == colors
+                && previous.selection == selection
+        });
+    if !reusable {
+        buffer.copied.fill(true);
+    }
 
-    for indexed in content.display_iter {
-        let Indexed { point, cell } = indexed;
-        let Some(row) = viewport_row_for_line(point.line.0, display_offset, num_lines) else {
+    let grid = term.grid();
+    for row in 0..num_lines {
+        if !buffer.copied[row] {
             continue;
-        };
-        let col = point.column.0;
-        if col < num_cols {
-            let mut fg = cell.fg;
-            let mut bg = cell.bg;
-
-            if cell.flags.contains(Flags::INVERSE) {
-                std::mem::swap(&mut fg, &mut bg);
+        }
+        let line = Line(row as i32 - display_offset as i32);
+        let grid_row = &grid[line];
+        let mut line_size = LineSize::Single;
+        let cells = &mut buffer.cells[row * num_cols..(row + 1) * num_cols];
+        for (col, slot) in cells.iter_mut().enumerate() {
+            let cell = &grid_row[Column(col)];
+            if let Some(size) = LineSize::from_cell(cell) {
+                line_size = size;
             }
-
-            if let Some(ref sel) = selection {
-                if sel.contains(point) {
-                    bg = selection_background_color(&bg, &colors, selection_tint);
+            let point = AlacPoint::new(line, Column(col));
+            *slot = snapshot_cell(cell, point, selection.as_ref(), &colors, selection_tint);
+        }
+        buffer.line_sizes[row] = line_size;
+    }
+
+    let cursor = GridCursor::from_term(term);
+    let show_cursor = cursor.is_visible();
+
+    let mut image_anchors = Vec::new();
+    if track_images {
+        let lookbehind = IMAGE_ANCHOR_LOOKBEHIND_LINES.min(history_size - display_offset);
+        let viewport_rows = (0..num_lines as isize).chain((1..=lookbehind as isize).map(|d| -d));
+        for row in viewport_rows {
+            let grid_row = &grid[Line(row as i32 - display_offset as i32)];
+            for col in 0..num_cols {
+                if let Some(id) = grid_row[Column(col)]
+                    .hyperlink()
+                    .and_then(|link| image_id_from_uri(link.uri()))
+                {
+                    image_anchors.push((id, row, col));
                 }
             }
-
-            rows[row][col] = CellSnapshot {
-                c: cell.c,
-                fg,
-                bg,
-                flags: cell.flags,
-            };
         }
     }
 
-    let cursor_row = viewport_row_for_line(cursor.point.line.0, display_offset, num_lines);
-    let cursor_col = cursor.point.column.0;
-    let cursor_shape = cursor.shape;
-    let cursor_blinking = term.cursor_style().blinking;
-    let show_cursor = cursor.shape != CursorShape::Hidden && cursor_row.is_some();
-
     let snapshot = TerminalSnapshot {
-        rows,
+        rows: Vec::new(),
+        line_sizes: Vec::new(),
         num_cols,
         num_lines,
         history_size,
         display_offset,
-        cursor_row,
-        cursor_col,
-        cursor_shape,
-        cursor_blinking,
+        selection,
+        scroll_region: None,
+        cursor_row: cursor.viewport_row,
+        cursor_col: cursor.point.column.0,
+        cursor_shape: cursor.shape,
+        cursor_blinking: cursor.blinking,
         show_cursor,
         cursor_draw_visible: show_cursor,
         colors,
+        images: Vec::new(),
+        cursor_glyph: None,
+        scrollbar_thumb: None,
     };
-    let lock_hold = lock_acquired_at.elapsed();
-    drop(term);
+    (snapshot, image_anchors)
+}
 
-    (
-        snapshot,
-        SnapshotTiming {
-            total: total_start.elapsed(),
-            lock_hold,
-        },
-    )
+/// How much longer to keep drawing the previous frame: a program inside a
+/// synchronized update holds it until the update ends or `timeout` passes.
+fn synchronized_update_hold(
+    update_started: Option<Instant>,
+    now: Instant,
+    timeout: Duration,
+) -> Option<Duration> {
+    let remaining = timeout.checked_sub(now.saturating_duration_since(update_started?))?;
+    (!remaining.is_zero()).then_some(remaining)
+}
+
+/// Build the snapshot rows from the cells `snapshot_term` copied, sharing the
+/// rows it skipped with `previous`.
+fn fill_snapshot_rows(
+    snapshot: &mut TerminalSnapshot,
+    buffer: &SnapshotBuffer,
+    previous: Option<&FrameCache>,
+) {
+    let (rows, line_sizes) = (0..snapshot.num_lines)
+        .map(|row| match previous {
+            Some(previous) if !buffer.copied[row] => {
+                (previous.rows[row].clone(), previous.line_sizes[row])
+            }
+            _ => (Arc::new(buffer.row(row).to_vec()), buffer.line_sizes[row]),
+        })
+        .unzip();
+    snapshot.rows = rows;
+    snapshot.line_sizes = line_sizes;
+}
+
+/// A cell as drawn: inverse video applied, then the selection colors. Block
+/// selections only tint the columns between their corners on every line.
+fn snapshot_cell(
+    cell: &Cell,
+    point: AlacPoint,
+    selection: Option<&SelectionRange>,
+    colors: &ColorsSnapshot,
+    selection_tint: AlacRgb,
+) -> GridCell {
+    let mut snapshot = GridCell::from_cell(cell);
+
+    if snapshot.flags.contains(Flags::INVERSE) {
+        std::mem::swap(&mut snapshot.fg, &mut snapshot.bg);
+    }
+
+    if selection.is_some_and(|selection| selection.contains(point)) {
+        snapshot.bg = selection_background_color(&snapshot.bg, colors, selection_tint);
+        if let Some(selected_fg) = colors.selection_foreground {
+            snapshot.fg = AlacColor::Spec(selected_fg);
+        }
+    }
+
+    snapshot
 }
 
 fn mark_row_dirty(dirty_rows: &mut [bool], row: Option<usize>) {
@@ -2271,9 +5508,85 @@ fn mark_row_dirty(dirty_rows: &mut [bool], row: Option<usize>) {
     }
 }
 
+/// Content of a scroll region that moved by `lines` rows since the previous
+/// frame. Positive `lines` means the content scrolled up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RegionScroll {
+    rows: Range<usize>,
+    lines: isize,
+}
+
+impl RegionScroll {
+    /// Row of the previous frame now shown at `row`, or `None` for a row the
+    /// scroll exposed.
+    fn source_row(&self, row: usize) -> Option<usize> {
+        if !self.rows.contains(&row) {
+            return Some(row);
+        }
+        let source = row.checked_add_signed(self.lines)?;
+        self.rows.contains(&source).then_some(source)
+    }
+}
+
+/// Find how far the scroll region's content moved since `previous`. Only the
+/// bottom of the scrollback is considered, where the region is on screen.
+fn detect_region_scroll(
+    snapshot: &TerminalSnapshot,
+    previous: Option<&FrameCache>,
+) -> Option<RegionScroll> {
+    let previous = previous?;
+    if previous.num_cols != snapshot.num_cols
+        || previous.num_lines != snapshot.num_lines
+        || previous.display_offset != 0
+        || snapshot.display_offset != 0
+        || previous.colors != snapshot.colors
+        || previous.scroll_region != snapshot.scroll_region
+    {
+        return None;
+    }
+
+    let rows = snapshot
+        .scroll_region
+        .clone()
+        .unwrap_or(0..snapshot.num_lines);
+    let old = previous.rows.get(rows.clone())?;
+    let new = snapshot.rows.get(rows.clone())?;
+    let matching_rows = |lines: isize| {
+        (0..new.len())
+            .filter(|&row| {
+                row.checked_add_signed(lines)
+                    .and_then(|source| old.get(source))
+                    .is_some_and(|old_row| *old_row == new[row])
+            })
+            .count()
+    };
+
+    // The region's first row either came from further down (scrolled up) or
+    // moved further down (scrolled down).
+    let up = old
+        .iter()
+        .skip(1)
+        .position(|row| Some(row) == new.first())
+        .map(|index| index as isize + 1);
+    let down = new
+        .iter()
+        .skip(1)
+        .position(|row| Some(row) == old.first())
+        .map(|index| -(index as isize + 1));
+    let unmoved = matching_rows(0);
+    [up, down]
+        .into_iter()
+        .flatten()
+        .map(|lines| (matching_rows(lines), lines))
+        .filter(|(matches, _)| *matches > unmoved)
+        .max_by_key(|(matches, _)| *matches)
+        .map(|(_, lines)| RegionScroll { rows, lines })
+}
+
 fn dirty_rows_for_snapshot(
     snapshot: &TerminalSnapshot,
     previous: Option<&FrameCache>,
+    region_scroll: Option<&RegionScroll>,
 ) -> Vec<bool> {
     let mut dirty_rows = vec![true; snapshot.num_lines];
     let Some(previous) = previous else {
@@ -2295,14 +5608,18 @@ fn dirty_rows_for_snapshot(
     dirty_rows.fill(false);
 
     for (new_row_idx, new_row) in snapshot.rows.iter().enumerate() {
-        let old_row_idx = new_row_idx as isize - display_offset_delta;
-        if old_row_idx < 0 {
+        let old_row_idx = match region_scroll {
+            Some(scroll) => scroll.source_row(new_row_idx),
+            None => new_row_idx.checked_add_signed(-display_offset_delta),
+        };
+        let Some(old_row_idx) = old_row_idx else {
             dirty_rows[new_row_idx] = true;
             continue;
-        }
+        };
 
-        let old_row_idx = old_row_idx as usize;
-        if previous.rows.get(old_row_idx) != Some(new_row) {
+        if previous.rows.get(old_row_idx) != Some(new_row)
+            || previous.line_sizes.get(old_row_idx) != snapshot.line_sizes.get(new_row_idx)
+        {
             dirty_rows[new_row_idx] = true;
         }
     }
@@ -2353,28 +5670,125 @@ fn shift_row_cache_for_display_offset(
     }
 }
 
+/// Move cached rows inside a scrolled region along with their content.
+fn shift_row_cache_for_region_scroll(row_cache: &mut [CachedRow], scroll: &RegionScroll) {
+    let Some(old) = row_cache
+        .get(scroll.rows.clone())
+        .map(<[CachedRow]>::to_vec)
+    else {
+        return;
+    };
+
+    for row in scroll.rows.clone() {
+        row_cache[row] = scroll
+            .source_row(row)
+            .map(|source| old[source - scroll.rows.start].clone())
+            .unwrap_or_default();
+    }
+}
+
 impl Render for TerminalView {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let active_theme_palette = theme_palette(self.settings.theme);
-        let (mut snapshot, snapshot_timing) =
-            take_snapshot(self.active_terminal(), self.settings.theme);
+        let active_theme_palette = theme_palette(&self.settings.theme, &self.theme_registry);
+        let (mut snapshot, snapshot_timing) = self.active_snapshot(active_theme_palette, cx);
         let now = Instant::now();
         let should_blink = cursor_should_blink(self.settings.blinking, snapshot.cursor_blinking)
             && !self.cursor_blink_suppressed(now);
+        let (cursor_shape, should_blink) =
+            cursor_style_for_focus(self.window_is_active, snapshot.cursor_shape, should_blink);
+        snapshot.cursor_shape = cursor_shape;
         snapshot.cursor_draw_visible =
             snapshot.show_cursor && (!should_blink || self.cursor_blink_visible);
+        if let Some(copy_mode) = self.copy_mode {
+            snapshot.cursor_row = viewport_row_for_line(
+                copy_mode.cursor.line.0,
+                snapshot.display_offset,
+                snapshot.num_lines,
+            );
+            snapshot.cursor_col = copy_mode.cursor.column.0;
+            snapshot.cursor_shape = CursorShape::HollowBlock;
+            snapshot.cursor_draw_visible = snapshot.cursor_row.is_some();
+        }
         let previous_view = self
             .previous_frame
             .as_ref()
             .map(PreviousFrameView::from_frame);
-        let dirty_rows = dirty_rows_for_snapshot(&snapshot, self.previous_frame.as_ref());
+        let region_scroll = detect_region_scroll(&snapshot, self.previous_frame.as_ref());
+        let dirty_rows = dirty_rows_for_snapshot(
+            &snapshot,
+            self.previous_frame.as_ref(),
+            region_scroll.as_ref(),
+        );
         let dirty_row_count = dirty_rows.iter().filter(|is_dirty| **is_dirty).count();
         let total_rows = snapshot.num_lines;
         let text_system = window.text_system().clone();
-        shift_row_cache_for_display_offset(&mut self.row_text_cache, previous_view, &snapshot);
+        if previous_view.is_some_and(|previous| previous.display_offset != snapshot.display_offset)
+        {
+            shift_row_cache_for_display_offset(
+                Arc::make_mut(&mut self.row_text_cache).as_mut_slice(),
+                previous_view,
+                &snapshot,
+            );
+            self.note_viewport_scrolled(now, cx);
+        }
+        let scrollbar_opacity = if self.scrollbar_hovered || self.scrollbar_drag_offset.is_some() {
+            1.0
+        } else {
+            scrollbar_opacity(self.scrollbar_scrolled_at, now)
+        };
+        // The position label fades with the scrollbar.
+        let scroll_position = (scrollbar_opacity > 0.0)
+            .then(|| scroll_position_label(snapshot.display_offset, snapshot.history_size))
+            .flatten()
+            .map(|label| (label, scrollbar_opacity));
+        if scrollbar_opacity > 0.0 {
+            let mut thumb = scrollbar_thumb_color(active_theme_palette, self.scrollbar_hovered);
+            thumb.a *= scrollbar_opacity;
+            snapshot.scrollbar_thumb = Some(thumb);
+            if scrollbar_opacity < 1.0 {
+                window.request_animation_frame();
+            }
+        }
+        if let Some(scroll) = &region_scroll {
+            shift_row_cache_for_region_scroll(
+                Arc::make_mut(&mut self.row_text_cache).as_mut_slice(),
+                scroll,
+            );
+        }
         let row_cache_stats = self.refresh_row_text_cache(&snapshot, &dirty_rows, &text_system);
+        snapshot.cursor_glyph = self.shape_cursor_glyph(&snapshot, &text_system);
         let row_text_cache = self.row_text_cache.clone();
         self.previous_frame = Some(FrameCache::from_snapshot(&snapshot));
+        if !bell_flash_active(self.bell_flash_until, Instant::now()) {
+            self.bell_flash_until = None;
+        }
+        let bell_flashing = self.bell_flash_until.is_some();
+        let hyperlink_underline_spans = self
+            .hovered_hyperlink
+            .as_ref()
+            .map(|hyperlink| {
+                grid_range_row_spans(
+                    hyperlink.start,
+                    hyperlink.end,
+                    snapshot.display_offset,
+                    snapshot.num_lines,
+                    snapshot.num_cols,
+                )
+            })
+            .unwrap_or_default();
+        let focused_pane_grid = self.focused_pane_grid();
+        let pane_layout = self.active_tab().pane_layout(self.grid_size);
+        let has_split_panes = pane_layout.len() > 1;
+        let pane_dividers = Self::pane_divider_grids(&pane_layout);
+        let mut unfocused_panes = self.unfocused_pane_frames(&pane_layout, &text_system);
+        self.prepare_snapshot_images(
+            std::iter::once(&mut snapshot).chain(
+                unfocused_panes
+                    .iter_mut()
+                    .map(|(_, pane_snapshot, _)| pane_snapshot),
+            ),
+            window,
+        );
 
         let active_tab_id = self.active_tab_id;
         let hovered_tab_id = self.hovered_tab_id;
@@ -2385,11 +5799,29 @@ impl Render for TerminalView {
         let settings_drawer_width = Self::settings_drawer_width_for_viewport(viewport_width);
         let settings_control_height = px(SETTINGS_CONTROL_HEIGHT_PX);
         let ui_accent = tab_brand_purple(1.0);
+        let show_cwd_in_tab_title = self.settings.show_cwd_in_tab_title;
         let tabs_for_render = self
             .tabs
             .iter()
             .enumerate()
-            .map(|(index, tab)| (tab.id, Self::tab_display_title(tab), index + 1 == tab_count))
+            .map(|(index, tab)| {
+                let rename_draft = self
+                    .tab_rename
+                    .as_ref()
+                    .filter(|rename| rename.tab_id == tab.id)
+                    .map(|rename| format!("{}▏", rename.draft));
+                let title = Self::tab_display_title(tab, show_cwd_in_tab_title);
+                let tooltip = tab_tooltip_text(&title, tab.last_exit_status);
+                (
+                    tab.id,
+                    title,
+                    tooltip,
+                    rename_draft,
+                    tab.unread.dot_color(),
+                    tab.tint,
+                    index + 1 == tab_count,
+                )
+            })
             .collect::<Vec<_>>();
         let find_panel_state = self.find_state.as_ref().map(|state| {
             let query_display = if state.query.is_empty() {
@@ -2401,6 +5833,17 @@ impl Render for TerminalView {
                 (Some(active), total) if total > 0 => format!("{active}/{total}"),
                 _ => format!("0/{}", state.match_count),
             };
+            let count_label = if state.cache.as_ref().is_some_and(|cache| cache.truncated) {
+                format!("{count_label}+")
+            } else {
+                count_label
+            };
+            let count_label = if matches!(state.wrapped_until, Some(until) if Instant::now() < until)
+            {
+                format!("Wrapped {count_label}")
+            } else {
+                count_label
+            };
             (
                 query_display,
                 state.query.is_empty(),
@@ -2409,6 +5852,50 @@ impl Render for TerminalView {
             )
         });
         let settings_panel_open = self.settings_panel_open;
+        let close_confirmation_open = self.close_confirmation_tab_id.is_some();
+        let paste_preview = self.paste_confirmation.as_deref().map(paste_preview);
+        let tab_dropdown_entries = (self.tab_dropdown_open && has_multiple_tabs).then(|| {
+            Self::tab_dropdown_entries(
+                self.tabs
+                    .iter()
+                    .map(|tab| (tab.id, Self::tab_display_title(tab, show_cwd_in_tab_title))),
+                active_tab_id,
+            )
+        });
+        let hovered_dropdown_tab_id = self.hovered_dropdown_tab_id;
+        let profile_picker_entries = self.profile_picker_open.then(|| {
+            self.settings
+                .profiles
+                .iter()
+                .map(|profile| profile.name.clone())
+                .enumerate()
+                .collect::<Vec<_>>()
+        });
+        let context_menu = self.context_menu_position.map(|position| {
+            let has_selection = self
+                .active_terminal()
+                .term
+                .lock()
+                .selection_to_string()
+                .is_some_and(|text| !text.is_empty());
+            let items = if self.context_menu_tab_id.is_some() {
+                Self::tab_context_menu_items()
+            } else {
+                Self::context_menu_items(has_selection)
+            };
+            let menu_size = size(
+                px(CONTEXT_MENU_WIDTH_PX),
+                px(CONTEXT_MENU_ITEM_HEIGHT_PX * items.len() as f32
+                    + CONTEXT_MENU_PADDING_PX * 2.0),
+            );
+            (
+                Self::context_menu_origin(position, menu_size, window.viewport_size()),
+                items,
+            )
+        });
+        let show_broadcast_indicator = find_panel_state.is_none() && self.broadcast_input;
+        let show_alt_screen_indicator = find_panel_state.is_none()
+            && alt_screen_indicator_visible(*self.active_terminal().term.lock().mode());
         let pinned = self.pinned;
         let pin_indicator_symbol = Self::pin_indicator_symbol(pinned);
         let pin_indicator_color = if pinned {
@@ -2420,10 +5907,18 @@ impl Render for TerminalView {
         let font_size_display = format!("{:.1}", self.settings.font_size);
         let line_height_mode = Self::line_height_mode(&self.settings.line_height);
         let line_height_display = Self::line_height_display(&self.settings.line_height);
-        let theme_display = Self::theme_label(self.settings.theme).to_string();
+        let theme_display = Self::theme_label(&self.settings.theme).to_string();
+        let light_dark_toggle_symbol = if active_theme_palette.is_light() {
+            "☾"
+        } else {
+            "☀"
+        };
         let scroll_multiplier_value =
             Self::normalized_scroll_multiplier(self.settings.scroll_multiplier);
         let scroll_multiplier_display = format!("{:.2}", scroll_multiplier_value);
+        let scrollback_display = Self::scrollback_lines(&self.settings).to_string();
+        let hyperlink_timeout_display =
+            Self::hyperlink_timeout_display(self.settings.path_hyperlink_timeout_ms);
         let global_hotkey_display = self.settings.global_hotkey.clone();
         let recording_global_hotkey = self.recording_global_hotkey;
         let cursor_shape_display = match self.settings.cursor_shape {
@@ -2439,28 +5934,55 @@ impl Render for TerminalView {
         };
 
         let cell_size = self.cell_size;
+        let padding = content_padding(&self.settings);
+        // Only the painted cursor follows the override; selection tints keep
+        // deriving from the theme cursor.
+        let surface_bg =
+            tinted_background(active_theme_palette.terminal_bg, self.active_tab().tint);
+        let pane_palette = ThemePalette {
+            cursor: cursor_color(&self.settings, active_theme_palette),
+            terminal_bg: surface_bg,
+            ..active_theme_palette
+        };
+        self.log_perf();
         let perf = self.perf.clone();
 
         let terminal_surface = div()
             .id("terminal-surface")
             .track_focus(&self.focus_handle)
             .flex_1()
-            .bg(rgb(active_theme_palette.terminal_bg))
+            .bg(rgb(surface_bg))
+            .when(!hyperlink_underline_spans.is_empty(), |this| {
+                this.cursor_pointer()
+            })
             .on_mouse_down(
                 MouseButton::Left,
                 cx.listener(|this, event: &MouseDownEvent, _window, cx| {
+                    if this.focus_pane_at_position(event.position) {
+                        cx.notify();
+                    }
+
                     if let Some(layout) = this.scrollbar_layout() {
                         if point_in_bounds(&layout.track, event.position) {
-                            let grab_offset = if point_in_bounds(&layout.thumb, event.position) {
-                                event.position.y - layout.thumb.origin.y
-                            } else {
-                                layout.thumb.size.height / 2.0
-                            };
-                            let target_offset =
-                                display_offset_from_pointer(event.position.y, &layout, grab_offset);
-                            this.scrollbar_drag_offset = Some(grab_offset);
                             this.selection_anchor = None;
-                            this.set_display_offset(target_offset);
+                            match scrollbar_track_click_pages_up(event.position.y, &layout) {
+                                Some(up) => {
+                                    let (display_offset, screen_lines) = {
+                                        let term = this.active_terminal().term.lock();
+                                        (term.grid().display_offset(), term.screen_lines())
+                                    };
+                                    this.set_display_offset(page_scroll_offset(
+                                        display_offset,
+                                        layout.max_offset,
+                                        screen_lines,
+                                        up,
+                                    ));
+                                }
+                                None => {
+                                    this.scrollbar_drag_offset =
+                                        Some(event.position.y - layout.thumb.origin.y);
+                                }
+                            }
                             cx.notify();
                             return;
                         }
@@ -2468,47 +5990,36 @@ impl Render for TerminalView {
 
                     let (mode, display_offset) = this.mode_and_display_offset();
 
-                    if event.modifiers.secondary() {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
-                        let term_handle = this.active_terminal().term.clone();
-                        let term = term_handle.lock();
-                        if let Some((target, is_url, _match)) = find_from_grid_point(
-                            &term,
-                            point,
-                            &mut this.regex_searches,
-                            PathStyle::Unix,
+                    if hyperlink_modifier_held(
+                        this.settings.hyperlink_modifier,
+                        &event.modifiers,
+                        mode,
+                    ) {
+                        if let Some(target) = this.hyperlink_at_position(event.position).and_then(
+                            |(target, is_url, _match)| {
+                                navigation_target(target, is_url, this.active_tab().cwd.clone())
+                            },
                         ) {
-                            drop(term);
-                            if is_url {
-                                cx.open_url(&target);
-                            } else {
-                                let file_path = strip_line_column_suffix(&target);
-                                let file_url = file_path_to_file_url(file_path);
-                                cx.open_url(&file_url);
-                            }
+                            this.open_navigation_target(target, cx);
                             return;
                         }
                     }
 
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, true, mode)
                         {
                             this.active_terminal().write(bytes);
                         }
                     } else {
-                        let (point, side) = grid_point_and_side(
-                            event.position,
-                            this.terminal_bounds(),
-                            display_offset,
-                        );
+                        let (point, side) =
+                            this.grid_point_and_side_at(event.position, display_offset);
                         this.selection_anchor = Some((point, side));
 
                         let mut term = this.active_terminal().term.lock();
-                        let selection_type = selection_type_for_click_count(event.click_count);
+                        let selection_type =
+                            selection_type_for_click_count(event.click_count, event.modifiers.alt);
                         term.selection = Some(Selection::new(selection_type, point, side));
                         drop(term);
 
@@ -2518,31 +6029,45 @@ impl Render for TerminalView {
             )
             .on_mouse_down(
                 MouseButton::Right,
-                cx.listener(|this, event: &MouseDownEvent, _window, _cx| {
+                cx.listener(|this, event: &MouseDownEvent, _window, cx| {
+                    if this.focus_pane_at_position(event.position) {
+                        cx.notify();
+                    }
+
                     let (mode, display_offset) = this.mode_and_display_offset();
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, true, mode)
                         {
                             this.active_terminal().write(bytes);
                         }
+                    } else {
+                        this.context_menu_position = Some(event.position);
+                        this.context_menu_tab_id = None;
+                        cx.notify();
                     }
                 }),
             )
             .on_mouse_down(
                 MouseButton::Middle,
-                cx.listener(|this, event: &MouseDownEvent, _window, _cx| {
+                cx.listener(|this, event: &MouseDownEvent, _window, cx| {
+                    if this.focus_pane_at_position(event.position) {
+                        cx.notify();
+                    }
+
                     let (mode, display_offset) = this.mode_and_display_offset();
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, true, mode)
                         {
                             this.active_terminal().write(bytes);
                         }
+                    } else if this.settings.middle_click_paste {
+                        if let Some(text) = this.primary_selection.clone() {
+                            this.paste_text(text, cx);
+                        }
                     }
                 }),
             )
@@ -2556,28 +6081,35 @@ impl Render for TerminalView {
 
                     let (mode, display_offset) = this.mode_and_display_offset();
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, false, mode)
                         {
                             this.active_terminal().write(bytes);
                         }
                     } else if this.selection_anchor.is_some() {
-                        let (point, side) = grid_point_and_side(
-                            event.position,
-                            this.terminal_bounds(),
-                            display_offset,
-                        );
+                        let (point, side) =
+                            this.grid_point_and_side_at(event.position, display_offset);
                         let mut term = this.active_terminal().term.lock();
                         if let Some(selection) = term.selection.as_mut() {
                             selection.update(point, side);
                         }
-
+                        let range = term
+                            .selection
+                            .as_ref()
+                            .and_then(|selection| selection.to_range(&*term));
+
+                        let selected_text = term
+                            .selection_to_string()
+                            .map(|text| strip_grid_markers(&text).into_owned());
+                        let primary_text = primary_selection_capture(
+                            this.settings.middle_click_paste,
+                            selected_text.as_deref(),
+                        );
                         let (copy_text, clear_selection) = selection_copy_plan(
                             this.settings.copy_on_select,
                             this.settings.keep_selection_on_copy,
-                            term.selection_to_string(),
+                            selected_text,
                         );
 
                         if clear_selection {
@@ -2586,11 +6118,15 @@ impl Render for TerminalView {
 
                         drop(term);
 
+                        if primary_text.is_some() {
+                            this.primary_selection = primary_text;
+                        }
                         if let Some(text) = copy_text {
                             cx.write_to_clipboard(ClipboardItem::new_string(text));
                         }
 
                         this.selection_anchor = None;
+                        this.report_selection(range, cx);
                         cx.notify();
                     }
                 }),
@@ -2600,8 +6136,7 @@ impl Render for TerminalView {
                 cx.listener(|this, event: &MouseUpEvent, _window, _cx| {
                     let (mode, display_offset) = this.mode_and_display_offset();
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, false, mode)
                         {
@@ -2615,8 +6150,7 @@ impl Render for TerminalView {
                 cx.listener(|this, event: &MouseUpEvent, _window, _cx| {
                     let (mode, display_offset) = this.mode_and_display_offset();
                     if mode.intersects(TermMode::MOUSE_MODE) {
-                        let point =
-                            grid_point(event.position, this.terminal_bounds(), display_offset);
+                        let point = this.grid_point_at(event.position, display_offset);
                         if let Some(bytes) =
                             mouse_button_report(point, event.button, event.modifiers, false, mode)
                         {
@@ -2626,6 +6160,18 @@ impl Render for TerminalView {
                 }),
             )
             .on_mouse_move(cx.listener(|this, event: &MouseMoveEvent, _window, cx| {
+                let scrollbar_hovered = this
+                    .scrollbar_layout()
+                    .is_some_and(|layout| point_in_bounds(&layout.track, event.position));
+                if scrollbar_hovered != this.scrollbar_hovered {
+                    this.scrollbar_hovered = scrollbar_hovered;
+                    if !scrollbar_hovered {
+                        // Linger before fading, as after a scroll.
+                        this.note_viewport_scrolled(Instant::now(), cx);
+                    }
+                    cx.notify();
+                }
+
                 if let Some(grab_offset) = this.scrollbar_drag_offset {
                     if event.pressed_button == Some(MouseButton::Left) {
                         if let Some(layout) = this.scrollbar_layout() {
@@ -2643,8 +6189,16 @@ impl Render for TerminalView {
                 }
 
                 let (mode, display_offset) = this.mode_and_display_offset();
+                let hover_position = (hyperlink_modifier_held(
+                    this.settings.hyperlink_modifier,
+                    &event.modifiers,
+                    mode,
+                ) && event.pressed_button.is_none())
+                .then_some(event.position);
+                this.update_hovered_hyperlink(hover_position, cx);
+
                 if mode.intersects(TermMode::MOUSE_MOTION | TermMode::MOUSE_DRAG) {
-                    let point = grid_point(event.position, this.terminal_bounds(), display_offset);
+                    let point = this.grid_point_at(event.position, display_offset);
                     if let Some(bytes) =
                         mouse_moved_report(point, event.pressed_button, event.modifiers, mode)
                     {
@@ -2655,8 +6209,7 @@ impl Render for TerminalView {
                         return;
                     };
 
-                    let (point, side) =
-                        grid_point_and_side(event.position, this.terminal_bounds(), display_offset);
+                    let (point, side) = this.grid_point_and_side_at(event.position, display_offset);
 
                     let mut term = this.active_terminal().term.lock();
                     if let Some(selection) = term.selection.as_mut() {
@@ -2672,7 +6225,19 @@ impl Render for TerminalView {
                     cx.notify();
                 }
             }))
-            .on_scroll_wheel(cx.listener(|this, event: &ScrollWheelEvent, _window, cx| {
+            .on_modifiers_changed(cx.listener(
+                |this, event: &gpui::ModifiersChangedEvent, window, cx| {
+                    let (mode, _) = this.mode_and_display_offset();
+                    let hover_position = hyperlink_modifier_held(
+                        this.settings.hyperlink_modifier,
+                        &event.modifiers,
+                        mode,
+                    )
+                    .then(|| window.mouse_position());
+                    this.update_hovered_hyperlink(hover_position, cx);
+                },
+            ))
+            .on_scroll_wheel(cx.listener(|this, event: &ScrollWheelEvent, window, cx| {
                 if should_ignore_scroll_event(
                     event.touch_phase,
                     matches!(event.delta, ScrollDelta::Pixels(_)),
@@ -2684,32 +6249,54 @@ impl Render for TerminalView {
                     return;
                 }
 
+                // Cmd+wheel zooms instead of scrolling, and is never reported
+                // to programs that track the mouse.
+                if event.modifiers.platform {
+                    let steps = wheel_font_zoom_steps(
+                        event.delta,
+                        this.cell_size.height,
+                        &mut this.pending_font_zoom_lines,
+                    );
+                    if steps != 0 {
+                        this.adjust_font_size(steps as f32 * SETTINGS_FONT_SIZE_STEP, window, cx);
+                    }
+                    return;
+                }
+
                 let (mode, display_offset) = this.mode_and_display_offset();
                 let lines = scroll_delta_to_lines(event.delta, this.cell_size.height)
-                    * effective_scroll_multiplier(this.settings.scroll_multiplier);
-                let delta = consume_scroll_lines(&mut this.pending_scroll_lines, lines);
+                    * scroll_multiplier_for_delta(event.delta, &this.settings);
+                let delta = directed_scroll_lines(
+                    consume_scroll_lines(&mut this.pending_scroll_lines, lines),
+                    this.settings.natural_scroll,
+                );
                 if delta == 0 {
                     return;
                 }
 
-                if mouse_mode_enabled_for_scroll(mode, event.modifiers.shift) {
-                    let point = grid_point(event.position, this.terminal_bounds(), display_offset);
-                    if let Some(reports) = scroll_report(point, delta, event, mode) {
-                        for bytes in reports {
-                            this.active_terminal().write(bytes);
-                        }
-                    }
-                } else if alternate_scroll_enabled(
+                match wheel_scroll_target(
                     mode,
                     this.settings.alternate_scroll,
                     event.modifiers.shift,
                 ) {
-                    this.active_terminal().write(alt_scroll(delta));
-                } else {
-                    this.active_terminal()
-                        .term
-                        .lock()
-                        .scroll_display(Scroll::Delta(delta));
+                    WheelScrollTarget::MouseReport => {
+                        let point = this.grid_point_at(event.position, display_offset);
+                        if let Some(reports) = scroll_report(point, delta, event, mode) {
+                            for bytes in reports {
+                                this.active_terminal().write(bytes);
+                            }
+                        }
+                    }
+                    WheelScrollTarget::ArrowKeys => {
+                        this.active_terminal().write(alt_scroll(delta));
+                    }
+                    WheelScrollTarget::Scrollback => {
+                        this.active_terminal()
+                            .term
+                            .lock()
+                            .scroll_display(Scroll::Delta(delta));
+                        this.last_user_scroll_at = Some(Instant::now());
+                    }
                 }
 
                 cx.notify();
@@ -2719,10 +6306,51 @@ impl Render for TerminalView {
                     return;
                 }
 
+                if this.handle_close_confirmation_keybinding(event, window, cx) {
+                    return;
+                }
+
+                if this.handle_paste_confirmation_keybinding(event, cx) {
+                    return;
+                }
+
+                if this.handle_tab_rename_keybinding(event, window, cx) {
+                    return;
+                }
+
                 if this.handle_tab_keybinding(event, window, cx) {
                     return;
                 }
 
+                if this.handle_font_size_keybinding(event, window, cx) {
+                    return;
+                }
+
+                if this.context_menu_position.is_some()
+                    && Self::should_close_settings_panel_for_keystroke(&event.keystroke)
+                {
+                    this.context_menu_position = None;
+                    cx.notify();
+                    return;
+                }
+
+                if this.profile_picker_open
+                    && Self::should_close_settings_panel_for_keystroke(&event.keystroke)
+                {
+                    this.profile_picker_open = false;
+                    cx.notify();
+                    return;
+                }
+
+                if this.tab_dropdown_open
+                    && Self::should_close_settings_panel_for_keystroke(&event.keystroke)
+                {
+                    this.tab_dropdown_open = false;
+                    this.hovered_dropdown_tab_id = None;
+                    cx.notify();
+                    return;
+                }
+
                 if this.settings_panel_open
                     && Self::should_close_settings_panel_for_keystroke(&event.keystroke)
                 {
@@ -2732,10 +6360,18 @@ impl Render for TerminalView {
                     return;
                 }
 
+                if this.handle_copy_mode_keybinding(event, cx) {
+                    return;
+                }
+
                 if this.handle_find_keybinding(event, cx) {
                     return;
                 }
 
+                if this.handle_selection_escape(event, cx) {
+                    return;
+                }
+
                 if this.handle_common_shortcut(event, cx) {
                     return;
                 }
@@ -2744,24 +6380,38 @@ impl Render for TerminalView {
                     return;
                 }
 
-                let mode = {
-                    let term = this.active_terminal().term.lock();
-                    *term.mode()
-                };
-
-                if let Some(esc) = simple_term::mappings::keys::to_esc_str(
-                    &event.keystroke,
-                    &mode,
-                    this.settings.option_as_meta,
-                ) {
-                    this.begin_terminal_input(cx);
-                    this.active_terminal().write(esc.as_bytes().to_vec());
+                if this.handle_exited_pane_keystroke(event, window, cx) {
                     return;
                 }
 
-                if let Some(text) = text_to_insert(&event.keystroke) {
+                // Encode per terminal, since each one may be in a different
+                // keypad or cursor key mode.
+                let option_as_meta = this.settings.option_as_meta;
+                let input_bytes = |terminal: &Terminal| {
+                    let mode = *terminal.term.lock().mode();
+                    simple_term::mappings::keys::to_esc_str(&event.keystroke, &mode, option_as_meta)
+                        .map(|esc| esc.as_bytes().to_vec())
+                        .or_else(|| {
+                            text_to_insert(&event.keystroke).map(|text| text.as_bytes().to_vec())
+                        })
+                };
+
+                if input_bytes(this.active_terminal()).is_some() {
                     this.begin_terminal_input(cx);
-                    this.active_terminal().write(text.as_bytes().to_vec());
+                    let typed = input_target_tabs(
+                        this.broadcast_input,
+                        this.tabs.len(),
+                        this.active_tab_index(),
+                    )
+                    .filter_map(|index| {
+                        let tab = &this.tabs[index];
+                        let bytes = input_bytes(tab.focused_terminal())?;
+                        Some((tab.id, tab.focused_pane_id, bytes))
+                    })
+                    .collect::<Vec<_>>();
+                    for (tab_id, pane_id, bytes) in typed {
+                        this.write_typed_input(tab_id, pane_id, bytes);
+                    }
                 }
             }))
             .child(
@@ -2769,141 +6419,83 @@ impl Render for TerminalView {
                     move |_bounds, _window, _cx| snapshot,
                     move |bounds, snapshot, window, cx| {
                         let paint_start = Instant::now();
-                        let content_bounds = Bounds {
-                            origin: bounds.origin,
+                        let bounds = Bounds {
+                            origin: bounds.origin + point(padding.width, padding.height),
                             size: size(
-                                cell_size.width * snapshot.num_cols as f32,
-                                cell_size.height * snapshot.num_lines as f32,
+                                (bounds.size.width - padding.width * 2.0).max(px(0.0)),
+                                (bounds.size.height - padding.height * 2.0).max(px(0.0)),
+                            ),
+                        };
+                        let pane_bounds = |pane_grid: &PaneGrid| Bounds {
+                            origin: point(
+                                bounds.origin.x + cell_size.width * pane_grid.col_offset as f32,
+                                bounds.origin.y + cell_size.height * pane_grid.line_offset as f32,
+                            ),
+                            size: size(
+                                cell_size.width * pane_grid.cols as f32,
+                                cell_size.height * pane_grid.lines as f32,
                             ),
                         };
-                        let scrollbar = scrollbar_layout(
-                            content_bounds,
-                            snapshot.num_lines,
-                            snapshot.history_size,
-                            snapshot.display_offset,
-                        );
 
-                        window.with_content_mask(Some(ContentMask { bounds }), |window| {
+                        for divider in pane_dividers.iter() {
                             window.paint_quad(fill(
-                                content_bounds,
-                                rgb(active_theme_palette.terminal_bg),
+                                pane_bounds(divider),
+                                rgb(active_theme_palette.ui_bg),
                             ));
-                            for (row_idx, cached_row) in row_text_cache.iter().enumerate() {
-                                for span in cached_row.background_spans.iter() {
-                                    let span_bounds = Bounds {
-                                        origin: point(
-                                            bounds.origin.x
-                                                + cell_size.width * span.start_col as f32,
-                                            bounds.origin.y + cell_size.height * row_idx as f32,
-                                        ),
-                                        size: size(
-                                            cell_size.width * span.len as f32,
-                                            cell_size.height,
-                                        ),
-                                    };
-                                    window.paint_quad(fill(span_bounds, span.color));
-                                }
-                            }
-
-                            for (row_idx, cached_row) in row_text_cache.iter().enumerate() {
-                                for run in cached_row.text_runs.iter() {
-                                    let origin = point(
-                                        bounds.origin.x + cell_size.width * run.start_col as f32,
-                                        bounds.origin.y + cell_size.height * row_idx as f32,
-                                    );
-                                    let _ = run.shaped.paint(origin, cell_size.height, window, cx);
-                                }
-                            }
-
-                            if snapshot.cursor_draw_visible
-                                && snapshot.cursor_col < snapshot.num_cols
-                            {
-                                if let Some(cursor_row) = snapshot.cursor_row {
-                                    let cell_bounds = Bounds {
-                                        origin: point(
-                                            bounds.origin.x
-                                                + cell_size.width * snapshot.cursor_col as f32,
-                                            bounds.origin.y + cell_size.height * cursor_row as f32,
-                                        ),
-                                        size: size(cell_size.width, cell_size.height),
-                                    };
-                                    match snapshot.cursor_shape {
-                                        CursorShape::Beam => {
-                                            let width = beam_cursor_width(cell_size.width);
-                                            let cursor_bounds = Bounds {
-                                                origin: cell_bounds.origin,
-                                                size: size(width, cell_size.height),
-                                            };
-                                            window.paint_quad(fill(
-                                                cursor_bounds,
-                                                rgb(active_theme_palette.cursor),
-                                            ));
-                                        }
-                                        CursorShape::Underline => {
-                                            let height = underline_cursor_height(cell_size.height);
-                                            let cursor_bounds = Bounds {
-                                                origin: point(
-                                                    cell_bounds.origin.x,
-                                                    cell_bounds.origin.y + cell_size.height
-                                                        - height,
-                                                ),
-                                                size: size(cell_size.width, height),
-                                            };
-                                            window.paint_quad(fill(
-                                                cursor_bounds,
-                                                rgb(active_theme_palette.cursor),
-                                            ));
-                                        }
-                                        CursorShape::HollowBlock => {
-                                            let stroke = hollow_cursor_thickness(cell_size);
-                                            let color = rgb(active_theme_palette.cursor);
-                                            let top = Bounds {
-                                                origin: cell_bounds.origin,
-                                                size: size(cell_bounds.size.width, stroke),
-                                            };
-                                            let bottom = Bounds {
-                                                origin: point(
-                                                    cell_bounds.origin.x,
-                                                    cell_bounds.origin.y + cell_bounds.size.height
-                                                        - stroke,
-                                                ),
-                                                size: size(cell_bounds.size.width, stroke),
-                                            };
-                                            let left = Bounds {
-                                                origin: cell_bounds.origin,
-                                                size: size(stroke, cell_bounds.size.height),
-                                            };
-                                            let right = Bounds {
-                                                origin: point(
-                                                    cell_bounds.origin.x + cell_bounds.size.width
-                                                        - stroke,
-                                                    cell_bounds.origin.y,
-                                                ),
-                                                size: size(stroke, cell_bounds.size.height),
-                                            };
-                                            window.paint_quad(fill(top, color));
-                                            window.paint_quad(fill(bottom, color));
-                                            window.paint_quad(fill(left, color));
-                                            window.paint_quad(fill(right, color));
-                                        }
-                                        _ => {
-                                            window.paint_quad(fill(
-                                                cell_bounds,
-                                                rgb(active_theme_palette.cursor),
-                                            ));
-                                        }
-                                    }
-                                }
-                            }
-
-                            if let Some(layout) = &scrollbar {
-                                window.paint_quad(fill(layout.track, hsla(0.0, 0.0, 0.0, 0.0)));
-                                window.paint_quad(fill(
-                                    layout.thumb,
-                                    hsla(223.0 / 360.0, 0.14, 0.34, 0.6),
-                                ));
-                            }
+                        }
+                        for (pane_grid, pane_snapshot, pane_rows) in unfocused_panes.iter() {
+                            paint_terminal_pane(
+                                pane_bounds(pane_grid),
+                                pane_snapshot,
+                                pane_rows,
+                                cell_size,
+                                pane_palette,
+                                window,
+                                cx,
+                            );
+                        }
+                        let focused_bounds = if has_split_panes {
+                            pane_bounds(&focused_pane_grid)
+                        } else {
+                            bounds
+                        };
+                        paint_terminal_pane(
+                            focused_bounds,
+                            &snapshot,
+                            &row_text_cache,
+                            cell_size,
+                            pane_palette,
+                            window,
+                            cx,
+                        );
+                        let (fg_r, fg_g, fg_b) = active_theme_palette.foreground;
+                        let underline_color = alac_rgb_to_hsla(AlacRgb {
+                            r: fg_r,
+                            g: fg_g,
+                            b: fg_b,
                         });
+                        let underline_height = underline_cursor_height(cell_size.height);
+                        for (row, start_col, len) in hyperlink_underline_spans.iter() {
+                            window.paint_quad(fill(
+                                Bounds {
+                                    origin: point(
+                                        focused_bounds.origin.x
+                                            + cell_size.width * *start_col as f32,
+                                        focused_bounds.origin.y
+                                            + cell_size.height * (*row + 1) as f32
+                                            - underline_height,
+                                    ),
+                                    size: size(cell_size.width * *len as f32, underline_height),
+                                },
+                                underline_color,
+                            ));
+                        }
+                        if bell_flashing {
+                            window.paint_quad(fill(
+                                focused_bounds,
+                                underline_color.opacity(BELL_FLASH_ALPHA),
+                            ));
+                        }
 
                         perf.record_frame(
                             snapshot_timing,
@@ -2915,8 +6507,34 @@ impl Render for TerminalView {
                     },
                 )
                 .size_full(),
-            );
+            )
+            .when_some(scroll_position, |this, (label, opacity)| {
+                let mut background = Hsla::from(rgb(active_theme_palette.ui_bg));
+                background.a = 0.88 * opacity;
+                let lightness = if active_theme_palette.is_light() {
+                    0.0
+                } else {
+                    1.0
+                };
+                this.child(
+                    div()
+                        .id("scroll-position-label")
+                        .absolute()
+                        .top(px(SCROLL_POSITION_LABEL_TOP_PX))
+                        .right(px(SCROLL_POSITION_LABEL_RIGHT_PX))
+                        .px_2()
+                        .py_1()
+                        .rounded_sm()
+                        .border_1()
+                        .border_color(hsla(0.0, 0.0, lightness, 0.12 * opacity))
+                        .bg(background)
+                        .text_xs()
+                        .text_color(hsla(0.0, 0.0, lightness, 0.78 * opacity))
+                        .child(label),
+                )
+            });
 
+        let tab_bar_position = self.tab_bar_layout();
         let tab_bar = div()
             .id("tab-bar")
             .h(px(TAB_BAR_HEIGHT_PX))
@@ -2925,7 +6543,8 @@ impl Render for TerminalView {
             .flex_row()
             .items_center()
             .bg(rgb(active_theme_palette.ui_bg))
-            .border_b_1()
+            .when(tab_bar_position == TabBarPosition::Bottom, |bar| bar.border_t_1())
+            .when(tab_bar_position != TabBarPosition::Bottom, |bar| bar.border_b_1())
             .border_color(hsla(0.0, 0.0, 1.0, 0.04))
             .child(
                 div()
@@ -2933,6 +6552,11 @@ impl Render for TerminalView {
                     .flex_none()
                     .h_full()
                     .window_control_area(WindowControlArea::Drag)
+                    // Linux backends ignore control areas, so ask the
+                    // compositor to move the window instead.
+                    .on_mouse_down(MouseButton::Left, |_event, window, _cx| {
+                        window.start_window_move();
+                    })
                     .border_r_1()
                     .border_color(hsla(0.0, 0.0, 1.0, 0.04)),
             )
@@ -2957,8 +6581,9 @@ impl Render for TerminalView {
                             .overflow_x_scroll()
                             .scrollbar_width(px(0.0))
                             .children(tabs_for_render.into_iter().map(
-                                |(tab_id, tab_title, is_last)| {
+                                |(tab_id, tab_title, tab_tooltip, rename_draft, unread_dot, tint, is_last)| {
                                     let is_active = tab_id == active_tab_id;
+                                    let is_renaming = rename_draft.is_some();
                                     let is_hovered = hovered_tab_id == Some(tab_id);
                                     let show_close_button = is_hovered && tab_count > 1;
                                     div()
@@ -3003,10 +6628,28 @@ impl Render for TerminalView {
                                                     MouseButton::Left,
                                                     cx.listener(
                                                         move |this,
-                                                              _event: &MouseDownEvent,
+                                                              event: &MouseDownEvent,
                                                               window,
                                                               cx| {
                                                             this.set_active_tab(tab_id, window, cx);
+                                                            if event.click_count == 2 {
+                                                                this.begin_tab_rename(tab_id, cx);
+                                                            }
+                                                        },
+                                                    ),
+                                                )
+                                                .on_mouse_down(
+                                                    MouseButton::Right,
+                                                    cx.listener(
+                                                        move |this,
+                                                              event: &MouseDownEvent,
+                                                              _window,
+                                                              cx| {
+                                                            cx.stop_propagation();
+                                                            this.context_menu_position =
+                                                                Some(event.position);
+                                                            this.context_menu_tab_id = Some(tab_id);
+                                                            cx.notify();
                                                         },
                                                     ),
                                                 )
@@ -3016,30 +6659,46 @@ impl Render for TerminalView {
                                                         .flex()
                                                         .items_center()
                                                         .gap_2()
+                                                        .when_some(unread_dot, |this, color| {
+                                                            this.child(
+                                                                div()
+                                                                    .id(("tab-unread", tab_id))
+                                                                    .size(px(TAB_UNREAD_DOT_SIZE_PX))
+                                                                    .flex_none()
+                                                                    .rounded_full()
+                                                                    .bg(color),
+                                                            )
+                                                        })
                                                         .child(
                                                             div()
                                                                 .flex_1()
                                                                 .truncate()
                                                                 .id(("tab-title", tab_id))
                                                                 .tooltip({
-                                                                    let tab_title = tab_title.clone();
                                                                     move |_window, cx| {
                                                                         cx.new({
-                                                                            let tab_title = tab_title.clone();
+                                                                            let tab_tooltip = tab_tooltip.clone();
                                                                             move |_cx| TabTitleTooltip {
-                                                                                title: tab_title.clone().into(),
+                                                                                title: tab_tooltip.clone().into(),
                                                                             }
                                                                         })
                                                                         .into()
                                                                     }
                                                                 })
                                                                 .text_xs()
-                                                                .text_color(if is_active {
+                                                                .text_color(if is_active || is_renaming {
                                                                     hsla(0.0, 0.0, 1.0, 0.9)
                                                                 } else {
                                                                     hsla(0.0, 0.0, 1.0, 0.5)
                                                                 })
-                                                                .child(tab_title),
+                                                                .when(is_renaming, |this| {
+                                                                    this.px_1()
+                                                                        .rounded_sm()
+                                                                        .bg(hsla(0.0, 0.0, 0.0, 0.75))
+                                                                        .border_1()
+                                                                        .border_color(tab_brand_purple(0.6))
+                                                                })
+                                                                .child(rename_draft.unwrap_or(tab_title)),
                                                         )
                                                         .when(show_close_button, |this| {
                                                             this.child(
@@ -3082,11 +6741,10 @@ impl Render for TerminalView {
                                                 ),
                                         )
                                         .child(
-                                            div().h(px(TAB_ITEM_INDICATOR_HEIGHT_PX)).w_full().bg(if is_active {
-                                                ui_accent
-                                            } else {
-                                                hsla(0.0, 0.0, 1.0, 0.0)
-                                            }),
+                                            div()
+                                                .h(px(TAB_ITEM_INDICATOR_HEIGHT_PX))
+                                                .w_full()
+                                                .bg(tab_indicator_color(is_active, tint, ui_accent)),
                                         )
                                         .child(div().h(px(TAB_ITEM_INDICATOR_BOTTOM_GAP_PX)).w_full())
                                 },
@@ -3224,7 +6882,7 @@ impl Render for TerminalView {
                                         .on_mouse_down(
                                             MouseButton::Left,
                                             cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
-                                                this.find_state = None;
+                                                this.close_find();
                                                 cx.notify();
                                             }),
                                         )
@@ -3232,6 +6890,59 @@ impl Render for TerminalView {
                                 ),
                         )
                     })
+                    .when(show_broadcast_indicator, |this| {
+                        this.child(
+                            div()
+                                .id("broadcast-input-indicator")
+                                .h(px(TAB_ITEM_HEIGHT_PX))
+                                .px_2()
+                                .flex()
+                                .items_center()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(tab_brand_purple(0.6))
+                                .text_xs()
+                                .text_color(tab_brand_purple(1.0))
+                                .cursor_pointer()
+                                .hover(|style| style.bg(tab_brand_purple(0.18)))
+                                .tooltip(|_window, cx| {
+                                    cx.new(|_cx| TabTitleTooltip {
+                                        title: BROADCAST_INPUT_INDICATOR_TOOLTIP.into(),
+                                    })
+                                    .into()
+                                })
+                                .on_mouse_down(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                        this.broadcast_input = false;
+                                        cx.notify();
+                                    }),
+                                )
+                                .child("BROADCAST"),
+                        )
+                    })
+                    .when(show_alt_screen_indicator, |this| {
+                        this.child(
+                            div()
+                                .id("alt-screen-indicator")
+                                .h(px(TAB_ITEM_HEIGHT_PX))
+                                .px_2()
+                                .flex()
+                                .items_center()
+                                .rounded_sm()
+                                .border_1()
+                                .border_color(hsla(0.0, 0.0, 1.0, 0.12))
+                                .text_xs()
+                                .text_color(hsla(0.0, 0.0, 1.0, 0.52))
+                                .tooltip(|_window, cx| {
+                                    cx.new(|_cx| TabTitleTooltip {
+                                        title: ALT_SCREEN_INDICATOR_TOOLTIP.into(),
+                                    })
+                                    .into()
+                                })
+                                .child("ALT"),
+                        )
+                    })
                     .when(find_panel_state.is_none(), |this| {
                         this.child(
                         div()
@@ -3257,7 +6968,7 @@ impl Render for TerminalView {
                             .on_mouse_down(
                                 MouseButton::Left,
                                 cx.listener(|this, _event: &MouseDownEvent, window, cx| {
-                                    this.create_tab(window, cx);
+                                    this.handle_add_tab_click(window, cx);
                                 }),
                             )
                             .child("+"),
@@ -3292,9 +7003,11 @@ impl Render for TerminalView {
                             })
                             .on_mouse_down(
                                 MouseButton::Left,
-                                cx.listener(move |this, _event: &MouseDownEvent, window, cx| {
+                                cx.listener(move |this, _event: &MouseDownEvent, _window, cx| {
                                     if has_multiple_tabs {
-                                        this.set_active_tab_relative(1, window, cx);
+                                        this.tab_dropdown_open = !this.tab_dropdown_open;
+                                        this.hovered_dropdown_tab_id = None;
+                                        cx.notify();
                                     }
                                 }),
                             )
@@ -3517,6 +7230,26 @@ impl Render for TerminalView {
                                                 }),
                                             )
                                             .child(">"),
+                                    )
+                                    .child(
+                                        div()
+                                            .h(settings_control_height)
+                                            .w(px(SETTINGS_NUMERIC_BUTTON_WIDTH_PX))
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded_sm()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.78))
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.toggle_light_dark_theme(cx);
+                                                }),
+                                            )
+                                            .child(light_dark_toggle_symbol),
                                     ),
                             ),
                     )
@@ -4388,29 +8121,48 @@ impl Render for TerminalView {
                                 div()
                                     .text_xs()
                                     .text_color(hsla(0.0, 0.0, 1.0, 0.72))
-                                    .child("Show/Hide Shortcut"),
+                                    .child("Scrollback Lines"),
                             )
                             .child(
                                 div()
                                     .flex()
                                     .items_center()
-                                    .gap_2()
+                                    .gap_1()
                                     .child(
                                         div()
-                                            .w(px(168.0))
+                                            .h(settings_control_height)
+                                            .w(px(SETTINGS_NUMERIC_BUTTON_WIDTH_PX))
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded_sm()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.78))
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.adjust_scrollback(
+                                                        -SETTINGS_SCROLLBACK_STEP,
+                                                        cx,
+                                                    );
+                                                }),
+                                            )
+                                            .child("-"),
+                                    )
+                                    .child(
+                                        div()
+                                            .w(px(64.0))
                                             .text_center()
                                             .text_xs()
                                             .text_color(hsla(0.0, 0.0, 1.0, 0.84))
-                                            .child(if recording_global_hotkey {
-                                                "Press shortcut...".to_string()
-                                            } else {
-                                                global_hotkey_display.clone()
-                                            }),
+                                            .child(scrollback_display),
                                     )
                                     .child(
                                         div()
-                                            .px_2()
                                             .h(settings_control_height)
+                                            .w(px(SETTINGS_NUMERIC_BUTTON_WIDTH_PX))
                                             .flex()
                                             .items_center()
                                             .justify_center()
@@ -4418,56 +8170,189 @@ impl Render for TerminalView {
                                             .text_xs()
                                             .text_color(hsla(0.0, 0.0, 1.0, 0.78))
                                             .cursor_pointer()
-                                            .border_1()
-                                            .border_color(hsla(0.0, 0.0, 1.0, 0.18))
-                                            .bg(if recording_global_hotkey {
-                                                hsla(0.0, 0.0, 1.0, 0.16)
-                                            } else {
-                                                hsla(0.0, 0.0, 1.0, 0.0)
-                                            })
-                                            .hover(|style| {
-                                                style
-                                                    .bg(tab_brand_purple(0.22))
-                                                    .border_color(tab_brand_purple(0.9))
-                                            })
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
                                             .on_mouse_down(
                                                 MouseButton::Left,
                                                 cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
-                                                    this.set_global_hotkey_recording(
-                                                        !this.recording_global_hotkey,
+                                                    this.adjust_scrollback(
+                                                        SETTINGS_SCROLLBACK_STEP,
                                                         cx,
                                                     );
                                                 }),
                                             )
-                                            .child(if recording_global_hotkey {
-                                                "cancel"
-                                            } else {
-                                                "record"
-                                            }),
+                                            .child("+"),
                                     ),
                             ),
                     )
-                    .child(div().text_xs().text_color(hsla(0.0, 0.0, 1.0, 0.46)).child("Advanced"))
                     .child(
                         div()
                             .p_3()
                             .rounded_sm()
                             .border_1()
                             .border_color(hsla(0.0, 0.0, 1.0, 0.12))
-                            .text_xs()
-                            .text_color(hsla(0.0, 0.0, 1.0, 0.46))
-                            .child(format!(
-                                "Cursor: {}  •  Blinking: {}",
-                                cursor_shape_display, blinking_display
-                            ))
+                            .flex()
+                            .items_center()
+                            .justify_between()
                             .child(
                                 div()
-                                    .mt_2()
-                                    .child("Advanced options are available in ~/.simple-term/settings.json"),
+                                    .text_xs()
+                                    .text_color(hsla(0.0, 0.0, 1.0, 0.72))
+                                    .child("Path Link Timeout"),
                             )
                             .child(
                                 div()
-                                    .mt_2()
+                                    .flex()
+                                    .items_center()
+                                    .gap_1()
+                                    .child(
+                                        div()
+                                            .h(settings_control_height)
+                                            .w(px(SETTINGS_NUMERIC_BUTTON_WIDTH_PX))
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded_sm()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.78))
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.adjust_hyperlink_timeout(
+                                                        -SETTINGS_HYPERLINK_TIMEOUT_STEP_MS,
+                                                        cx,
+                                                    );
+                                                }),
+                                            )
+                                            .child("-"),
+                                    )
+                                    .child(
+                                        div()
+                                            .w(px(64.0))
+                                            .text_center()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.84))
+                                            .child(hyperlink_timeout_display),
+                                    )
+                                    .child(
+                                        div()
+                                            .h(settings_control_height)
+                                            .w(px(SETTINGS_NUMERIC_BUTTON_WIDTH_PX))
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded_sm()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.78))
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.adjust_hyperlink_timeout(
+                                                        SETTINGS_HYPERLINK_TIMEOUT_STEP_MS,
+                                                        cx,
+                                                    );
+                                                }),
+                                            )
+                                            .child("+"),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .p_3()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.12))
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(hsla(0.0, 0.0, 1.0, 0.72))
+                                    .child("Show/Hide Shortcut"),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .child(
+                                        div()
+                                            .w(px(168.0))
+                                            .text_center()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.84))
+                                            .child(if recording_global_hotkey {
+                                                "Press shortcut...".to_string()
+                                            } else {
+                                                global_hotkey_display.clone()
+                                            }),
+                                    )
+                                    .child(
+                                        div()
+                                            .px_2()
+                                            .h(settings_control_height)
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .rounded_sm()
+                                            .text_xs()
+                                            .text_color(hsla(0.0, 0.0, 1.0, 0.78))
+                                            .cursor_pointer()
+                                            .border_1()
+                                            .border_color(hsla(0.0, 0.0, 1.0, 0.18))
+                                            .bg(if recording_global_hotkey {
+                                                hsla(0.0, 0.0, 1.0, 0.16)
+                                            } else {
+                                                hsla(0.0, 0.0, 1.0, 0.0)
+                                            })
+                                            .hover(|style| {
+                                                style
+                                                    .bg(tab_brand_purple(0.22))
+                                                    .border_color(tab_brand_purple(0.9))
+                                            })
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.set_global_hotkey_recording(
+                                                        !this.recording_global_hotkey,
+                                                        cx,
+                                                    );
+                                                }),
+                                            )
+                                            .child(if recording_global_hotkey {
+                                                "cancel"
+                                            } else {
+                                                "record"
+                                            }),
+                                    ),
+                            ),
+                    )
+                    .child(div().text_xs().text_color(hsla(0.0, 0.0, 1.0, 0.46)).child("Advanced"))
+                    .child(
+                        div()
+                            .p_3()
+                            .rounded_sm()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.12))
+                            .text_xs()
+                            .text_color(hsla(0.0, 0.0, 1.0, 0.46))
+                            .child(format!(
+                                "Cursor: {}  •  Blinking: {}",
+                                cursor_shape_display, blinking_display
+                            ))
+                            .child(
+                                div()
+                                    .mt_2()
+                                    .child("Advanced options are available in ~/.simple-term/settings.json"),
+                            )
+                            .child(
+                                div()
+                                    .mt_2()
                                     .text_color(hsla(0.0, 0.0, 1.0, 0.56))
                                     .child(
                                         "Includes shell, working directory, env, path regexes, and pin hotkey.",
@@ -4515,14 +8400,17 @@ impl Render for TerminalView {
             .relative()
             .bg(rgb(active_theme_palette.terminal_bg))
             .flex()
-            .flex_col()
-            .child(tab_bar)
-            .child(content_row);
+            .flex_col();
+        terminal_root = match tab_bar_position {
+            TabBarPosition::Top => terminal_root.child(tab_bar).child(content_row),
+            TabBarPosition::Bottom => terminal_root.child(content_row).child(tab_bar),
+            TabBarPosition::Hidden => terminal_root.child(content_row),
+        };
 
-        if settings_panel_open {
+        if let Some(tab_dropdown_entries) = tab_dropdown_entries {
             terminal_root = terminal_root.child(
                 div()
-                    .id("settings-popup-overlay")
+                    .id("tab-dropdown-overlay")
                     .absolute()
                     .top(px(0.0))
                     .right(px(0.0))
@@ -4536,62 +8424,531 @@ impl Render for TerminalView {
                             .right(px(0.0))
                             .bottom(px(0.0))
                             .left(px(0.0))
-                            .bg(hsla(0.0, 0.0, 0.0, SETTINGS_OVERLAY_BACKDROP_ALPHA))
                             .on_mouse_down(
                                 MouseButton::Left,
                                 cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
-                                    this.settings_panel_open = false;
-                                    this.recording_global_hotkey = false;
+                                    this.tab_dropdown_open = false;
+                                    this.hovered_dropdown_tab_id = None;
                                     cx.notify();
                                 }),
                             ),
                     )
                     .child(
                         div()
-                            .relative()
-                            .size_full()
-                            .p_4()
+                            .id("tab-dropdown-menu")
+                            .absolute()
+                            .when(tab_bar_position == TabBarPosition::Bottom, |menu| {
+                                menu.bottom(px(TAB_BAR_HEIGHT_PX))
+                            })
+                            .when(tab_bar_position != TabBarPosition::Bottom, |menu| {
+                                menu.top(px(TAB_BAR_HEIGHT_PX))
+                            })
+                            .right(px(TAB_DROPDOWN_MENU_RIGHT_OFFSET_PX))
+                            .w(px(TAB_DROPDOWN_MENU_WIDTH_PX))
+                            .max_h(px(TAB_DROPDOWN_MENU_MAX_HEIGHT_PX))
+                            .overflow_y_scroll()
+                            .p_1()
                             .flex()
-                            .items_center()
-                            .justify_center()
-                            .child(settings_drawer),
+                            .flex_col()
+                            .gap_1()
+                            .rounded_lg()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                            .bg(rgb(active_theme_palette.ui_bg))
+                            .children(tab_dropdown_entries.into_iter().map(
+                                |(tab_id, title, is_active)| {
+                                    let is_hovered = hovered_dropdown_tab_id == Some(tab_id);
+                                    div()
+                                        .id(("tab-dropdown-item", tab_id))
+                                        .w_full()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded_sm()
+                                        .text_xs()
+                                        .cursor_pointer()
+                                        .text_color(if is_active {
+                                            hsla(0.0, 0.0, 1.0, 0.92)
+                                        } else {
+                                            hsla(0.0, 0.0, 1.0, 0.62)
+                                        })
+                                        .when(is_active, |this| this.bg(tab_brand_purple(0.28)))
+                                        .when(!is_hovered, |this| this.truncate())
+                                        .hover(|style| style.bg(tab_brand_purple(0.18)))
+                                        .on_hover(cx.listener(
+                                            move |this, is_hovered_event: &bool, _window, cx| {
+                                                let next = Self::hovered_tab_id_after_event(
+                                                    this.hovered_dropdown_tab_id,
+                                                    tab_id,
+                                                    *is_hovered_event,
+                                                );
+                                                if next != this.hovered_dropdown_tab_id {
+                                                    this.hovered_dropdown_tab_id = next;
+                                                    cx.notify();
+                                                }
+                                            },
+                                        ))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(
+                                                move |this, _event: &MouseDownEvent, window, cx| {
+                                                    this.select_tab_from_dropdown(
+                                                        tab_id, window, cx,
+                                                    );
+                                                },
+                                            ),
+                                        )
+                                        .child(title)
+                                },
+                            )),
                     ),
             );
         }
 
-        terminal_root
-    }
-}
-
-/// Snapshot of the terminal color palette.
-#[derive(Clone, PartialEq, Eq)]
-struct ColorsSnapshot {
-    palette: [AlacRgb; 256],
-    foreground: AlacRgb,
-    background: AlacRgb,
-}
-
-impl ColorsSnapshot {
-    fn from_colors(colors: &AlacColors, theme: TerminalTheme) -> Self {
-        let mut palette = [AlacRgb { r: 0, g: 0, b: 0 }; 256];
-        let palette_theme = theme_palette(theme);
-
-        for (i, &(r, g, b)) in palette_theme.ansi_colors.iter().enumerate() {
-            palette[i] = colors[i].unwrap_or(AlacRgb { r, g, b });
+        if let Some(profile_picker_entries) = profile_picker_entries {
+            terminal_root = terminal_root.child(
+                div()
+                    .id("profile-picker-overlay")
+                    .absolute()
+                    .top(px(0.0))
+                    .right(px(0.0))
+                    .bottom(px(0.0))
+                    .left(px(0.0))
+                    .occlude()
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(0.0))
+                            .right(px(0.0))
+                            .bottom(px(0.0))
+                            .left(px(0.0))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.profile_picker_open = false;
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("profile-picker-menu")
+                            .absolute()
+                            .when(tab_bar_position == TabBarPosition::Bottom, |menu| {
+                                menu.bottom(px(TAB_BAR_HEIGHT_PX))
+                            })
+                            .when(tab_bar_position != TabBarPosition::Bottom, |menu| {
+                                menu.top(px(TAB_BAR_HEIGHT_PX))
+                            })
+                            .right(px(TAB_DROPDOWN_MENU_RIGHT_OFFSET_PX))
+                            .w(px(TAB_DROPDOWN_MENU_WIDTH_PX))
+                            .max_h(px(TAB_DROPDOWN_MENU_MAX_HEIGHT_PX))
+                            .overflow_y_scroll()
+                            .p_1()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .rounded_lg()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                            .bg(rgb(active_theme_palette.ui_bg))
+                            .child(
+                                div()
+                                    .id("profile-picker-default")
+                                    .w_full()
+                                    .px_2()
+                                    .py_1()
+                                    .rounded_sm()
+                                    .text_xs()
+                                    .cursor_pointer()
+                                    .truncate()
+                                    .text_color(hsla(0.0, 0.0, 1.0, 0.62))
+                                    .hover(|style| style.bg(tab_brand_purple(0.18)))
+                                    .on_mouse_down(
+                                        MouseButton::Left,
+                                        cx.listener(|this, _event: &MouseDownEvent, window, cx| {
+                                            this.profile_picker_open = false;
+                                            this.create_tab(window, cx);
+                                        }),
+                                    )
+                                    .child("Default shell"),
+                            )
+                            .children(profile_picker_entries.into_iter().map(
+                                |(profile_index, name)| {
+                                    div()
+                                        .id(("profile-picker-item", profile_index))
+                                        .w_full()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded_sm()
+                                        .text_xs()
+                                        .cursor_pointer()
+                                        .truncate()
+                                        .text_color(hsla(0.0, 0.0, 1.0, 0.62))
+                                        .hover(|style| style.bg(tab_brand_purple(0.18)))
+                                        .on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(
+                                                move |this, _event: &MouseDownEvent, window, cx| {
+                                                    this.create_tab_with_profile(
+                                                        profile_index,
+                                                        window,
+                                                        cx,
+                                                    );
+                                                },
+                                            ),
+                                        )
+                                        .child(name)
+                                },
+                            )),
+                    ),
+            );
         }
 
-        // 216 color cube (16-231)
-        for i in 16..232 {
-            let idx = i - 16;
-            let r = if idx / 36 > 0 {
-                (idx / 36) * 40 + 55
-            } else {
-                0
-            };
-            let g = if (idx % 36) / 6 > 0 {
-                ((idx % 36) / 6) * 40 + 55
-            } else {
-                0
+        if let Some((menu_origin, menu_items)) = context_menu {
+            terminal_root = terminal_root.child(
+                div()
+                    .id("context-menu-overlay")
+                    .absolute()
+                    .top(px(0.0))
+                    .right(px(0.0))
+                    .bottom(px(0.0))
+                    .left(px(0.0))
+                    .occlude()
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(0.0))
+                            .right(px(0.0))
+                            .bottom(px(0.0))
+                            .left(px(0.0))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.context_menu_position = None;
+                                    cx.notify();
+                                }),
+                            )
+                            .on_mouse_down(
+                                MouseButton::Right,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.context_menu_position = None;
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .id("context-menu")
+                            .absolute()
+                            .left(menu_origin.x)
+                            .top(menu_origin.y)
+                            .w(px(CONTEXT_MENU_WIDTH_PX))
+                            .p(px(CONTEXT_MENU_PADDING_PX))
+                            .flex()
+                            .flex_col()
+                            .rounded_lg()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                            .bg(rgb(active_theme_palette.ui_bg))
+                            .children(menu_items.into_iter().enumerate().map(
+                                |(index, (action, enabled))| {
+                                    div()
+                                        .id(("context-menu-item", index))
+                                        .h(px(CONTEXT_MENU_ITEM_HEIGHT_PX))
+                                        .w_full()
+                                        .px_2()
+                                        .flex()
+                                        .items_center()
+                                        .rounded_sm()
+                                        .text_xs()
+                                        .text_color(if enabled {
+                                            hsla(0.0, 0.0, 1.0, 0.86)
+                                        } else {
+                                            hsla(0.0, 0.0, 1.0, 0.32)
+                                        })
+                                        .when(enabled, |this| {
+                                            this.cursor_pointer()
+                                            .hover(|style| style.bg(tab_brand_purple(0.22)))
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    move |this,
+                                                          _event: &MouseDownEvent,
+                                                          _window,
+                                                          cx| {
+                                                        this.run_context_menu_action(action, cx);
+                                                    },
+                                                ),
+                                            )
+                                        })
+                                        .when_some(action.swatch(), |this, swatch| {
+                                            this.gap_2().child(
+                                                div()
+                                                    .size(px(CONTEXT_MENU_SWATCH_SIZE_PX))
+                                                    .flex_none()
+                                                    .rounded_full()
+                                                    .bg(rgb(swatch)),
+                                            )
+                                        })
+                                        .child(action.label())
+                                },
+                            )),
+                    ),
+            );
+        }
+
+        if settings_panel_open {
+            terminal_root = terminal_root.child(
+                div()
+                    .id("settings-popup-overlay")
+                    .absolute()
+                    .top(px(0.0))
+                    .right(px(0.0))
+                    .bottom(px(0.0))
+                    .left(px(0.0))
+                    .occlude()
+                    .child(
+                        div()
+                            .absolute()
+                            .top(px(0.0))
+                            .right(px(0.0))
+                            .bottom(px(0.0))
+                            .left(px(0.0))
+                            .bg(hsla(0.0, 0.0, 0.0, SETTINGS_OVERLAY_BACKDROP_ALPHA))
+                            .on_mouse_down(
+                                MouseButton::Left,
+                                cx.listener(|this, _event: &MouseDownEvent, _window, cx| {
+                                    this.settings_panel_open = false;
+                                    this.recording_global_hotkey = false;
+                                    cx.notify();
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .relative()
+                            .size_full()
+                            .p_4()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .child(settings_drawer),
+                    ),
+            );
+        }
+
+        if let Some((preview, line_count)) = paste_preview {
+            let button = |id: &'static str, label: &'static str| {
+                div()
+                    .id(id)
+                    .px_3()
+                    .py_1()
+                    .rounded_sm()
+                    .text_xs()
+                    .cursor_pointer()
+                    .border_1()
+                    .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                    .text_color(hsla(0.0, 0.0, 1.0, 0.86))
+                    .hover(|style| style.bg(tab_brand_purple(0.22)))
+                    .child(label)
+            };
+            terminal_root = terminal_root.child(
+                div()
+                    .id("paste-confirmation-overlay")
+                    .absolute()
+                    .top(px(0.0))
+                    .right(px(0.0))
+                    .bottom(px(0.0))
+                    .left(px(0.0))
+                    .occlude()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .bg(hsla(0.0, 0.0, 0.0, SETTINGS_OVERLAY_BACKDROP_ALPHA))
+                    .child(
+                        div()
+                            .id("paste-confirmation")
+                            .max_w(px(PASTE_CONFIRMATION_MAX_WIDTH_PX))
+                            .p_3()
+                            .flex()
+                            .flex_col()
+                            .gap_3()
+                            .rounded_lg()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                            .bg(rgb(active_theme_palette.ui_bg))
+                            .text_sm()
+                            .text_color(hsla(0.0, 0.0, 1.0, 0.92))
+                            .child(format!(
+                                "Paste {line_count} lines? Each line may run as a command."
+                            ))
+                            .child(
+                                div()
+                                    .p_2()
+                                    .rounded_sm()
+                                    .bg(rgb(active_theme_palette.terminal_bg))
+                                    .font_family(self.settings.font_family.clone())
+                                    .text_xs()
+                                    .whitespace_nowrap()
+                                    .overflow_hidden()
+                                    .children(preview.into_iter().map(|line| div().child(line))),
+                            )
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .justify_end()
+                                    .gap_2()
+                                    .child(
+                                        button("paste-confirmation-cancel", "Cancel")
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    |this, _event: &MouseDownEvent, _window, cx| {
+                                                        this.paste_confirmation = None;
+                                                        cx.notify();
+                                                    },
+                                                ),
+                                            ),
+                                    )
+                                    .child(
+                                        button("paste-confirmation-paste", "Paste").on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(
+                                                |this, _event: &MouseDownEvent, _window, cx| {
+                                                    this.confirm_paste(cx);
+                                                },
+                                            ),
+                                        ),
+                                    ),
+                            ),
+                    ),
+            );
+        }
+
+        if close_confirmation_open {
+            let button = |id: &'static str, label: &'static str| {
+                div()
+                    .id(id)
+                    .px_3()
+                    .py_1()
+                    .rounded_sm()
+                    .text_xs()
+                    .cursor_pointer()
+                    .border_1()
+                    .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                    .text_color(hsla(0.0, 0.0, 1.0, 0.86))
+                    .hover(|style| style.bg(tab_brand_purple(0.22)))
+                    .child(label)
+            };
+            terminal_root = terminal_root.child(
+                div()
+                    .id("close-confirmation-overlay")
+                    .absolute()
+                    .top(px(0.0))
+                    .right(px(0.0))
+                    .bottom(px(0.0))
+                    .left(px(0.0))
+                    .occlude()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .bg(hsla(0.0, 0.0, 0.0, SETTINGS_OVERLAY_BACKDROP_ALPHA))
+                    .child(
+                        div()
+                            .id("close-confirmation")
+                            .p_3()
+                            .flex()
+                            .flex_col()
+                            .gap_3()
+                            .rounded_lg()
+                            .border_1()
+                            .border_color(hsla(0.0, 0.0, 1.0, 0.14))
+                            .bg(rgb(active_theme_palette.ui_bg))
+                            .text_sm()
+                            .text_color(hsla(0.0, 0.0, 1.0, 0.92))
+                            .child("A process is running. Close anyway?")
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_row()
+                                    .justify_end()
+                                    .gap_2()
+                                    .child(
+                                        button("close-confirmation-cancel", "Cancel")
+                                            .on_mouse_down(
+                                                MouseButton::Left,
+                                                cx.listener(
+                                                    |this, _event: &MouseDownEvent, _window, cx| {
+                                                        this.close_confirmation_tab_id = None;
+                                                        cx.notify();
+                                                    },
+                                                ),
+                                            ),
+                                    )
+                                    .child(
+                                        button("close-confirmation-close", "Close").on_mouse_down(
+                                            MouseButton::Left,
+                                            cx.listener(
+                                                |this, _event: &MouseDownEvent, window, cx| {
+                                                    this.confirm_close_tab(window, cx);
+                                                },
+                                            ),
+                                        ),
+                                    ),
+                            ),
+                    ),
+            );
+        }
+
+        terminal_root
+    }
+}
+
+/// Snapshot of the terminal color palette.
+#[derive(Clone, PartialEq)]
+struct ColorsSnapshot {
+    palette: [AlacRgb; 256],
+    foreground: AlacRgb,
+    background: AlacRgb,
+    /// Bold text in ANSI colors 0-7 uses colors 8-15.
+    bold_is_bright: bool,
+    /// Contrast ratio text must reach against its cell background.
+    minimum_contrast: f32,
+    /// Fraction every color moves toward `background`; 0 unless the window
+    /// is inactive and `unfocused_dim` is set.
+    dim: f32,
+    /// `selection_background`, replacing the selection tint when set.
+    selection_background: Option<AlacRgb>,
+    /// `selection_foreground`, replacing the color of selected text when set.
+    selection_foreground: Option<AlacRgb>,
+}
+
+impl ColorsSnapshot {
+    fn from_colors(
+        colors: &AlacColors,
+        palette_theme: ThemePalette,
+        settings: &TerminalSettings,
+        dim: f32,
+    ) -> Self {
+        let mut palette = [AlacRgb { r: 0, g: 0, b: 0 }; 256];
+
+        for (i, &(r, g, b)) in palette_theme.ansi_colors.iter().enumerate() {
+            palette[i] = colors[i].unwrap_or(AlacRgb { r, g, b });
+        }
+
+        // 216 color cube (16-231)
+        for i in 16..232 {
+            let idx = i - 16;
+            let r = if idx / 36 > 0 {
+                (idx / 36) * 40 + 55
+            } else {
+                0
+            };
+            let g = if (idx % 36) / 6 > 0 {
+                ((idx % 36) / 6) * 40 + 55
+            } else {
+                0
             };
             let b = if idx % 6 > 0 { (idx % 6) * 40 + 55 } else { 0 };
             palette[i] = colors[i].unwrap_or(AlacRgb {
@@ -4622,12 +8979,52 @@ impl ColorsSnapshot {
             palette,
             foreground,
             background,
+            bold_is_bright: settings.bold_is_bright,
+            minimum_contrast: settings.minimum_contrast,
+            dim,
+            selection_background: settings.selection_background.map(rgb_u32_to_alac_rgb),
+            selection_foreground: settings.selection_foreground.map(rgb_u32_to_alac_rgb),
         }
     }
+
+    /// `rgb` as drawn, faded toward the background by `dim`.
+    fn dimmed(&self, rgb: AlacRgb) -> AlacRgb {
+        if self.dim <= 0.0 {
+            return rgb;
+        }
+        blend_rgb(rgb, self.background, self.dim)
+    }
+}
+
+/// Foreground of a text run. With `bold_is_bright`, bold text in one of the
+/// eight standard ANSI colors moves to its bright variant.
+fn run_foreground(fg: AlacColor, bold: bool, bold_is_bright: bool) -> AlacColor {
+    if !(bold && bold_is_bright) {
+        return fg;
+    }
+    match fg {
+        AlacColor::Named(named) if (named as usize) < 8 => AlacColor::Named(named.to_bright()),
+        AlacColor::Indexed(index) if index < 8 => AlacColor::Indexed(index + 8),
+        other => other,
+    }
+}
+
+/// Color of the glyph drawn on a block cursor: `cursor_text_color` when set,
+/// otherwise the cell's own background so the glyph reads as inverted.
+fn cursor_text_rgb(setting: Option<u32>, cell: &GridCell, colors: &ColorsSnapshot) -> AlacRgb {
+    if let Some(color) = setting {
+        return rgb_u32_to_alac_rgb(color);
+    }
+    let background = if cell.flags.contains(Flags::INVERSE) {
+        &cell.fg
+    } else {
+        &cell.bg
+    };
+    resolve_alac_rgb(background, colors, false)
 }
 
 fn resolve_color(color: &AlacColor, colors: &ColorsSnapshot, is_fg: bool) -> Hsla {
-    alac_rgb_to_hsla(resolve_alac_rgb(color, colors, is_fg))
+    alac_rgb_to_hsla(colors.dimmed(resolve_alac_rgb(color, colors, is_fg)))
 }
 
 fn alac_rgb_to_hsla(rgb: AlacRgb) -> Hsla {
@@ -4637,15 +9034,103 @@ fn alac_rgb_to_hsla(rgb: AlacRgb) -> Hsla {
     Hsla::from(Rgba { r, g, b, a: 1.0 })
 }
 
-fn selection_tint_rgb(theme: TerminalTheme) -> AlacRgb {
-    rgb_u32_to_alac_rgb(theme_palette(theme).cursor)
+/// Selection tint derived from the cursor color. When the cursor has too
+/// little contrast with the background, it is pushed toward black on light
+/// backgrounds and toward white on dark ones.
+fn selection_tint_rgb(palette: ThemePalette) -> AlacRgb {
+    let tint = rgb_u32_to_alac_rgb(palette.cursor);
+    let background = rgb_u32_to_alac_rgb(palette.terminal_bg);
+    if contrast_ratio(tint, background) >= SELECTION_TINT_MIN_CONTRAST {
+        return tint;
+    }
+
+    let anchor = if palette.is_light() {
+        AlacRgb { r: 0, g: 0, b: 0 }
+    } else {
+        AlacRgb {
+            r: 0xFF,
+            g: 0xFF,
+            b: 0xFF,
+        }
+    };
+    blend_rgb(tint, anchor, 0.5)
+}
+
+/// Thumb color, pushed further from the background and made more opaque
+/// while the pointer is over the track.
+fn scrollbar_thumb_color(palette: ThemePalette, hovered: bool) -> Hsla {
+    match (palette.is_light(), hovered) {
+        (true, false) => hsla(223.0 / 360.0, 0.14, 0.22, 0.45),
+        (true, true) => hsla(223.0 / 360.0, 0.14, 0.14, 0.65),
+        (false, false) => hsla(223.0 / 360.0, 0.14, 0.34, 0.6),
+        (false, true) => hsla(223.0 / 360.0, 0.14, 0.5, 0.8),
+    }
+}
+
+/// WCAG relative luminance in `0.0..=1.0`.
+fn relative_luminance(rgb: AlacRgb) -> f32 {
+    let linear = |channel: u8| {
+        let value = channel as f32 / 255.0;
+        if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(rgb.r) + 0.7152 * linear(rgb.g) + 0.0722 * linear(rgb.b)
+}
+
+/// WCAG contrast ratio in `1.0..=21.0`.
+fn contrast_ratio(left: AlacRgb, right: AlacRgb) -> f32 {
+    let left = relative_luminance(left);
+    let right = relative_luminance(right);
+    (left.max(right) + 0.05) / (left.min(right) + 0.05)
+}
+
+/// `fg`, moved toward white or black just far enough to reach `minimum`
+/// contrast with `bg`. Pairs that already meet the ratio are left alone.
+fn ensure_contrast(fg: AlacRgb, bg: AlacRgb, minimum: f32) -> AlacRgb {
+    if contrast_ratio(fg, bg) >= minimum {
+        return fg;
+    }
+
+    let white = AlacRgb {
+        r: 0xFF,
+        g: 0xFF,
+        b: 0xFF,
+    };
+    let black = AlacRgb { r: 0, g: 0, b: 0 };
+    let anchor = if contrast_ratio(white, bg) >= contrast_ratio(black, bg) {
+        white
+    } else {
+        black
+    };
+    if contrast_ratio(anchor, bg) < minimum {
+        return anchor;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..CONTRAST_SEARCH_STEPS {
+        let mid = (low + high) / 2.0;
+        if contrast_ratio(blend_rgb(fg, anchor, mid), bg) >= minimum {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+    blend_rgb(fg, anchor, high)
 }
 
+/// Background of a selected cell: `selection_background` when set, else the
+/// selection tint blended into the cell's own background.
 fn selection_background_color(
     background: &AlacColor,
     colors: &ColorsSnapshot,
     selection_tint: AlacRgb,
 ) -> AlacColor {
+    if let Some(selected_bg) = colors.selection_background {
+        return AlacColor::Spec(selected_bg);
+    }
     let base_bg = resolve_alac_rgb(background, colors, false);
     AlacColor::Spec(blend_rgb(base_bg, selection_tint, SELECTION_TINT_ALPHA))
 }
@@ -4692,6 +9177,30 @@ fn tab_brand_purple(alpha: f32) -> Hsla {
     hsla(272.0 / 360.0, 0.91, 0.65, alpha.clamp(0.0, 1.0))
 }
 
+/// Terminal background with the tab tint, if any, blended over it.
+fn tinted_background(terminal_bg: u32, tint: Option<u32>) -> u32 {
+    let Some(tint) = tint else {
+        return terminal_bg;
+    };
+    let blended = blend_rgb(
+        rgb_u32_to_alac_rgb(terminal_bg),
+        rgb_u32_to_alac_rgb(tint),
+        TAB_TINT_ALPHA,
+    );
+    ((blended.r as u32) << 16) | ((blended.g as u32) << 8) | blended.b as u32
+}
+
+/// Strip under a tab: the tint when set, dimmed on background tabs, or the
+/// accent on the active tab.
+fn tab_indicator_color(is_active: bool, tint: Option<u32>, accent: Hsla) -> Hsla {
+    match (tint, is_active) {
+        (Some(tint), true) => Hsla::from(rgb(tint)),
+        (Some(tint), false) => Hsla::from(rgb(tint)).opacity(0.5),
+        (None, true) => accent,
+        (None, false) => hsla(0.0, 0.0, 1.0, 0.0),
+    }
+}
+
 fn blend_rgb(base: AlacRgb, overlay: AlacRgb, overlay_alpha: f32) -> AlacRgb {
     let alpha = overlay_alpha.clamp(0.0, 1.0);
     let mix = |base_channel: u8, overlay_channel: u8| -> u8 {
@@ -4713,6 +9222,10 @@ struct PositionedTextRun {
     fg: AlacColor,
     bg: AlacColor,
     bold: bool,
+    /// A single cell whose base character carries combining marks.
+    has_combining_marks: bool,
+    /// A single double-width cell, followed by its spacer.
+    is_wide: bool,
 }
 
 #[derive(Clone)]
@@ -4728,6 +9241,20 @@ struct CachedBackgroundSpan {
     color: Hsla,
 }
 
+/// Fonts resolved from the typography settings.
+#[derive(Clone)]
+struct TerminalFonts {
+    regular: Font,
+    /// Bold face: `bold_font_family` when set and monospace, otherwise the
+    /// regular family at bold weight.
+    bold: Font,
+}
+
+/// Map a CSS-style numeric weight onto GPUI's weight scale.
+fn font_weight_from_setting(weight: u16) -> FontWeight {
+    FontWeight(f32::from(weight.clamp(100, 900)))
+}
+
 #[derive(Clone)]
 struct CachedRow {
     initialized: bool,
@@ -4745,36 +9272,163 @@ impl Default for CachedRow {
     }
 }
 
+/// Reshape the rows of `cache` that are dirty or were never built.
+fn refresh_row_cache(
+    cache: &mut Arc<Vec<CachedRow>>,
+    snapshot: &TerminalSnapshot,
+    dirty_rows: &[bool],
+    text_system: &gpui::WindowTextSystem,
+    fonts: &TerminalFonts,
+    font_size: Pixels,
+    cell_width: Pixels,
+) -> RowCacheStats {
+    let mut stats = RowCacheStats::default();
+
+    if cache.len() != snapshot.num_lines {
+        *cache = Arc::new(vec![CachedRow::default(); snapshot.num_lines]);
+    }
+
+    let mut rebuild_rows = Vec::new();
+    for row_idx in 0..snapshot.rows.len() {
+        if row_cache_rebuild_required(
+            dirty_rows.get(row_idx).copied().unwrap_or(true),
+            &cache[row_idx],
+        ) {
+            stats.record_miss();
+            rebuild_rows.push(row_idx);
+        } else {
+            stats.record_hit();
+        }
+    }
+
+    let rebuilt = map_rows_in_parallel(
+        &rebuild_rows,
+        shaping_thread_count(rebuild_rows.len()),
+        |&row_idx| {
+            build_cached_row(
+                &snapshot.rows[row_idx],
+                snapshot
+                    .line_sizes
+                    .get(row_idx)
+                    .copied()
+                    .unwrap_or_default(),
+                &snapshot.colors,
+                text_system,
+                fonts,
+                font_size,
+                cell_width,
+            )
+        },
+    );
+    store_rebuilt_rows(cache, rebuild_rows.into_iter().zip(rebuilt));
+
+    stats
+}
+
+/// Write rebuilt rows into the shared cache. The cache is only copied when a
+/// row changed and the previous frame still holds it.
+fn store_rebuilt_rows(
+    cache: &mut Arc<Vec<CachedRow>>,
+    rebuilt: impl IntoIterator<Item = (usize, CachedRow)>,
+) {
+    let mut rebuilt = rebuilt.into_iter().peekable();
+    if rebuilt.peek().is_none() {
+        return;
+    }
+
+    let rows = Arc::make_mut(cache);
+    for (row_idx, row) in rebuilt {
+        rows[row_idx] = row;
+    }
+}
+
 fn row_cache_rebuild_required(is_dirty: bool, cached_row: &CachedRow) -> bool {
     is_dirty || !cached_row.initialized
 }
 
+/// Threads for shaping `rows` dirty rows: one below
+/// `MIN_ROWS_FOR_PARALLEL_SHAPING`, otherwise one per
+/// `ROWS_PER_SHAPING_THREAD`, capped by the available cores and
+/// `MAX_SHAPING_THREADS`.
+fn shaping_thread_count(rows: usize) -> usize {
+    if rows < MIN_ROWS_FOR_PARALLEL_SHAPING {
+        return 1;
+    }
+    let available = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+    (rows / ROWS_PER_SHAPING_THREAD).clamp(1, available.min(MAX_SHAPING_THREADS))
+}
+
+/// Apply `build` to every job on up to `threads` scoped threads. Each thread
+/// takes a contiguous chunk, so results come back in job order.
+fn map_rows_in_parallel<J: Sync, T: Send>(
+    jobs: &[J],
+    threads: usize,
+    build: impl Fn(&J) -> T + Sync,
+) -> Vec<T> {
+    if threads <= 1 || jobs.len() <= 1 {
+        return jobs.iter().map(&build).collect();
+    }
+
+    let build = &build;
+    let chunk_size = jobs.len().div_ceil(threads);
+    std::thread::scope(|scope| {
+        let workers = jobs
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || chunk.iter().map(build).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect()
+    })
+}
+
 fn build_cached_row(
-    row: &[CellSnapshot],
+    row: &[GridCell],
+    line_size: LineSize,
     colors: &ColorsSnapshot,
     text_system: &gpui::WindowTextSystem,
-    font: &Font,
+    fonts: &TerminalFonts,
     font_size: Pixels,
     cell_width: Pixels,
 ) -> CachedRow {
+    let font_size = if line_size.is_double_height() {
+        font_size * 2.0
+    } else {
+        font_size
+    };
+    let cell_width = line_cell_width(cell_width, line_size);
     CachedRow {
         initialized: true,
-        text_runs: shape_row_text_runs(row, colors, text_system, font, font_size, cell_width),
+        text_runs: shape_row_text_runs(row, colors, text_system, fonts, font_size, cell_width),
         background_spans: build_background_spans(row, colors),
     }
 }
 
 fn build_background_spans(
-    row: &[CellSnapshot],
+    row: &[GridCell],
     colors: &ColorsSnapshot,
 ) -> Arc<[CachedBackgroundSpan]> {
     let mut spans = Vec::new();
     let mut current_start: Option<usize> = None;
     let mut current_len = 0usize;
     let mut current_color = gpui::black();
+    let mut wide_char_bg = None;
 
     for (col_idx, cell) in row.iter().enumerate() {
-        let bg = resolve_color(&cell.bg, colors, false);
+        // A spacer is the second column of the wide character before it and
+        // shares its background.
+        let bg = match wide_char_bg.take() {
+            Some(bg) if cell.flags.contains(Flags::WIDE_CHAR_SPACER) => bg,
+            _ => resolve_color(&cell.bg, colors, false),
+        };
+        if cell.flags.contains(Flags::WIDE_CHAR) {
+            wide_char_bg = Some(bg);
+        }
         if bg == gpui::black() {
             if let Some(start_col) = current_start.take() {
                 spans.push(CachedBackgroundSpan {
@@ -4821,18 +9475,29 @@ fn build_background_spans(
 }
 
 fn shape_row_text_runs(
-    row: &[CellSnapshot],
+    row: &[GridCell],
     colors: &ColorsSnapshot,
     text_system: &gpui::WindowTextSystem,
-    font: &Font,
+    fonts: &TerminalFonts,
     font_size: Pixels,
     cell_width: Pixels,
 ) -> Arc<[CachedTextRun]> {
     let mut shaped_runs = Vec::new();
 
     for positioned_run in build_positioned_text_runs(row) {
-        let fg_color = resolve_color(&positioned_run.fg, colors, true);
-        let bg_color = resolve_color(&positioned_run.bg, colors, false);
+        let fg = run_foreground(
+            positioned_run.fg,
+            positioned_run.bold,
+            colors.bold_is_bright,
+        );
+        let bg_rgb = resolve_alac_rgb(&positioned_run.bg, colors, false);
+        let fg_rgb = ensure_contrast(
+            resolve_alac_rgb(&fg, colors, true),
+            bg_rgb,
+            colors.minimum_contrast,
+        );
+        let fg_color = alac_rgb_to_hsla(colors.dimmed(fg_rgb));
+        let bg_color = alac_rgb_to_hsla(colors.dimmed(bg_rgb));
         let bg_option = if bg_color != gpui::black() {
             Some(bg_color)
         } else {
@@ -4843,20 +9508,23 @@ fn shape_row_text_runs(
             font_size,
             &[TextRun {
                 len: positioned_run.text.len(),
-                font: Font {
-                    weight: if positioned_run.bold {
-                        FontWeight::BOLD
-                    } else {
-                        font.weight
-                    },
-                    ..font.clone()
+                font: if positioned_run.bold {
+                    fonts.bold.clone()
+                } else {
+                    fonts.regular.clone()
                 },
                 color: fg_color,
                 background_color: bg_option,
                 underline: None,
                 strikethrough: None,
             }],
-            Some(cell_width),
+            // Marks in a combining run are positioned by the font, and a wide
+            // cell advances two columns.
+            match (positioned_run.has_combining_marks, positioned_run.is_wide) {
+                (true, _) => None,
+                (false, true) => Some(cell_width * 2.0),
+                (false, false) => Some(cell_width),
+            },
         );
 
         shaped_runs.push(CachedTextRun {
@@ -4868,7 +9536,7 @@ fn shape_row_text_runs(
     Arc::from(shaped_runs)
 }
 
-fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
+fn build_positioned_text_runs(row: &[GridCell]) -> Vec<PositionedTextRun> {
     #[derive(Clone)]
     struct PendingRun {
         start_col: usize,
@@ -4891,6 +9559,8 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
                     fg: run.fg,
                     bg: run.bg,
                     bold: run.bold,
+                    has_combining_marks: false,
+                    is_wide: false,
                 });
             }
             continue;
@@ -4904,6 +9574,34 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
         let bold = cell.flags.contains(Flags::BOLD);
         let display_char = if cell.c == '\0' { ' ' } else { cell.c };
 
+        // Wide characters and characters with combining marks get a run of
+        // their own. Shaping with a forced cell width would squeeze a wide
+        // glyph into one column and give each mark a column of its own.
+        let is_wide = cell.flags.contains(Flags::WIDE_CHAR);
+        if is_wide || cell.zerowidth.is_some() {
+            if let Some(run) = current.take() {
+                runs.push(PositionedTextRun {
+                    start_col: run.start_col,
+                    text: run.text,
+                    fg: run.fg,
+                    bg: run.bg,
+                    bold: run.bold,
+                    has_combining_marks: false,
+                    is_wide: false,
+                });
+            }
+            runs.push(PositionedTextRun {
+                start_col: col_idx,
+                text: format!("{display_char}{}", cell.zerowidth.as_deref().unwrap_or("")),
+                fg,
+                bg,
+                bold,
+                has_combining_marks: cell.zerowidth.is_some(),
+                is_wide,
+            });
+            continue;
+        }
+
         // Preserve exact grid positioning by skipping blank cells and starting
         // new runs at the corresponding column index.
         if display_char == ' ' {
@@ -4914,6 +9612,8 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
                     fg: run.fg,
                     bg: run.bg,
                     bold: run.bold,
+                    has_combining_marks: false,
+                    is_wide: false,
                 });
             }
             continue;
@@ -4934,6 +9634,8 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
                     fg: old.fg,
                     bg: old.bg,
                     bold: old.bold,
+                    has_combining_marks: false,
+                    is_wide: false,
                 });
                 current = Some(PendingRun {
                     start_col: col_idx,
@@ -4964,6 +9666,8 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
             fg: run.fg,
             bg: run.bg,
             bold: run.bold,
+            has_combining_marks: false,
+            is_wide: false,
         });
     }
 
@@ -4972,47 +9676,100 @@ fn build_positioned_text_runs(row: &[CellSnapshot]) -> Vec<PositionedTextRun> {
 
 #[cfg(test)]
 mod tests {
+    use super::copy_mode::{
+        copy_mode_key, move_copy_cursor, CopyModeKey, CopyModeMotion, CopyModeState,
+    };
+    use super::find_history::{FindHistory, FIND_HISTORY_LIMIT};
+    use super::keybindings::{parse_keybinding, KeyAction, KeyBindings};
+    use super::panes::{PaneGrid, PaneNode, PANE_DIVIDER_COLS};
     use super::utils::{
-        common_shortcut_action, display_offset_from_thumb_top,
+        alternate_scroll_enabled, effective_scroll_multiplier, file_open_url,
+        file_path_to_file_url, mouse_mode_enabled_for_scroll, scroll_multiplier_for_delta,
+        split_line_column_suffix, strip_line_column_suffix,
+    };
+    use super::utils::{
+        display_offset_from_thumb_top, grid_range_row_spans, new_tab_working_directory,
         resolve_working_directory_with_fallback, scrollbar_thumb_metrics,
-        selection_type_for_click_count, CommonShortcutAction, INPUT_SCROLL_SUPPRESSION_WINDOW,
+        selection_type_for_click_count, text_entry_key, TextEntryKey,
+        INPUT_SCROLL_SUPPRESSION_WINDOW,
     };
     use super::{
-        alternate_scroll_enabled, beam_cursor_width, blend_rgb, build_background_spans,
-        build_positioned_text_runs, consume_scroll_lines, cursor_blink_is_suppressed,
-        cursor_should_blink, dirty_rows_for_snapshot, display_offset_from_pointer,
-        effective_scroll_multiplier, file_path_to_file_url, mouse_mode_enabled_for_scroll,
-        point_in_bounds, prepare_for_terminal_input, row_cache_rebuild_required,
-        scroll_delta_to_lines, scrollbar_layout, selection_background_color, selection_copy_plan,
-        selection_tint_rgb, shift_row_cache_for_display_offset, should_ignore_scroll_event,
-        strip_line_column_suffix, tab_brand_purple, text_to_insert, theme_palette,
-        underline_cursor_height, update_action_for_terminal_event, viewport_row_for_line,
-        CachedRow, CachedTextRun, CellSnapshot, ColorsSnapshot, CursorShape, FrameCache,
-        PreviousFrameView, ScrollbarLayout, SettingsLineHeightMode, TerminalSnapshot, TerminalView,
-        ViewUpdateAction, FIND_PANEL_MAX_WIDTH_PX, FIND_PANEL_MIN_WIDTH_PX,
-        SETTINGS_DRAWER_WIDTH_PX, SETTINGS_OVERLAY_BACKDROP_ALPHA, TAB_BAR_HEIGHT_PX,
+        alac_rgb_to_hsla, alt_screen_indicator_visible, beam_cursor_width, bell_flash_active,
+        bell_flash_deadline, blend_rgb, build_background_spans, build_cached_row,
+        build_positioned_text_runs, clamp_font_size, consume_scroll_lines, contrast_ratio,
+        cursor_blink_is_suppressed, cursor_blink_suppressed_until, cursor_color,
+        cursor_should_blink, cursor_style_for_focus, cursor_text_rgb, detect_region_scroll,
+        directed_scroll_lines, dirty_rows_for_snapshot, display_offset_from_pointer,
+        double_width_column, ensure_contrast, escape_clears_selection, exit_notice,
+        fill_snapshot_rows, find_wrapped, focus_report, font_weight_from_setting,
+        hyperlink_modifier_held, image_paint_bounds, input_target_tabs, line_cell_width,
+        map_rows_in_parallel, navigation_target, page_scroll_offset, pane_terminal_bounds,
+        pane_window_sizes, paste_needs_confirmation, paste_preview, point_in_bounds,
+        prepare_for_terminal_input, primary_selection_capture, queue_behind_pending_paste,
+        render_image_frame, reset_font_size, resolve_color, resolve_path_target,
+        rgb_u32_to_alac_rgb, row_cache_rebuild_required, run_foreground, scroll_delta_to_lines,
+        scroll_position_label, scrollbar_layout, scrollbar_opacity, scrollbar_thumb_color,
+        scrollbar_track_click_pages_up, selection_background_color, selection_changed,
+        selection_copy_plan, selection_tint_rgb, shaping_thread_count,
+        shift_row_cache_for_display_offset, shift_row_cache_for_region_scroll,
+        should_confirm_close, should_ignore_scroll_event, should_scroll_on_output,
+        snap_to_device_pixels, snapshot_cell, snapshot_term, store_rebuilt_rows,
+        synchronized_update_hold, tab_bar_height, tab_bar_layout_position, tab_brand_purple,
+        tab_title_text, tab_tooltip_text, text_to_insert, theme_palette, tinted_background,
+        typography_needs_refresh, underline_cursor_height, unfocused_color_dim,
+        update_action_for_terminal_event, viewport_row_for_line, wheel_font_zoom_steps,
+        wheel_scroll_target, AlacColors, CachedRow, CachedTextRun, ColorsSnapshot,
+        ContextMenuAction, CursorShape, FindCache, FindMatch, FrameCache, GridCell, PendingPaste,
+        PerfInstrumentation, PerfSnapshot, PreviousFrameView, RegionScroll, RowCacheStats,
+        ScrollbarLayout, SettingsLineHeightMode, SnapshotBuffer, SnapshotImage, SnapshotTiming,
+        TabUnread, TerminalFonts, TerminalSnapshot, TerminalTab, TerminalView, ThemePalette,
+        ThemeRegistry, ViewUpdateAction, WheelScrollTarget, WindowSize, BELL_FLASH_DURATION,
+        FIND_PANEL_MAX_WIDTH_PX, FIND_PANEL_MIN_WIDTH_PX, MAX_SHAPING_THREADS,
+        MIN_ROWS_FOR_PARALLEL_SHAPING, PASTE_PREVIEW_MAX_COLUMNS, PASTE_PREVIEW_MAX_LINES,
+        SCROLLBAR_FADE_DELAY, SCROLLBAR_FADE_DURATION, SCROLL_ON_OUTPUT_REVIEW_WINDOW,
+        SELECTION_TINT_ALPHA, SETTINGS_DRAWER_WIDTH_PX, SETTINGS_FONT_SIZE_STEP,
+        SETTINGS_MAX_FONT_SIZE, SETTINGS_MAX_HYPERLINK_TIMEOUT_MS, SETTINGS_MIN_FONT_SIZE,
+        SETTINGS_OVERLAY_BACKDROP_ALPHA, SYNC_UPDATE_TIMEOUT, TAB_BAR_HEIGHT_PX,
         TAB_CLOSE_BUTTON_SIZE_PX, TAB_ITEM_INDICATOR_BOTTOM_GAP_PX, TAB_ITEM_WIDTH_PX,
+        TAB_TINT_PRESETS, THEME_PRESETS,
     };
-    use alacritty_terminal::term::cell::Flags;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::grid::Scroll;
+    use alacritty_terminal::index::Side;
+    use alacritty_terminal::term::cell::{Cell, Flags};
+    use alacritty_terminal::term::search::RegexSearch;
+    use alacritty_terminal::term::test::mock_term;
+    use alacritty_terminal::term::Term;
     use alacritty_terminal::vte::ansi::{Color as AlacColor, NamedColor, Rgb as AlacRgb};
-    use gpui::{point, px, size, Bounds, Keystroke, Modifiers, Point, ScrollDelta, TouchPhase};
+    use alacritty_terminal::vte::ansi::{Processor, StdSyncHandler};
+    use gpui::{
+        point, px, size, Bounds, FontWeight, Keystroke, Modifiers, Point, ScrollDelta, TouchPhase,
+    };
+    use simple_term::images::TerminalImage;
+    use simple_term::line_size::LineSize;
+    use simple_term::mappings::mouse::grid_point_and_side;
     use simple_term::terminal::TerminalEvent;
     use simple_term::terminal_settings::{
-        Blinking, LineHeight, TerminalSettings, TerminalTheme, WorkingDirectory,
+        Blinking, CustomThemeDefinition, HyperlinkModifier, LineHeight, ShellConfig,
+        TabBarPosition, TerminalSettings, TerminalTheme, WorkingDirectory,
     };
     use simple_term::TermMode;
-    use simple_term::{AlternateScroll, SelectionType};
+    use simple_term::{
+        AlacDirection, AlacPoint, AlternateScroll, Column, Dimensions, Line, MaybeNavigationTarget,
+        PathLikeTarget, Selection, SelectionRange, SelectionType,
+    };
     use std::path::PathBuf;
     use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
     use std::time::{Duration, Instant};
 
-    fn cell(c: char, flags: Flags) -> CellSnapshot {
-        CellSnapshot {
+    fn cell(c: char, flags: Flags) -> GridCell {
+        GridCell {
             c,
             fg: AlacColor::Named(NamedColor::Foreground),
             bg: AlacColor::Named(NamedColor::Background),
             flags,
+            zerowidth: None,
         }
     }
 
@@ -5025,9 +9782,28 @@ mod tests {
                 b: 0xCF,
             },
             background: alacritty_terminal::vte::ansi::Rgb { r: 0, g: 0, b: 0 },
+            bold_is_bright: false,
+            minimum_contrast: 1.0,
+            dim: 0.0,
+            selection_background: None,
+            selection_foreground: None,
         }
     }
 
+    /// Snapshot a test terminal the way `take_snapshot` does, without images.
+    fn snapshot_mock_term(
+        term: &mut Term<VoidListener>,
+        settings: &TerminalSettings,
+        previous: Option<&FrameCache>,
+        buffer: &mut SnapshotBuffer,
+    ) -> TerminalSnapshot {
+        let palette = theme_palette(&TerminalTheme::AtomOneDark, &ThemeRegistry::default());
+        let (mut snapshot, _) =
+            snapshot_term(term, palette, settings, 0.0, previous, false, buffer);
+        fill_snapshot_rows(&mut snapshot, buffer, previous);
+        snapshot
+    }
+
     fn snapshot_from_rows(
         rows: &[&str],
         cursor_row: Option<usize>,
@@ -5053,26 +9829,30 @@ mod tests {
         let rows = rows
             .iter()
             .map(|line| {
-                let mut row: Vec<CellSnapshot> = line
+                let mut row: Vec<GridCell> = line
                     .chars()
-                    .map(|ch| CellSnapshot {
+                    .map(|ch| GridCell {
                         c: ch,
                         fg: AlacColor::Named(NamedColor::Foreground),
                         bg: AlacColor::Named(NamedColor::Background),
                         flags: Flags::empty(),
+                        zerowidth: None,
                     })
                     .collect();
                 row.resize(num_cols, cell(' ', Flags::empty()));
-                row
+                Arc::new(row)
             })
             .collect();
 
         TerminalSnapshot {
             rows,
+            line_sizes: vec![LineSize::Single; num_lines],
             num_cols,
             num_lines,
             history_size: 0,
             display_offset,
+            selection: None,
+            scroll_region: None,
             cursor_row,
             cursor_col,
             cursor_shape: CursorShape::Block,
@@ -5080,91 +9860,901 @@ mod tests {
             show_cursor,
             cursor_draw_visible: show_cursor,
             colors: test_colors(),
+            images: Vec::new(),
+            cursor_glyph: None,
+            scrollbar_thumb: None,
         }
     }
 
+    fn placed_image(row: isize, col: usize, width: u32, height: u32) -> SnapshotImage {
+        SnapshotImage {
+            id: 1,
+            row,
+            col,
+            image: Arc::new(TerminalImage {
+                width,
+                height,
+                rgba: vec![0; (width * height * 4) as usize],
+                display_width: width,
+                display_height: height,
+                cell_width: 8,
+                cell_height: 16,
+            }),
+            rendered: None,
+        }
+    }
+
+    #[test]
+    fn image_bounds_start_at_anchor_cell_and_scale_with_cell_size() {
+        let placed = placed_image(2, 3, 16, 32);
+        let bounds = image_paint_bounds(point(px(0.0), px(0.0)), &placed, size(px(10.0), px(20.0)));
+
+        assert_eq!(bounds.origin, point(px(30.0), px(40.0)));
+        assert_eq!(bounds.size, size(px(20.0), px(40.0)));
+    }
+
+    #[test]
+    fn image_anchored_above_viewport_paints_from_negative_offset() {
+        let placed = placed_image(-1, 0, 8, 32);
+        let bounds =
+            image_paint_bounds(point(px(5.0), px(100.0)), &placed, size(px(8.0), px(16.0)));
+
+        assert_eq!(bounds.origin, point(px(5.0), px(84.0)));
+        assert_eq!(bounds.size.height, px(32.0));
+    }
+
+    #[test]
+    fn image_frame_is_converted_to_bgra() {
+        let image = TerminalImage {
+            width: 1,
+            height: 1,
+            rgba: vec![1, 2, 3, 4],
+            display_width: 1,
+            display_height: 1,
+            cell_width: 8,
+            cell_height: 16,
+        };
+        let frame = render_image_frame(&image).expect("frame");
+
+        assert_eq!(frame.as_bytes(0), Some(&[3, 2, 1, 4][..]));
+    }
+
+    #[test]
+    fn snapshot_cursor_follows_cursor_styles_set_by_the_program() {
+        let settings = TerminalSettings::default();
+        let mut term = mock_term("$ ");
+        let mut cursor_after = |bytes: &[u8]| {
+            Processor::<StdSyncHandler>::new().advance(&mut term, bytes);
+            let snapshot =
+                snapshot_mock_term(&mut term, &settings, None, &mut SnapshotBuffer::default());
+            (snapshot.cursor_shape, snapshot.cursor_blinking)
+        };
+
+        // neovim switches to a steady bar in insert mode and back to a block.
+        assert_eq!(cursor_after(b"\x1b[6 q"), (CursorShape::Beam, false));
+        assert_eq!(cursor_after(b"\x1b[2 q"), (CursorShape::Block, false));
+        assert_eq!(cursor_after(b"\x1b[3 q"), (CursorShape::Underline, true));
+        assert_eq!(cursor_after(b"\x1b[5 q"), (CursorShape::Beam, true));
+
+        // `CSI 0 SP q` restores the configured default style.
+        assert_eq!(cursor_after(b"\x1b[0 q"), (CursorShape::Block, false));
+    }
+
+    #[test]
+    fn damage_driven_snapshot_matches_full_snapshot_after_single_cell_change() {
+        let settings = TerminalSettings::default();
+        let mut buffer = SnapshotBuffer::default();
+        let mut term = mock_term("one\ntwo\nsix");
+        let first = snapshot_mock_term(&mut term, &settings, None, &mut buffer);
+        let previous = FrameCache::from_snapshot(&first);
+
+        Processor::<StdSyncHandler>::new().advance(&mut term, b"\x1b[3;2HX");
+        let damaged = snapshot_mock_term(&mut term, &settings, Some(&previous), &mut buffer);
+        let full = snapshot_mock_term(&mut term, &settings, None, &mut SnapshotBuffer::default());
+
+        assert!(damaged.rows == full.rows);
+        assert_eq!(damaged.line_sizes, full.line_sizes);
+        assert_eq!(full.rows[2][1].c, 'X');
+        assert!(Arc::ptr_eq(&damaged.rows[1], &previous.rows[1]));
+        assert!(!Arc::ptr_eq(&damaged.rows[2], &previous.rows[2]));
+    }
+
+    #[test]
+    fn snapshot_buffer_is_resized_when_the_grid_changes_size() {
+        let settings = TerminalSettings::default();
+        let mut buffer = SnapshotBuffer::default();
+        let mut small = mock_term("ab\ncd");
+        let mut wide = mock_term("wxyz\r\n1234\r\nabcd");
+
+        let first = snapshot_mock_term(&mut small, &settings, None, &mut buffer);
+        let previous = FrameCache::from_snapshot(&first);
+        assert_eq!(buffer.cells.len(), 4);
+
+        let grown = snapshot_mock_term(&mut wide, &settings, Some(&previous), &mut buffer);
+        assert_eq!(buffer.cells.len(), 12);
+        assert_eq!((grown.num_cols, grown.num_lines), (4, 3));
+        let text: Vec<String> = grown
+            .rows
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.c).collect())
+            .collect();
+        assert_eq!(text, ["wxyz", "1234", "abcd"]);
+
+        let shrunk = snapshot_mock_term(&mut small, &settings, None, &mut buffer);
+        assert_eq!(buffer.cells.len(), 4);
+        assert_eq!(
+            shrunk.rows[1].iter().map(|cell| cell.c).collect::<String>(),
+            "cd"
+        );
+    }
+
     #[test]
     fn dirty_rows_mark_all_rows_without_previous_frame() {
         let snapshot = snapshot_from_rows(&["abc", "def"], Some(0), 0, true);
-        let dirty = dirty_rows_for_snapshot(&snapshot, None);
+        let dirty = dirty_rows_for_snapshot(&snapshot, None, None);
+        assert_eq!(dirty, vec![true, true]);
+    }
+
+    #[test]
+    fn dirty_rows_mark_only_changed_content_rows() {
+        let previous = snapshot_from_rows(&["abc", "def", "ghi"], Some(1), 1, true);
+        let current = snapshot_from_rows(&["abc", "dXf", "ghi"], Some(1), 1, true);
+        let previous_cache = FrameCache::from_snapshot(&previous);
+
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
+        assert_eq!(dirty, vec![false, true, false]);
+    }
+
+    #[test]
+    fn region_scroll_only_dirties_rows_it_exposes() {
+        let mut previous =
+            snapshot_from_rows(&["top", "aaa", "bbb", "ccc", "ddd", "bar"], None, 0, false);
+        let mut current =
+            snapshot_from_rows(&["top", "bbb", "ccc", "ddd", "", "bar"], None, 0, false);
+        previous.scroll_region = Some(1..5);
+        current.scroll_region = Some(1..5);
+        let previous_cache = FrameCache::from_snapshot(&previous);
+
+        let scroll = detect_region_scroll(&current, Some(&previous_cache));
+        assert_eq!(
+            scroll,
+            Some(RegionScroll {
+                rows: 1..5,
+                lines: 1
+            })
+        );
+
+        let unaware = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
+        assert_eq!(unaware, vec![false, true, true, true, true, false]);
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), scroll.as_ref());
+        assert_eq!(dirty, vec![false, false, false, false, true, false]);
+
+        let mut row_cache = vec![CachedRow::default(); 6];
+        row_cache[2].initialized = true;
+        shift_row_cache_for_region_scroll(
+            &mut row_cache,
+            &RegionScroll {
+                rows: 1..5,
+                lines: 1,
+            },
+        );
+        let initialized: Vec<bool> = row_cache.iter().map(|row| row.initialized).collect();
+        assert_eq!(initialized, vec![false, true, false, false, false, false]);
+    }
+
+    #[test]
+    fn dirty_rows_mark_rows_whose_line_size_changed() {
+        let previous = snapshot_from_rows(&["abc", "def"], None, 0, false);
+        let mut current = snapshot_from_rows(&["abc", "def"], None, 0, false);
+        current.line_sizes[1] = LineSize::DoubleWidth;
+        let previous_cache = FrameCache::from_snapshot(&previous);
+
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
+        assert_eq!(dirty, vec![false, true]);
+    }
+
+    #[test]
+    fn double_width_row_columns_map_to_twice_the_cell_width() {
+        let cell_width = px(8.0);
+
+        assert_eq!(
+            line_cell_width(cell_width, LineSize::Single) * 3.0,
+            px(24.0)
+        );
+        assert_eq!(
+            line_cell_width(cell_width, LineSize::DoubleWidth) * 3.0,
+            px(48.0)
+        );
+        assert_eq!(
+            line_cell_width(cell_width, LineSize::DoubleHeightBottom) * 3.0,
+            px(48.0)
+        );
+    }
+
+    #[test]
+    fn clicks_on_double_width_row_map_back_to_grid_columns() {
+        let cell_width = px(8.0);
+
+        assert_eq!(
+            double_width_column(px(50.0), cell_width, 80),
+            (3, Side::Left)
+        );
+        assert_eq!(
+            double_width_column(px(61.0), cell_width, 80),
+            (3, Side::Right)
+        );
+        assert_eq!(
+            double_width_column(px(-4.0), cell_width, 80),
+            (0, Side::Left)
+        );
+    }
+
+    #[test]
+    fn clicks_past_visible_half_of_double_width_row_clamp_to_last_column() {
+        assert_eq!(
+            double_width_column(px(700.0), px(8.0), 80),
+            (39, Side::Right)
+        );
+        assert_eq!(
+            double_width_column(px(700.0), px(8.0), 81),
+            (40, Side::Right)
+        );
+    }
+
+    #[test]
+    fn dirty_rows_mark_cursor_source_and_destination_rows() {
+        let previous = snapshot_from_rows(&["abc", "def"], Some(0), 1, true);
+        let current = snapshot_from_rows(&["abc", "def"], Some(1), 1, true);
+        let previous_cache = FrameCache::from_snapshot(&previous);
+
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
         assert_eq!(dirty, vec![true, true]);
     }
 
     #[test]
-    fn dirty_rows_mark_only_changed_content_rows() {
-        let previous = snapshot_from_rows(&["abc", "def", "ghi"], Some(1), 1, true);
-        let current = snapshot_from_rows(&["abc", "dXf", "ghi"], Some(1), 1, true);
-        let previous_cache = FrameCache::from_snapshot(&previous);
+    fn wakeup_event_maps_to_notify_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::Wakeup),
+            ViewUpdateAction::Notify
+        );
+    }
+
+    #[test]
+    fn title_event_maps_to_title_update_and_notify_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::TitleChanged("hello".to_string())),
+            ViewUpdateAction::SetTitleAndNotify("hello".to_string())
+        );
+    }
+
+    #[test]
+    fn title_reset_returns_the_tab_to_the_title_it_opened_with() {
+        let settings = TerminalSettings {
+            shell: ShellConfig::Program {
+                program: "/bin/sh".to_string(),
+            },
+            ..TerminalSettings::default()
+        };
+        let window_size = TerminalView::window_size_for_grid(size(80, 24), size(px(8.0), px(16.0)));
+        let terminal =
+            TerminalView::spawn_terminal(&settings, None, None, window_size).expect("spawn");
+        let mut tab = TerminalTab::new(1, 1, "1".to_string(), None, 1, terminal);
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::TitleReset),
+            ViewUpdateAction::ResetTitle
+        );
+        assert!(!tab.reset_shell_title());
+
+        tab.title = "vim".to_string();
+        tab.title_from_shell = true;
+        assert!(tab.reset_shell_title());
+
+        assert_eq!(tab.title, "1");
+        assert!(!tab.title_from_shell);
+        assert_ne!(
+            tab_title_text(None, &tab.title, tab.title_from_shell, None),
+            "vim"
+        );
+    }
+
+    #[test]
+    fn profile_with_a_missing_program_fails_to_spawn_without_panicking() {
+        let profile = simple_term::terminal_settings::ShellProfile {
+            name: "broken".to_string(),
+            shell: ShellConfig::Program {
+                program: "/nonexistent/simple-term-shell".to_string(),
+            },
+            ..Default::default()
+        };
+        let window_size = TerminalView::window_size_for_grid(size(80, 24), size(px(8.0), px(16.0)));
+
+        let error = TerminalView::spawn_terminal(
+            &TerminalSettings::default(),
+            Some(&profile),
+            None,
+            window_size,
+        )
+        .err()
+        .expect("missing program should fail");
+
+        assert_eq!(error.kind, simple_term::error::ErrorKind::ShellNotFound);
+    }
+
+    #[test]
+    fn cwd_event_maps_to_set_cwd_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::CwdChanged(PathBuf::from("/tmp"))),
+            ViewUpdateAction::SetCwd(PathBuf::from("/tmp"))
+        );
+    }
+
+    #[test]
+    fn command_finished_event_maps_to_exit_status_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::CommandFinished(Some(2))),
+            ViewUpdateAction::SetExitStatus(Some(2))
+        );
+    }
+
+    #[test]
+    fn tab_tooltip_shows_last_exit_status_when_reported() {
+        assert_eq!(tab_tooltip_text("zsh", None), "zsh");
+        assert_eq!(
+            tab_tooltip_text("zsh", Some(1)),
+            "zsh\nLast command exited with status 1"
+        );
+    }
+
+    #[test]
+    fn bell_event_maps_to_bell_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::Bell),
+            ViewUpdateAction::Bell
+        );
+    }
+
+    #[test]
+    fn ensure_contrast_keeps_pairs_that_already_meet_the_ratio() {
+        let fg = AlacRgb {
+            r: 0xE0,
+            g: 0xE0,
+            b: 0xE0,
+        };
+        let bg = AlacRgb { r: 0, g: 0, b: 0 };
+
+        assert_eq!(ensure_contrast(fg, bg, 4.5), fg);
+        assert_eq!(ensure_contrast(bg, bg, 1.0), bg);
+    }
+
+    #[test]
+    fn ensure_contrast_lightens_dark_gray_on_black() {
+        let fg = AlacRgb {
+            r: 0x30,
+            g: 0x30,
+            b: 0x30,
+        };
+        let bg = AlacRgb { r: 0, g: 0, b: 0 };
+
+        let adjusted = ensure_contrast(fg, bg, 4.5);
+
+        assert!(contrast_ratio(adjusted, bg) >= 4.5);
+        assert!(adjusted.r > fg.r);
+        assert!(
+            contrast_ratio(adjusted, bg) < 5.0,
+            "moved only as far as needed"
+        );
+    }
+
+    #[test]
+    fn ensure_contrast_darkens_light_text_on_white() {
+        let fg = AlacRgb {
+            r: 0xF0,
+            g: 0xE0,
+            b: 0xA0,
+        };
+        let bg = AlacRgb {
+            r: 0xFF,
+            g: 0xFF,
+            b: 0xFF,
+        };
+
+        let adjusted = ensure_contrast(fg, bg, 7.0);
+
+        assert!(contrast_ratio(adjusted, bg) >= 7.0);
+        assert!(adjusted.r < fg.r);
+    }
+
+    #[test]
+    fn ensure_contrast_falls_back_to_black_or_white_for_unreachable_ratios() {
+        let gray = AlacRgb {
+            r: 0x80,
+            g: 0x80,
+            b: 0x80,
+        };
+
+        assert_eq!(
+            ensure_contrast(gray, gray, 21.0),
+            AlacRgb { r: 0, g: 0, b: 0 }
+        );
+    }
+
+    #[test]
+    fn bold_red_becomes_bright_red_only_when_bold_is_bright() {
+        let red = AlacColor::Indexed(1);
+
+        assert_eq!(run_foreground(red, true, true), AlacColor::Indexed(9));
+        assert_eq!(run_foreground(red, true, false), red);
+        assert_eq!(run_foreground(red, false, true), red);
+        assert_eq!(
+            run_foreground(AlacColor::Named(NamedColor::Red), true, true),
+            AlacColor::Named(NamedColor::BrightRed)
+        );
+    }
+
+    #[test]
+    fn bold_is_bright_leaves_non_palette_colors_alone() {
+        let colors = [
+            AlacColor::Indexed(9),
+            AlacColor::Indexed(196),
+            AlacColor::Named(NamedColor::Foreground),
+            AlacColor::Spec(AlacRgb { r: 1, g: 2, b: 3 }),
+        ];
+        for color in colors {
+            assert_eq!(run_foreground(color, true, true), color);
+        }
+    }
+
+    #[test]
+    fn numeric_font_weights_map_to_gpui_weights() {
+        assert_eq!(font_weight_from_setting(400), FontWeight::NORMAL);
+        assert_eq!(font_weight_from_setting(300), FontWeight::LIGHT);
+        assert_eq!(font_weight_from_setting(700), FontWeight::BOLD);
+        assert_eq!(font_weight_from_setting(350), FontWeight(350.0));
+    }
+
+    #[test]
+    fn out_of_range_font_weights_are_clamped() {
+        assert_eq!(font_weight_from_setting(0), FontWeight::THIN);
+        assert_eq!(font_weight_from_setting(1000), FontWeight::BLACK);
+    }
+
+    #[test]
+    fn bell_flash_is_active_until_its_deadline() {
+        let rung_at = Instant::now();
+        let flash_until = Some(bell_flash_deadline(rung_at));
+
+        assert_eq!(flash_until, Some(rung_at + BELL_FLASH_DURATION));
+        assert!(bell_flash_active(flash_until, rung_at));
+        assert!(bell_flash_active(
+            flash_until,
+            rung_at + Duration::from_millis(119)
+        ));
+        assert!(!bell_flash_active(
+            flash_until,
+            rung_at + BELL_FLASH_DURATION
+        ));
+        assert!(!bell_flash_active(None, rung_at));
+    }
+
+    #[test]
+    fn scrollbar_fades_out_after_the_viewport_stops_scrolling() {
+        let scrolled_at = Instant::now();
+        let fade_start = scrolled_at + SCROLLBAR_FADE_DELAY;
+
+        assert_eq!(scrollbar_opacity(None, scrolled_at), 0.0);
+        assert_eq!(scrollbar_opacity(Some(scrolled_at), scrolled_at), 1.0);
+        assert_eq!(scrollbar_opacity(Some(scrolled_at), fade_start), 1.0);
+        let halfway =
+            scrollbar_opacity(Some(scrolled_at), fade_start + SCROLLBAR_FADE_DURATION / 2);
+        assert!((halfway - 0.5).abs() < 1e-3);
+        assert_eq!(
+            scrollbar_opacity(Some(scrolled_at), fade_start + SCROLLBAR_FADE_DURATION),
+            0.0
+        );
+    }
+
+    #[test]
+    fn exit_notice_names_the_code_and_how_to_restart() {
+        assert_eq!(
+            exit_notice(2),
+            "[Process exited with code 2 — press Enter to restart]"
+        );
+    }
+
+    #[test]
+    fn respawn_keeps_the_tab_id_number_and_pane() {
+        let settings = TerminalSettings {
+            shell: ShellConfig::Program {
+                program: "/bin/sh".to_string(),
+            },
+            ..TerminalSettings::default()
+        };
+        let window_size = TerminalView::window_size_for_grid(size(80, 24), size(px(8.0), px(16.0)));
+        let spawn =
+            || TerminalView::spawn_terminal(&settings, None, None, window_size).expect("spawn");
+        let mut tab = TerminalTab::new(7, 3, "3".to_string(), None, 11, spawn());
+        tab.panes[0].exited = Some(1);
+        tab.last_exit_status = Some(1);
+
+        assert!(tab.respawn(11, spawn()));
+        assert!(!tab.respawn(12, spawn()));
+        assert_eq!((tab.id, tab.number, tab.focused_pane_id), (7, 3, 11));
+        assert_eq!(tab.panes.len(), 1);
+        assert_eq!(tab.panes[0].id, 11);
+        assert_eq!(tab.panes[0].exited, None);
+        assert_eq!(tab.last_exit_status, None);
+    }
+
+    #[test]
+    fn exit_event_maps_to_exit_action() {
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::Exit(0)),
+            ViewUpdateAction::Exit(0)
+        );
+        assert_eq!(
+            update_action_for_terminal_event(TerminalEvent::Exit(127)),
+            ViewUpdateAction::Exit(127)
+        );
+    }
+
+    #[test]
+    fn next_tab_number_fills_first_available_slot() {
+        assert_eq!(TerminalView::next_tab_number_from_numbers(&[]), 1);
+        assert_eq!(TerminalView::next_tab_number_from_numbers(&[1, 2, 4]), 3);
+        assert_eq!(TerminalView::next_tab_number_from_numbers(&[2, 3]), 1);
+    }
+
+    #[test]
+    fn shell_title_update_is_ignored_while_custom_title_is_set() {
+        assert_eq!(
+            TerminalView::shell_title_update("zsh", Some("api server"), "vim main.rs"),
+            None
+        );
+        assert_eq!(
+            TerminalView::shell_title_update("zsh", None, "vim main.rs"),
+            Some("vim main.rs".to_string())
+        );
+        assert_eq!(TerminalView::shell_title_update("zsh", None, "zsh"), None);
+    }
+
+    #[test]
+    fn custom_title_from_draft_clears_on_blank_and_sanitizes() {
+        assert_eq!(TerminalView::custom_title_from_draft("   "), None);
+        assert_eq!(
+            TerminalView::custom_title_from_draft("  logs  "),
+            Some("logs".to_string())
+        );
+    }
+
+    #[test]
+    fn text_entry_key_classifies_editing_keys() {
+        let enter = Keystroke::parse("shift-enter").expect("valid keystroke");
+        let escape = Keystroke::parse("escape").expect("valid keystroke");
+        let backspace = Keystroke::parse("backspace").expect("valid keystroke");
+        let letter = Keystroke::parse("a").expect("valid keystroke");
+        let platform = Keystroke::parse("cmd-a").expect("valid keystroke");
+
+        assert_eq!(
+            text_entry_key(&enter),
+            Some(TextEntryKey::Submit { shift: true })
+        );
+        assert_eq!(text_entry_key(&escape), Some(TextEntryKey::Cancel));
+        assert_eq!(text_entry_key(&backspace), Some(TextEntryKey::Backspace));
+        assert_eq!(
+            text_entry_key(&letter),
+            Some(TextEntryKey::Insert("a".to_string()))
+        );
+        assert_eq!(text_entry_key(&platform), None);
+    }
+
+    #[test]
+    fn pane_dividers_sit_left_of_non_leading_panes() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+        let layout = node.layout(size(81, 24));
+
+        assert_eq!(
+            TerminalView::pane_divider_grids(&layout),
+            vec![PaneGrid {
+                col_offset: 40,
+                line_offset: 0,
+                cols: 1,
+                lines: 24,
+            }]
+        );
+        assert!(
+            TerminalView::pane_divider_grids(&PaneNode::Leaf(1).layout(size(80, 24))).is_empty()
+        );
+    }
+
+    #[test]
+    fn grid_cell_for_position_ignores_tab_bar() {
+        let cell_size = size(px(10.0), px(20.0));
+
+        assert_eq!(
+            TerminalView::grid_cell_for_position(
+                point(px(25.0), px(10.0)),
+                cell_size,
+                size(px(0.0), px(0.0)),
+                TabBarPosition::Top
+            ),
+            None
+        );
+        assert_eq!(
+            TerminalView::grid_cell_for_position(
+                point(px(25.0), px(TAB_BAR_HEIGHT_PX + 45.0)),
+                cell_size,
+                size(px(0.0), px(0.0)),
+                TabBarPosition::Top
+            ),
+            Some((2, 2))
+        );
+    }
+
+    #[test]
+    fn cursor_color_falls_back_to_theme_cursor() {
+        let palette = theme_palette(&TerminalTheme::AtomOneDark, &ThemeRegistry::default());
+
+        assert_eq!(
+            cursor_color(&TerminalSettings::default(), palette),
+            palette.cursor
+        );
+    }
+
+    #[test]
+    fn cursor_color_setting_overrides_the_theme() {
+        let palette = theme_palette(&TerminalTheme::AtomOneDark, &ThemeRegistry::default());
+        let settings = TerminalSettings {
+            cursor_color: Some(0xff8800),
+            ..TerminalSettings::default()
+        };
+
+        assert_eq!(cursor_color(&settings, palette), 0xff8800);
+    }
+
+    #[test]
+    fn snapshot_exposes_character_under_cursor() {
+        let snapshot = snapshot_from_rows(&["abc", "def"], Some(1), 2, true);
+
+        assert_eq!(snapshot.cursor_cell().map(|cell| cell.c), Some('f'));
+    }
+
+    #[test]
+    fn snapshot_has_no_cursor_cell_outside_viewport() {
+        let above = snapshot_from_rows(&["abc"], None, 0, true);
+        let past_end = snapshot_from_rows(&["abc"], Some(0), 3, true);
+
+        assert!(above.cursor_cell().is_none());
+        assert!(past_end.cursor_cell().is_none());
+    }
+
+    #[test]
+    fn cursor_text_uses_cell_background_unless_overridden() {
+        let colors = test_colors();
+        let plain = cell('x', Flags::empty());
+        let inverse = GridCell {
+            fg: AlacColor::Spec(AlacRgb { r: 1, g: 2, b: 3 }),
+            ..cell('x', Flags::INVERSE)
+        };
+
+        assert_eq!(cursor_text_rgb(None, &plain, &colors), colors.background);
+        assert_eq!(
+            cursor_text_rgb(None, &inverse, &colors),
+            AlacRgb { r: 1, g: 2, b: 3 }
+        );
+        assert_eq!(
+            cursor_text_rgb(Some(0x102030), &plain, &colors),
+            AlacRgb {
+                r: 0x10,
+                g: 0x20,
+                b: 0x30
+            }
+        );
+    }
+
+    #[test]
+    fn grid_cell_for_position_skips_padding() {
+        let cell_size = size(px(10.0), px(20.0));
+        let padding = size(px(12.0), px(6.0));
+
+        assert_eq!(
+            TerminalView::grid_cell_for_position(
+                point(px(8.0), px(TAB_BAR_HEIGHT_PX + 30.0)),
+                cell_size,
+                padding,
+                TabBarPosition::Top
+            ),
+            None
+        );
+        assert_eq!(
+            TerminalView::grid_cell_for_position(
+                point(px(37.0), px(TAB_BAR_HEIGHT_PX + 51.0)),
+                cell_size,
+                padding,
+                TabBarPosition::Top
+            ),
+            Some((2, 2))
+        );
+    }
+
+    #[test]
+    fn click_inside_padded_grid_maps_to_grid_point() {
+        let pane = PaneGrid {
+            col_offset: 0,
+            line_offset: 0,
+            cols: 80,
+            lines: 24,
+        };
+        let bounds = pane_terminal_bounds(
+            &pane,
+            size(px(10.0), px(20.0)),
+            size(px(12.0), px(6.0)),
+            TabBarPosition::Top,
+        );
+
+        // Column 3, line 2, left half of the cell.
+        let position = point(px(12.0 + 32.0), px(TAB_BAR_HEIGHT_PX + 6.0 + 45.0));
+        let (grid, side) = grid_point_and_side(position, bounds, 0);
+
+        assert_eq!(grid, AlacPoint::new(Line(2), Column(3)));
+        assert_eq!(side, Side::Left);
+    }
+
+    #[test]
+    fn padding_shrinks_the_grid_on_both_sides() {
+        let viewport = size(px(820.0), px(TAB_BAR_HEIGHT_PX + 500.0));
+        let cell_size = size(px(10.0), px(20.0));
 
-        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache));
-        assert_eq!(dirty, vec![false, true, false]);
+        let unpadded = TerminalView::terminal_grid_for_viewport(
+            viewport,
+            cell_size,
+            size(px(0.0), px(0.0)),
+            TabBarPosition::Top,
+        );
+        let padded = TerminalView::terminal_grid_for_viewport(
+            viewport,
+            cell_size,
+            size(px(10.0), px(10.0)),
+            TabBarPosition::Top,
+        );
+
+        assert_eq!((unpadded.width, unpadded.height), (82, 25));
+        assert_eq!((padded.width, padded.height), (80, 24));
     }
 
     #[test]
-    fn dirty_rows_mark_cursor_source_and_destination_rows() {
-        let previous = snapshot_from_rows(&["abc", "def"], Some(0), 1, true);
-        let current = snapshot_from_rows(&["abc", "def"], Some(1), 1, true);
-        let previous_cache = FrameCache::from_snapshot(&previous);
+    fn background_tab_collects_unread_flags_until_activated() {
+        let mut unread = TabUnread::default();
 
-        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache));
-        assert_eq!(dirty, vec![true, true]);
+        unread.note(false, true);
+        unread.note(true, true);
+        assert_eq!(unread, TabUnread::default());
+        assert_eq!(unread.dot_color(), None);
+
+        unread.note(false, false);
+        assert!(unread.has_activity && !unread.has_bell);
+        assert_eq!(unread.dot_color(), Some(tab_brand_purple(0.9)));
+
+        unread.note(true, false);
+        assert!(unread.has_activity && unread.has_bell);
+        assert_ne!(unread.dot_color(), Some(tab_brand_purple(0.9)));
+
+        // Activating the tab resets it, as `set_active_tab` does.
+        unread = TabUnread::default();
+        assert_eq!(unread.dot_color(), None);
     }
 
     #[test]
-    fn wakeup_event_maps_to_notify_action() {
-        assert_eq!(
-            update_action_for_terminal_event(TerminalEvent::Wakeup),
-            ViewUpdateAction::Notify
-        );
+    fn single_tab_hides_the_tab_bar_only_when_enabled() {
+        let height = |hide_tab_bar_with_single_tab, tab_count| {
+            let settings = TerminalSettings {
+                hide_tab_bar_with_single_tab,
+                ..TerminalSettings::default()
+            };
+            tab_bar_height(tab_bar_layout_position(&settings, tab_count))
+        };
+
+        assert_eq!(height(true, 1), px(0.0));
+        assert_eq!(height(true, 2), px(TAB_BAR_HEIGHT_PX));
+        assert_eq!(height(false, 1), px(TAB_BAR_HEIGHT_PX));
+        assert_eq!(height(false, 3), px(TAB_BAR_HEIGHT_PX));
     }
 
     #[test]
-    fn title_event_maps_to_title_update_and_notify_action() {
-        assert_eq!(
-            update_action_for_terminal_event(TerminalEvent::TitleChanged("hello".to_string())),
-            ViewUpdateAction::SetTitleAndNotify("hello".to_string())
-        );
+    fn tab_bar_position_sets_grid_height_and_origin() {
+        let viewport = size(px(800.0), px(TAB_BAR_HEIGHT_PX + 500.0));
+        let cell_size = size(px(10.0), px(20.0));
+        let padding = size(px(0.0), px(0.0));
+        let pane = PaneGrid {
+            col_offset: 0,
+            line_offset: 0,
+            cols: 80,
+            lines: 25,
+        };
+
+        for (position, lines, origin_y) in [
+            (TabBarPosition::Top, 25, TAB_BAR_HEIGHT_PX),
+            (TabBarPosition::Bottom, 25, 0.0),
+            (TabBarPosition::Hidden, 27, 0.0),
+        ] {
+            let grid =
+                TerminalView::terminal_grid_for_viewport(viewport, cell_size, padding, position);
+            let bounds = pane_terminal_bounds(&pane, cell_size, padding, position);
+
+            assert_eq!((grid.width, grid.height), (80, lines), "{position:?}");
+            assert_eq!(bounds.bounds.origin.y, px(origin_y), "{position:?}");
+            assert_eq!(
+                TerminalView::grid_cell_for_position(
+                    point(px(25.0), px(origin_y + 45.0)),
+                    cell_size,
+                    padding,
+                    position
+                ),
+                Some((2, 2)),
+                "{position:?}"
+            );
+        }
     }
 
     #[test]
-    fn bell_event_maps_to_ignore_action() {
+    fn context_menu_items_enable_copy_only_with_selection() {
         assert_eq!(
-            update_action_for_terminal_event(TerminalEvent::Bell),
-            ViewUpdateAction::Ignore
+            TerminalView::context_menu_items(false),
+            vec![
+                (ContextMenuAction::Copy, false),
+                (ContextMenuAction::Paste, true),
+                (ContextMenuAction::SelectAll, true),
+                (ContextMenuAction::ClearScrollback, true),
+            ]
         );
+        assert!(TerminalView::context_menu_items(true)
+            .iter()
+            .all(|(_, enabled)| *enabled));
     }
 
     #[test]
-    fn exit_event_maps_to_exit_action() {
+    fn context_menu_origin_flips_at_viewport_edges() {
+        let menu = size(px(180.0), px(104.0));
+        let viewport = size(px(800.0), px(600.0));
+
         assert_eq!(
-            update_action_for_terminal_event(TerminalEvent::Exit(0)),
-            ViewUpdateAction::Exit
+            TerminalView::context_menu_origin(point(px(100.0), px(100.0)), menu, viewport),
+            point(px(100.0), px(100.0))
+        );
+        assert_eq!(
+            TerminalView::context_menu_origin(point(px(700.0), px(550.0)), menu, viewport),
+            point(px(520.0), px(446.0))
         );
     }
 
     #[test]
-    fn sanitize_tab_title_removes_control_characters_and_limits_length() {
-        let sanitized = TerminalView::sanitize_tab_title("  \u{0007}hello\tworld\u{001b}[31m  ");
-        assert_eq!(sanitized, "hello\tworld[31m");
-
-        let long = "x".repeat(120);
-        let truncated = TerminalView::sanitize_tab_title(&long);
-        assert_eq!(truncated.len(), 60);
-    }
+    fn tab_dropdown_entries_mark_only_active_tab() {
+        let entries = TerminalView::tab_dropdown_entries(
+            vec![
+                (1, "1: zsh".to_string()),
+                (4, "2: vim".to_string()),
+                (7, "3: logs".to_string()),
+            ],
+            4,
+        );
 
-    #[test]
-    fn sanitize_tab_title_falls_back_when_empty_after_sanitization() {
         assert_eq!(
-            TerminalView::sanitize_tab_title("\u{0000}\u{0001}  "),
-            "shell"
+            entries,
+            vec![
+                (1, "1: zsh".to_string(), false),
+                (4, "2: vim".to_string(), true),
+                (7, "3: logs".to_string(), false),
+            ]
         );
     }
 
     #[test]
-    fn next_tab_number_fills_first_available_slot() {
-        assert_eq!(TerminalView::next_tab_number_from_numbers(&[]), 1);
-        assert_eq!(TerminalView::next_tab_number_from_numbers(&[1, 2, 4]), 3);
-        assert_eq!(TerminalView::next_tab_number_from_numbers(&[2, 3]), 1);
+    fn duplicated_tab_title_skips_number_placeholder() {
+        assert_eq!(TerminalView::duplicated_tab_title(2, "2"), None);
+        assert_eq!(
+            TerminalView::duplicated_tab_title(2, "vim"),
+            Some("vim".to_string())
+        );
     }
 
     #[test]
@@ -5330,19 +10920,92 @@ mod tests {
 
     #[test]
     fn next_theme_wraps_for_forward_and_backward_navigation() {
+        let registry = ThemeRegistry::default();
+        assert_eq!(
+            TerminalView::next_theme(&TerminalTheme::GithubLight, &registry, 1),
+            TerminalTheme::AtomOneDark
+        );
+        assert_eq!(
+            TerminalView::next_theme(&TerminalTheme::AtomOneDark, &registry, -1),
+            TerminalTheme::GithubLight
+        );
+    }
+
+    fn test_custom_theme_definition() -> CustomThemeDefinition {
+        CustomThemeDefinition::from_json(
+            r##"{
+                "ui_bg": "#191a21",
+                "terminal_bg": "#282a36",
+                "cursor": "#f8f8f2",
+                "ansi_colors": [
+                    "#21222c", "#ff5555", "#50fa7b", "#f1fa8c",
+                    "#bd93f9", "#ff79c6", "#8be9fd", "#f8f8f2",
+                    "#6272a4", "#ff6e6e", "#69ff94", "#ffffa5",
+                    "#d6acff", "#ff92df", "#a4ffff", "#ffffff"
+                ],
+                "foreground": "#f8f8f2",
+                "background": "#282a36"
+            }"##,
+        )
+        .expect("valid custom theme")
+    }
+
+    #[test]
+    fn custom_theme_json_converts_into_palette() {
+        let palette = ThemePalette::from_custom(&test_custom_theme_definition());
+
+        assert_eq!(palette.ui_bg, 0x191a21);
+        assert_eq!(palette.terminal_bg, 0x282a36);
+        assert_eq!(palette.cursor, 0xf8f8f2);
+        assert_eq!(palette.ansi_colors[0], (0x21, 0x22, 0x2c));
+        assert_eq!(palette.ansi_colors[9], (0xff, 0x6e, 0x6e));
+        assert_eq!(palette.foreground, (0xf8, 0xf8, 0xf2));
+        assert_eq!(palette.background, (0x28, 0x2a, 0x36));
+    }
+
+    #[test]
+    fn theme_palette_resolves_custom_themes_and_falls_back_when_missing() {
+        let registry = ThemeRegistry::from_definitions(vec![(
+            "dracula".to_string(),
+            test_custom_theme_definition(),
+        )]);
+
+        let dracula = TerminalTheme::Custom("dracula".to_string());
+        assert_eq!(theme_palette(&dracula, &registry).terminal_bg, 0x282a36);
+        assert_eq!(TerminalView::theme_label(&dracula), "dracula");
+
+        let missing = TerminalTheme::Custom("missing".to_string());
+        assert_eq!(
+            theme_palette(&missing, &registry).terminal_bg,
+            theme_palette(&TerminalTheme::AtomOneDark, &registry).terminal_bg
+        );
+    }
+
+    #[test]
+    fn next_theme_cycles_through_custom_themes_after_presets() {
+        let registry = ThemeRegistry::from_definitions(vec![(
+            "dracula".to_string(),
+            test_custom_theme_definition(),
+        )]);
+        let dracula = TerminalTheme::Custom("dracula".to_string());
+
+        assert_eq!(
+            TerminalView::next_theme(&TerminalTheme::GithubLight, &registry, 1),
+            dracula
+        );
         assert_eq!(
-            TerminalView::next_theme(TerminalTheme::SolarizedDark, 1),
+            TerminalView::next_theme(&dracula, &registry, 1),
             TerminalTheme::AtomOneDark
         );
         assert_eq!(
-            TerminalView::next_theme(TerminalTheme::AtomOneDark, -1),
-            TerminalTheme::SolarizedDark
+            TerminalView::next_theme(&TerminalTheme::AtomOneDark, &registry, -1),
+            dracula
         );
     }
 
     #[test]
     fn atom_one_dark_theme_palette_matches_configured_black_and_white_bias() {
-        let palette = theme_palette(TerminalTheme::AtomOneDark);
+        let palette = theme_palette(&TerminalTheme::AtomOneDark, &ThemeRegistry::default());
         assert_eq!(palette.ui_bg, 0x101010);
         assert_eq!(palette.terminal_bg, 0x000000);
         assert_eq!(palette.cursor, 0x528bff);
@@ -5386,6 +11049,45 @@ mod tests {
         assert_eq!(TerminalView::pin_indicator_symbol(false), "○");
     }
 
+    #[test]
+    fn unfocused_dim_fades_colors_toward_the_background_only_while_inactive() {
+        let background = AlacRgb {
+            r: 0x20,
+            g: 0x20,
+            b: 0x20,
+        };
+        let colors = ColorsSnapshot {
+            background,
+            dim: unfocused_color_dim(false, 0.5),
+            ..test_colors()
+        };
+        let red = AlacRgb {
+            r: 0xE0,
+            g: 0,
+            b: 0,
+        };
+
+        assert_eq!(
+            colors.dimmed(red),
+            AlacRgb {
+                r: 0x80,
+                g: 0x10,
+                b: 0x10
+            }
+        );
+        assert_eq!(colors.dimmed(background), background);
+        assert_eq!(
+            resolve_color(&AlacColor::Spec(red), &colors, true),
+            alac_rgb_to_hsla(colors.dimmed(red))
+        );
+
+        let focused = ColorsSnapshot {
+            dim: unfocused_color_dim(true, 0.5),
+            ..colors
+        };
+        assert_eq!(focused.dimmed(red), red);
+    }
+
     #[test]
     fn blend_rgb_interpolates_channels() {
         let base = AlacRgb { r: 0, g: 0, b: 0 };
@@ -5406,13 +11108,116 @@ mod tests {
         );
     }
 
+    #[test]
+    fn tab_tint_blends_over_the_terminal_background() {
+        assert_eq!(tinted_background(0x282828, None), 0x282828);
+        assert_eq!(tinted_background(0x282828, Some(0xE5484D)), 0x3F2C2C);
+
+        let items = TerminalView::tab_context_menu_items();
+        assert_eq!(items.len(), TAB_TINT_PRESETS.len());
+        assert_eq!(items[0].0.label(), "No Tint");
+        assert_eq!(items[0].0.swatch(), None);
+        assert_eq!(items[1].0.swatch(), Some(0xE5484D));
+    }
+
+    #[test]
+    fn light_presets_report_light_backgrounds() {
+        let registry = ThemeRegistry::default();
+        assert!(theme_palette(&TerminalTheme::SolarizedLight, &registry).is_light());
+        assert!(theme_palette(&TerminalTheme::GithubLight, &registry).is_light());
+        assert!(!theme_palette(&TerminalTheme::SolarizedDark, &registry).is_light());
+        assert!(!theme_palette(&TerminalTheme::AtomOneDark, &registry).is_light());
+    }
+
+    #[test]
+    fn selection_tint_stays_perceptible_on_light_and_dark_backgrounds() {
+        let registry = ThemeRegistry::default();
+        let pale_cursor_on_white = ThemePalette {
+            cursor: 0xF0F0F0,
+            ..theme_palette(&TerminalTheme::GithubLight, &registry)
+        };
+        let palettes = THEME_PRESETS
+            .iter()
+            .map(|theme| theme_palette(theme, &registry))
+            .chain([pale_cursor_on_white]);
+
+        for palette in palettes {
+            let background = rgb_u32_to_alac_rgb(palette.terminal_bg);
+            let selected = blend_rgb(
+                background,
+                selection_tint_rgb(palette),
+                SELECTION_TINT_ALPHA,
+            );
+            assert!(
+                contrast_ratio(selected, background) > 1.2,
+                "selection on {:06x} is too close to the background",
+                palette.terminal_bg
+            );
+        }
+    }
+
+    #[test]
+    fn scrollbar_thumb_darkens_on_light_backgrounds() {
+        let registry = ThemeRegistry::default();
+        let light = theme_palette(&TerminalTheme::GithubLight, &registry);
+        let dark = theme_palette(&TerminalTheme::AtomOneDark, &registry);
+        assert!(scrollbar_thumb_color(light, false).l < scrollbar_thumb_color(dark, false).l);
+    }
+
+    #[test]
+    fn hovered_scrollbar_thumb_stands_out_more_from_the_background() {
+        let registry = ThemeRegistry::default();
+        let light = theme_palette(&TerminalTheme::GithubLight, &registry);
+        let dark = theme_palette(&TerminalTheme::AtomOneDark, &registry);
+
+        assert!(scrollbar_thumb_color(light, true).l < scrollbar_thumb_color(light, false).l);
+        assert!(scrollbar_thumb_color(dark, true).l > scrollbar_thumb_color(dark, false).l);
+        assert!(scrollbar_thumb_color(dark, true).a > scrollbar_thumb_color(dark, false).a);
+    }
+
+    #[test]
+    fn light_dark_counterpart_pairs_solarized_and_restores_previous_theme() {
+        let registry = ThemeRegistry::default();
+        assert_eq!(
+            TerminalView::light_dark_counterpart(&TerminalTheme::SolarizedDark, None, &registry),
+            TerminalTheme::SolarizedLight
+        );
+        assert_eq!(
+            TerminalView::light_dark_counterpart(&TerminalTheme::Nord, None, &registry),
+            TerminalTheme::GithubLight
+        );
+        assert_eq!(
+            TerminalView::light_dark_counterpart(&TerminalTheme::GithubLight, None, &registry),
+            TerminalTheme::AtomOneDark
+        );
+        assert_eq!(
+            TerminalView::light_dark_counterpart(
+                &TerminalTheme::GithubLight,
+                Some(&TerminalTheme::Nord),
+                &registry,
+            ),
+            TerminalTheme::Nord
+        );
+        assert_eq!(
+            TerminalView::light_dark_counterpart(
+                &TerminalTheme::Nord,
+                Some(&TerminalTheme::TokyoNight),
+                &registry,
+            ),
+            TerminalTheme::GithubLight
+        );
+    }
+
     #[test]
     fn selection_background_color_uses_soft_tint_instead_of_foreground_swap() {
         let colors = test_colors();
         let selected = selection_background_color(
             &AlacColor::Named(NamedColor::Background),
             &colors,
-            selection_tint_rgb(TerminalTheme::AtomOneDark),
+            selection_tint_rgb(theme_palette(
+                &TerminalTheme::AtomOneDark,
+                &ThemeRegistry::default(),
+            )),
         );
 
         match selected {
@@ -5431,6 +11236,89 @@ mod tests {
         }
     }
 
+    #[test]
+    fn synchronized_update_holds_the_frame_until_it_ends_or_times_out() {
+        let started = Instant::now();
+        let timeout = SYNC_UPDATE_TIMEOUT;
+
+        assert_eq!(
+            synchronized_update_hold(None, started + Duration::from_millis(10), timeout),
+            None
+        );
+        assert_eq!(
+            synchronized_update_hold(Some(started), started + Duration::from_millis(50), timeout),
+            Some(timeout - Duration::from_millis(50))
+        );
+        assert_eq!(
+            synchronized_update_hold(Some(started), started + timeout, timeout),
+            None
+        );
+        assert_eq!(
+            synchronized_update_hold(Some(started), started + timeout * 4, timeout),
+            None
+        );
+    }
+
+    #[test]
+    fn selection_color_settings_replace_the_tint_and_text_color() {
+        let tint = AlacRgb { r: 1, g: 2, b: 3 };
+        let selection = SelectionRange::new(
+            AlacPoint::new(Line(0), Column(0)),
+            AlacPoint::new(Line(0), Column(3)),
+            false,
+        );
+        let point = AlacPoint::new(Line(0), Column(1));
+        let cell = Cell {
+            fg: AlacColor::Named(NamedColor::Red),
+            ..Cell::default()
+        };
+
+        let fallback = snapshot_cell(&cell, point, Some(&selection), &test_colors(), tint);
+        assert_eq!(
+            fallback.bg,
+            AlacColor::Spec(blend_rgb(
+                test_colors().background,
+                tint,
+                SELECTION_TINT_ALPHA
+            ))
+        );
+        assert_eq!(fallback.fg, AlacColor::Named(NamedColor::Red));
+
+        let settings = TerminalSettings {
+            selection_background: Some(0x264F78),
+            selection_foreground: Some(0xFFFFFF),
+            ..TerminalSettings::default()
+        };
+        let palette = theme_palette(&TerminalTheme::AtomOneDark, &ThemeRegistry::default());
+        let colors = ColorsSnapshot::from_colors(&AlacColors::default(), palette, &settings, 0.0);
+        let overridden = snapshot_cell(&cell, point, Some(&selection), &colors, tint);
+        assert_eq!(
+            overridden.bg,
+            AlacColor::Spec(AlacRgb {
+                r: 0x26,
+                g: 0x4F,
+                b: 0x78
+            })
+        );
+        assert_eq!(
+            overridden.fg,
+            AlacColor::Spec(AlacRgb {
+                r: 0xFF,
+                g: 0xFF,
+                b: 0xFF
+            })
+        );
+
+        let unselected = snapshot_cell(
+            &cell,
+            AlacPoint::new(Line(1), Column(1)),
+            Some(&selection),
+            &colors,
+            tint,
+        );
+        assert_eq!(unselected.fg, AlacColor::Named(NamedColor::Red));
+    }
+
     #[test]
     fn toggled_settings_panel_open_flips_boolean_state() {
         assert!(TerminalView::toggled_settings_panel_open(false));
@@ -5523,6 +11411,10 @@ mod tests {
             "command+Backquote",
             &mismatched
         ));
+        assert!(TerminalView::pin_hotkey_matches_keystroke(
+            "Win + Backquote",
+            &matched
+        ));
     }
 
     #[test]
@@ -5564,7 +11456,7 @@ mod tests {
     #[test]
     fn theme_label_formats_atom_one_dark_with_spaces() {
         assert_eq!(
-            TerminalView::theme_label(TerminalTheme::AtomOneDark),
+            TerminalView::theme_label(&TerminalTheme::AtomOneDark),
             "Atom One Dark"
         );
     }
@@ -5644,33 +11536,179 @@ mod tests {
             SettingsLineHeightMode::Comfortable
         );
         assert_eq!(
-            TerminalView::line_height_mode(&LineHeight::Standard),
-            SettingsLineHeightMode::Standard
+            TerminalView::line_height_mode(&LineHeight::Standard),
+            SettingsLineHeightMode::Standard
+        );
+        assert_eq!(
+            TerminalView::line_height_mode(&LineHeight::Custom { value: 1.75 }),
+            SettingsLineHeightMode::Custom
+        );
+    }
+
+    #[test]
+    fn scrollback_lines_default_and_steps_are_capped() {
+        let max = simple_term::config::MAX_SCROLL_HISTORY_LINES;
+        let unset = TerminalSettings {
+            max_scroll_history_lines: None,
+            ..TerminalSettings::default()
+        };
+        let oversized = TerminalSettings {
+            max_scroll_history_lines: Some(max * 2),
+            ..TerminalSettings::default()
+        };
+
+        assert_eq!(
+            TerminalView::scrollback_lines(&unset),
+            simple_term::config::DEFAULT_SCROLL_HISTORY_LINES
+        );
+        assert_eq!(TerminalView::scrollback_lines(&oversized), max);
+        assert_eq!(TerminalView::stepped_scrollback_lines(500, -1_000), 0);
+        assert_eq!(TerminalView::stepped_scrollback_lines(max - 10, 1_000), max);
+        assert_eq!(TerminalView::stepped_scrollback_lines(2_000, 1_000), 3_000);
+    }
+
+    #[test]
+    fn hyperlink_timeout_steps_stop_at_off_and_the_cap() {
+        assert_eq!(TerminalView::stepped_hyperlink_timeout_ms(50, -100), 0);
+        assert_eq!(TerminalView::stepped_hyperlink_timeout_ms(500, 100), 600);
+        assert_eq!(
+            TerminalView::stepped_hyperlink_timeout_ms(SETTINGS_MAX_HYPERLINK_TIMEOUT_MS, 100),
+            SETTINGS_MAX_HYPERLINK_TIMEOUT_MS
+        );
+        assert_eq!(TerminalView::hyperlink_timeout_display(0), "off");
+        assert_eq!(TerminalView::hyperlink_timeout_display(500), "500ms");
+    }
+
+    #[test]
+    fn normalized_scroll_multiplier_falls_back_for_invalid_values() {
+        assert_eq!(TerminalView::normalized_scroll_multiplier(3.5), 3.5);
+        assert_eq!(TerminalView::normalized_scroll_multiplier(0.0), 0.01);
+        assert_eq!(TerminalView::normalized_scroll_multiplier(20.0), 10.0);
+        assert_eq!(TerminalView::normalized_scroll_multiplier(f32::NAN), 1.0);
+        assert_eq!(
+            TerminalView::normalized_scroll_multiplier(f32::INFINITY),
+            1.0
+        );
+    }
+
+    #[test]
+    fn page_scroll_moves_by_screen_minus_one_line() {
+        assert_eq!(page_scroll_offset(0, 100, 24, true), 23);
+        assert_eq!(page_scroll_offset(46, 100, 24, false), 23);
+    }
+
+    #[test]
+    fn page_scroll_stays_within_history() {
+        assert_eq!(page_scroll_offset(90, 100, 24, true), 100);
+        assert_eq!(page_scroll_offset(100, 100, 24, true), 100);
+        assert_eq!(page_scroll_offset(10, 100, 24, false), 0);
+        assert_eq!(page_scroll_offset(0, 0, 24, true), 0);
+        assert_eq!(page_scroll_offset(0, 5, 1, true), 1);
+    }
+
+    #[test]
+    fn scroll_and_prompt_actions_have_default_bindings() {
+        let bindings = KeyBindings::default();
+
+        for (keystroke, action) in [
+            ("shift-home", KeyAction::ScrollToTop),
+            ("shift-end", KeyAction::ScrollToBottom),
+            ("shift-pageup", KeyAction::ScrollPageUp),
+            ("shift-pagedown", KeyAction::ScrollPageDown),
+            ("cmd-up", KeyAction::PreviousPrompt),
+            ("cmd-down", KeyAction::NextPrompt),
+        ] {
+            let keystroke = Keystroke::parse(keystroke).expect("keystroke");
+            assert_eq!(bindings.action_for(&keystroke), Some(action));
+        }
+    }
+
+    #[test]
+    fn font_size_actions_have_default_bindings() {
+        let bindings = KeyBindings::default();
+
+        for (keystroke, action) in [
+            ("cmd-=", KeyAction::IncreaseFontSize),
+            ("cmd-shift-=", KeyAction::IncreaseFontSize),
+            ("cmd--", KeyAction::DecreaseFontSize),
+            ("cmd-0", KeyAction::ResetFontSize),
+        ] {
+            let keystroke = Keystroke::parse(keystroke).expect("keystroke");
+            assert_eq!(bindings.action_for(&keystroke), Some(action));
+        }
+    }
+
+    #[test]
+    fn font_size_steps_stay_within_the_settings_range() {
+        assert_eq!(clamp_font_size(14.0 + SETTINGS_FONT_SIZE_STEP), 15.0);
+        assert_eq!(
+            clamp_font_size(SETTINGS_MAX_FONT_SIZE - 0.5 + SETTINGS_FONT_SIZE_STEP),
+            SETTINGS_MAX_FONT_SIZE
+        );
+        assert_eq!(
+            clamp_font_size(SETTINGS_MIN_FONT_SIZE - SETTINGS_FONT_SIZE_STEP),
+            SETTINGS_MIN_FONT_SIZE
+        );
+    }
+
+    #[test]
+    fn font_size_reset_returns_to_the_default_setting() {
+        let size = reset_font_size();
+
+        assert_eq!(size, TerminalSettings::default().font_size);
+        assert!((SETTINGS_MIN_FONT_SIZE..=SETTINGS_MAX_FONT_SIZE).contains(&size));
+    }
+
+    #[test]
+    fn output_scrolls_to_bottom_only_when_enabled_and_not_reviewing() {
+        let now = Instant::now();
+        let long_ago = now - SCROLL_ON_OUTPUT_REVIEW_WINDOW;
+        let just_now = now - Duration::from_millis(200);
+
+        assert!(should_scroll_on_output(true, 40, None, now));
+        assert!(should_scroll_on_output(true, 40, Some(long_ago), now));
+        assert!(!should_scroll_on_output(true, 40, Some(just_now), now));
+        assert!(!should_scroll_on_output(true, 0, None, now));
+        assert!(!should_scroll_on_output(false, 40, None, now));
+    }
+
+    #[test]
+    fn broadcast_input_targets_every_tab_only_while_enabled() {
+        assert_eq!(input_target_tabs(true, 3, 1).collect::<Vec<_>>(), [0, 1, 2]);
+        assert_eq!(input_target_tabs(false, 3, 1).collect::<Vec<_>>(), [1]);
+        assert_eq!(input_target_tabs(true, 1, 0).collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    fn cursor_should_blink_respects_blinking_mode() {
+        assert!(!cursor_should_blink(Blinking::Off, true));
+        assert!(cursor_should_blink(Blinking::On, false));
+        assert!(cursor_should_blink(Blinking::TerminalControlled, true));
+        assert!(!cursor_should_blink(Blinking::TerminalControlled, false));
+    }
+
+    #[test]
+    fn inactive_window_shows_steady_hollow_cursor() {
+        assert_eq!(
+            cursor_style_for_focus(false, CursorShape::Beam, true),
+            (CursorShape::HollowBlock, false)
         );
         assert_eq!(
-            TerminalView::line_height_mode(&LineHeight::Custom { value: 1.75 }),
-            SettingsLineHeightMode::Custom
+            cursor_style_for_focus(false, CursorShape::Hidden, true),
+            (CursorShape::Hidden, true)
         );
     }
 
     #[test]
-    fn normalized_scroll_multiplier_falls_back_for_invalid_values() {
-        assert_eq!(TerminalView::normalized_scroll_multiplier(3.5), 3.5);
-        assert_eq!(TerminalView::normalized_scroll_multiplier(0.0), 0.01);
-        assert_eq!(TerminalView::normalized_scroll_multiplier(20.0), 10.0);
-        assert_eq!(TerminalView::normalized_scroll_multiplier(f32::NAN), 1.0);
+    fn active_window_keeps_configured_cursor_style() {
         assert_eq!(
-            TerminalView::normalized_scroll_multiplier(f32::INFINITY),
-            1.0
+            cursor_style_for_focus(true, CursorShape::Beam, true),
+            (CursorShape::Beam, true)
+        );
+        assert_eq!(
+            cursor_style_for_focus(true, CursorShape::Block, false),
+            (CursorShape::Block, false)
         );
-    }
-
-    #[test]
-    fn cursor_should_blink_respects_blinking_mode() {
-        assert!(!cursor_should_blink(Blinking::Off, true));
-        assert!(cursor_should_blink(Blinking::On, false));
-        assert!(cursor_should_blink(Blinking::TerminalControlled, true));
-        assert!(!cursor_should_blink(Blinking::TerminalControlled, false));
     }
 
     #[test]
@@ -5687,6 +11725,30 @@ mod tests {
         assert!(!cursor_blink_is_suppressed(None, now));
     }
 
+    #[test]
+    fn cursor_blink_suppression_lasts_the_configured_window() {
+        let now = Instant::now();
+        let settings = TerminalSettings {
+            cursor_blink_suppress_ms: 2_000,
+            ..TerminalSettings::default()
+        };
+        let until = cursor_blink_suppressed_until(now, settings.cursor_blink_suppression());
+
+        assert!(cursor_blink_is_suppressed(
+            until,
+            now + Duration::from_millis(1_999)
+        ));
+        assert!(!cursor_blink_is_suppressed(
+            until,
+            now + Duration::from_millis(2_000)
+        ));
+        assert_eq!(cursor_blink_suppressed_until(now, Duration::ZERO), None);
+        assert!(!cursor_blink_is_suppressed(
+            cursor_blink_suppressed_until(now, Duration::ZERO),
+            now
+        ));
+    }
+
     #[test]
     fn beam_cursor_width_is_narrower_than_cell_width() {
         let cell_width = px(10.0);
@@ -5709,7 +11771,7 @@ mod tests {
         let current = snapshot_from_rows(&["abc", "def"], Some(1), 2, true);
         let previous_cache = FrameCache::from_snapshot(&previous);
 
-        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache));
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
         assert_eq!(dirty, vec![false, true]);
     }
 
@@ -5719,7 +11781,7 @@ mod tests {
         let current = snapshot_from_rows_with_offset(&["X", "A", "B", "C"], None, 0, false, 1);
         let previous_cache = FrameCache::from_snapshot(&previous);
 
-        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache));
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
         assert_eq!(dirty, vec![true, false, false, false]);
     }
 
@@ -5729,38 +11791,42 @@ mod tests {
         let current = snapshot_from_rows_with_offset(&["W", "X", "Y", "Z"], None, 0, false, 4);
         let previous_cache = FrameCache::from_snapshot(&previous);
 
-        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache));
+        let dirty = dirty_rows_for_snapshot(&current, Some(&previous_cache), None);
         assert_eq!(dirty, vec![true, true, true, true]);
     }
 
     #[test]
     fn background_spans_merge_adjacent_cells_with_same_color() {
         let row = vec![
-            CellSnapshot {
+            GridCell {
                 c: 'a',
                 fg: AlacColor::Named(NamedColor::Foreground),
                 bg: AlacColor::Named(NamedColor::Background),
                 flags: Flags::empty(),
+                zerowidth: None,
             },
-            CellSnapshot {
+            GridCell {
                 c: 'b',
                 fg: AlacColor::Named(NamedColor::Foreground),
                 bg: AlacColor::Spec(AlacRgb { r: 200, g: 0, b: 0 }),
                 flags: Flags::empty(),
+                zerowidth: None,
             },
-            CellSnapshot {
+            GridCell {
                 c: 'c',
                 fg: AlacColor::Named(NamedColor::Foreground),
                 bg: AlacColor::Spec(AlacRgb { r: 200, g: 0, b: 0 }),
                 flags: Flags::empty(),
+                zerowidth: None,
             },
-            CellSnapshot {
+            GridCell {
                 c: 'd',
                 fg: AlacColor::Named(NamedColor::Foreground),
                 bg: AlacColor::Named(NamedColor::Background),
                 flags: Flags::empty(),
+                zerowidth: None,
             },
-            CellSnapshot {
+            GridCell {
                 c: 'e',
                 fg: AlacColor::Named(NamedColor::Foreground),
                 bg: AlacColor::Spec(AlacRgb {
@@ -5769,6 +11835,7 @@ mod tests {
                     b: 200,
                 }),
                 flags: Flags::empty(),
+                zerowidth: None,
             },
         ];
 
@@ -5780,6 +11847,164 @@ mod tests {
         assert_eq!(spans[1].len, 1);
     }
 
+    #[test]
+    fn row_cache_is_shared_until_a_row_is_rebuilt() {
+        let mut cache = Arc::new(vec![CachedRow::default(); 3]);
+        let painted = cache.clone();
+
+        store_rebuilt_rows(&mut cache, Vec::new());
+        assert!(Arc::ptr_eq(&cache, &painted));
+
+        let rebuilt = CachedRow {
+            initialized: true,
+            ..CachedRow::default()
+        };
+        store_rebuilt_rows(&mut cache, vec![(1, rebuilt)]);
+        assert!(!Arc::ptr_eq(&cache, &painted));
+        assert!(cache[1].initialized);
+        assert!(!painted[1].initialized);
+    }
+
+    #[test]
+    fn perf_snapshot_averages_recorded_frames() {
+        let perf = PerfInstrumentation {
+            log_enabled: false,
+            counters: Arc::default(),
+            logged_frames: 0,
+        };
+        assert_eq!(perf.snapshot(), PerfSnapshot::default());
+
+        for (snapshot_ms, paint_ms, dirty_rows, hits) in [(2, 4, 10, 30), (4, 8, 30, 10)] {
+            perf.record_frame(
+                SnapshotTiming {
+                    total: Duration::from_millis(snapshot_ms),
+                    lock_hold: Duration::from_millis(1),
+                },
+                Duration::from_millis(paint_ms),
+                dirty_rows,
+                40,
+                RowCacheStats {
+                    text_hits: hits,
+                    text_misses: 40 - hits,
+                    background_hits: 40,
+                    background_misses: 0,
+                },
+            );
+        }
+
+        let snapshot = perf.snapshot();
+        assert_eq!(snapshot.frames, 2);
+        assert!((snapshot.avg_snapshot_ms - 3.0).abs() < 1e-3);
+        assert!((snapshot.avg_lock_hold_ms - 1.0).abs() < 1e-3);
+        assert!((snapshot.avg_paint_ms - 6.0).abs() < 1e-3);
+        assert_eq!(snapshot.dirty_row_ratio, 0.5);
+        assert_eq!(snapshot.text_row_cache_hit_ratio, 0.5);
+        assert_eq!(snapshot.background_row_cache_hit_ratio, 1.0);
+    }
+
+    #[gpui::test]
+    fn parallel_row_building_matches_serial_order_and_output(cx: &mut gpui::TestAppContext) {
+        let rows = (0..100u8)
+            .map(|index| {
+                (0..40u8)
+                    .map(|col| GridCell {
+                        c: char::from(b'a' + (index + col) % 26),
+                        fg: AlacColor::Named(NamedColor::Foreground),
+                        bg: if (col / 5 + index) % 3 == 0 {
+                            AlacColor::Spec(AlacRgb {
+                                r: index,
+                                g: col,
+                                b: 200,
+                            })
+                        } else {
+                            AlacColor::Named(NamedColor::Background)
+                        },
+                        flags: if col % 7 == 0 {
+                            Flags::BOLD
+                        } else {
+                            Flags::empty()
+                        },
+                        zerowidth: None,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let colors = test_colors();
+        let fonts = TerminalFonts {
+            regular: gpui::font("Menlo"),
+            bold: gpui::font("Menlo").bold(),
+        };
+        let cx = cx.add_empty_window();
+        let (serial, parallel) = cx.update(|window, _| {
+            let text_system = window.text_system();
+            let build = |row: &Vec<GridCell>| {
+                let cached = build_cached_row(
+                    row,
+                    LineSize::Single,
+                    &colors,
+                    text_system,
+                    &fonts,
+                    px(14.0),
+                    px(8.0),
+                );
+                let runs = cached
+                    .text_runs
+                    .iter()
+                    .map(|run| (run.start_col, format!("{:?}", run.shaped)))
+                    .collect::<Vec<_>>();
+                let spans = cached
+                    .background_spans
+                    .iter()
+                    .map(|span| (span.start_col, span.len, span.color))
+                    .collect::<Vec<_>>();
+                (cached.initialized, runs, spans)
+            };
+            (
+                map_rows_in_parallel(&rows, 1, build),
+                map_rows_in_parallel(&rows, 4, build),
+            )
+        });
+
+        assert_eq!(serial.len(), rows.len());
+        assert!(serial.iter().all(|(_, runs, _)| !runs.is_empty()));
+        assert_eq!(parallel, serial);
+    }
+
+    #[test]
+    fn small_redraws_shape_on_one_thread() {
+        assert_eq!(shaping_thread_count(0), 1);
+        assert_eq!(shaping_thread_count(MIN_ROWS_FOR_PARALLEL_SHAPING - 1), 1);
+        assert!(shaping_thread_count(1_000) <= MAX_SHAPING_THREADS);
+    }
+
+    #[test]
+    fn wide_char_background_covers_its_spacer_column() {
+        let highlight = AlacColor::Spec(AlacRgb {
+            r: 0x40,
+            g: 0x80,
+            b: 0xC0,
+        });
+        let row = vec![
+            GridCell {
+                bg: highlight,
+                ..cell('界', Flags::WIDE_CHAR)
+            },
+            cell(' ', Flags::WIDE_CHAR_SPACER),
+            cell('a', Flags::empty()),
+        ];
+
+        let spans = build_background_spans(&row, &test_colors());
+        assert_eq!(spans.len(), 1);
+        assert_eq!((spans[0].start_col, spans[0].len), (0, 2));
+
+        let runs = build_positioned_text_runs(&row);
+        let columns = runs
+            .iter()
+            .map(|run| (run.start_col, run.text.as_str(), run.is_wide))
+            .collect::<Vec<_>>();
+        assert_eq!(columns, vec![(0, "界", true), (2, "a", false)]);
+    }
+
     #[test]
     fn background_spans_ignore_default_background_cells() {
         let row = vec![cell('a', Flags::empty()), cell('b', Flags::empty())];
@@ -5887,7 +12112,7 @@ mod tests {
         let row_refs: Vec<&str> = rows.iter().map(String::as_str).collect();
         let baseline = snapshot_from_rows_with_offset(&row_refs, Some(10), 3, true, 0);
         let mut next = snapshot_from_rows_with_offset(&row_refs, Some(11), 4, true, 1);
-        next.rows[5][7] = cell('Z', Flags::empty());
+        Arc::make_mut(&mut next.rows[5])[7] = cell('Z', Flags::empty());
         let previous_cache = FrameCache::from_snapshot(&baseline);
         let previous_view = PreviousFrameView::from_frame(&previous_cache);
         let mut row_cache = vec![CachedRow::default(); next.num_lines];
@@ -5895,7 +12120,7 @@ mod tests {
         let iterations = 1_000;
         let start = Instant::now();
         for _ in 0..iterations {
-            let _ = dirty_rows_for_snapshot(&next, Some(&previous_cache));
+            let _ = dirty_rows_for_snapshot(&next, Some(&previous_cache), None);
             shift_row_cache_for_display_offset(&mut row_cache, Some(previous_view), &next);
         }
         let elapsed = start.elapsed();
@@ -5926,11 +12151,61 @@ mod tests {
         assert_eq!(strip_line_column_suffix("/tmp/file.rs:12"), "/tmp/file.rs");
     }
 
+    #[test]
+    fn strips_line_column_suffix_without_touching_drive_letters() {
+        assert_eq!(
+            strip_line_column_suffix(r"C:\src\main.rs:12:3"),
+            r"C:\src\main.rs"
+        );
+        assert_eq!(strip_line_column_suffix("C:12"), "C:12");
+        assert_eq!(strip_line_column_suffix("C:"), "C:");
+        assert_eq!(strip_line_column_suffix("/tmp/file.rs:"), "/tmp/file.rs:");
+    }
+
     #[test]
     fn leaves_plain_paths_unchanged() {
         assert_eq!(strip_line_column_suffix("/tmp/file.rs"), "/tmp/file.rs");
     }
 
+    #[test]
+    fn splits_line_and_column_off_path_links() {
+        assert_eq!(
+            split_line_column_suffix("/tmp/file.rs:12:34"),
+            ("/tmp/file.rs", Some(12), Some(34))
+        );
+        assert_eq!(
+            split_line_column_suffix("src/main.rs:7"),
+            ("src/main.rs", Some(7), None)
+        );
+        assert_eq!(
+            split_line_column_suffix(r"C:\src\main.rs:3:1"),
+            (r"C:\src\main.rs", Some(3), Some(1))
+        );
+        assert_eq!(split_line_column_suffix("C:12"), ("C:12", None, None));
+        assert_eq!(
+            split_line_column_suffix("/tmp/file.rs"),
+            ("/tmp/file.rs", None, None)
+        );
+    }
+
+    #[test]
+    fn file_open_template_fills_path_line_and_column() {
+        let template = Some("vscode://file/{path}:{line}:{col}");
+
+        assert_eq!(
+            file_open_url(template, "/work/src/main.rs", Some(12), Some(4)),
+            "vscode://file//work/src/main.rs:12:4"
+        );
+        assert_eq!(
+            file_open_url(template, "/work/src/main.rs", Some(12), None),
+            "vscode://file//work/src/main.rs:12:1"
+        );
+        assert_eq!(
+            file_open_url(None, "/work/src/main.rs", Some(12), Some(4)),
+            "file:///work/src/main.rs"
+        );
+    }
+
     #[test]
     fn file_path_to_file_url_encodes_spaces_and_reserved_characters() {
         let url = file_path_to_file_url("/tmp/hello world#frag?.rs");
@@ -5963,6 +12238,34 @@ mod tests {
         assert_eq!(runs[1].text, "B");
     }
 
+    #[test]
+    fn combining_marks_join_the_base_character_run() {
+        let accented = GridCell {
+            zerowidth: Some(Arc::from("\u{301}")),
+            ..cell('e', Flags::empty())
+        };
+
+        let runs = build_positioned_text_runs(std::slice::from_ref(&accented));
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].start_col, 0);
+        assert_eq!(runs[0].text, "e\u{301}");
+        assert!(runs[0].has_combining_marks);
+
+        let runs = build_positioned_text_runs(&[
+            cell('a', Flags::empty()),
+            accented,
+            cell('b', Flags::empty()),
+        ]);
+        let columns = runs
+            .iter()
+            .map(|run| (run.start_col, run.text.as_str(), run.has_combining_marks))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            columns,
+            vec![(0, "a", false), (1, "e\u{301}", true), (2, "b", false)]
+        );
+    }
+
     #[test]
     fn positioned_runs_preserve_gaps_for_blank_cells() {
         let row = vec![
@@ -6009,6 +12312,41 @@ mod tests {
         assert!(pending < 0.0);
     }
 
+    #[test]
+    fn scroll_multiplier_is_chosen_by_delta_type() {
+        let lines = ScrollDelta::Lines(point(0.0, 1.0));
+        let pixels = ScrollDelta::Pixels(point(px(0.0), px(16.0)));
+        let shared = TerminalSettings {
+            scroll_multiplier: 2.0,
+            ..TerminalSettings::default()
+        };
+        assert_eq!(scroll_multiplier_for_delta(lines, &shared), 2.0);
+        assert_eq!(scroll_multiplier_for_delta(pixels, &shared), 2.0);
+
+        let per_device = TerminalSettings {
+            mouse_scroll_multiplier: Some(5.0),
+            trackpad_scroll_multiplier: Some(0.5),
+            ..shared
+        };
+        assert_eq!(scroll_multiplier_for_delta(lines, &per_device), 5.0);
+        assert_eq!(scroll_multiplier_for_delta(pixels, &per_device), 0.5);
+
+        let invalid = TerminalSettings {
+            trackpad_scroll_multiplier: Some(f32::NAN),
+            ..TerminalSettings::default()
+        };
+        assert_eq!(scroll_multiplier_for_delta(pixels, &invalid), 1.0);
+    }
+
+    #[test]
+    fn natural_scroll_flips_the_direction_only_when_enabled() {
+        assert_eq!(directed_scroll_lines(3, false), 3);
+        assert_eq!(directed_scroll_lines(-2, false), -2);
+        assert_eq!(directed_scroll_lines(3, true), -3);
+        assert_eq!(directed_scroll_lines(-2, true), 2);
+        assert_eq!(directed_scroll_lines(0, true), 0);
+    }
+
     #[test]
     fn effective_scroll_multiplier_has_positive_finite_floor() {
         assert_eq!(effective_scroll_multiplier(3.0), 3.0);
@@ -6038,6 +12376,43 @@ mod tests {
         assert!(!alternate_scroll_enabled(mode, AlternateScroll::On, true));
     }
 
+    #[test]
+    fn wheel_scroll_target_covers_every_mode_setting_and_shift() {
+        use WheelScrollTarget::{ArrowKeys, MouseReport, Scrollback};
+
+        let pager = TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL;
+        let mouse = TermMode::MOUSE_REPORT_CLICK | TermMode::SGR_MOUSE;
+        // (mode, target with the setting on, target with it off), without shift.
+        let cases = [
+            (TermMode::NONE, Scrollback, Scrollback),
+            (TermMode::ALTERNATE_SCROLL, Scrollback, Scrollback),
+            (TermMode::ALT_SCREEN, Scrollback, Scrollback),
+            (pager, ArrowKeys, Scrollback),
+            (mouse, MouseReport, MouseReport),
+            (pager | mouse, MouseReport, MouseReport),
+        ];
+
+        for (mode, when_on, when_off) in cases {
+            assert_eq!(
+                wheel_scroll_target(mode, AlternateScroll::On, false),
+                when_on,
+                "{mode:?} with alternate scroll on"
+            );
+            assert_eq!(
+                wheel_scroll_target(mode, AlternateScroll::Off, false),
+                when_off,
+                "{mode:?} with alternate scroll off"
+            );
+            for setting in [AlternateScroll::On, AlternateScroll::Off] {
+                assert_eq!(
+                    wheel_scroll_target(mode, setting, true),
+                    Scrollback,
+                    "{mode:?} with shift held"
+                );
+            }
+        }
+    }
+
     #[test]
     fn working_directory_prefers_explicit_always_path() {
         let configured = PathBuf::from("/tmp/simple-term");
@@ -6047,9 +12422,266 @@ mod tests {
             },
             Some(PathBuf::from("/cwd")),
             Some(PathBuf::from("/home")),
+            |_| None,
+        );
+
+        assert_eq!(resolved, Some(configured));
+    }
+
+    fn always_directory(directory: &str) -> WorkingDirectory {
+        WorkingDirectory::Always {
+            directory: PathBuf::from(directory),
+        }
+    }
+
+    #[test]
+    fn working_directory_expands_leading_tilde_to_home() {
+        let resolve = |directory| {
+            resolve_working_directory_with_fallback(
+                &always_directory(directory),
+                Some(PathBuf::from("/cwd")),
+                Some(PathBuf::from("/home/test")),
+                |_| None,
+            )
+        };
+
+        assert_eq!(
+            resolve("~/projects"),
+            Some(PathBuf::from("/home/test/projects"))
+        );
+        assert_eq!(resolve("~"), Some(PathBuf::from("/home/test")));
+        assert_eq!(resolve("/srv/~user"), Some(PathBuf::from("/srv/~user")));
+    }
+
+    #[test]
+    fn working_directory_substitutes_defined_variables() {
+        let env_var = |name: &str| (name == "WORKSPACE").then(|| "/work".to_string());
+        let resolve = |directory| {
+            resolve_working_directory_with_fallback(
+                &always_directory(directory),
+                Some(PathBuf::from("/cwd")),
+                Some(PathBuf::from("/home/test")),
+                env_var,
+            )
+        };
+
+        assert_eq!(resolve("$WORKSPACE/app"), Some(PathBuf::from("/work/app")));
+        assert_eq!(
+            resolve("${WORKSPACE}_old"),
+            Some(PathBuf::from("/work_old"))
+        );
+        assert_eq!(resolve("/price/$5"), Some(PathBuf::from("/price/$5")));
+    }
+
+    #[test]
+    fn working_directory_expands_undefined_variables_to_nothing() {
+        let resolve = |directory| {
+            resolve_working_directory_with_fallback(
+                &always_directory(directory),
+                Some(PathBuf::from("/cwd")),
+                Some(PathBuf::from("/home/test")),
+                |_| None,
+            )
+        };
+
+        assert_eq!(
+            resolve("/data/$MISSING/logs"),
+            Some(PathBuf::from("/data//logs"))
+        );
+        assert_eq!(resolve("${MISSING}"), Some(PathBuf::from("/cwd")));
+    }
+
+    #[test]
+    fn new_tab_working_directory_prefers_active_tab_cwd() {
+        let resolved = new_tab_working_directory(
+            Some(std::path::Path::new("/from/osc7")),
+            &WorkingDirectory::Always {
+                directory: PathBuf::from("/configured"),
+            },
+        );
+
+        assert_eq!(resolved, Some(PathBuf::from("/from/osc7")));
+    }
+
+    #[test]
+    fn new_tab_working_directory_prefers_tracked_cwd_over_home_strategy() {
+        let resolved = new_tab_working_directory(
+            Some(std::path::Path::new("/from/osc7")),
+            &WorkingDirectory::AlwaysHome,
+        );
+
+        assert_eq!(resolved, Some(PathBuf::from("/from/osc7")));
+    }
+
+    #[test]
+    fn new_tab_working_directory_falls_back_to_configured_strategy() {
+        let resolved = new_tab_working_directory(
+            None,
+            &WorkingDirectory::Always {
+                directory: PathBuf::from("/configured"),
+            },
+        );
+
+        assert_eq!(resolved, Some(PathBuf::from("/configured")));
+    }
+
+    #[test]
+    fn find_wraps_between_two_matches_in_both_directions() {
+        let term = mock_term("foo bar foo");
+        let mut regex = RegexSearch::new("foo").expect("regex");
+        let first = FindMatch {
+            start: AlacPoint::new(Line(0), Column(0)),
+            end: AlacPoint::new(Line(0), Column(2)),
+        };
+        let second = FindMatch {
+            start: AlacPoint::new(Line(0), Column(8)),
+            end: AlacPoint::new(Line(0), Column(10)),
+        };
+
+        let forward =
+            TerminalView::next_find_match(&term, &mut regex, Some(first), AlacDirection::Right);
+        assert_eq!(forward, Some(second));
+        assert!(!find_wrapped(AlacDirection::Right, first, second));
+
+        let wrapped =
+            TerminalView::next_find_match(&term, &mut regex, Some(second), AlacDirection::Right);
+        assert_eq!(wrapped, Some(first));
+        assert!(find_wrapped(AlacDirection::Right, second, first));
+        let mut cache = FindCache::new("foo").expect("cache");
+        assert_eq!(cache.stats(&term, Some(first)), (2, Some(1)));
+
+        let backward =
+            TerminalView::next_find_match(&term, &mut regex, Some(first), AlacDirection::Left);
+        assert_eq!(backward, Some(second));
+        assert!(find_wrapped(AlacDirection::Left, first, second));
+        assert_eq!(cache.stats(&term, Some(second)), (2, Some(2)));
+    }
+
+    #[test]
+    fn find_cache_is_reused_across_navigations_until_invalidated() {
+        let text = (0..200)
+            .map(|line| format!("line {line} foo"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let term = mock_term(&text);
+        let mut cache = FindCache::new("foo").expect("cache");
+        let first = cache.stats(&term, None);
+        assert_eq!(first, (200, None));
+        let matches = cache.matches.clone().expect("scanned");
+
+        // Navigating against a buffer without matches only answers from the
+        // cached list; a rescan would report zero matches.
+        let cleared = mock_term("bar");
+        for (index, active) in matches.iter().enumerate() {
+            assert_eq!(cache.stats(&cleared, Some(*active)), (200, Some(index + 1)));
+        }
+
+        cache.invalidate();
+        assert_eq!(cache.stats(&cleared, None), (0, None));
+        assert!(!cache.truncated);
+    }
+
+    fn unique_temp_dir(name: &str) -> PathBuf {
+        let stamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        std::env::temp_dir().join(format!("simple-term-{name}-{stamp}"))
+    }
+
+    #[test]
+    fn links_are_classified_as_urls_or_paths_with_the_tab_directory() {
+        let dir = unique_temp_dir("links");
+        std::fs::create_dir_all(dir.join("src")).expect("create temp dir");
+        std::fs::write(dir.join("src/main.rs"), "").expect("write temp file");
+
+        assert_eq!(
+            navigation_target("https://example.com".to_string(), true, None),
+            Some(MaybeNavigationTarget::Url(
+                "https://example.com".to_string()
+            ))
+        );
+        assert_eq!(
+            navigation_target("src/main.rs:3".to_string(), false, Some(dir.clone())),
+            Some(MaybeNavigationTarget::PathLike(PathLikeTarget {
+                maybe_path: "src/main.rs:3".to_string(),
+                terminal_dir: Some(dir.clone()),
+            }))
+        );
+        assert_eq!(
+            navigation_target("src/missing.rs".to_string(), false, Some(dir.clone())),
+            None
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn relative_paths_resolve_only_when_the_file_exists_in_the_tab_directory() {
+        let dir = unique_temp_dir("resolve");
+        std::fs::create_dir_all(dir.join("src")).expect("create temp dir");
+        std::fs::write(dir.join("src/lib.rs"), "").expect("write temp file");
+        let target = |maybe_path: &str, terminal_dir: Option<PathBuf>| PathLikeTarget {
+            maybe_path: maybe_path.to_string(),
+            terminal_dir,
+        };
+
+        assert_eq!(
+            resolve_path_target(&target("src/lib.rs:12:4", Some(dir.clone()))),
+            Some(dir.join("src/lib.rs"))
+        );
+        assert_eq!(
+            resolve_path_target(&target("src/nope.rs", Some(dir.clone()))),
+            None
+        );
+        assert_eq!(resolve_path_target(&target("src/lib.rs", None)), None);
+        assert_eq!(
+            resolve_path_target(&target("/tmp/elsewhere.rs:1", None)),
+            Some(PathBuf::from("/tmp/elsewhere.rs"))
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn close_is_confirmed_only_when_a_program_other_than_the_shell_runs() {
+        assert!(should_confirm_close(Some("vim"), Some("zsh")));
+        assert!(!should_confirm_close(Some("zsh"), Some("zsh")));
+        assert!(!should_confirm_close(None, Some("zsh")));
+        assert!(!should_confirm_close(Some("vim"), None));
+    }
+
+    #[test]
+    fn tab_title_prefers_custom_then_shell_then_process_then_opening_title() {
+        assert_eq!(
+            tab_title_text(Some("build"), "vim", true, Some("cargo")),
+            "build"
+        );
+        assert_eq!(tab_title_text(None, "vim", true, Some("cargo")), "vim");
+        assert_eq!(tab_title_text(None, "2", false, Some("cargo")), "cargo");
+        assert_eq!(tab_title_text(None, "2", false, None), "2");
+    }
+
+    #[test]
+    fn tab_title_label_shows_cwd_name_only_when_enabled() {
+        let cwd = std::path::Path::new("/Users/me/project");
+
+        assert_eq!(
+            TerminalView::tab_title_label(2, "zsh", Some(cwd), false),
+            "2: zsh"
+        );
+        assert_eq!(
+            TerminalView::tab_title_label(2, "zsh", Some(cwd), true),
+            "2: project"
+        );
+        assert_eq!(
+            TerminalView::tab_title_label(2, "zsh", None, true),
+            "2: zsh"
+        );
+        assert_eq!(
+            TerminalView::tab_title_label(3, "zsh", Some(std::path::Path::new("/")), true),
+            "3: /"
         );
-
-        assert_eq!(resolved, Some(configured));
     }
 
     #[test]
@@ -6058,6 +12690,7 @@ mod tests {
             &WorkingDirectory::AlwaysHome,
             Some(PathBuf::from("/cwd")),
             Some(PathBuf::from("/home/test")),
+            |_| None,
         );
 
         assert_eq!(resolved, Some(PathBuf::from("/home/test")));
@@ -6073,6 +12706,7 @@ mod tests {
                 &WorkingDirectory::CurrentFileDirectory,
                 cwd.clone(),
                 home.clone(),
+                |_| None,
             ),
             cwd
         );
@@ -6081,6 +12715,7 @@ mod tests {
                 &WorkingDirectory::CurrentProjectDirectory,
                 cwd.clone(),
                 home.clone(),
+                |_| None,
             ),
             cwd
         );
@@ -6089,6 +12724,7 @@ mod tests {
                 &WorkingDirectory::FirstProjectDirectory,
                 cwd.clone(),
                 home.clone(),
+                |_| None,
             ),
             cwd
         );
@@ -6117,10 +12753,56 @@ mod tests {
 
     #[test]
     fn selection_type_for_click_count_matches_terminal_conventions() {
-        assert_eq!(selection_type_for_click_count(1), SelectionType::Simple);
-        assert_eq!(selection_type_for_click_count(2), SelectionType::Semantic);
-        assert_eq!(selection_type_for_click_count(3), SelectionType::Lines);
-        assert_eq!(selection_type_for_click_count(4), SelectionType::Lines);
+        assert_eq!(
+            selection_type_for_click_count(1, false),
+            SelectionType::Simple
+        );
+        assert_eq!(
+            selection_type_for_click_count(2, false),
+            SelectionType::Semantic
+        );
+        assert_eq!(
+            selection_type_for_click_count(3, false),
+            SelectionType::Lines
+        );
+        assert_eq!(
+            selection_type_for_click_count(4, false),
+            SelectionType::Lines
+        );
+    }
+
+    #[test]
+    fn alt_click_starts_block_selection() {
+        assert_eq!(
+            selection_type_for_click_count(1, true),
+            SelectionType::Block
+        );
+        assert_eq!(
+            selection_type_for_click_count(2, true),
+            SelectionType::Semantic
+        );
+    }
+
+    #[test]
+    fn block_selection_tints_a_rectangle_of_cells() {
+        let colors = test_colors();
+        let tint = AlacRgb { r: 1, g: 2, b: 3 };
+        let selection = SelectionRange::new(
+            AlacPoint::new(Line(0), Column(2)),
+            AlacPoint::new(Line(2), Column(3)),
+            true,
+        );
+        let tinted = |line: i32, column: usize| {
+            let point = AlacPoint::new(Line(line), Column(column));
+            snapshot_cell(&Cell::default(), point, Some(&selection), &colors, tint).bg
+                != AlacColor::Named(NamedColor::Background)
+        };
+
+        for line in 0..3 {
+            let columns: Vec<usize> = (0..6).filter(|column| tinted(line, *column)).collect();
+            assert_eq!(columns, vec![2, 3], "line {line}");
+        }
+        assert!(!tinted(3, 2));
     }
 
     #[test]
@@ -6177,6 +12859,61 @@ mod tests {
         assert_eq!(lines, 3.0);
     }
 
+    #[test]
+    fn cmd_wheel_zooms_one_font_step_per_line_scrolled() {
+        let mut pending = 0.0;
+        let lines = |y| ScrollDelta::Lines(Point { x: 0.0, y });
+        let pixels = |y| {
+            ScrollDelta::Pixels(Point {
+                x: px(0.0),
+                y: px(y),
+            })
+        };
+
+        assert_eq!(wheel_font_zoom_steps(lines(2.0), px(20.0), &mut pending), 2);
+        assert_eq!(
+            wheel_font_zoom_steps(lines(-1.0), px(20.0), &mut pending),
+            -1
+        );
+        assert_eq!(
+            wheel_font_zoom_steps(pixels(12.0), px(20.0), &mut pending),
+            0
+        );
+        assert_eq!(
+            wheel_font_zoom_steps(pixels(12.0), px(20.0), &mut pending),
+            1
+        );
+        assert!((pending - 0.2).abs() < 1e-4);
+    }
+
+    #[test]
+    fn grid_range_row_spans_covers_single_row_match() {
+        let start = AlacPoint::new(Line(2), Column(4));
+        let end = AlacPoint::new(Line(2), Column(9));
+
+        assert_eq!(grid_range_row_spans(start, end, 0, 24, 80), vec![(2, 4, 6)]);
+    }
+
+    #[test]
+    fn grid_range_row_spans_wraps_across_rows() {
+        let start = AlacPoint::new(Line(1), Column(75));
+        let end = AlacPoint::new(Line(3), Column(2));
+
+        assert_eq!(
+            grid_range_row_spans(start, end, 0, 24, 80),
+            vec![(1, 75, 5), (2, 0, 80), (3, 0, 3)]
+        );
+    }
+
+    #[test]
+    fn grid_range_row_spans_applies_display_offset_and_clips_hidden_rows() {
+        let start = AlacPoint::new(Line(-3), Column(10));
+        let end = AlacPoint::new(Line(-2), Column(5));
+
+        assert_eq!(grid_range_row_spans(start, end, 2, 24, 80), vec![(0, 0, 6)]);
+        assert!(grid_range_row_spans(end, start, 2, 24, 80).is_empty());
+    }
+
     #[test]
     fn viewport_row_maps_scrollback_lines_into_visible_rows() {
         assert_eq!(viewport_row_for_line(-5, 5, 20), Some(0));
@@ -6606,7 +13343,7 @@ mod tests {
     }
 
     #[test]
-    fn common_shortcut_action_matches_platform_shortcuts() {
+    fn default_keybindings_match_platform_shortcuts() {
         let platform_copy = Keystroke {
             modifiers: Modifiers {
                 platform: true,
@@ -6616,8 +13353,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&platform_copy),
-            Some(CommonShortcutAction::CopySelection)
+            KeyBindings::default().action_for(&platform_copy),
+            Some(KeyAction::Copy)
         );
 
         let platform_paste = Keystroke {
@@ -6629,8 +13366,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&platform_paste),
-            Some(CommonShortcutAction::Paste)
+            KeyBindings::default().action_for(&platform_paste),
+            Some(KeyAction::Paste)
         );
 
         let platform_select_all = Keystroke {
@@ -6642,8 +13379,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&platform_select_all),
-            Some(CommonShortcutAction::SelectAll)
+            KeyBindings::default().action_for(&platform_select_all),
+            Some(KeyAction::SelectAll)
         );
 
         let platform_find = Keystroke {
@@ -6655,13 +13392,13 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&platform_find),
-            Some(CommonShortcutAction::Find)
+            KeyBindings::default().action_for(&platform_find),
+            Some(KeyAction::Find)
         );
     }
 
     #[test]
-    fn common_shortcut_action_matches_ctrl_shift_shortcuts() {
+    fn default_keybindings_match_ctrl_shift_shortcuts() {
         let ctrl_shift_copy = Keystroke {
             modifiers: Modifiers {
                 control: true,
@@ -6672,8 +13409,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&ctrl_shift_copy),
-            Some(CommonShortcutAction::CopySelection)
+            KeyBindings::default().action_for(&ctrl_shift_copy),
+            Some(KeyAction::Copy)
         );
 
         let ctrl_shift_paste = Keystroke {
@@ -6686,8 +13423,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&ctrl_shift_paste),
-            Some(CommonShortcutAction::Paste)
+            KeyBindings::default().action_for(&ctrl_shift_paste),
+            Some(KeyAction::Paste)
         );
 
         let ctrl_shift_select_all = Keystroke {
@@ -6700,8 +13437,8 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&ctrl_shift_select_all),
-            Some(CommonShortcutAction::SelectAll)
+            KeyBindings::default().action_for(&ctrl_shift_select_all),
+            Some(KeyAction::SelectAll)
         );
 
         let ctrl_shift_find = Keystroke {
@@ -6714,21 +13451,21 @@ mod tests {
             key_char: None,
         };
         assert_eq!(
-            common_shortcut_action(&ctrl_shift_find),
-            Some(CommonShortcutAction::Find)
+            KeyBindings::default().action_for(&ctrl_shift_find),
+            Some(KeyAction::Find)
         );
     }
 
     #[test]
-    fn common_shortcut_action_does_not_intercept_terminal_control_keys() {
+    fn default_keybindings_do_not_intercept_terminal_control_keys() {
         let ctrl_c = Keystroke::parse("ctrl-c").expect("valid ctrl-c");
-        assert_eq!(common_shortcut_action(&ctrl_c), None);
+        assert_eq!(KeyBindings::default().action_for(&ctrl_c), None);
 
         let ctrl_v = Keystroke::parse("ctrl-v").expect("valid ctrl-v");
-        assert_eq!(common_shortcut_action(&ctrl_v), None);
+        assert_eq!(KeyBindings::default().action_for(&ctrl_v), None);
 
         let ctrl_f = Keystroke::parse("ctrl-f").expect("valid ctrl-f");
-        assert_eq!(common_shortcut_action(&ctrl_f), None);
+        assert_eq!(KeyBindings::default().action_for(&ctrl_f), None);
 
         let platform_tab = Keystroke {
             modifiers: Modifiers {
@@ -6738,7 +13475,52 @@ mod tests {
             key: "tab".to_string(),
             key_char: None,
         };
-        assert_eq!(common_shortcut_action(&platform_tab), None);
+        assert_eq!(KeyBindings::default().action_for(&platform_tab), None);
+    }
+
+    #[test]
+    fn parse_keybinding_accepts_modifier_aliases_and_case() {
+        assert_eq!(
+            parse_keybinding("cmd+shift+t"),
+            parse_keybinding("Command+SHIFT+T")
+        );
+        assert_eq!(
+            parse_keybinding("ctrl+alt+k"),
+            parse_keybinding("control+option+k")
+        );
+        assert!(parse_keybinding("cmd++").is_some());
+        assert!(parse_keybinding("t").is_some());
+    }
+
+    #[test]
+    fn parse_keybinding_rejects_unknown_modifiers_and_missing_keys() {
+        assert_eq!(parse_keybinding(""), None);
+        assert_eq!(parse_keybinding("cmd+"), None);
+        assert_eq!(parse_keybinding("hyper+t"), None);
+    }
+
+    #[test]
+    fn keybindings_from_settings_override_only_mapped_actions() {
+        let overrides = [
+            ("new_tab".to_string(), "ctrl+shift+n".to_string()),
+            ("copy".to_string(), "not+a+key".to_string()),
+            ("unknown".to_string(), "cmd+u".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        let bindings = KeyBindings::from_settings(&overrides);
+
+        let ctrl_shift_n = Keystroke::parse("ctrl-shift-n").expect("valid ctrl-shift-n");
+        assert_eq!(bindings.action_for(&ctrl_shift_n), Some(KeyAction::NewTab));
+        let cmd_t = Keystroke::parse("cmd-t").expect("valid cmd-t");
+        assert_eq!(bindings.action_for(&cmd_t), None);
+        let cmd_c = Keystroke::parse("cmd-c").expect("valid cmd-c");
+        assert_eq!(bindings.action_for(&cmd_c), Some(KeyAction::Copy));
+        let cmd_shift_t = Keystroke::parse("cmd-shift-t").expect("valid cmd-shift-t");
+        assert_eq!(
+            bindings.action_for(&cmd_shift_t),
+            Some(KeyAction::DuplicateTab)
+        );
     }
 
     #[test]
@@ -6765,6 +13547,39 @@ mod tests {
         );
     }
 
+    #[test]
+    fn primary_selection_capture_keeps_only_non_empty_selections_when_enabled() {
+        assert_eq!(
+            primary_selection_capture(true, Some("ls -la")),
+            Some("ls -la".to_string())
+        );
+        assert_eq!(primary_selection_capture(true, Some("")), None);
+        assert_eq!(primary_selection_capture(true, None), None);
+        assert_eq!(primary_selection_capture(false, Some("ls -la")), None);
+    }
+
+    #[test]
+    fn completing_a_selection_is_reported_exactly_once() {
+        let mut term = mock_term("hello world");
+        let mut selection = Selection::new(
+            SelectionType::Simple,
+            AlacPoint::new(Line(0), Column(0)),
+            Side::Left,
+        );
+        selection.update(AlacPoint::new(Line(0), Column(4)), Side::Right);
+        let range = selection.to_range(&term);
+        term.selection = Some(selection);
+        let mut reported = None;
+
+        assert!(selection_changed(&mut reported, range));
+        assert!(!selection_changed(&mut reported, range));
+        assert_eq!(term.selection_to_string().as_deref(), Some("hello"));
+
+        // A click without a drag clears the reported selection once.
+        assert!(selection_changed(&mut reported, None));
+        assert!(!selection_changed(&mut reported, None));
+    }
+
     #[test]
     fn point_in_bounds_is_inclusive_on_edges() {
         let bounds = Bounds {
@@ -6778,6 +13593,28 @@ mod tests {
         assert!(!point_in_bounds(&bounds, point(px(40.1), px(60.0))));
     }
 
+    #[test]
+    fn scroll_position_label_counts_lines_and_percent_of_history() {
+        assert_eq!(scroll_position_label(0, 1000), None);
+        assert_eq!(scroll_position_label(5, 0), None);
+        assert_eq!(
+            scroll_position_label(1, 1000).as_deref(),
+            Some("1 line up (1%)")
+        );
+        assert_eq!(
+            scroll_position_label(250, 1000).as_deref(),
+            Some("250 lines up (25%)")
+        );
+        assert_eq!(
+            scroll_position_label(1000, 1000).as_deref(),
+            Some("1000 lines up (100%)")
+        );
+        assert_eq!(
+            scroll_position_label(1500, 1000).as_deref(),
+            Some("1000 lines up (100%)")
+        );
+    }
+
     #[test]
     fn scrollbar_layout_requires_enough_width_and_history() {
         let tiny = scrollbar_layout(
@@ -6823,4 +13660,444 @@ mod tests {
         assert_eq!(offset_top, 80);
         assert_eq!(offset_bottom, 0);
     }
+
+    #[test]
+    fn alt_screen_indicator_follows_the_alternate_screen_mode() {
+        assert!(!alt_screen_indicator_visible(TermMode::NONE));
+        assert!(!alt_screen_indicator_visible(
+            TermMode::ALTERNATE_SCROLL | TermMode::BRACKETED_PASTE
+        ));
+        assert!(alt_screen_indicator_visible(TermMode::ALT_SCREEN));
+        assert!(alt_screen_indicator_visible(
+            TermMode::ALT_SCREEN | TermMode::MOUSE_REPORT_CLICK
+        ));
+    }
+
+    #[test]
+    fn escape_clears_a_selection_before_reaching_the_program() {
+        let escape = Keystroke::parse("escape").expect("valid keystroke");
+
+        assert!(escape_clears_selection(&escape, true));
+        // With the selection gone, the next Escape goes to the program.
+        assert!(!escape_clears_selection(&escape, false));
+        let shift_escape = Keystroke::parse("shift-escape").expect("valid keystroke");
+        assert!(!escape_clears_selection(&shift_escape, true));
+        let letter = Keystroke::parse("a").expect("valid keystroke");
+        assert!(!escape_clears_selection(&letter, true));
+    }
+
+    #[test]
+    fn focus_changes_are_reported_only_when_the_program_asks() {
+        assert_eq!(focus_report(TermMode::NONE, true), None);
+        assert_eq!(focus_report(TermMode::NONE, false), None);
+        assert_eq!(
+            focus_report(TermMode::FOCUS_IN_OUT, true),
+            Some(&b"\x1b[I"[..])
+        );
+        assert_eq!(
+            focus_report(TermMode::FOCUS_IN_OUT | TermMode::ALT_SCREEN, false),
+            Some(&b"\x1b[O"[..])
+        );
+    }
+
+    #[test]
+    fn hyperlink_modifier_setting_picks_the_key_that_opens_links() {
+        let plain = Modifiers::default();
+        let cmd = Modifiers {
+            platform: true,
+            ..Modifiers::default()
+        };
+        let ctrl = Modifiers {
+            control: true,
+            ..Modifiers::default()
+        };
+        let alt = Modifiers {
+            alt: true,
+            ..Modifiers::default()
+        };
+        let held = |setting, modifiers: &Modifiers| {
+            hyperlink_modifier_held(setting, modifiers, TermMode::NONE)
+        };
+
+        assert!(held(HyperlinkModifier::None, &plain));
+        assert!(held(HyperlinkModifier::Cmd, &cmd));
+        assert!(!held(HyperlinkModifier::Cmd, &ctrl));
+        assert!(held(HyperlinkModifier::Ctrl, &ctrl));
+        assert!(!held(HyperlinkModifier::Ctrl, &plain));
+        assert!(held(HyperlinkModifier::Alt, &alt));
+        assert!(!held(HyperlinkModifier::Alt, &cmd));
+
+        // A plain click goes to programs that capture the mouse.
+        let secondary = if cfg!(target_os = "macos") { cmd } else { ctrl };
+        let mouse_mode = TermMode::MOUSE_REPORT_CLICK;
+        assert!(!hyperlink_modifier_held(
+            HyperlinkModifier::None,
+            &plain,
+            mouse_mode
+        ));
+        assert!(hyperlink_modifier_held(
+            HyperlinkModifier::None,
+            &secondary,
+            mouse_mode
+        ));
+        assert!(hyperlink_modifier_held(
+            HyperlinkModifier::Alt,
+            &alt,
+            mouse_mode
+        ));
+    }
+
+    #[test]
+    fn resize_gives_every_pane_of_every_tab_its_grid_and_cell_pixels() {
+        let single = PaneNode::Leaf(1);
+        let split = PaneNode::Split {
+            ratio: 0.5,
+            left: Box::new(PaneNode::Leaf(2)),
+            right: Box::new(PaneNode::Leaf(3)),
+        };
+        let grid_size = size(81, 24);
+        let cell_size = size(px(8.4), px(17.6));
+
+        let sizes: Vec<(u64, WindowSize)> = [&single, &split]
+            .into_iter()
+            .flat_map(|layout| pane_window_sizes(layout, grid_size, cell_size))
+            .collect();
+
+        let ids: Vec<u64> = sizes.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        for (_, window_size) in &sizes {
+            assert_eq!(window_size.num_lines, 24);
+            assert_eq!(window_size.cell_width, 8);
+            assert_eq!(window_size.cell_height, 18);
+        }
+        assert_eq!(sizes[0].1.num_cols, 81);
+        assert_eq!(
+            sizes[1].1.num_cols + sizes[2].1.num_cols + PANE_DIVIDER_COLS,
+            81
+        );
+    }
+
+    #[test]
+    fn multi_line_paste_needs_confirmation_without_bracketed_paste() {
+        assert!(paste_needs_confirmation("ls\nrm -rf build", TermMode::NONE));
+        assert!(paste_needs_confirmation("make\r", TermMode::NONE));
+        assert!(paste_needs_confirmation("echo done\n", TermMode::NONE));
+        assert!(!paste_needs_confirmation("git status", TermMode::NONE));
+        assert!(!paste_needs_confirmation("a\tb", TermMode::NONE));
+        assert!(!paste_needs_confirmation(
+            "ls\nrm -rf build",
+            TermMode::BRACKETED_PASTE
+        ));
+    }
+
+    #[test]
+    fn scale_factor_change_refreshes_typography_and_resnaps_cells() {
+        assert!(!typography_needs_refresh(2.0, 2.0));
+        assert!(typography_needs_refresh(2.0, 1.0));
+        assert!(typography_needs_refresh(1.0, 1.5));
+
+        assert_eq!(snap_to_device_pixels(px(8.4), 1.0), px(8.0));
+        assert_eq!(snap_to_device_pixels(px(8.4), 2.0), px(8.5));
+        assert_eq!(snap_to_device_pixels(px(0.2), 1.0), px(1.0));
+    }
+
+    #[test]
+    fn paste_preview_shortens_long_pastes() {
+        let long_line = "x".repeat(100);
+        let text = format!("{long_line}\n2\n3\n4\n5\n6\n7\n8");
+
+        let (preview, line_count) = paste_preview(&text);
+
+        assert_eq!(line_count, 8);
+        assert_eq!(preview.len(), PASTE_PREVIEW_MAX_LINES + 1);
+        assert_eq!(preview[0].chars().count(), PASTE_PREVIEW_MAX_COLUMNS + 1);
+        assert_eq!(preview[PASTE_PREVIEW_MAX_LINES], "… 2 more");
+    }
+
+    #[test]
+    fn typed_input_waits_behind_the_newest_pending_paste_for_its_pane() {
+        let paste = |pane_id, chunks: &[&[u8]]| PendingPaste {
+            tab_id: 1,
+            pane_id,
+            chunks: chunks.iter().map(|chunk| chunk.to_vec()).collect(),
+        };
+        let mut pending = vec![
+            paste(1, &[b"\x1b[200~one", b"two\x1b[201~"]),
+            paste(1, &[]),
+            paste(2, &[b"other"]),
+        ];
+
+        assert_eq!(
+            queue_behind_pending_paste(&mut pending, 1, 1, b"a".to_vec()),
+            None
+        );
+        assert_eq!(
+            queue_behind_pending_paste(&mut pending, 1, 1, b"b".to_vec()),
+            None
+        );
+        assert_eq!(pending[0].chunks.back(), Some(&b"two\x1b[201~".to_vec()));
+        assert_eq!(pending[1].chunks, [b"ab".to_vec()]);
+        assert_eq!(pending[2].chunks, [b"other".to_vec()]);
+
+        assert_eq!(
+            queue_behind_pending_paste(&mut pending, 1, 3, b"c".to_vec()),
+            Some(b"c".to_vec())
+        );
+        assert_eq!(
+            queue_behind_pending_paste(&mut pending, 2, 1, b"c".to_vec()),
+            Some(b"c".to_vec())
+        );
+    }
+
+    #[test]
+    fn track_clicks_page_toward_the_pointer_and_thumb_clicks_drag() {
+        let layout = ScrollbarLayout {
+            track: Bounds {
+                origin: point(px(0.0), px(0.0)),
+                size: size(px(10.0), px(100.0)),
+            },
+            thumb: Bounds {
+                origin: point(px(1.0), px(40.0)),
+                size: size(px(8.0), px(24.0)),
+            },
+            max_offset: 80,
+        };
+
+        assert_eq!(scrollbar_track_click_pages_up(px(5.0), &layout), Some(true));
+        assert_eq!(
+            scrollbar_track_click_pages_up(px(39.5), &layout),
+            Some(true)
+        );
+        assert_eq!(scrollbar_track_click_pages_up(px(40.0), &layout), None);
+        assert_eq!(scrollbar_track_click_pages_up(px(63.5), &layout), None);
+        assert_eq!(
+            scrollbar_track_click_pages_up(px(64.0), &layout),
+            Some(false)
+        );
+        assert_eq!(
+            scrollbar_track_click_pages_up(px(99.0), &layout),
+            Some(false)
+        );
+    }
+
+    #[test]
+    fn find_history_moves_repeated_queries_to_the_newest_entry() {
+        let mut history = FindHistory::default();
+        history.push("error");
+        history.push("warning");
+        history.push("");
+        history.push("error");
+
+        assert_eq!(history.previous("").as_deref(), Some("error"));
+        assert_eq!(history.previous("").as_deref(), Some("warning"));
+        assert_eq!(history.previous("").as_deref(), Some("warning"));
+    }
+
+    #[test]
+    fn find_history_keeps_only_the_most_recent_queries() {
+        let mut history = FindHistory::default();
+        for index in 0..FIND_HISTORY_LIMIT + 5 {
+            history.push(&format!("query {index}"));
+        }
+
+        let mut oldest = None;
+        for _ in 0..FIND_HISTORY_LIMIT + 5 {
+            oldest = history.previous("");
+        }
+        assert_eq!(oldest.as_deref(), Some("query 5"));
+    }
+
+    #[test]
+    fn find_history_cycles_back_to_the_typed_query() {
+        let mut history = FindHistory::default();
+        assert_eq!(history.previous("draft"), None);
+        assert_eq!(history.next(), None);
+
+        history.push("first");
+        history.push("second");
+        assert_eq!(history.previous("sec").as_deref(), Some("second"));
+        assert_eq!(history.previous("sec").as_deref(), Some("first"));
+        assert_eq!(history.next().as_deref(), Some("second"));
+        assert_eq!(history.next().as_deref(), Some("sec"));
+        assert_eq!(history.next(), None);
+
+        // Recalling while the newest entry is shown skips straight past it.
+        assert_eq!(history.previous("second").as_deref(), Some("first"));
+        history.reset_recall();
+        assert_eq!(history.next(), None);
+    }
+
+    #[test]
+    fn copy_mode_cursor_stays_within_grid_bounds() {
+        let top = Line(-3);
+        let bottom = Line(4);
+        let last_column = Column(9);
+        let step = |line, column, motion| {
+            move_copy_cursor(
+                AlacPoint::new(Line(line), Column(column)),
+                motion,
+                top,
+                bottom,
+                last_column,
+            )
+        };
+
+        assert_eq!(
+            step(-3, 0, CopyModeMotion::Up),
+            AlacPoint::new(top, Column(0))
+        );
+        assert_eq!(
+            step(-3, 0, CopyModeMotion::Left),
+            AlacPoint::new(top, Column(0))
+        );
+        assert_eq!(
+            step(4, 9, CopyModeMotion::Down),
+            AlacPoint::new(bottom, last_column)
+        );
+        assert_eq!(
+            step(4, 9, CopyModeMotion::Right),
+            AlacPoint::new(bottom, last_column)
+        );
+        assert_eq!(
+            step(1, 4, CopyModeMotion::Up),
+            AlacPoint::new(Line(0), Column(4))
+        );
+        assert_eq!(
+            step(1, 4, CopyModeMotion::LineEnd),
+            AlacPoint::new(Line(1), last_column)
+        );
+        assert_eq!(
+            step(1, 4, CopyModeMotion::LineStart),
+            AlacPoint::new(Line(1), Column(0))
+        );
+    }
+
+    #[test]
+    fn copy_mode_selection_extends_from_anchor_in_either_direction() {
+        let mut term = mock_term("hello world");
+        let mut state = CopyModeState::new(AlacPoint::new(Line(0), Column(6)));
+        assert!(state.selection().is_none());
+
+        state.toggle_selection();
+        for _ in 0..4 {
+            state.cursor = move_copy_cursor(
+                state.cursor,
+                CopyModeMotion::Right,
+                Line(0),
+                Line(0),
+                term.last_column(),
+            );
+        }
+        term.selection = state.selection();
+        assert_eq!(term.selection_to_string().as_deref(), Some("world"));
+
+        state.cursor = AlacPoint::new(Line(0), Column(2));
+        term.selection = state.selection();
+        assert_eq!(term.selection_to_string().as_deref(), Some("llo w"));
+
+        state.toggle_selection();
+        assert!(state.selection().is_none());
+    }
+
+    #[test]
+    fn copy_mode_selection_keeps_its_text_when_output_scrolls_the_grid() {
+        let mut term = mock_term("one\ntwo\nsix");
+        let mut state = CopyModeState::new(AlacPoint::new(Line(1), Column(0)));
+        state.toggle_selection();
+        state.cursor = AlacPoint::new(Line(1), Column(2));
+        state.store_cursor_in(&mut term);
+        term.selection = state.selection();
+        assert_eq!(term.selection_to_string().as_deref(), Some("two"));
+
+        Processor::<StdSyncHandler>::new().advance(&mut term, b"\x1b[3;1H\r\nnew\r\nmore");
+        state.follow_output(&term);
+        term.selection = state.selection();
+
+        assert!(state.cursor.line < Line(0));
+        assert_eq!(term.selection_to_string().as_deref(), Some("two"));
+    }
+
+    #[test]
+    fn copy_mode_cursor_follows_output_while_it_is_in_view() {
+        let size = alacritty_terminal::term::test::TermSize::new(10, 3);
+        let mut term = Term::new(Default::default(), &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let output: String = (0..10).map(|line| format!("\r\nline{line}")).collect();
+        processor.advance(&mut term, output.as_bytes());
+        term.scroll_display(Scroll::Delta(2));
+        let mut state = CopyModeState::new(AlacPoint::new(Line(-1), Column(0)));
+        state.store_cursor_in(&mut term);
+
+        processor.advance(&mut term, b"\r\nnew");
+        state.follow_output(&term);
+
+        assert_eq!(state.cursor, AlacPoint::new(Line(-2), Column(0)));
+        assert!(state.anchor.is_none());
+    }
+
+    #[test]
+    fn copy_mode_anchor_is_dropped_once_its_selection_leaves_history() {
+        let config = alacritty_terminal::term::Config {
+            scrolling_history: 2,
+            ..Default::default()
+        };
+        let size = alacritty_terminal::term::test::TermSize::new(10, 3);
+        let mut term = Term::new(config, &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        processor.advance(&mut term, b"one\r\ntwo");
+        let mut state = CopyModeState::new(AlacPoint::new(Line(0), Column(0)));
+        state.toggle_selection();
+        state.cursor = AlacPoint::new(Line(0), Column(2));
+        state.store_cursor_in(&mut term);
+        term.selection = state.selection();
+
+        processor.advance(&mut term, b"\r\na\r\nb\r\nc\r\nd\r\ne");
+        state.follow_output(&term);
+
+        assert!(state.anchor.is_none());
+        assert_eq!(state.cursor.line, Line(0));
+    }
+
+    #[test]
+    fn copy_mode_selection_keeps_its_text_when_full_scrollback_drops_lines() {
+        let config = alacritty_terminal::term::Config {
+            scrolling_history: 5,
+            ..Default::default()
+        };
+        let size = alacritty_terminal::term::test::TermSize::new(10, 3);
+        let mut term = Term::new(config, &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let output: String = (0..20).map(|line| format!("\r\nline{line}")).collect();
+        processor.advance(&mut term, output.as_bytes());
+        assert_eq!(term.history_size(), 5);
+
+        let mut state = CopyModeState::new(AlacPoint::new(Line(-2), Column(0)));
+        state.toggle_selection();
+        state.cursor = AlacPoint::new(Line(-2), Column(5));
+        term.selection = state.selection();
+        assert_eq!(term.selection_to_string().as_deref(), Some("line15"));
+
+        state.store_cursor_in(&mut term);
+        processor.advance(&mut term, b"\r\nnew\r\nmore");
+        state.follow_output(&term);
+        term.selection = state.selection();
+
+        assert_eq!(term.history_size(), 5);
+        assert_eq!(state.cursor, AlacPoint::new(Line(-4), Column(5)));
+        assert_eq!(term.selection_to_string().as_deref(), Some("line15"));
+    }
+
+    #[test]
+    fn copy_mode_keys_leave_modified_keystrokes_to_shortcuts() {
+        let key = |raw: &str| copy_mode_key(&Keystroke::parse(raw).expect("valid keystroke"));
+
+        assert_eq!(key("k"), Some(CopyModeKey::Move(CopyModeMotion::Up)));
+        assert_eq!(key("left"), Some(CopyModeKey::Move(CopyModeMotion::Left)));
+        assert_eq!(key("v"), Some(CopyModeKey::ToggleSelection));
+        assert_eq!(key("enter"), Some(CopyModeKey::Copy));
+        assert_eq!(key("escape"), Some(CopyModeKey::Exit));
+        assert_eq!(key("cmd-c"), None);
+        assert_eq!(key("cmd-shift-space"), None);
+        assert_eq!(key("x"), None);
+    }
 }
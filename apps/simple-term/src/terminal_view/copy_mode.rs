@@ -0,0 +1,133 @@
+use alacritty_terminal::index::Side;
+use alacritty_terminal::term::Term;
+use alacritty_terminal::vi_mode::ViModeCursor;
+use gpui::Keystroke;
+use simple_term::{AlacPoint, Column, Line, Selection, SelectionType};
+
+/// What a key does while copy mode is active.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum CopyModeKey {
+    Move(CopyModeMotion),
+    ToggleSelection,
+    Copy,
+    Exit,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum CopyModeMotion {
+    Left,
+    Right,
+    Up,
+    Down,
+    LineStart,
+    LineEnd,
+}
+
+/// Copy mode action for a keystroke. Keys held with Cmd, Ctrl or Option are
+/// left to the regular shortcuts.
+pub(super) fn copy_mode_key(keystroke: &Keystroke) -> Option<CopyModeKey> {
+    let modifiers = keystroke.modifiers;
+    if modifiers.platform || modifiers.control || modifiers.alt {
+        return None;
+    }
+    if keystroke.key_char.as_deref() == Some("$") {
+        return Some(CopyModeKey::Move(CopyModeMotion::LineEnd));
+    }
+    let key = match keystroke.key.as_str() {
+        "left" | "h" => CopyModeKey::Move(CopyModeMotion::Left),
+        "right" | "l" => CopyModeKey::Move(CopyModeMotion::Right),
+        "up" | "k" => CopyModeKey::Move(CopyModeMotion::Up),
+        "down" | "j" => CopyModeKey::Move(CopyModeMotion::Down),
+        "home" | "0" => CopyModeKey::Move(CopyModeMotion::LineStart),
+        "end" => CopyModeKey::Move(CopyModeMotion::LineEnd),
+        "v" | "space" => CopyModeKey::ToggleSelection,
+        "enter" | "y" => CopyModeKey::Copy,
+        "escape" | "q" => CopyModeKey::Exit,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// Keyboard-driven cursor over the grid, with an optional selection anchored
+/// where `v` was pressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct CopyModeState {
+    pub(super) cursor: AlacPoint,
+    pub(super) anchor: Option<AlacPoint>,
+}
+
+impl CopyModeState {
+    pub(super) fn new(cursor: AlacPoint) -> Self {
+        Self {
+            cursor,
+            anchor: None,
+        }
+    }
+
+    pub(super) fn toggle_selection(&mut self) {
+        self.anchor = match self.anchor {
+            Some(_) => None,
+            None => Some(self.cursor),
+        };
+    }
+
+    /// Mirror the cursor into alacritty's vi mode cursor, which alacritty
+    /// moves along with the text as output scrolls the grid, like the
+    /// selection.
+    pub(super) fn store_cursor_in<T>(&self, term: &mut Term<T>) {
+        term.vi_mode_cursor = ViModeCursor::new(self.cursor);
+    }
+
+    /// Pick the cursor and anchor back up after output moved the grid. They
+    /// come from the ends of alacritty's selection while it lasts; once it was
+    /// cleared or scrolled out of history the anchor is dropped, and the
+    /// cursor follows alacritty's vi mode cursor, which stays in the viewport.
+    pub(super) fn follow_output<T>(&mut self, term: &Term<T>) {
+        let range = self
+            .anchor
+            .and_then(|_| term.selection.as_ref()?.to_range(term));
+        let anchor_first = self.anchor.is_some_and(|anchor| anchor <= self.cursor);
+        (self.anchor, self.cursor) = match range {
+            Some(range) if anchor_first => (Some(range.start), range.end),
+            Some(range) => (Some(range.end), range.start),
+            None => (None, term.vi_mode_cursor.point),
+        };
+    }
+
+    /// Selection covering the anchor and cursor cells, in either order.
+    pub(super) fn selection(&self) -> Option<Selection> {
+        let anchor = self.anchor?;
+        let (start, end) = if anchor <= self.cursor {
+            (anchor, self.cursor)
+        } else {
+            (self.cursor, anchor)
+        };
+        let mut selection = Selection::new(SelectionType::Simple, start, Side::Left);
+        selection.update(end, Side::Right);
+        Some(selection)
+    }
+}
+
+/// Move `point` one step, staying between `topmost_line` (the oldest
+/// scrollback line) and `bottommost_line` and within `last_column`.
+pub(super) fn move_copy_cursor(
+    point: AlacPoint,
+    motion: CopyModeMotion,
+    topmost_line: Line,
+    bottommost_line: Line,
+    last_column: Column,
+) -> AlacPoint {
+    let AlacPoint { line, column } = point;
+    let (line, column) = match motion {
+        CopyModeMotion::Left => (line, Column(column.0.saturating_sub(1))),
+        CopyModeMotion::Right => (line, column + 1),
+        CopyModeMotion::Up => (line - 1, column),
+        CopyModeMotion::Down => (line + 1, column),
+        CopyModeMotion::LineStart => (line, Column(0)),
+        CopyModeMotion::LineEnd => (line, last_column),
+    };
+    AlacPoint::new(
+        line.max(topmost_line).min(bottommost_line),
+        column.min(last_column),
+    )
+}
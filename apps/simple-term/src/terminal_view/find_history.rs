@@ -0,0 +1,77 @@
+use std::collections::VecDeque;
+
+/// Most queries the find panel remembers.
+pub(super) const FIND_HISTORY_LIMIT: usize = 50;
+
+/// Recent find queries, oldest first, recalled with Up and Down in the find
+/// panel.
+#[derive(Debug, Default)]
+pub(super) struct FindHistory {
+    entries: VecDeque<String>,
+    /// Entry shown by the last recall; `None` while typing a new query.
+    position: Option<usize>,
+    /// Query being typed when recall started, restored by moving past the
+    /// newest entry.
+    draft: String,
+}
+
+impl FindHistory {
+    /// Remember `query` as the newest entry, moving it up if it was already
+    /// there, and end any recall in progress.
+    pub(super) fn push(&mut self, query: &str) {
+        self.position = None;
+        self.draft.clear();
+        if query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.push_back(query.to_string());
+        while self.entries.len() > FIND_HISTORY_LIMIT {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Next older query, keeping `current` to come back to. The first recall
+    /// skips an entry equal to `current`, and recall stays on the oldest entry
+    /// once reached.
+    pub(super) fn previous(&mut self, current: &str) -> Option<String> {
+        let position = match self.position {
+            Some(position) => position.saturating_sub(1),
+            None => {
+                let newest = self.entries.len().checked_sub(1)?;
+                self.draft = current.to_string();
+                if newest > 0
+                    && self
+                        .entries
+                        .get(newest)
+                        .is_some_and(|entry| entry == current)
+                {
+                    newest - 1
+                } else {
+                    newest
+                }
+            }
+        };
+        self.position = Some(position);
+        self.entries.get(position).cloned()
+    }
+
+    /// Next newer query, or the query typed before recall started once past
+    /// the newest entry. `None` when no recall is in progress.
+    pub(super) fn next(&mut self) -> Option<String> {
+        let position = self.position?;
+        if position + 1 < self.entries.len() {
+            self.position = Some(position + 1);
+            self.entries.get(position + 1).cloned()
+        } else {
+            self.position = None;
+            Some(std::mem::take(&mut self.draft))
+        }
+    }
+
+    /// Stop recalling, so the next Up starts from the newest entry again.
+    pub(super) fn reset_recall(&mut self) {
+        self.position = None;
+        self.draft.clear();
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use gpui::{Keystroke, Modifiers};
+
+/// View actions that can be rebound through `settings.keybindings`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum KeyAction {
+    Copy,
+    Paste,
+    SelectAll,
+    Find,
+    Clear,
+    NewTab,
+    DuplicateTab,
+    CloseTab,
+    SplitPane,
+    PreviousTab,
+    NextTab,
+    ScrollToTop,
+    ScrollToBottom,
+    ScrollPageUp,
+    ScrollPageDown,
+    PreviousPrompt,
+    NextPrompt,
+    CopyLastOutput,
+    CopyMode,
+    SaveScrollback,
+    IncreaseFontSize,
+    DecreaseFontSize,
+    ResetFontSize,
+    BroadcastInput,
+}
+
+impl KeyAction {
+    const ALL: [KeyAction; 24] = [
+        KeyAction::Copy,
+        KeyAction::Paste,
+        KeyAction::SelectAll,
+        KeyAction::Find,
+        KeyAction::Clear,
+        KeyAction::NewTab,
+        KeyAction::DuplicateTab,
+        KeyAction::CloseTab,
+        KeyAction::SplitPane,
+        KeyAction::PreviousTab,
+        KeyAction::NextTab,
+        KeyAction::ScrollToTop,
+        KeyAction::ScrollToBottom,
+        KeyAction::ScrollPageUp,
+        KeyAction::ScrollPageDown,
+        KeyAction::PreviousPrompt,
+        KeyAction::NextPrompt,
+        KeyAction::CopyLastOutput,
+        KeyAction::CopyMode,
+        KeyAction::SaveScrollback,
+        KeyAction::IncreaseFontSize,
+        KeyAction::DecreaseFontSize,
+        KeyAction::ResetFontSize,
+        KeyAction::BroadcastInput,
+    ];
+
+    /// Name used as the key in `settings.keybindings`.
+    pub(super) fn name(self) -> &'static str {
+        match self {
+            KeyAction::Copy => "copy",
+            KeyAction::Paste => "paste",
+            KeyAction::SelectAll => "select_all",
+            KeyAction::Find => "find",
+            KeyAction::Clear => "clear",
+            KeyAction::NewTab => "new_tab",
+            KeyAction::DuplicateTab => "duplicate_tab",
+            KeyAction::CloseTab => "close_tab",
+            KeyAction::SplitPane => "split_pane",
+            KeyAction::PreviousTab => "previous_tab",
+            KeyAction::NextTab => "next_tab",
+            KeyAction::ScrollToTop => "scroll_to_top",
+            KeyAction::ScrollToBottom => "scroll_to_bottom",
+            KeyAction::ScrollPageUp => "scroll_page_up",
+            KeyAction::ScrollPageDown => "scroll_page_down",
+            KeyAction::PreviousPrompt => "previous_prompt",
+            KeyAction::NextPrompt => "next_prompt",
+            KeyAction::CopyLastOutput => "copy_last_output",
+            KeyAction::CopyMode => "copy_mode",
+            KeyAction::SaveScrollback => "save_scrollback",
+            KeyAction::IncreaseFontSize => "increase_font_size",
+            KeyAction::DecreaseFontSize => "decrease_font_size",
+            KeyAction::ResetFontSize => "reset_font_size",
+            KeyAction::BroadcastInput => "broadcast_input",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|action| action.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    fn default_bindings(self) -> &'static [&'static str] {
+        match self {
+            KeyAction::Copy => &["cmd+c", "ctrl+shift+c"],
+            KeyAction::Paste => &["cmd+v", "ctrl+shift+v"],
+            KeyAction::SelectAll => &["cmd+a", "ctrl+shift+a"],
+            KeyAction::Find => &["cmd+f", "ctrl+shift+f"],
+            KeyAction::Clear => &["cmd+k"],
+            KeyAction::NewTab => &["cmd+t"],
+            KeyAction::DuplicateTab => &["cmd+shift+t"],
+            KeyAction::CloseTab => &["cmd+w"],
+            KeyAction::SplitPane => &["cmd+d"],
+            KeyAction::PreviousTab => &["cmd+[", "ctrl+shift+tab"],
+            KeyAction::NextTab => &["cmd+]", "ctrl+tab"],
+            KeyAction::ScrollToTop => &["shift+home"],
+            KeyAction::ScrollToBottom => &["shift+end"],
+            KeyAction::ScrollPageUp => &["shift+pageup"],
+            KeyAction::ScrollPageDown => &["shift+pagedown"],
+            KeyAction::PreviousPrompt => &["cmd+up"],
+            KeyAction::NextPrompt => &["cmd+down"],
+            KeyAction::CopyLastOutput => &["cmd+shift+c"],
+            KeyAction::CopyMode => &["cmd+shift+space"],
+            KeyAction::SaveScrollback => &["cmd+s"],
+            KeyAction::IncreaseFontSize => &["cmd+=", "cmd+shift+=", "cmd++"],
+            KeyAction::DecreaseFontSize => &["cmd+-"],
+            KeyAction::ResetFontSize => &["cmd+0"],
+            KeyAction::BroadcastInput => &["cmd+shift+i"],
+        }
+    }
+}
+
+/// A parsed keystroke such as `cmd+shift+t`. Modifiers must match exactly.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) struct KeyBinding {
+    key: String,
+    platform: bool,
+    control: bool,
+    alt: bool,
+    shift: bool,
+}
+
+impl KeyBinding {
+    fn matches(&self, keystroke: &Keystroke) -> bool {
+        let Modifiers {
+            platform,
+            control,
+            alt,
+            shift,
+            ..
+        } = keystroke.modifiers;
+        self.platform == platform
+            && self.control == control
+            && self.alt == alt
+            && self.shift == shift
+            && self.key.eq_ignore_ascii_case(&keystroke.key)
+    }
+}
+
+/// Parse `modifier+...+key`. Modifier names are case-insensitive, and a
+/// trailing `++` binds the `+` key itself.
+pub(super) fn parse_keybinding(raw: &str) -> Option<KeyBinding> {
+    let raw = raw.trim();
+    let (modifiers, key) = match raw.strip_suffix("++") {
+        Some(modifiers) => (modifiers, "+"),
+        None => match raw.rsplit_once('+') {
+            Some((modifiers, key)) => (modifiers, key),
+            None => ("", raw),
+        },
+    };
+
+    let key = match key.trim().to_ascii_lowercase().as_str() {
+        "" => return None,
+        "esc" => "escape".to_string(),
+        "return" => "enter".to_string(),
+        key => key.to_string(),
+    };
+    let mut binding = KeyBinding {
+        key,
+        platform: false,
+        control: false,
+        alt: false,
+        shift: false,
+    };
+
+    for modifier in modifiers.split('+').filter(|part| !part.is_empty()) {
+        match modifier.trim().to_ascii_lowercase().as_str() {
+            "cmd" | "command" | "super" | "win" | "platform" => binding.platform = true,
+            "ctrl" | "control" => binding.control = true,
+            "alt" | "option" | "opt" => binding.alt = true,
+            "shift" => binding.shift = true,
+            _ => return None,
+        }
+    }
+
+    Some(binding)
+}
+
+/// Keystroke → action lookup built once from settings, with defaults for
+/// actions the user has not rebound.
+#[derive(Clone, Debug)]
+pub(super) struct KeyBindings {
+    entries: Vec<(KeyBinding, KeyAction)>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self::from_settings(&HashMap::new())
+    }
+}
+
+impl KeyBindings {
+    pub(super) fn from_settings(overrides: &HashMap<String, String>) -> Self {
+        let mut user_bindings = HashMap::new();
+        for (name, keystroke) in overrides {
+            let Some(action) = KeyAction::from_name(name) else {
+                log::warn!("ignoring keybinding for unknown action '{name}'");
+                continue;
+            };
+            match parse_keybinding(keystroke) {
+                Some(binding) => {
+                    user_bindings.insert(action.name(), binding);
+                }
+                None => log::warn!("ignoring invalid keybinding '{keystroke}' for '{name}'"),
+            }
+        }
+
+        let mut entries = Vec::new();
+        for action in KeyAction::ALL {
+            match user_bindings.remove(action.name()) {
+                Some(binding) => entries.push((binding, action)),
+                None => entries.extend(
+                    action
+                        .default_bindings()
+                        .iter()
+                        .filter_map(|raw| parse_keybinding(raw))
+                        .map(|binding| (binding, action)),
+                ),
+            }
+        }
+
+        Self { entries }
+    }
+
+    pub(super) fn action_for(&self, keystroke: &Keystroke) -> Option<KeyAction> {
+        self.entries
+            .iter()
+            .find(|(binding, _)| binding.matches(keystroke))
+            .map(|(_, action)| *action)
+    }
+}
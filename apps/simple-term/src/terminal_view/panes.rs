@@ -0,0 +1,247 @@
+use gpui::Size;
+
+/// Columns reserved between the two sides of a split for the divider.
+pub(super) const PANE_DIVIDER_COLS: u16 = 1;
+const DEFAULT_SPLIT_RATIO: f32 = 0.5;
+
+/// Layout tree of the panes inside one tab. Leaves carry pane ids.
+#[derive(Clone, Debug, PartialEq)]
+pub(super) enum PaneNode {
+    Leaf(u64),
+    /// Left/right split; `ratio` is the share of columns given to `left`.
+    Split {
+        ratio: f32,
+        left: Box<PaneNode>,
+        right: Box<PaneNode>,
+    },
+}
+
+/// Cell-grid placement of one pane inside the tab's grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) struct PaneGrid {
+    pub(super) col_offset: u16,
+    pub(super) line_offset: u16,
+    pub(super) cols: u16,
+    pub(super) lines: u16,
+}
+
+impl PaneGrid {
+    pub(super) fn size(&self) -> Size<u16> {
+        Size {
+            width: self.cols,
+            height: self.lines,
+        }
+    }
+
+    pub(super) fn contains_cell(&self, col: u16, line: u16) -> bool {
+        col >= self.col_offset
+            && col < self.col_offset.saturating_add(self.cols)
+            && line >= self.line_offset
+            && line < self.line_offset.saturating_add(self.lines)
+    }
+}
+
+impl PaneNode {
+    pub(super) fn leaf_ids(&self) -> Vec<u64> {
+        match self {
+            PaneNode::Leaf(id) => vec![*id],
+            PaneNode::Split { left, right, .. } => {
+                let mut ids = left.leaf_ids();
+                ids.extend(right.leaf_ids());
+                ids
+            }
+        }
+    }
+
+    /// Split `target` into a left/right pair with `new_id` on the right.
+    pub(super) fn split_leaf(&mut self, target: u64, new_id: u64) -> bool {
+        match self {
+            PaneNode::Leaf(id) if *id == target => {
+                *self = PaneNode::Split {
+                    ratio: DEFAULT_SPLIT_RATIO,
+                    left: Box::new(PaneNode::Leaf(target)),
+                    right: Box::new(PaneNode::Leaf(new_id)),
+                };
+                true
+            }
+            PaneNode::Leaf(_) => false,
+            PaneNode::Split { left, right, .. } => {
+                left.split_leaf(target, new_id) || right.split_leaf(target, new_id)
+            }
+        }
+    }
+
+    /// Remove `target`, letting its sibling take over the parent split.
+    /// The last remaining leaf is never removed.
+    pub(super) fn remove_leaf(&mut self, target: u64) -> bool {
+        let PaneNode::Split { left, right, .. } = self else {
+            return false;
+        };
+
+        let survivor = if matches!(left.as_ref(), PaneNode::Leaf(id) if *id == target) {
+            right.as_ref().clone()
+        } else if matches!(right.as_ref(), PaneNode::Leaf(id) if *id == target) {
+            left.as_ref().clone()
+        } else {
+            return left.remove_leaf(target) || right.remove_leaf(target);
+        };
+        *self = survivor;
+        true
+    }
+
+    /// Place every leaf inside `grid`, in left-to-right order.
+    pub(super) fn layout(&self, grid: Size<u16>) -> Vec<(u64, PaneGrid)> {
+        let mut placements = Vec::new();
+        self.layout_into(
+            PaneGrid {
+                col_offset: 0,
+                line_offset: 0,
+                cols: grid.width.max(1),
+                lines: grid.height.max(1),
+            },
+            &mut placements,
+        );
+        placements
+    }
+
+    fn layout_into(&self, area: PaneGrid, placements: &mut Vec<(u64, PaneGrid)>) {
+        match self {
+            PaneNode::Leaf(id) => placements.push((*id, area)),
+            PaneNode::Split { ratio, left, right } => {
+                let (left_cols, right_cols) = split_columns(area.cols, *ratio);
+                left.layout_into(
+                    PaneGrid {
+                        cols: left_cols,
+                        ..area
+                    },
+                    placements,
+                );
+                right.layout_into(
+                    PaneGrid {
+                        col_offset: area.col_offset + left_cols + PANE_DIVIDER_COLS,
+                        cols: right_cols,
+                        ..area
+                    },
+                    placements,
+                );
+            }
+        }
+    }
+}
+
+/// Column counts for the two sides of a split. Each side keeps at least one
+/// column, even when the grid is too narrow to also fit the divider.
+fn split_columns(total_cols: u16, ratio: f32) -> (u16, u16) {
+    let available = total_cols.saturating_sub(PANE_DIVIDER_COLS);
+    if available < 2 {
+        return (1, 1);
+    }
+
+    let left = (f32::from(available) * ratio.clamp(0.0, 1.0)).round() as u16;
+    let left = left.clamp(1, available - 1);
+    (left, available - left)
+}
+
+pub(super) fn pane_at_cell(layout: &[(u64, PaneGrid)], col: u16, line: u16) -> Option<u64> {
+    layout
+        .iter()
+        .find(|(_, grid)| grid.contains_cell(col, line))
+        .map(|(id, _)| *id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(cols: u16, lines: u16) -> gpui::Size<u16> {
+        gpui::Size {
+            width: cols,
+            height: lines,
+        }
+    }
+
+    fn pane_grid(col_offset: u16, cols: u16, lines: u16) -> PaneGrid {
+        PaneGrid {
+            col_offset,
+            line_offset: 0,
+            cols,
+            lines,
+        }
+    }
+
+    #[test]
+    fn single_pane_layout_fills_whole_grid() {
+        let layout = PaneNode::Leaf(1).layout(grid(80, 24));
+        assert_eq!(layout, vec![(1, pane_grid(0, 80, 24))]);
+    }
+
+    #[test]
+    fn horizontal_split_reserves_divider_column_between_panes() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+
+        assert_eq!(
+            node.layout(grid(81, 24)),
+            vec![(1, pane_grid(0, 40, 24)), (2, pane_grid(41, 40, 24))]
+        );
+        assert_eq!(
+            node.layout(grid(80, 24)),
+            vec![(1, pane_grid(0, 40, 24)), (2, pane_grid(41, 39, 24))]
+        );
+    }
+
+    #[test]
+    fn nested_split_divides_only_the_split_pane() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+        assert!(node.split_leaf(2, 3));
+
+        assert_eq!(
+            node.layout(grid(81, 10)),
+            vec![
+                (1, pane_grid(0, 40, 10)),
+                (2, pane_grid(41, 20, 10)),
+                (3, pane_grid(62, 19, 10)),
+            ]
+        );
+        assert_eq!(node.leaf_ids(), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn split_layout_keeps_one_column_per_pane_on_narrow_grids() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+
+        for cols in 1..=3 {
+            let layout = node.layout(grid(cols, 5));
+            assert_eq!(layout.len(), 2);
+            assert!(layout.iter().all(|(_, pane)| pane.cols >= 1));
+        }
+    }
+
+    #[test]
+    fn pane_at_cell_skips_divider_column() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+        let layout = node.layout(grid(81, 24));
+
+        assert_eq!(pane_at_cell(&layout, 0, 0), Some(1));
+        assert_eq!(pane_at_cell(&layout, 39, 23), Some(1));
+        assert_eq!(pane_at_cell(&layout, 40, 0), None);
+        assert_eq!(pane_at_cell(&layout, 41, 0), Some(2));
+        assert_eq!(pane_at_cell(&layout, 41, 24), None);
+    }
+
+    #[test]
+    fn remove_leaf_collapses_split_into_sibling() {
+        let mut node = PaneNode::Leaf(1);
+        assert!(node.split_leaf(1, 2));
+        assert!(node.split_leaf(2, 3));
+
+        assert!(node.remove_leaf(2));
+        assert_eq!(node.leaf_ids(), vec![1, 3]);
+        assert!(node.remove_leaf(1));
+        assert_eq!(node, PaneNode::Leaf(3));
+        assert!(!node.remove_leaf(3));
+    }
+}
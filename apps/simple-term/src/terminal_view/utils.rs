@@ -1,6 +1,9 @@
-use gpui::{point, px, size, Bounds, Pixels, ScrollDelta, TouchPhase};
-use simple_term::terminal_settings::{AlternateScroll, WorkingDirectory};
-use simple_term::{SelectionType, TermMode};
+use gpui::{point, px, size, Bounds, Keystroke, Modifiers, Pixels, ScrollDelta, TouchPhase};
+use simple_term::terminal_settings::{
+    AlternateScroll, HyperlinkModifier, TerminalSettings, WorkingDirectory,
+};
+use simple_term::{AlacPoint, PathLikeTarget, SelectionRange, SelectionType, TermMode};
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use url::Url;
 
@@ -43,6 +46,26 @@ pub(super) fn effective_scroll_multiplier(multiplier: f32) -> f32 {
     }
 }
 
+/// Multiplier for a wheel event. Trackpads report pixels and mouse wheels
+/// report lines, each with its own setting over `scroll_multiplier`.
+pub(super) fn scroll_multiplier_for_delta(delta: ScrollDelta, settings: &TerminalSettings) -> f32 {
+    let device_multiplier = match delta {
+        ScrollDelta::Pixels(_) => settings.trackpad_scroll_multiplier,
+        ScrollDelta::Lines(_) => settings.mouse_scroll_multiplier,
+    };
+    effective_scroll_multiplier(device_multiplier.unwrap_or(settings.scroll_multiplier))
+}
+
+/// Whole scroll lines in the direction the user asked for, flipped when
+/// natural scrolling is on.
+pub(super) fn directed_scroll_lines(lines: i32, natural_scroll: bool) -> i32 {
+    if natural_scroll {
+        -lines
+    } else {
+        lines
+    }
+}
+
 pub(super) fn scroll_delta_to_lines(delta: ScrollDelta, line_height: Pixels) -> f32 {
     match delta {
         ScrollDelta::Lines(pt) => pt.y,
@@ -50,6 +73,17 @@ pub(super) fn scroll_delta_to_lines(delta: ScrollDelta, line_height: Pixels) ->
     }
 }
 
+/// Whole font size steps for a wheel event held with the platform modifier,
+/// one step per line scrolled. Scrolling up zooms in; the remainder of a
+/// precise scroll carries over in `pending` like scrolling does.
+pub(super) fn wheel_font_zoom_steps(
+    delta: ScrollDelta,
+    line_height: Pixels,
+    pending: &mut f32,
+) -> i32 {
+    consume_scroll_lines(pending, scroll_delta_to_lines(delta, line_height))
+}
+
 pub(super) fn viewport_row_for_line(
     line: i32,
     display_offset: usize,
@@ -64,6 +98,38 @@ pub(super) fn viewport_row_for_line(
     (row < viewport_lines).then_some(row)
 }
 
+/// Viewport `(row, start_col, len)` spans covering the inclusive grid range
+/// `start..=end`, clipped to the visible rows.
+pub(super) fn grid_range_row_spans(
+    start: AlacPoint,
+    end: AlacPoint,
+    display_offset: usize,
+    viewport_lines: usize,
+    num_cols: usize,
+) -> Vec<(usize, usize, usize)> {
+    if num_cols == 0 || end < start {
+        return Vec::new();
+    }
+
+    (start.line.0..=end.line.0)
+        .filter_map(|line| {
+            let row = viewport_row_for_line(line, display_offset, viewport_lines)?;
+            let first_col = if line == start.line.0 {
+                start.column.0
+            } else {
+                0
+            };
+            let last_col = if line == end.line.0 {
+                end.column.0
+            } else {
+                num_cols - 1
+            };
+            let last_col = last_col.min(num_cols - 1);
+            (first_col <= last_col).then_some((row, first_col, last_col - first_col + 1))
+        })
+        .collect()
+}
+
 pub(super) fn prepare_for_terminal_input(
     was_scrolled: bool,
     pending_scroll_lines: &mut f32,
@@ -132,14 +198,46 @@ pub(super) fn mouse_mode_enabled_for_scroll(mode: TermMode, shift_held: bool) ->
     mode.intersects(TermMode::MOUSE_MODE) && !shift_held
 }
 
-pub(super) fn selection_type_for_click_count(click_count: usize) -> SelectionType {
+/// Selection started by a click. Alt+click starts a block selection; double
+/// and triple clicks still select words and lines.
+pub(super) fn selection_type_for_click_count(click_count: usize, block: bool) -> SelectionType {
     match click_count {
+        0 | 1 if block => SelectionType::Block,
         0 | 1 => SelectionType::Simple,
         2 => SelectionType::Semantic,
         _ => SelectionType::Lines,
     }
 }
 
+/// Where a wheel scroll goes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(super) enum WheelScrollTarget {
+    /// Reported to a program that tracks the mouse.
+    MouseReport,
+    /// Sent as Up/Down arrow keys to a program on the alternate screen, so
+    /// pagers like `less` and `man` scroll.
+    ArrowKeys,
+    /// Moves the viewport through scrollback.
+    Scrollback,
+}
+
+/// Wheel scrolls go to a mouse-tracking program first, then become arrow
+/// keys on the alternate screen when `alternate_scroll` allows it. Shift
+/// overrides both and always scrolls the buffer.
+pub(super) fn wheel_scroll_target(
+    mode: TermMode,
+    setting: AlternateScroll,
+    shift_held: bool,
+) -> WheelScrollTarget {
+    if mouse_mode_enabled_for_scroll(mode, shift_held) {
+        WheelScrollTarget::MouseReport
+    } else if alternate_scroll_enabled(mode, setting, shift_held) {
+        WheelScrollTarget::ArrowKeys
+    } else {
+        WheelScrollTarget::Scrollback
+    }
+}
+
 pub(super) fn alternate_scroll_enabled(
     mode: TermMode,
     setting: AlternateScroll,
@@ -150,6 +248,64 @@ pub(super) fn alternate_scroll_enabled(
         && mode.contains(TermMode::ALT_SCREEN | TermMode::ALTERNATE_SCROLL)
 }
 
+/// Whether `modifiers` make a click or hover act on hyperlinks. A plain click
+/// belongs to programs that capture the mouse, so `None` falls back to the
+/// platform modifier while mouse reporting is on.
+pub(super) fn hyperlink_modifier_held(
+    setting: HyperlinkModifier,
+    modifiers: &Modifiers,
+    mode: TermMode,
+) -> bool {
+    match setting {
+        HyperlinkModifier::None if mode.intersects(TermMode::MOUSE_MODE) => modifiers.secondary(),
+        HyperlinkModifier::None => true,
+        HyperlinkModifier::Cmd => modifiers.platform,
+        HyperlinkModifier::Ctrl => modifiers.control,
+        HyperlinkModifier::Alt => modifiers.alt,
+    }
+}
+
+/// Whether a plain Escape clears the visible selection instead of reaching
+/// the program. With nothing selected, Escape passes through.
+pub(super) fn escape_clears_selection(keystroke: &Keystroke, has_selection: bool) -> bool {
+    has_selection && keystroke.key == "escape" && !keystroke.modifiers.modified()
+}
+
+/// Whether pasting `text` should be confirmed first: line breaks would run
+/// each line as a command unless the program enabled bracketed paste.
+pub(super) fn paste_needs_confirmation(text: &str, mode: TermMode) -> bool {
+    !mode.contains(TermMode::BRACKETED_PASTE) && text.contains(['\n', '\r'])
+}
+
+/// Whether the tab bar shows that the active program is on the alternate
+/// screen, where scrollback and the scrollbar are unavailable.
+pub(super) fn alt_screen_indicator_visible(mode: TermMode) -> bool {
+    mode.contains(TermMode::ALT_SCREEN)
+}
+
+/// Focus event (`CSI I` / `CSI O`) for programs that enabled DECSET 1004.
+pub(super) fn focus_report(mode: TermMode, focused: bool) -> Option<&'static [u8]> {
+    if !mode.contains(TermMode::FOCUS_IN_OUT) {
+        return None;
+    }
+    Some(if focused { b"\x1b[I" } else { b"\x1b[O" })
+}
+
+/// Round `length` to whole device pixels at `scale_factor` so cell edges land
+/// on pixel boundaries, keeping at least one device pixel.
+pub(super) fn snap_to_device_pixels(length: Pixels, scale_factor: f32) -> Pixels {
+    if scale_factor <= 0.0 {
+        return length;
+    }
+    px((f32::from(length) * scale_factor).round().max(1.0) / scale_factor)
+}
+
+/// Whether moving to a display with `current` scale factor changes how cells
+/// snap to device pixels, so fonts and cell size must be resolved again.
+pub(super) fn typography_needs_refresh(previous: f32, current: f32) -> bool {
+    (previous - current).abs() > f32::EPSILON
+}
+
 pub(super) fn point_in_bounds(bounds: &Bounds<Pixels>, point: gpui::Point<Pixels>) -> bool {
     point.x >= bounds.origin.x
         && point.x <= bounds.origin.x + bounds.size.width
@@ -247,6 +403,34 @@ pub(super) fn scrollbar_layout(
     })
 }
 
+/// Label shown beside the scrollbar while the viewport is scrolled into
+/// history: lines above the bottom and how far toward the oldest line that is.
+pub(super) fn scroll_position_label(display_offset: usize, history_size: usize) -> Option<String> {
+    if display_offset == 0 || history_size == 0 {
+        return None;
+    }
+    let offset = display_offset.min(history_size);
+    let percent = ((offset * 100 + history_size / 2) / history_size).max(1);
+    let unit = if offset == 1 { "line" } else { "lines" };
+    Some(format!("{offset} {unit} up ({percent}%)"))
+}
+
+/// Whether a click at `pointer_y` on the track pages up into history
+/// (`Some(true)`) or down toward the prompt (`Some(false)`). Clicks on the
+/// thumb return `None` and start a drag instead.
+pub(super) fn scrollbar_track_click_pages_up(
+    pointer_y: Pixels,
+    layout: &ScrollbarLayout,
+) -> Option<bool> {
+    if pointer_y < layout.thumb.origin.y {
+        Some(true)
+    } else if pointer_y >= layout.thumb.origin.y + layout.thumb.size.height {
+        Some(false)
+    } else {
+        None
+    }
+}
+
 pub(super) fn display_offset_from_pointer(
     pointer_y: Pixels,
     layout: &ScrollbarLayout,
@@ -266,16 +450,27 @@ pub(super) fn resolve_working_directory(strategy: &WorkingDirectory) -> Option<s
         strategy,
         std::env::current_dir().ok(),
         dirs::home_dir(),
+        |name| std::env::var(name).ok(),
     )
 }
 
+/// `Always` directories get `~` and `$NAME`/`${NAME}` expansion; a path that
+/// expands to nothing falls back like the project strategies.
 pub(super) fn resolve_working_directory_with_fallback(
     strategy: &WorkingDirectory,
     current_dir: Option<std::path::PathBuf>,
     home_dir: Option<std::path::PathBuf>,
+    env_var: impl Fn(&str) -> Option<String>,
 ) -> Option<std::path::PathBuf> {
     match strategy {
-        WorkingDirectory::Always { directory } => Some(directory.clone()),
+        WorkingDirectory::Always { directory } => {
+            let expanded = expand_path_variables(directory, home_dir.as_deref(), env_var);
+            if expanded.as_os_str().is_empty() {
+                current_dir.or(home_dir)
+            } else {
+                Some(expanded)
+            }
+        }
         WorkingDirectory::AlwaysHome => home_dir,
         WorkingDirectory::CurrentFileDirectory
         | WorkingDirectory::CurrentProjectDirectory
@@ -283,6 +478,77 @@ pub(super) fn resolve_working_directory_with_fallback(
     }
 }
 
+/// Expand a leading `~` to `home_dir` and substitute `$NAME`/`${NAME}` via
+/// `env_var`. Unknown variables expand to nothing; a `$` that does not start a
+/// variable name is kept. Non-UTF-8 paths are returned unchanged.
+pub(super) fn expand_path_variables(
+    path: &std::path::Path,
+    home_dir: Option<&std::path::Path>,
+    env_var: impl Fn(&str) -> Option<String>,
+) -> std::path::PathBuf {
+    let Some(raw) = path.to_str() else {
+        return path.to_path_buf();
+    };
+
+    let mut expanded = String::with_capacity(raw.len());
+    let mut rest = raw;
+    if let Some(home) = home_dir.and_then(std::path::Path::to_str) {
+        if rest == "~" || rest.starts_with("~/") {
+            expanded.push_str(home);
+            rest = &rest[1..];
+        }
+    }
+
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+        let (name, remainder) = match after.strip_prefix('{') {
+            Some(braced) => match braced.find('}') {
+                Some(end) => (&braced[..end], &braced[end + 1..]),
+                None => ("", after),
+            },
+            None => {
+                let end = after
+                    .char_indices()
+                    .find(|(index, ch)| {
+                        !(ch.is_ascii_alphanumeric() || *ch == '_')
+                            || (*index == 0 && ch.is_ascii_digit())
+                    })
+                    .map_or(after.len(), |(index, _)| index);
+                (&after[..end], &after[end..])
+            }
+        };
+
+        if name.is_empty() {
+            expanded.push('$');
+            rest = after;
+            continue;
+        }
+        expanded.push_str(&env_var(name).unwrap_or_default());
+        rest = remainder;
+    }
+    expanded.push_str(rest);
+
+    std::path::PathBuf::from(expanded)
+}
+
+/// New tabs start in the directory the active shell last reported (OSC 7),
+/// falling back to the configured working-directory strategy.
+pub(super) fn new_tab_working_directory(
+    active_cwd: Option<&std::path::Path>,
+    strategy: &WorkingDirectory,
+) -> Option<std::path::PathBuf> {
+    active_cwd
+        .map(std::path::Path::to_path_buf)
+        .or_else(|| resolve_working_directory(strategy))
+}
+
+pub(super) fn cwd_display_name(cwd: &std::path::Path) -> String {
+    cwd.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| cwd.display().to_string())
+}
+
 pub(super) fn selection_copy_plan(
     copy_on_select: bool,
     keep_selection_on_copy: bool,
@@ -299,39 +565,32 @@ pub(super) fn selection_copy_plan(
     (Some(text), !keep_selection_on_copy)
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub(super) enum CommonShortcutAction {
-    CopySelection,
-    Paste,
-    SelectAll,
-    Find,
+/// Record a completed selection, returning whether it differs from the last
+/// one reported to embedders.
+pub(super) fn selection_changed(
+    reported: &mut Option<SelectionRange>,
+    selection: Option<SelectionRange>,
+) -> bool {
+    if *reported == selection {
+        return false;
+    }
+    *reported = selection;
+    true
 }
 
-pub(super) fn common_shortcut_action(keystroke: &gpui::Keystroke) -> Option<CommonShortcutAction> {
-    let modifiers = keystroke.modifiers;
-    let platform_shortcut =
-        modifiers.platform && !modifiers.control && !modifiers.alt && !modifiers.function;
-    let ctrl_shift_shortcut =
-        modifiers.control && modifiers.shift && !modifiers.platform && !modifiers.alt;
-
-    if !(platform_shortcut || ctrl_shift_shortcut) {
+/// Text to remember as the primary selection once a mouse selection completes.
+/// Empty selections keep the previous primary selection.
+pub(super) fn primary_selection_capture(
+    middle_click_paste: bool,
+    selected_text: Option<&str>,
+) -> Option<String> {
+    if !middle_click_paste {
         return None;
     }
 
-    if keystroke.key.eq_ignore_ascii_case("c") {
-        return Some(CommonShortcutAction::CopySelection);
-    }
-    if keystroke.key.eq_ignore_ascii_case("v") {
-        return Some(CommonShortcutAction::Paste);
-    }
-    if keystroke.key.eq_ignore_ascii_case("a") {
-        return Some(CommonShortcutAction::SelectAll);
-    }
-    if keystroke.key.eq_ignore_ascii_case("f") {
-        return Some(CommonShortcutAction::Find);
-    }
-
-    None
+    selected_text
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
 }
 
 pub(super) fn text_to_insert(keystroke: &gpui::Keystroke) -> Option<String> {
@@ -356,28 +615,89 @@ pub(super) fn text_to_insert(keystroke: &gpui::Keystroke) -> Option<String> {
     None
 }
 
-pub(super) fn strip_line_column_suffix(target: &str) -> &str {
-    let mut end = target.len();
-    let bytes = target.as_bytes();
+/// Keys understood by the inline single-line text entries (find panel, tab rename).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(super) enum TextEntryKey {
+    Cancel,
+    Submit { shift: bool },
+    Backspace,
+    Insert(String),
+}
 
-    while end > 0 && bytes[end - 1].is_ascii_digit() {
-        end -= 1;
+pub(super) fn text_entry_key(keystroke: &gpui::Keystroke) -> Option<TextEntryKey> {
+    let modifiers = keystroke.modifiers;
+    let plain = !modifiers.platform && !modifiers.control && !modifiers.alt;
+    match keystroke.key.as_str() {
+        "escape" if plain => Some(TextEntryKey::Cancel),
+        "enter" if plain => Some(TextEntryKey::Submit {
+            shift: modifiers.shift,
+        }),
+        "backspace" if plain => Some(TextEntryKey::Backspace),
+        _ => text_to_insert(keystroke).map(TextEntryKey::Insert),
     }
+}
 
-    if end > 0 && bytes[end - 1] == b':' {
-        let mut second = end - 1;
-        while second > 0 && bytes[second - 1].is_ascii_digit() {
-            second -= 1;
-        }
+/// Strips one `:<digits>` group from the end, unless the colon belongs to a
+/// drive letter such as `C:`.
+fn strip_position_number(target: &str) -> Option<&str> {
+    let without_digits = target.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_digits.len() == target.len() {
+        return None;
+    }
+    let rest = without_digits.strip_suffix(':')?;
+    let is_drive_letter = rest.len() == 1 && rest.as_bytes()[0].is_ascii_alphabetic();
+    (!rest.is_empty() && !is_drive_letter).then_some(rest)
+}
 
-        if second > 0 && bytes[second - 1] == b':' {
-            return &target[..second - 1];
-        }
+/// Like `strip_position_number`, also returning the number.
+fn split_position_number(target: &str) -> Option<(&str, u32)> {
+    let rest = strip_position_number(target)?;
+    let number = target[rest.len() + 1..].parse().ok()?;
+    Some((rest, number))
+}
 
-        return &target[..end - 1];
+/// Path, line and column of a link ending in `:line` or `:line:column`.
+pub(super) fn split_line_column_suffix(target: &str) -> (&str, Option<u32>, Option<u32>) {
+    match split_position_number(target) {
+        Some((rest, last)) => match split_position_number(rest) {
+            Some((path, line)) => (path, Some(line), Some(last)),
+            None => (rest, Some(last), None),
+        },
+        None => (target, None, None),
     }
+}
 
-    target
+pub(super) fn strip_line_column_suffix(target: &str) -> &str {
+    split_line_column_suffix(target).0
+}
+
+/// URL that opens `path` for a clicked link: `template` with its placeholders
+/// filled in, or a plain `file://` URL. A missing position opens line 1.
+pub(super) fn file_open_url(
+    template: Option<&str>,
+    path: &str,
+    line: Option<u32>,
+    column: Option<u32>,
+) -> String {
+    match template {
+        Some(template) => template
+            .replace("{path}", path)
+            .replace("{line}", &line.unwrap_or(1).to_string())
+            .replace("{col}", &column.unwrap_or(1).to_string()),
+        None => file_path_to_file_url(path),
+    }
+}
+
+/// File a path link points at, without its `:line:column` suffix. Relative
+/// paths are joined to the tab's directory and only count when the file
+/// exists there.
+pub(super) fn resolve_path_target(target: &PathLikeTarget) -> Option<PathBuf> {
+    let path = std::path::Path::new(strip_line_column_suffix(&target.maybe_path));
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+    let joined = target.terminal_dir.as_ref()?.join(path);
+    joined.exists().then_some(joined)
 }
 
 fn percent_encode_file_path(path: &str) -> String {
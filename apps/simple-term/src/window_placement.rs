@@ -0,0 +1,300 @@
+//! Window geometry saved per monitor, shared by the macOS panel and the
+//! standard window used elsewhere.
+//!
+//! Positions are stored relative to the monitor's top-left corner, so a
+//! placement survives the monitor moving in the desktop layout.
+
+use gpui::{point, px, size, Bounds, Pixels};
+use simple_term::terminal_settings::MonitorWindowPlacement;
+#[cfg(any(test, target_os = "macos"))]
+use std::time::Duration;
+
+const MIN_WINDOW_WIDTH: f32 = 320.0;
+const MIN_WINDOW_HEIGHT: f32 = 180.0;
+/// Time between frames of the panel slide animation.
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) const SLIDE_FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Settings key for a monitor, from its frame in desktop coordinates.
+pub(crate) fn monitor_key(x: f64, y: f64, width: f64, height: f64) -> String {
+    format!("{x:.3}:{y:.3}:{width:.3}:{height:.3}")
+}
+
+/// Desired length of one side of the panel: `fraction` of `available` when
+/// set, otherwise `pixels`.
+pub(crate) fn panel_length(pixels: u32, fraction: Option<f32>, available: f32) -> f32 {
+    fraction.map_or(pixels as f32, |fraction| (available * fraction).round())
+}
+
+/// `saved` without the sides a screen fraction sizes, so a configured
+/// fraction keeps following the monitor after a placement was saved there.
+/// The saved position still applies.
+pub(crate) fn saved_placement_for_fractions(
+    saved: Option<&MonitorWindowPlacement>,
+    width_fraction: Option<f32>,
+    height_fraction: Option<f32>,
+) -> Option<MonitorWindowPlacement> {
+    saved.map(|saved| MonitorWindowPlacement {
+        width: saved.width.filter(|_| width_fraction.is_none()),
+        height: saved.height.filter(|_| height_fraction.is_none()),
+        ..*saved
+    })
+}
+
+/// Window bounds relative to the monitor's top-left corner.
+///
+/// `visible` is the part of the monitor windows may cover, in the same
+/// coordinates. A saved placement keeps its size and position, clamped so
+/// the window stays inside `visible`. Without one the window is centered
+/// horizontally and placed `top_inset` below the top of `visible`, or
+/// centered vertically when `top_inset` is `None`.
+pub(crate) fn reconcile_window_placement(
+    desired_width: f32,
+    desired_height: f32,
+    screen_width: f32,
+    visible: Bounds<f32>,
+    top_inset: Option<f32>,
+    saved: Option<&MonitorWindowPlacement>,
+) -> Bounds<Pixels> {
+    let width = saved
+        .and_then(|placement| placement.width)
+        .unwrap_or(desired_width)
+        .max(MIN_WINDOW_WIDTH)
+        .min(visible.size.width);
+    let height = saved
+        .and_then(|placement| placement.height)
+        .unwrap_or(desired_height)
+        .max(MIN_WINDOW_HEIGHT)
+        .min(visible.size.height);
+
+    let min_x = visible.origin.x;
+    let max_x = (visible.origin.x + visible.size.width - width).max(min_x);
+    let min_y = visible.origin.y;
+    let max_y = (visible.origin.y + visible.size.height - height).max(min_y);
+
+    let (x, y) = match saved {
+        Some(saved) => (saved.x.clamp(min_x, max_x), saved.y.clamp(min_y, max_y)),
+        None => {
+            let x = (screen_width - width) / 2.0;
+            let y = match top_inset {
+                Some(inset) => visible.origin.y + inset.max(0.0),
+                None => visible.origin.y + (visible.size.height - height) / 2.0,
+            };
+            (x.clamp(min_x, max_x), y.clamp(min_y, max_y))
+        }
+    };
+
+    Bounds::new(point(px(x), px(y)), size(px(width), px(height)))
+}
+
+/// Settings key for a display, from its bounds in desktop coordinates.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn display_monitor_key(display: Bounds<Pixels>) -> String {
+    monitor_key(
+        f64::from(display.origin.x),
+        f64::from(display.origin.y),
+        f64::from(display.size.width),
+        f64::from(display.size.height),
+    )
+}
+
+/// Placement to save for a window at `bounds`, relative to its monitor's
+/// top-left corner.
+#[cfg(not(target_os = "macos"))]
+pub(crate) fn placement_from_bounds(bounds: Bounds<Pixels>) -> MonitorWindowPlacement {
+    MonitorWindowPlacement {
+        x: f32::from(bounds.origin.x),
+        y: f32::from(bounds.origin.y),
+        width: Some(f32::from(bounds.size.width)),
+        height: Some(f32::from(bounds.size.height)),
+    }
+}
+
+/// `shown` moved straight up until its bottom edge meets the top of the
+/// monitor, where a sliding panel starts and ends.
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn slid_out_bounds(shown: Bounds<Pixels>) -> Bounds<Pixels> {
+    Bounds::new(point(shown.origin.x, -shown.size.height), shown.size)
+}
+
+/// Window bounds for each frame of a slide from `from` to `to` lasting
+/// `duration`, one frame per `SLIDE_FRAME_INTERVAL`. The motion eases out
+/// and the last frame is exactly `to`; `from` itself is not included.
+#[cfg(any(test, target_os = "macos"))]
+pub(crate) fn slide_frames(
+    from: Bounds<Pixels>,
+    to: Bounds<Pixels>,
+    duration: Duration,
+) -> Vec<Bounds<Pixels>> {
+    let steps = duration
+        .as_millis()
+        .div_ceil(SLIDE_FRAME_INTERVAL.as_millis())
+        .max(1) as usize;
+    (1..=steps)
+        .map(|step| {
+            if step == steps {
+                return to;
+            }
+            let progress = step as f32 / steps as f32;
+            let eased = 1.0 - (1.0 - progress).powi(3);
+            Bounds::new(
+                point(
+                    from.origin.x + (to.origin.x - from.origin.x) * eased,
+                    from.origin.y + (to.origin.y - from.origin.y) * eased,
+                ),
+                size(
+                    from.size.width + (to.size.width - from.size.width) * eased,
+                    from.size.height + (to.size.height - from.size.height) * eased,
+                ),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        monitor_key, panel_length, reconcile_window_placement, saved_placement_for_fractions,
+        slid_out_bounds, slide_frames, MIN_WINDOW_WIDTH,
+    };
+    use gpui::{point, px, size, Bounds};
+    use simple_term::terminal_settings::MonitorWindowPlacement;
+    use std::time::Duration;
+
+    fn screen() -> Bounds<f32> {
+        Bounds::new(point(0.0, 0.0), size(1920.0, 1080.0))
+    }
+
+    #[test]
+    fn monitor_key_uses_the_frame_with_fixed_precision() {
+        assert_eq!(
+            monitor_key(-1920.0, 0.0, 1920.0, 1080.0),
+            "-1920.000:0.000:1920.000:1080.000"
+        );
+    }
+
+    #[test]
+    fn panel_length_scales_with_the_monitor_only_when_a_fraction_is_set() {
+        assert_eq!(panel_length(960, None, 2560.0), 960.0);
+        assert_eq!(panel_length(960, Some(0.5), 2560.0), 1280.0);
+        assert_eq!(panel_length(960, Some(0.5), 1440.0), 720.0);
+        assert_eq!(panel_length(600, Some(0.4), 875.0), 350.0);
+        assert_eq!(panel_length(600, Some(1.0), 875.0), 875.0);
+    }
+
+    #[test]
+    fn screen_fraction_overrides_the_saved_size_but_keeps_the_position() {
+        let resolve = |saved: Option<&MonitorWindowPlacement>| {
+            reconcile_window_placement(
+                panel_length(960, Some(0.5), 1920.0),
+                panel_length(600, None, 1080.0),
+                1920.0,
+                screen(),
+                None,
+                saved_placement_for_fractions(saved, Some(0.5), None).as_ref(),
+            )
+        };
+        let first = resolve(None);
+        assert_eq!(first.size, size(px(960.0), px(600.0)));
+
+        // Hiding or closing the window saves its current frame.
+        let saved = MonitorWindowPlacement {
+            x: 100.0,
+            y: 50.0,
+            width: Some(700.0),
+            height: Some(500.0),
+        };
+        let reopened = resolve(Some(&saved));
+
+        assert_eq!(reopened.origin, point(px(100.0), px(50.0)));
+        assert_eq!(reopened.size, size(px(960.0), px(500.0)));
+    }
+
+    #[test]
+    fn unsaved_window_is_centered_below_the_inset_or_on_screen() {
+        let below_inset =
+            reconcile_window_placement(960.0, 600.0, 1920.0, screen(), Some(8.0), None);
+        assert_eq!(below_inset.origin, point(px(480.0), px(8.0)));
+        assert_eq!(below_inset.size, size(px(960.0), px(600.0)));
+
+        let centered = reconcile_window_placement(960.0, 600.0, 1920.0, screen(), None, None);
+        assert_eq!(centered.origin, point(px(480.0), px(240.0)));
+    }
+
+    #[test]
+    fn saved_placement_restores_size_and_position() {
+        let saved = MonitorWindowPlacement {
+            x: 100.0,
+            y: 50.0,
+            width: Some(800.0),
+            height: Some(500.0),
+        };
+
+        let bounds = reconcile_window_placement(960.0, 600.0, 1920.0, screen(), None, Some(&saved));
+
+        assert_eq!(bounds.origin, point(px(100.0), px(50.0)));
+        assert_eq!(bounds.size, size(px(800.0), px(500.0)));
+    }
+
+    #[test]
+    fn saved_placement_is_pulled_back_inside_the_visible_area() {
+        // A 25px menu bar at the top of a smaller monitor.
+        let visible = Bounds::new(point(0.0, 25.0), size(1280.0, 775.0));
+        let off_screen = MonitorWindowPlacement {
+            x: 1500.0,
+            y: -40.0,
+            width: Some(2000.0),
+            height: Some(100.0),
+        };
+
+        let bounds =
+            reconcile_window_placement(960.0, 600.0, 1280.0, visible, None, Some(&off_screen));
+
+        assert_eq!(bounds.size, size(px(1280.0), px(180.0)));
+        assert_eq!(bounds.origin, point(px(0.0), px(25.0)));
+
+        let tiny = MonitorWindowPlacement {
+            x: 10.0,
+            y: 700.0,
+            width: Some(10.0),
+            height: None,
+        };
+        let bounds = reconcile_window_placement(960.0, 600.0, 1280.0, visible, None, Some(&tiny));
+        assert_eq!(bounds.size.width, px(MIN_WINDOW_WIDTH));
+        assert_eq!(bounds.origin.y, px(200.0));
+    }
+
+    #[test]
+    fn slide_frames_move_monotonically_and_end_on_the_target() {
+        let shown = Bounds::new(point(px(480.0), px(33.0)), size(px(960.0), px(600.0)));
+        let hidden = slid_out_bounds(shown);
+        assert_eq!(hidden.origin, point(px(480.0), px(-600.0)));
+
+        let slide_in = slide_frames(hidden, shown, Duration::from_millis(200));
+        assert_eq!(slide_in.len(), 13);
+        assert_eq!(slide_in.last(), Some(&shown));
+        let mut previous_y = hidden.origin.y;
+        for frame in &slide_in {
+            assert!(frame.origin.y > previous_y, "{slide_in:?}");
+            assert_eq!(frame.origin.x, shown.origin.x);
+            assert_eq!(frame.size, shown.size);
+            previous_y = frame.origin.y;
+        }
+
+        let slide_out = slide_frames(shown, hidden, Duration::from_millis(200));
+        assert_eq!(slide_out.last(), Some(&hidden));
+        assert!(slide_out
+            .windows(2)
+            .all(|pair| pair[1].origin.y < pair[0].origin.y));
+    }
+
+    #[test]
+    fn zero_length_slide_jumps_straight_to_the_target() {
+        let shown = Bounds::new(point(px(0.0), px(0.0)), size(px(800.0), px(500.0)));
+
+        assert_eq!(
+            slide_frames(slid_out_bounds(shown), shown, Duration::ZERO),
+            vec![shown]
+        );
+    }
+}
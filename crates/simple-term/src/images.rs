@@ -0,0 +1,380 @@
+//! Inline images placed on the terminal grid.
+//!
+//! Decoded images live in an [`ImageStore`] shared between the PTY reader and
+//! the UI. Their position is tracked by alacritty itself: the reader injects an
+//! OSC 8 hyperlink with a `simple-term-image:<id>` URI on the image's top-left
+//! cell, so the anchor scrolls, clears and leaves the scrollback together with
+//! the rest of the grid.
+
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use alacritty_terminal::event::WindowSize;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+use parking_lot::Mutex;
+
+/// URI prefix of the hyperlinks that anchor images to grid cells.
+pub const IMAGE_URI_PREFIX: &str = "simple-term-image:";
+
+/// Decoded bytes kept per terminal, enough for one image of the largest
+/// accepted size. The oldest images are evicted first.
+const MAX_STORED_IMAGE_BYTES: usize =
+    MAX_DECODED_IMAGE_DIMENSION as usize * MAX_DECODED_IMAGE_DIMENSION as usize * 4;
+
+/// Largest width or height accepted from encoded image files.
+pub(crate) const MAX_DECODED_IMAGE_DIMENSION: u32 = 8192;
+
+/// Base64 as sent by Kitty and iTerm2 clients, with or without padding.
+const BASE64: GeneralPurpose = GeneralPurpose::new(
+    &base64::alphabet::STANDARD,
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+);
+
+/// Ids are unique across terminals so the UI can cache by id alone.
+static NEXT_IMAGE_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Default)]
+struct StoredImages {
+    images: VecDeque<(u64, Arc<TerminalImage>)>,
+    /// Sum of [`TerminalImage::stored_bytes`] over `images`.
+    bytes: usize,
+}
+
+/// A decoded image in straight-alpha RGBA, row-major.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TerminalImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+    /// Area the image covers on the grid, in pixels of `cell_width` x
+    /// `cell_height` cells. Differs from the pixel size when the sender asked
+    /// for a specific size.
+    pub display_width: u32,
+    pub display_height: u32,
+    /// Cell size in pixels when the image arrived, used to scale it onto the
+    /// grid if the font size changes later.
+    pub cell_width: u16,
+    pub cell_height: u16,
+}
+
+impl TerminalImage {
+    pub(crate) fn new(
+        decoded: DecodedImage,
+        (display_width, display_height): (u32, u32),
+        window_size: WindowSize,
+    ) -> Self {
+        Self {
+            width: decoded.width,
+            height: decoded.height,
+            rgba: decoded.rgba,
+            display_width,
+            display_height,
+            cell_width: window_size.cell_width,
+            cell_height: window_size.cell_height,
+        }
+    }
+
+    /// Grid lines covered by the image's top edge through its bottom edge.
+    pub fn lines(&self) -> usize {
+        let cell_height = u32::from(self.cell_height.max(1));
+        self.display_height.div_ceil(cell_height).max(1) as usize
+    }
+
+    /// Memory the image holds while it is stored, counted against the
+    /// store's budget.
+    fn stored_bytes(&self) -> usize {
+        self.rgba.len() + std::mem::size_of::<Self>()
+    }
+}
+
+/// Pixels of an image before it is sized for the grid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct DecodedImage {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    /// Straight-alpha RGBA, row-major.
+    pub(crate) rgba: Vec<u8>,
+}
+
+/// A decoded image and how the sender asked to place it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ImagePlacement {
+    pub(crate) image: DecodedImage,
+    pub(crate) width: ImageExtent,
+    pub(crate) height: ImageExtent,
+    pub(crate) preserve_aspect: bool,
+    /// Whether the cursor moves to the image's last line afterwards.
+    pub(crate) move_cursor: bool,
+}
+
+impl ImagePlacement {
+    /// Place `image` at its pixel size and move the cursor past it.
+    pub(crate) fn natural(image: DecodedImage) -> Self {
+        Self {
+            image,
+            width: ImageExtent::Auto,
+            height: ImageExtent::Auto,
+            preserve_aspect: true,
+            move_cursor: true,
+        }
+    }
+}
+
+/// Requested width or height of an image on the grid.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum ImageExtent {
+    /// The image's own pixel size.
+    #[default]
+    Auto,
+    Cells(u32),
+    Pixels(u32),
+    /// Percentage of the terminal's width or height.
+    Percent(u32),
+}
+
+impl ImageExtent {
+    fn to_pixels(self, cell_px: u16, cells: u16) -> Option<u32> {
+        let cell_px = u32::from(cell_px);
+        match self {
+            ImageExtent::Auto => None,
+            ImageExtent::Cells(count) => Some(count.saturating_mul(cell_px)),
+            ImageExtent::Pixels(pixels) => Some(pixels),
+            ImageExtent::Percent(percent) => {
+                Some(u32::from(cells) * cell_px * percent.min(100) / 100)
+            }
+        }
+    }
+}
+
+/// Size in pixels an image covers after applying the requested extents.
+/// With `preserve_aspect` a single extent scales the other side, and two
+/// extents fit the image inside the requested box.
+pub(crate) fn display_size(
+    (width, height): (u32, u32),
+    (requested_width, requested_height): (ImageExtent, ImageExtent),
+    preserve_aspect: bool,
+    window_size: WindowSize,
+) -> (u32, u32) {
+    let target_width = requested_width.to_pixels(window_size.cell_width, window_size.num_cols);
+    let target_height = requested_height.to_pixels(window_size.cell_height, window_size.num_lines);
+    let (width, height) = (u64::from(width.max(1)), u64::from(height.max(1)));
+    let scaled = |value: u64, numerator: u32, denominator: u64| {
+        (value * u64::from(numerator) / denominator) as u32
+    };
+
+    let (display_width, display_height) = match (target_width, target_height) {
+        (None, None) => (width as u32, height as u32),
+        (Some(target), None) if preserve_aspect => (target, scaled(height, target, width)),
+        (None, Some(target)) if preserve_aspect => (scaled(width, target, height), target),
+        (Some(target), None) => (target, height as u32),
+        (None, Some(target)) => (width as u32, target),
+        (Some(target_width), Some(target_height)) if preserve_aspect => {
+            // Compare width/height ratios without floating point.
+            if u64::from(target_width) * height <= u64::from(target_height) * width {
+                (target_width, scaled(height, target_width, width))
+            } else {
+                (scaled(width, target_height, height), target_height)
+            }
+        }
+        (Some(target_width), Some(target_height)) => (target_width, target_height),
+    };
+    (display_width.max(1), display_height.max(1))
+}
+
+/// Decode base64 image data, ignoring embedded whitespace.
+pub(crate) fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+    let compact: Vec<u8> = data
+        .iter()
+        .copied()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    BASE64.decode(compact).ok()
+}
+
+/// Decode an encoded image file (PNG, JPEG or GIF) into RGBA pixels.
+pub(crate) fn decode_image_file(bytes: &[u8]) -> Option<DecodedImage> {
+    let mut reader = image::ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .ok()?;
+    let mut limits = image::Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_IMAGE_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODED_IMAGE_DIMENSION);
+    reader.limits(limits);
+    let rgba = reader.decode().ok()?.to_rgba8();
+    Some(DecodedImage {
+        width: rgba.width(),
+        height: rgba.height(),
+        rgba: rgba.into_raw(),
+    })
+}
+
+/// Shared, bounded store of the images a terminal has received.
+#[derive(Clone, Debug, Default)]
+pub struct ImageStore {
+    images: Arc<Mutex<StoredImages>>,
+}
+
+impl ImageStore {
+    pub(crate) fn insert(&self, image: TerminalImage) -> u64 {
+        let id = NEXT_IMAGE_ID.fetch_add(1, Ordering::Relaxed);
+        let bytes = image.stored_bytes();
+        let mut stored = self.images.lock();
+        while stored.bytes + bytes > MAX_STORED_IMAGE_BYTES {
+            let Some((_, evicted)) = stored.images.pop_front() else {
+                break;
+            };
+            stored.bytes -= evicted.stored_bytes();
+        }
+        stored.images.push_back((id, Arc::new(image)));
+        stored.bytes += bytes;
+        id
+    }
+
+    pub fn get(&self, id: u64) -> Option<Arc<TerminalImage>> {
+        self.images
+            .lock()
+            .images
+            .iter()
+            .find(|(image_id, _)| *image_id == id)
+            .map(|(_, image)| image.clone())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.images.lock().images.is_empty()
+    }
+}
+
+/// Image id carried by an anchor hyperlink URI, if `uri` is one.
+pub fn image_id_from_uri(uri: &str) -> Option<u64> {
+    uri.strip_prefix(IMAGE_URI_PREFIX)?.parse().ok()
+}
+
+/// Bytes that anchor image `id` at the cursor and then move the cursor to the
+/// image's last line, as a text cursor would after drawing it.
+pub(crate) fn placeholder_bytes(id: u64, lines: usize) -> Vec<u8> {
+    let mut bytes =
+        format!("\x1b]8;;{IMAGE_URI_PREFIX}{id}\x1b\\ \x1b]8;;\x1b\\\x1b[D").into_bytes();
+    bytes.extend(std::iter::repeat_n(b'\n', lines.saturating_sub(1)));
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        decode_base64, display_size, image_id_from_uri, placeholder_bytes, ImageExtent, ImageStore,
+        TerminalImage, MAX_DECODED_IMAGE_DIMENSION,
+    };
+    use alacritty_terminal::event::WindowSize;
+
+    const WINDOW: WindowSize = WindowSize {
+        num_lines: 24,
+        num_cols: 80,
+        cell_width: 8,
+        cell_height: 16,
+    };
+
+    fn image(height: u32) -> TerminalImage {
+        TerminalImage {
+            width: 1,
+            height,
+            rgba: vec![0; height as usize * 4],
+            display_width: 1,
+            display_height: height,
+            cell_width: 8,
+            cell_height: 16,
+        }
+    }
+
+    #[test]
+    fn image_lines_round_up_to_whole_cells() {
+        assert_eq!(image(1).lines(), 1);
+        assert_eq!(image(16).lines(), 1);
+        assert_eq!(image(17).lines(), 2);
+    }
+
+    #[test]
+    fn store_evicts_oldest_images_when_decoded_bytes_exceed_budget() {
+        let store = ImageStore::default();
+        let first = store.insert(image(1));
+        let second = store.insert(image(1));
+        let largest = store.insert(image(
+            MAX_DECODED_IMAGE_DIMENSION * MAX_DECODED_IMAGE_DIMENSION,
+        ));
+
+        assert!(store.get(first).is_none());
+        assert!(store.get(second).is_none());
+        assert!(store.get(largest).is_some());
+    }
+
+    #[test]
+    fn store_keeps_many_small_images() {
+        let store = ImageStore::default();
+        let first = store.insert(image(1));
+        for _ in 0..1000 {
+            store.insert(image(1));
+        }
+
+        assert!(store.get(first).is_some());
+    }
+
+    #[test]
+    fn placeholder_anchors_image_id_and_moves_to_last_line() {
+        let bytes = String::from_utf8(placeholder_bytes(42, 3)).expect("utf8");
+
+        assert!(bytes.starts_with("\x1b]8;;simple-term-image:42\x1b\\ "));
+        assert!(bytes.ends_with("\x1b[D\n\n"));
+    }
+
+    #[test]
+    fn image_id_parses_only_image_uris() {
+        assert_eq!(image_id_from_uri("simple-term-image:7"), Some(7));
+        assert_eq!(image_id_from_uri("simple-term-image:x"), None);
+        assert_eq!(image_id_from_uri("https://example.com"), None);
+    }
+
+    #[test]
+    fn display_size_defaults_to_pixel_size() {
+        let size = display_size(
+            (30, 20),
+            (ImageExtent::Auto, ImageExtent::Auto),
+            true,
+            WINDOW,
+        );
+        assert_eq!(size, (30, 20));
+    }
+
+    #[test]
+    fn display_size_scales_missing_side_to_keep_aspect() {
+        let size = display_size(
+            (100, 50),
+            (ImageExtent::Cells(10), ImageExtent::Auto),
+            true,
+            WINDOW,
+        );
+        assert_eq!(size, (80, 40));
+    }
+
+    #[test]
+    fn display_size_fits_inside_requested_box_or_stretches() {
+        let requested = (ImageExtent::Percent(50), ImageExtent::Pixels(100));
+        assert_eq!(
+            display_size((100, 100), requested, true, WINDOW),
+            (100, 100)
+        );
+        assert_eq!(
+            display_size((100, 100), requested, false, WINDOW),
+            (320, 100)
+        );
+    }
+
+    #[test]
+    fn base64_decoding_accepts_missing_padding_and_line_breaks() {
+        assert_eq!(decode_base64(b"aGVs\nbG8"), Some(b"hello".to_vec()));
+        assert_eq!(decode_base64(b"aGVsbG8="), Some(b"hello".to_vec()));
+        assert_eq!(decode_base64(b"not base64!"), None);
+    }
+}
@@ -0,0 +1,148 @@
+//! iTerm2 inline images (`OSC 1337 ; File=<args> : <base64> ST`).
+//!
+//! The OSC scanner hands over complete payloads; this module parses the
+//! arguments and decodes the embedded file.
+
+use crate::images::{decode_base64, decode_image_file, DecodedImage, ImageExtent};
+
+const FILE_PREFIX: &[u8] = b"1337;File=";
+
+/// Arguments of a `File=` sequence. Unknown keys are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct InlineImageArgs {
+    pub(crate) name: Option<String>,
+    pub(crate) size: Option<usize>,
+    pub(crate) width: ImageExtent,
+    pub(crate) height: ImageExtent,
+    pub(crate) preserve_aspect_ratio: bool,
+    /// `inline=1` displays the file; anything else is a download request.
+    pub(crate) inline: bool,
+}
+
+impl Default for InlineImageArgs {
+    fn default() -> Self {
+        Self {
+            name: None,
+            size: None,
+            width: ImageExtent::Auto,
+            height: ImageExtent::Auto,
+            preserve_aspect_ratio: true,
+            inline: false,
+        }
+    }
+}
+
+/// Parse `N`, `Npx`, `N%` or `auto`.
+fn parse_extent(value: &str) -> ImageExtent {
+    let value = value.trim();
+    let number = |digits: &str| digits.parse::<u32>().ok();
+    if let Some(pixels) = value.strip_suffix("px").and_then(number) {
+        ImageExtent::Pixels(pixels)
+    } else if let Some(percent) = value.strip_suffix('%').and_then(number) {
+        ImageExtent::Percent(percent)
+    } else if let Some(cells) = number(value) {
+        ImageExtent::Cells(cells)
+    } else {
+        ImageExtent::Auto
+    }
+}
+
+/// Parse the `;`-separated `key=value` list between `File=` and `:`.
+pub(crate) fn parse_inline_image_args(args: &str) -> InlineImageArgs {
+    let mut parsed = InlineImageArgs::default();
+    for (key, value) in args.split(';').filter_map(|pair| pair.split_once('=')) {
+        match key.trim() {
+            // Names are base64 so they can carry `;` and `:`.
+            "name" => {
+                parsed.name = decode_base64(value.as_bytes())
+                    .map(|name| String::from_utf8_lossy(&name).into_owned())
+            }
+            "size" => parsed.size = value.trim().parse().ok(),
+            "width" => parsed.width = parse_extent(value),
+            "height" => parsed.height = parse_extent(value),
+            "preserveAspectRatio" => parsed.preserve_aspect_ratio = value.trim() != "0",
+            "inline" => parsed.inline = value.trim() == "1",
+            _ => {}
+        }
+    }
+    parsed
+}
+
+/// Decode an OSC payload if it is an inline `File=` image.
+pub(crate) fn inline_image_from_osc(payload: &[u8]) -> Option<(InlineImageArgs, DecodedImage)> {
+    let rest = payload.strip_prefix(FILE_PREFIX)?;
+    let separator = rest.iter().position(|&byte| byte == b':')?;
+    let args = parse_inline_image_args(std::str::from_utf8(&rest[..separator]).ok()?);
+    if !args.inline {
+        return None;
+    }
+    let file = decode_base64(&rest[separator + 1..])?;
+    let image = decode_image_file(&file)?;
+    Some((args, image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inline_image_from_osc, parse_inline_image_args, InlineImageArgs};
+    use crate::images::ImageExtent;
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use std::io::Cursor;
+
+    fn png_base64(width: u32, height: u32, pixel: [u8; 4]) -> String {
+        let image = image::RgbaImage::from_pixel(width, height, image::Rgba(pixel));
+        let mut png = Vec::new();
+        image
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .expect("encode png");
+        STANDARD.encode(png)
+    }
+
+    #[test]
+    fn args_parse_name_size_and_inline_flag() {
+        let args = parse_inline_image_args("name=Y2F0LnBuZw==;size=1234;inline=1");
+
+        assert_eq!(args.name.as_deref(), Some("cat.png"));
+        assert_eq!(args.size, Some(1234));
+        assert!(args.inline);
+    }
+
+    #[test]
+    fn args_parse_cell_pixel_percent_and_auto_extents() {
+        let args = parse_inline_image_args("width=10;height=50%;preserveAspectRatio=0");
+        assert_eq!(args.width, ImageExtent::Cells(10));
+        assert_eq!(args.height, ImageExtent::Percent(50));
+        assert!(!args.preserve_aspect_ratio);
+
+        let args = parse_inline_image_args("width=120px;height=auto");
+        assert_eq!(args.width, ImageExtent::Pixels(120));
+        assert_eq!(args.height, ImageExtent::Auto);
+    }
+
+    #[test]
+    fn args_default_to_download_with_preserved_aspect() {
+        assert_eq!(parse_inline_image_args(""), InlineImageArgs::default());
+        assert!(!parse_inline_image_args("inline=0;bogus").inline);
+        assert!(parse_inline_image_args("").preserve_aspect_ratio);
+    }
+
+    #[test]
+    fn download_requests_and_other_oscs_are_not_images() {
+        assert!(inline_image_from_osc(b"1337;File=inline=0:AAAA").is_none());
+        assert!(inline_image_from_osc(b"1337;SetMark").is_none());
+        assert!(inline_image_from_osc(b"7;file://host/tmp").is_none());
+    }
+
+    #[test]
+    fn inline_png_payload_decodes_to_rgba() {
+        let payload = format!(
+            "1337;File=inline=1;width=2:{}",
+            png_base64(2, 1, [9, 8, 7, 255])
+        );
+        let (args, image) = inline_image_from_osc(payload.as_bytes()).expect("image");
+
+        assert_eq!(args.width, ImageExtent::Cells(2));
+        assert_eq!((image.width, image.height), (2, 1));
+        assert_eq!(image.rgba, vec![9, 8, 7, 255, 9, 8, 7, 255]);
+    }
+}
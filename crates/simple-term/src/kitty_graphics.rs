@@ -0,0 +1,447 @@
+//! Kitty graphics protocol (`ESC _ G <control> ; <base64 payload> ESC \`).
+//!
+//! alacritty drops APC sequences, so graphics commands are captured from the
+//! raw PTY stream next to the other image scanners. Supported: direct
+//! transmission (`t=d`) of RGB, RGBA and PNG data, chunking with `m=1`, and
+//! the transmit (`a=t`), transmit-and-display (`a=T`), place (`a=p`) and query
+//! (`a=q`) actions. Deletion and animation commands are ignored.
+
+use std::collections::VecDeque;
+
+use crate::images::{
+    decode_base64, decode_image_file, DecodedImage, ImageExtent, ImagePlacement,
+    MAX_DECODED_IMAGE_DIMENSION,
+};
+
+const ESC: u8 = 0x1b;
+
+/// Upper bound for one buffered APC sequence and for a chunked transfer.
+const MAX_COMMAND_BYTES: usize = 32 * 1024 * 1024;
+/// Images kept for later `a=p` placements. The oldest is evicted first.
+const MAX_TRANSMITTED_IMAGES: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    Apc,
+    ApcEscape,
+}
+
+/// Incremental APC scanner that survives sequences split across reads.
+#[derive(Debug)]
+struct ApcScanner {
+    state: ScanState,
+    body: Vec<u8>,
+    overflowed: bool,
+}
+
+impl Default for ApcScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            body: Vec::new(),
+            overflowed: false,
+        }
+    }
+}
+
+impl ApcScanner {
+    /// Feed raw PTY bytes, invoking `on_apc` with the offset just past the
+    /// terminator and the body of every complete APC sequence.
+    fn advance(&mut self, bytes: &[u8], mut on_apc: impl FnMut(usize, Vec<u8>)) {
+        let mut index = 0;
+        while index < bytes.len() {
+            match self.state {
+                ScanState::Ground => {
+                    match bytes[index..].iter().position(|&byte| byte == ESC) {
+                        Some(offset) => {
+                            index += offset + 1;
+                            self.state = ScanState::Escape;
+                        }
+                        None => return,
+                    }
+                    continue;
+                }
+                ScanState::Apc => {
+                    let rest = &bytes[index..];
+                    let end = rest
+                        .iter()
+                        .position(|&byte| byte == ESC)
+                        .unwrap_or(rest.len());
+                    self.push(&rest[..end]);
+                    index += end;
+                    if end < rest.len() {
+                        index += 1;
+                        self.state = ScanState::ApcEscape;
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            match self.state {
+                ScanState::Ground | ScanState::Apc => {}
+                ScanState::Escape => self.escape(byte),
+                ScanState::ApcEscape => {
+                    if byte == b'\\' {
+                        self.state = ScanState::Ground;
+                        if !self.overflowed {
+                            on_apc(index, std::mem::take(&mut self.body));
+                        }
+                    } else {
+                        self.escape(byte);
+                    }
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self, byte: u8) {
+        self.state = match byte {
+            b'_' => {
+                self.body.clear();
+                self.overflowed = false;
+                ScanState::Apc
+            }
+            ESC => ScanState::Escape,
+            _ => ScanState::Ground,
+        };
+    }
+
+    fn push(&mut self, bytes: &[u8]) {
+        if self.body.len() + bytes.len() > MAX_COMMAND_BYTES {
+            self.overflowed = true;
+            return;
+        }
+        self.body.extend_from_slice(bytes);
+    }
+}
+
+/// Control keys of one graphics command. Keys this terminal does not act on
+/// are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct GraphicsCommand {
+    action: u8,
+    format: u32,
+    medium: u8,
+    compression: Option<u8>,
+    pixel_width: u32,
+    pixel_height: u32,
+    image_id: u32,
+    more: bool,
+    quiet: u32,
+    columns: u32,
+    rows: u32,
+    keep_cursor: bool,
+}
+
+impl Default for GraphicsCommand {
+    fn default() -> Self {
+        Self {
+            action: b't',
+            format: 32,
+            medium: b'd',
+            compression: None,
+            pixel_width: 0,
+            pixel_height: 0,
+            image_id: 0,
+            more: false,
+            quiet: 0,
+            columns: 0,
+            rows: 0,
+            keep_cursor: false,
+        }
+    }
+}
+
+/// Parse the comma-separated `key=value` control data.
+fn parse_control(control: &[u8]) -> GraphicsCommand {
+    let mut command = GraphicsCommand::default();
+    for pair in control.split(|&byte| byte == b',') {
+        let [key, b'=', value @ ..] = pair else {
+            continue;
+        };
+        let number = || {
+            std::str::from_utf8(value)
+                .ok()
+                .and_then(|value| value.parse::<u32>().ok())
+                .unwrap_or(0)
+        };
+        let letter = value.first().copied();
+        match key {
+            b'a' => command.action = letter.unwrap_or(b't'),
+            b'f' => command.format = number(),
+            b't' => command.medium = letter.unwrap_or(b'd'),
+            b'o' => command.compression = letter,
+            b's' => command.pixel_width = number(),
+            b'v' => command.pixel_height = number(),
+            b'i' => command.image_id = number(),
+            b'm' => command.more = number() == 1,
+            b'q' => command.quiet = number(),
+            b'c' => command.columns = number(),
+            b'r' => command.rows = number(),
+            b'C' => command.keep_cursor = number() == 1,
+            _ => {}
+        }
+    }
+    command
+}
+
+/// Turn transmitted data into pixels, or a protocol error string.
+fn load_image(command: &GraphicsCommand, data: &[u8]) -> Result<DecodedImage, &'static str> {
+    if command.medium != b'd' {
+        return Err("EINVAL:only direct transmission is supported");
+    }
+    if command.compression.is_some() {
+        return Err("EINVAL:compressed data is not supported");
+    }
+    let bytes = decode_base64(data).ok_or("EINVAL:invalid base64 data")?;
+    let (width, height) = (command.pixel_width, command.pixel_height);
+    let raw_size_is_valid = |channels: usize| {
+        width > 0
+            && height > 0
+            && width <= MAX_DECODED_IMAGE_DIMENSION
+            && height <= MAX_DECODED_IMAGE_DIMENSION
+            && bytes.len() == width as usize * height as usize * channels
+    };
+
+    match command.format {
+        24 if raw_size_is_valid(3) => Ok(DecodedImage {
+            width,
+            height,
+            rgba: bytes
+                .chunks_exact(3)
+                .flat_map(|rgb| [rgb[0], rgb[1], rgb[2], 0xff])
+                .collect(),
+        }),
+        32 if raw_size_is_valid(4) => Ok(DecodedImage {
+            width,
+            height,
+            rgba: bytes,
+        }),
+        24 | 32 => Err("EINVAL:data size does not match s and v"),
+        100 => decode_image_file(&bytes).ok_or("EBADPNG:cannot decode PNG data"),
+        _ => Err("EINVAL:unsupported format"),
+    }
+}
+
+/// Result of one graphics command that the PTY reader acts on.
+#[derive(Debug)]
+pub(crate) enum KittyOutput {
+    /// Anchor an image at the cursor; `offset` is just past the command.
+    Place {
+        offset: usize,
+        placement: ImagePlacement,
+    },
+    /// Response to write back to the client.
+    Reply(Vec<u8>),
+}
+
+/// Graphics protocol state for one terminal.
+#[derive(Debug, Default)]
+pub(crate) struct KittyGraphics {
+    scanner: ApcScanner,
+    /// First command and data of a chunked transfer (`m=1`).
+    chunked: Option<(GraphicsCommand, Vec<u8>)>,
+    /// Images transmitted with `a=t`, by image id.
+    transmitted: VecDeque<(u32, DecodedImage)>,
+}
+
+impl KittyGraphics {
+    /// Feed raw PTY bytes, invoking `on_output` for placements and replies.
+    pub(crate) fn advance(&mut self, bytes: &[u8], mut on_output: impl FnMut(KittyOutput)) {
+        let mut commands = Vec::new();
+        self.scanner
+            .advance(bytes, |offset, body| commands.push((offset, body)));
+        for (offset, body) in commands {
+            self.handle(offset, &body, &mut on_output);
+        }
+    }
+
+    fn handle(&mut self, offset: usize, body: &[u8], on_output: &mut impl FnMut(KittyOutput)) {
+        let Some(body) = body.strip_prefix(b"G") else {
+            return;
+        };
+        let (control, payload) = match body.iter().position(|&byte| byte == b';') {
+            Some(separator) => (&body[..separator], &body[separator + 1..]),
+            None => (body, &[][..]),
+        };
+        let command = parse_control(control);
+
+        let (command, data) = match self.chunked.take() {
+            Some((first, mut data)) => {
+                if data.len() + payload.len() > MAX_COMMAND_BYTES {
+                    return;
+                }
+                data.extend_from_slice(payload);
+                if command.more {
+                    self.chunked = Some((first, data));
+                    return;
+                }
+                (first, data)
+            }
+            None if command.more => {
+                self.chunked = Some((command, payload.to_vec()));
+                return;
+            }
+            None => (command, payload.to_vec()),
+        };
+        self.execute(offset, command, &data, on_output);
+    }
+
+    fn execute(
+        &mut self,
+        offset: usize,
+        command: GraphicsCommand,
+        data: &[u8],
+        on_output: &mut impl FnMut(KittyOutput),
+    ) {
+        let image = match command.action {
+            b'q' | b't' | b'T' => load_image(&command, data),
+            b'p' => self
+                .transmitted
+                .iter()
+                .find(|(id, _)| *id == command.image_id && command.image_id != 0)
+                .map(|(_, image)| image.clone())
+                .ok_or("ENOENT:no image with this id"),
+            _ => return,
+        };
+
+        let status = match &image {
+            Ok(_) => Ok(()),
+            Err(error) => Err(*error),
+        };
+        if let Some(reply) = reply_bytes(&command, status) {
+            on_output(KittyOutput::Reply(reply));
+        }
+        let Ok(image) = image else {
+            return;
+        };
+        match command.action {
+            b't' if command.image_id != 0 => {
+                self.transmitted.retain(|(id, _)| *id != command.image_id);
+                if self.transmitted.len() >= MAX_TRANSMITTED_IMAGES {
+                    self.transmitted.pop_front();
+                }
+                self.transmitted.push_back((command.image_id, image));
+            }
+            b'T' | b'p' => on_output(KittyOutput::Place {
+                offset,
+                placement: placement(&command, image),
+            }),
+            _ => {}
+        }
+    }
+}
+
+/// `c`/`r` request a size in cells. With only one of them the other side
+/// keeps the aspect ratio; with both the image is stretched to fill them.
+fn placement(command: &GraphicsCommand, image: DecodedImage) -> ImagePlacement {
+    let extent = |cells: u32| match cells {
+        0 => ImageExtent::Auto,
+        cells => ImageExtent::Cells(cells),
+    };
+    ImagePlacement {
+        image,
+        width: extent(command.columns),
+        height: extent(command.rows),
+        preserve_aspect: command.columns == 0 || command.rows == 0,
+        move_cursor: !command.keep_cursor,
+    }
+}
+
+/// Response for commands that carry an image id, honoring `q=1` (errors
+/// only) and `q=2` (silent).
+fn reply_bytes(command: &GraphicsCommand, result: Result<(), &str>) -> Option<Vec<u8>> {
+    if command.image_id == 0 {
+        return None;
+    }
+    let message = match result {
+        Ok(()) if command.quiet == 0 => "OK",
+        Err(error) if command.quiet < 2 => error,
+        _ => return None,
+    };
+    Some(format!("\x1b_Gi={};{message}\x1b\\", command.image_id).into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_control, KittyGraphics, KittyOutput};
+    use crate::images::ImageExtent;
+
+    fn run(graphics: &mut KittyGraphics, bytes: &[u8]) -> Vec<KittyOutput> {
+        let mut outputs = Vec::new();
+        graphics.advance(bytes, |output| outputs.push(output));
+        outputs
+    }
+
+    #[test]
+    fn control_keys_parse_numbers_and_letters() {
+        let command = parse_control(b"a=T,f=24,s=2,v=1,i=7,c=4,C=1,q=2,x");
+
+        assert_eq!(command.action, b'T');
+        assert_eq!(command.format, 24);
+        assert_eq!((command.pixel_width, command.pixel_height), (2, 1));
+        assert_eq!(
+            (command.image_id, command.columns, command.quiet),
+            (7, 4, 2)
+        );
+        assert!(command.keep_cursor);
+    }
+
+    #[test]
+    fn transmit_and_display_places_rgb_image_after_command() {
+        let mut graphics = KittyGraphics::default();
+        // Two pixels: red and blue. "/wAAAAD/" is ff0000 0000ff in base64.
+        let bytes = b"x\x1b_Ga=T,f=24,s=2,v=1,c=3;/wAAAAD/\x1b\\y";
+        let outputs = run(&mut graphics, bytes);
+
+        let [KittyOutput::Place { offset, placement }] = &outputs[..] else {
+            panic!("expected one placement, got {outputs:?}");
+        };
+        assert_eq!(*offset, bytes.len() - 1);
+        assert_eq!(placement.image.rgba, vec![255, 0, 0, 255, 0, 0, 255, 255]);
+        assert_eq!(placement.width, ImageExtent::Cells(3));
+        assert!(placement.preserve_aspect);
+    }
+
+    #[test]
+    fn chunked_transfer_is_reassembled_before_display() {
+        let mut graphics = KittyGraphics::default();
+        assert!(run(&mut graphics, b"\x1b_Ga=T,f=32,s=1,v=1,m=1;AAAA\x1b\\").is_empty());
+
+        let outputs = run(&mut graphics, b"\x1b_Gm=0;AA==\x1b\\");
+        assert!(matches!(
+            &outputs[..],
+            [KittyOutput::Place { placement, .. }] if placement.image.rgba == vec![0; 4]
+        ));
+    }
+
+    #[test]
+    fn transmitted_image_is_placed_by_id_and_acknowledged() {
+        let mut graphics = KittyGraphics::default();
+        let outputs = run(&mut graphics, b"\x1b_Ga=t,f=32,s=1,v=1,i=5;AAAAAA==\x1b\\");
+        assert!(matches!(
+            &outputs[..],
+            [KittyOutput::Reply(reply)] if reply == b"\x1b_Gi=5;OK\x1b\\"
+        ));
+
+        let outputs = run(&mut graphics, b"\x1b_Ga=p,i=5,q=2\x1b\\");
+        assert!(matches!(&outputs[..], [KittyOutput::Place { .. }]));
+    }
+
+    #[test]
+    fn unsupported_transmission_reports_error_unless_silenced() {
+        let mut graphics = KittyGraphics::default();
+        let outputs = run(&mut graphics, b"\x1b_Ga=q,t=f,i=1;AAAA\x1b\\");
+        assert!(matches!(
+            &outputs[..],
+            [KittyOutput::Reply(reply)] if reply.starts_with(b"\x1b_Gi=1;EINVAL")
+        ));
+
+        assert!(run(&mut graphics, b"\x1b_Ga=q,t=f,i=1,q=2;AAAA\x1b\\").is_empty());
+    }
+}
@@ -3,9 +3,18 @@
 //! This crate provides a terminal emulator based on alacritty_terminal,
 //! adapted for use as a standalone library with GPUI.
 
+pub mod images;
+mod iterm_images;
+mod kitty_graphics;
+pub mod line_size;
 pub mod mappings;
+mod osc_scanner;
 pub mod platform;
 pub mod pty_info;
+pub mod scroll_region;
+pub mod shell_integration;
+mod sixel;
+mod sync_update;
 pub mod terminal;
 pub mod terminal_hyperlinks;
 pub mod terminal_settings;
@@ -14,7 +23,9 @@ pub use alacritty_terminal;
 
 use serde::{Deserialize, Serialize};
 
-pub use terminal_settings::{AlternateScroll, CursorShape, TerminalSettings, TerminalTheme};
+pub use terminal_settings::{
+    AlternateScroll, CursorShape, CustomThemeDefinition, TerminalSettings, TerminalTheme,
+};
 
 /// Re-export commonly used types
 pub use alacritty_terminal::{
@@ -32,8 +43,10 @@ pub mod events {
     /// Upward flowing events from terminal
     #[derive(Clone, Debug, PartialEq, Eq)]
     pub enum Event {
-        TitleChanged,
-        BreadcrumbsChanged,
+        /// The program set a new title, sanitized for display.
+        TitleChanged(String),
+        /// The shell reported a new working directory via OSC 7.
+        BreadcrumbsChanged(std::path::PathBuf),
         CloseTerminal,
         Bell,
         Wakeup,
@@ -166,10 +179,27 @@ pub mod config {
 
 /// Terminal error types
 pub mod error {
+    use std::io;
     use std::path::PathBuf;
 
+    use crate::Shell;
+
+    /// What went wrong while spawning a terminal.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ErrorKind {
+        /// The shell program does not exist.
+        ShellNotFound,
+        /// The working directory does not exist or is not a directory.
+        WorkingDirMissing,
+        /// Opening the pseudoterminal or starting its reader failed.
+        Pty,
+        /// Any other I/O failure, such as a shell that is not executable.
+        Io,
+    }
+
     #[derive(Debug)]
     pub struct TerminalError {
+        pub kind: ErrorKind,
         pub directory: Option<PathBuf>,
         pub program: Option<String>,
         pub args: Option<Vec<String>>,
@@ -199,11 +229,47 @@ pub mod error {
         }
     }
 
-    impl std::error::Error for TerminalError {}
+    impl TerminalError {
+        /// Error for spawning `shell` in `directory`.
+        pub(crate) fn spawning(
+            kind: ErrorKind,
+            shell: &Shell,
+            directory: Option<PathBuf>,
+            source: io::Error,
+        ) -> Self {
+            let (program, args, title_override) = match shell {
+                Shell::System => (None, None, None),
+                Shell::Program(program) => (Some(program.clone()), None, None),
+                Shell::WithArguments {
+                    program,
+                    args,
+                    title_override,
+                } => (
+                    Some(program.clone()),
+                    Some(args.clone()),
+                    title_override.clone(),
+                ),
+            };
+            Self {
+                kind,
+                directory,
+                program,
+                args,
+                title_override,
+                source,
+            }
+        }
+    }
+
+    impl std::error::Error for TerminalError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.source)
+        }
+    }
 }
 
 /// Shell configuration
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub enum Shell {
     #[default]
     System,
@@ -215,8 +281,63 @@ pub enum Shell {
     },
 }
 
+/// Flags that start a shell as a login shell, so it sources profile files.
+/// Windows shells have no such mode.
+const LOGIN_SHELL_ARGS: &[&str] = if cfg!(windows) { &[] } else { &["-l"] };
+
+impl Shell {
+    /// The user's shell from `$SHELL`, or `System` when it is unset.
+    pub fn from_env() -> Self {
+        Self::from_shell_var(std::env::var("SHELL").ok())
+    }
+
+    pub(crate) fn from_shell_var(shell: Option<String>) -> Self {
+        match shell {
+            Some(program) if !program.trim().is_empty() => Shell::Program(program),
+            _ => Shell::System,
+        }
+    }
+
+    /// This shell started as a login shell. `System` has no program to pass
+    /// the flag to and is returned as is; resolve it with `from_env` first.
+    pub fn into_login(self) -> Self {
+        match self {
+            Shell::System => Shell::System,
+            Shell::Program(program) if LOGIN_SHELL_ARGS.is_empty() => Shell::Program(program),
+            Shell::Program(program) => Shell::WithArguments {
+                program,
+                args: login_args(Vec::new()),
+                title_override: None,
+            },
+            Shell::WithArguments {
+                program,
+                args,
+                title_override,
+            } => Shell::WithArguments {
+                program,
+                args: login_args(args),
+                title_override,
+            },
+        }
+    }
+}
+
+/// `args` with the login flags in front, unless they already ask for a
+/// login shell.
+fn login_args(args: Vec<String>) -> Vec<String> {
+    if args.iter().any(|arg| arg == "-l" || arg == "--login") {
+        return args;
+    }
+    LOGIN_SHELL_ARGS
+        .iter()
+        .map(|arg| arg.to_string())
+        .chain(args)
+        .collect()
+}
+
 /// Path style for hyperlinks
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PathStyle {
     #[default]
     Unix,
@@ -227,7 +348,7 @@ pub enum PathStyle {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::TerminalError;
+    use crate::error::{ErrorKind, TerminalError};
     use gpui::{point, px, size, Bounds};
     use std::io;
     use std::path::PathBuf;
@@ -273,6 +394,43 @@ mod tests {
         assert!(matches!(Shell::default(), Shell::System));
     }
 
+    #[test]
+    fn shell_from_env_falls_back_to_system_without_shell_var() {
+        assert_eq!(
+            Shell::from_shell_var(Some("/bin/fish".to_string())),
+            Shell::Program("/bin/fish".to_string())
+        );
+        assert_eq!(Shell::from_shell_var(Some(" ".to_string())), Shell::System);
+        assert_eq!(Shell::from_shell_var(None), Shell::System);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn login_shell_prepends_login_flag_once() {
+        assert_eq!(Shell::System.into_login(), Shell::System);
+        assert_eq!(
+            Shell::Program("/bin/zsh".to_string()).into_login(),
+            Shell::WithArguments {
+                program: "/bin/zsh".to_string(),
+                args: vec!["-l".to_string()],
+                title_override: None,
+            }
+        );
+        let with_arguments = |args: &[&str]| Shell::WithArguments {
+            program: "/bin/bash".to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            title_override: Some("bash".to_string()),
+        };
+        assert_eq!(
+            with_arguments(&["-i"]).into_login(),
+            with_arguments(&["-l", "-i"])
+        );
+        assert_eq!(
+            with_arguments(&["--login"]).into_login(),
+            with_arguments(&["--login"])
+        );
+    }
+
     #[test]
     fn path_style_default_is_unix() {
         assert!(matches!(PathStyle::default(), PathStyle::Unix));
@@ -281,6 +439,7 @@ mod tests {
     #[test]
     fn terminal_error_display_uses_placeholder_values_when_not_provided() {
         let error = TerminalError {
+            kind: ErrorKind::Io,
             directory: None,
             program: None,
             args: None,
@@ -297,6 +456,7 @@ mod tests {
     #[test]
     fn terminal_error_display_includes_directory_program_and_args() {
         let error = TerminalError {
+            kind: ErrorKind::ShellNotFound,
             directory: Some(PathBuf::from("/tmp/project")),
             program: Some("/bin/zsh".to_string()),
             args: Some(vec!["-l".to_string(), "-i".to_string()]),
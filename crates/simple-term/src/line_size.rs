@@ -0,0 +1,331 @@
+//! DEC line size attributes (`ESC # 3` to `ESC # 6`).
+//!
+//! alacritty parses DECDHL, DECSWL and DECDWL but has nowhere to store them,
+//! so the PTY reader records the size as an invisible zero-width marker
+//! character. alacritty attaches the marker to a cell of the affected line,
+//! where it scrolls, clears and wraps with the rest of the row.
+
+use std::borrow::Cow;
+
+use alacritty_terminal::term::cell::Cell;
+
+use crate::shell_integration::MarkKind;
+
+const ESC: u8 = 0x1b;
+const DEL: u8 = 0x7f;
+
+/// How a row is scaled when drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum LineSize {
+    /// DECSWL (`ESC # 5`).
+    #[default]
+    Single,
+    /// DECDWL (`ESC # 6`).
+    DoubleWidth,
+    /// Upper half of a DECDHL pair (`ESC # 3`).
+    DoubleHeightTop,
+    /// Lower half of a DECDHL pair (`ESC # 4`).
+    DoubleHeightBottom,
+}
+
+impl LineSize {
+    /// Tag digits are zero-width and never printed on their own.
+    const MARKERS: [(char, LineSize); 4] = [
+        ('\u{E0033}', LineSize::DoubleHeightTop),
+        ('\u{E0034}', LineSize::DoubleHeightBottom),
+        ('\u{E0035}', LineSize::Single),
+        ('\u{E0036}', LineSize::DoubleWidth),
+    ];
+
+    fn from_final_byte(byte: u8) -> Option<Self> {
+        match byte {
+            b'3' => Some(LineSize::DoubleHeightTop),
+            b'4' => Some(LineSize::DoubleHeightBottom),
+            b'5' => Some(LineSize::Single),
+            b'6' => Some(LineSize::DoubleWidth),
+            _ => None,
+        }
+    }
+
+    /// Line size recorded on `cell` by the last marker attached to it.
+    pub fn from_cell(cell: &Cell) -> Option<Self> {
+        cell.zerowidth()?
+            .iter()
+            .rev()
+            .find_map(|c| Self::from_marker(*c))
+    }
+
+    /// Line size recorded by `c`, if it is a marker character.
+    pub fn from_marker(c: char) -> Option<Self> {
+        Self::MARKERS
+            .iter()
+            .find(|(marker, _)| *marker == c)
+            .map(|(_, size)| *size)
+    }
+
+    fn marker(self) -> char {
+        Self::MARKERS
+            .iter()
+            .find(|(_, size)| *size == self)
+            .map_or('\u{E0035}', |(marker, _)| *marker)
+    }
+
+    /// Whether each cell of the row covers two grid columns.
+    pub fn is_double_width(self) -> bool {
+        self != LineSize::Single
+    }
+
+    /// Whether glyphs are drawn at twice the font size.
+    pub fn is_double_height(self) -> bool {
+        matches!(
+            self,
+            LineSize::DoubleHeightTop | LineSize::DoubleHeightBottom
+        )
+    }
+}
+
+pub(crate) fn is_grid_marker(c: char) -> bool {
+    LineSize::from_marker(c).is_some() || MarkKind::from_marker(c).is_some()
+}
+
+/// Remove line size and shell integration markers from text copied out of
+/// the grid.
+pub fn strip_grid_markers(text: &str) -> Cow<'_, str> {
+    if text.chars().any(is_grid_marker) {
+        Cow::Owned(text.chars().filter(|c| !is_grid_marker(*c)).collect())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// Bytes of the marker recording `size`.
+pub(crate) fn marker_bytes(size: LineSize) -> Vec<u8> {
+    let mut buf = [0; 4];
+    size.marker().encode_utf8(&mut buf).as_bytes().to_vec()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    Hash,
+    /// A size was set; the next printed character gets a second marker.
+    Pending(LineSize),
+    /// Inside the UTF-8 character printed after a size change.
+    PendingUtf8(LineSize, u8),
+    PendingEscape(LineSize),
+    /// SGR sequences between the size change and the text are skipped.
+    PendingCsi(LineSize),
+}
+
+/// Incremental scanner for line size sequences that survives reads split
+/// mid-sequence.
+///
+/// A marker is reported right after the sequence, which lands on the cell
+/// before the cursor. That cell is often the first column and about to be
+/// overwritten, so a second marker follows the first character printed on the
+/// line, unless a control sequence moves the cursor first.
+#[derive(Debug)]
+pub(crate) struct LineSizeScanner {
+    state: ScanState,
+}
+
+impl Default for LineSizeScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+        }
+    }
+}
+
+impl LineSizeScanner {
+    /// Feed raw PTY bytes, invoking `on_marker` with the offset at which a
+    /// marker for the given size should be inserted.
+    pub(crate) fn advance(&mut self, bytes: &[u8], mut on_marker: impl FnMut(usize, LineSize)) {
+        let mut index = 0;
+        while index < bytes.len() {
+            if self.state == ScanState::Ground {
+                match bytes[index..].iter().position(|&byte| byte == ESC) {
+                    Some(offset) => {
+                        index += offset + 1;
+                        self.state = ScanState::Escape;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            self.state = match self.state {
+                ScanState::Ground => ScanState::Ground,
+                ScanState::Escape | ScanState::PendingEscape(_) if byte == b'#' => ScanState::Hash,
+                ScanState::Escape if byte == ESC => ScanState::Escape,
+                ScanState::Escape => ScanState::Ground,
+                ScanState::Hash => match LineSize::from_final_byte(byte) {
+                    Some(size) => {
+                        on_marker(index, size);
+                        ScanState::Pending(size)
+                    }
+                    None if byte == ESC => ScanState::Escape,
+                    None => ScanState::Ground,
+                },
+                ScanState::Pending(size) => match byte {
+                    0x20..=0x7e => {
+                        on_marker(index, size);
+                        ScanState::Ground
+                    }
+                    0xc2..=0xdf => ScanState::PendingUtf8(size, 1),
+                    0xe0..=0xef => ScanState::PendingUtf8(size, 2),
+                    0xf0..=0xf4 => ScanState::PendingUtf8(size, 3),
+                    ESC => ScanState::PendingEscape(size),
+                    _ => ScanState::Ground,
+                },
+                ScanState::PendingUtf8(size, remaining) => match byte {
+                    0x80..=0xbf if remaining == 1 => {
+                        on_marker(index, size);
+                        ScanState::Ground
+                    }
+                    0x80..=0xbf => ScanState::PendingUtf8(size, remaining - 1),
+                    ESC => ScanState::Escape,
+                    _ => ScanState::Ground,
+                },
+                ScanState::PendingEscape(size) => match byte {
+                    b'[' => ScanState::PendingCsi(size),
+                    ESC => ScanState::Escape,
+                    _ => ScanState::Ground,
+                },
+                ScanState::PendingCsi(size) => match byte {
+                    0x20..=0x3f => ScanState::PendingCsi(size),
+                    b'm' => ScanState::Pending(size),
+                    ESC => ScanState::Escape,
+                    DEL => ScanState::PendingCsi(size),
+                    _ => ScanState::Ground,
+                },
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{marker_bytes, strip_grid_markers, LineSize, LineSizeScanner};
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::grid::Dimensions;
+    use alacritty_terminal::index::{Column, Line};
+    use alacritty_terminal::term::{Config, Term};
+    use alacritty_terminal::vte::ansi::Processor;
+
+    struct Size;
+
+    impl Dimensions for Size {
+        fn total_lines(&self) -> usize {
+            4
+        }
+
+        fn screen_lines(&self) -> usize {
+            4
+        }
+
+        fn columns(&self) -> usize {
+            10
+        }
+    }
+
+    fn markers(chunks: &[&[u8]]) -> Vec<(usize, usize, LineSize)> {
+        let mut scanner = LineSizeScanner::default();
+        let mut found = Vec::new();
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            scanner.advance(chunk, |offset, size| {
+                found.push((chunk_index, offset, size))
+            });
+        }
+        found
+    }
+
+    /// Run `bytes` through the scanner and alacritty, returning the line size
+    /// recorded on each screen line.
+    fn line_sizes(bytes: &[u8]) -> Vec<LineSize> {
+        let mut inserted = Vec::new();
+        let mut copied = 0;
+        LineSizeScanner::default().advance(bytes, |offset, size| {
+            inserted.extend_from_slice(&bytes[copied..offset]);
+            inserted.extend(marker_bytes(size));
+            copied = offset;
+        });
+        inserted.extend_from_slice(&bytes[copied..]);
+
+        let mut term = Term::new(Config::default(), &Size, VoidListener);
+        Processor::<alacritty_terminal::vte::ansi::StdSyncHandler>::new()
+            .advance(&mut term, &inserted);
+        (0..4)
+            .map(|line| {
+                let row = &term.grid()[Line(line)];
+                (0..10)
+                    .filter_map(|column| row[Column(column)].zerowidth())
+                    .flatten()
+                    .filter_map(|c| LineSize::from_marker(*c))
+                    .next_back()
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn marker_follows_sequence_and_first_printed_character() {
+        assert_eq!(
+            markers(&[b"\x1b#6ab"]),
+            vec![(0, 3, LineSize::DoubleWidth), (0, 4, LineSize::DoubleWidth)]
+        );
+    }
+
+    #[test]
+    fn marker_waits_through_sgr_and_multibyte_characters() {
+        assert_eq!(
+            markers(&[b"\x1b#3\x1b[1;31m\xc3", b"\xa9"]),
+            vec![
+                (0, 3, LineSize::DoubleHeightTop),
+                (1, 1, LineSize::DoubleHeightTop)
+            ]
+        );
+    }
+
+    #[test]
+    fn cursor_movement_cancels_the_pending_marker() {
+        assert_eq!(
+            markers(&[b"\x1b#", b"4\x1b[2;1Hx\r\nyz"]),
+            vec![(1, 1, LineSize::DoubleHeightBottom)]
+        );
+    }
+
+    #[test]
+    fn other_hash_sequences_are_ignored() {
+        assert!(markers(&[b"\x1b#8\x1b#9 text"]).is_empty());
+    }
+
+    #[test]
+    fn markers_survive_in_alacritty_grid_as_zero_width_characters() {
+        let sizes = line_sizes(b"\x1b#3\x1b[7mTop\r\n\x1b#4Top\r\nplain\r\n\x1b#6wide");
+
+        assert_eq!(
+            sizes,
+            vec![
+                LineSize::DoubleHeightTop,
+                LineSize::DoubleHeightBottom,
+                LineSize::Single,
+                LineSize::DoubleWidth
+            ]
+        );
+    }
+
+    #[test]
+    fn copied_text_drops_markers() {
+        let text = format!("a{}b\u{E0041}", LineSize::DoubleWidth.marker());
+
+        assert_eq!(strip_grid_markers(&text), "ab");
+        assert!(matches!(
+            strip_grid_markers("plain"),
+            std::borrow::Cow::Borrowed("plain")
+        ));
+    }
+}
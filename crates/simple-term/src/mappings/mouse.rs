@@ -149,6 +149,8 @@ pub fn mouse_moved_report(
     }
 }
 
+/// Grid point under the window position `pos`. See [`grid_point_and_side`]
+/// for the coordinate conventions.
 pub fn grid_point(
     pos: Point<Pixels>,
     cur_size: TerminalBounds,
@@ -157,6 +159,20 @@ pub fn grid_point(
     grid_point_and_side(pos, cur_size, display_offset).0
 }
 
+/// Grid point under the window position `pos`, and which half of the cell it
+/// falls in.
+///
+/// `pos` is in window coordinates, the same space as `cur_size.bounds`, whose
+/// origin is the top-left corner of cell (0, 0). The returned line is a grid
+/// line: `Line(0)` is the top of the live screen and scrollback lines are
+/// negative, so a viewport scrolled up by `display_offset` lines maps its top
+/// row to `Line(-display_offset)`.
+///
+/// Positions right of the last column or below the last row clamp to that
+/// column or row and report [`Side::Right`]. Positions left of the grid clamp
+/// to column 0, while positions above it keep their negative line so a drag
+/// can select upward into scrollback. Cells are assumed to be single width;
+/// callers drawing DECDWL lines halve the column themselves.
 pub fn grid_point_and_side(
     pos: Point<Pixels>,
     cur_size: TerminalBounds,
@@ -395,6 +411,37 @@ mod tests {
         assert_eq!(side, Side::Left);
     }
 
+    #[test]
+    fn grid_point_and_side_map_pixel_positions_to_cells() {
+        // Ten columns of 10px and three lines of 20px, drawn at (5, 8).
+        let bounds = TerminalBounds::new(
+            px(20.0),
+            px(10.0),
+            Bounds {
+                origin: point(px(5.0), px(8.0)),
+                size: Size {
+                    width: px(100.0),
+                    height: px(60.0),
+                },
+            },
+        );
+        let at = |x: f32, y: f32, display_offset| {
+            let (point, side) = grid_point_and_side(point(px(x), px(y)), bounds, display_offset);
+            (point.line.0, point.column.0, side)
+        };
+
+        assert_eq!(at(5.0, 8.0, 0), (0, 0, Side::Left));
+        assert_eq!(at(11.0, 8.0, 0), (0, 0, Side::Right));
+        assert_eq!(at(36.0, 49.0, 0), (2, 3, Side::Left));
+        assert_eq!(at(36.0, 49.0, 5), (-3, 3, Side::Left));
+        // Past the right and bottom edges.
+        assert_eq!(at(500.0, 30.0, 0), (1, 9, Side::Right));
+        assert_eq!(at(40.0, 500.0, 0), (2, 3, Side::Right));
+        // Left of the grid clamps; above it keeps counting lines upward.
+        assert_eq!(at(-50.0, 30.0, 0), (1, 0, Side::Left));
+        assert_eq!(at(40.0, -52.0, 0), (-3, 3, Side::Left));
+    }
+
     #[test]
     fn alt_scroll_emits_expected_arrow_sequence_for_direction_and_magnitude() {
         assert_eq!(alt_scroll(2), b"\x1bOA\x1bOA".to_vec());
@@ -0,0 +1,250 @@
+//! Side-channel scanner for OSC sequences that alacritty does not handle.
+//!
+//! alacritty's parser silently drops OSC 7 (working directory reports), so the
+//! PTY output is scanned before it reaches the emulator and recognized payloads
+//! are surfaced as [`crate::terminal::TerminalEvent`]s.
+
+use std::path::PathBuf;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+/// Upper bound for a buffered OSC payload. Longer sequences are discarded.
+const MAX_OSC_PAYLOAD_BYTES: usize = 4096;
+/// Upper bound for iTerm2 inline image payloads (`1337;File=`), which carry
+/// a whole base64-encoded file.
+const MAX_INLINE_IMAGE_PAYLOAD_BYTES: usize = 32 * 1024 * 1024;
+const INLINE_IMAGE_PREFIX: &[u8] = b"1337;File=";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    Osc,
+    OscEscape,
+}
+
+/// Incremental OSC scanner that survives sequences split across reads.
+#[derive(Debug)]
+pub(crate) struct OscScanner {
+    state: ScanState,
+    payload: Vec<u8>,
+    overflowed: bool,
+}
+
+impl Default for OscScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            payload: Vec::new(),
+            overflowed: false,
+        }
+    }
+}
+
+impl OscScanner {
+    /// Feed raw PTY bytes, invoking `on_osc` with the offset just past the
+    /// terminator and the payload of every complete OSC sequence.
+    pub(crate) fn advance(&mut self, bytes: &[u8], mut on_osc: impl FnMut(usize, &[u8])) {
+        let mut index = 0;
+        while index < bytes.len() {
+            if self.state == ScanState::Ground {
+                match bytes[index..].iter().position(|&byte| byte == ESC) {
+                    Some(offset) => {
+                        index += offset + 1;
+                        self.state = ScanState::Escape;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            match self.state {
+                ScanState::Ground => {}
+                ScanState::Escape => {
+                    if byte == b']' {
+                        self.payload.clear();
+                        self.overflowed = false;
+                        self.state = ScanState::Osc;
+                    } else if byte != ESC {
+                        self.state = ScanState::Ground;
+                    }
+                }
+                ScanState::Osc => match byte {
+                    BEL => self.finish(index, &mut on_osc),
+                    ESC => self.state = ScanState::OscEscape,
+                    _ => self.push(byte),
+                },
+                ScanState::OscEscape => {
+                    if byte == b'\\' {
+                        self.finish(index, &mut on_osc);
+                    } else if byte == b']' {
+                        // An unterminated OSC followed by a new one.
+                        self.payload.clear();
+                        self.overflowed = false;
+                        self.state = ScanState::Osc;
+                    } else {
+                        self.state = if byte == ESC {
+                            ScanState::Escape
+                        } else {
+                            ScanState::Ground
+                        };
+                    }
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, byte: u8) {
+        let limit = if self.payload.starts_with(INLINE_IMAGE_PREFIX) {
+            MAX_INLINE_IMAGE_PAYLOAD_BYTES
+        } else {
+            MAX_OSC_PAYLOAD_BYTES
+        };
+        if self.payload.len() >= limit {
+            self.overflowed = true;
+            return;
+        }
+        self.payload.push(byte);
+    }
+
+    fn finish(&mut self, offset: usize, on_osc: &mut impl FnMut(usize, &[u8])) {
+        if !self.overflowed {
+            on_osc(offset, &self.payload);
+        }
+        self.payload.clear();
+        self.overflowed = false;
+        self.state = ScanState::Ground;
+    }
+}
+
+/// Extract the working directory from an OSC 7 payload (`7;file://host/path`).
+pub(crate) fn osc7_working_directory(payload: &[u8]) -> Option<PathBuf> {
+    let url = payload.strip_prefix(b"7;")?;
+    parse_osc7_url(std::str::from_utf8(url).ok()?)
+}
+
+/// Parse an OSC 7 `file://` URL into a local path.
+///
+/// The host component is stripped without validation: shells report their own
+/// hostname, and remote hosts are still useful as a display hint.
+pub(crate) fn parse_osc7_url(url: &str) -> Option<PathBuf> {
+    let rest = url.trim().strip_prefix("file://")?;
+    let path_start = rest.find('/')?;
+    let encoded_path = &rest[path_start..];
+    let decoded = urlencoding::decode_binary(encoded_path.as_bytes());
+    let path = String::from_utf8(decoded.into_owned()).ok()?;
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{osc7_working_directory, parse_osc7_url, OscScanner, MAX_OSC_PAYLOAD_BYTES};
+    use std::path::PathBuf;
+
+    fn scan_all(scanner: &mut OscScanner, chunks: &[&[u8]]) -> Vec<Vec<u8>> {
+        let mut payloads = Vec::new();
+        for chunk in chunks {
+            scanner.advance(chunk, |_, payload| payloads.push(payload.to_vec()));
+        }
+        payloads
+    }
+
+    #[test]
+    fn osc7_url_strips_host_component() {
+        assert_eq!(
+            parse_osc7_url("file://my-laptop.local/Users/me/src"),
+            Some(PathBuf::from("/Users/me/src"))
+        );
+    }
+
+    #[test]
+    fn osc7_url_accepts_empty_host() {
+        assert_eq!(
+            parse_osc7_url("file:///tmp/project"),
+            Some(PathBuf::from("/tmp/project"))
+        );
+    }
+
+    #[test]
+    fn osc7_url_decodes_percent_encoded_path() {
+        assert_eq!(
+            parse_osc7_url("file://host/Users/me/My%20Projects/caf%C3%A9"),
+            Some(PathBuf::from("/Users/me/My Projects/café"))
+        );
+    }
+
+    #[test]
+    fn osc7_url_rejects_non_file_schemes_and_missing_paths() {
+        assert_eq!(parse_osc7_url("https://host/path"), None);
+        assert_eq!(parse_osc7_url("file://host-only"), None);
+        assert_eq!(parse_osc7_url("file://host/%FF"), None);
+    }
+
+    #[test]
+    fn osc7_payload_requires_osc7_prefix() {
+        assert_eq!(
+            osc7_working_directory(b"7;file://host/tmp"),
+            Some(PathBuf::from("/tmp"))
+        );
+        assert_eq!(osc7_working_directory(b"2;file://host/tmp"), None);
+    }
+
+    #[test]
+    fn scanner_reports_bel_and_st_terminated_sequences() {
+        let mut scanner = OscScanner::default();
+        let payloads = scan_all(
+            &mut scanner,
+            &[b"ls\r\n\x1b]7;file://h/a\x07prompt\x1b]7;file://h/b\x1b\\$ "],
+        );
+        assert_eq!(
+            payloads,
+            vec![b"7;file://h/a".to_vec(), b"7;file://h/b".to_vec()]
+        );
+    }
+
+    #[test]
+    fn scanner_reassembles_sequences_split_across_reads() {
+        let mut scanner = OscScanner::default();
+        let payloads = scan_all(
+            &mut scanner,
+            &[b"out\x1b", b"]7;file:", b"//h/c\x1b", b"\\"],
+        );
+        assert_eq!(payloads, vec![b"7;file://h/c".to_vec()]);
+    }
+
+    #[test]
+    fn scanner_ignores_other_escape_sequences() {
+        let mut scanner = OscScanner::default();
+        let payloads = scan_all(&mut scanner, &[b"\x1b[31mred\x1b[0m\x1b(B"]);
+        assert!(payloads.is_empty());
+    }
+
+    #[test]
+    fn scanner_discards_oversized_payloads() {
+        let mut scanner = OscScanner::default();
+        let mut sequence = b"\x1b]7;".to_vec();
+        sequence.extend(std::iter::repeat_n(b'a', MAX_OSC_PAYLOAD_BYTES + 1));
+        sequence.push(0x07);
+        sequence.extend_from_slice(b"\x1b]7;file://h/ok\x07");
+
+        let payloads = scan_all(&mut scanner, &[&sequence]);
+        assert_eq!(payloads, vec![b"7;file://h/ok".to_vec()]);
+    }
+
+    #[test]
+    fn scanner_keeps_inline_image_payloads_past_the_osc_limit() {
+        let mut scanner = OscScanner::default();
+        let mut sequence = b"\x1b]1337;File=inline=1:".to_vec();
+        sequence.extend(std::iter::repeat_n(b'A', MAX_OSC_PAYLOAD_BYTES * 2));
+        sequence.push(0x07);
+
+        let mut ends = Vec::new();
+        scanner.advance(&sequence, |offset, payload| {
+            ends.push((offset, payload.len()))
+        });
+        assert_eq!(ends, vec![(sequence.len(), sequence.len() - 3)]);
+    }
+}
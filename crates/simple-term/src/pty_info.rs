@@ -79,6 +79,26 @@ pub struct ProcessInfo {
     pub argv: Vec<String>,
 }
 
+/// Name of process `pid`, such as `zsh` or `vim`.
+#[cfg(target_os = "linux")]
+pub fn foreground_process(pid: Pid) -> Option<String> {
+    let comm = std::fs::read_to_string(format!("/proc/{pid}/comm")).ok()?;
+    let name = comm.trim_end();
+    (!name.is_empty()).then(|| name.to_string())
+}
+
+/// Name of process `pid`, such as `zsh` or `vim`.
+#[cfg(not(target_os = "linux"))]
+pub fn foreground_process(pid: Pid) -> Option<String> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        sysinfo::ProcessesToUpdate::Some(&[pid]),
+        ProcessRefreshKind::new(),
+    );
+    let name = system.process(pid)?.name().to_str()?;
+    (!name.is_empty()).then(|| name.to_string())
+}
+
 /// Fetches Pseudo-Terminal (PTY) process information
 pub struct PtyProcessInfo {
     system: RwLock<System>,
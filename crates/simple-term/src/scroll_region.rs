@@ -0,0 +1,187 @@
+//! DECSTBM scroll region tracking (`CSI Pt ; Pb r`).
+//!
+//! alacritty keeps the scroll region private, so the PTY reader follows the
+//! same sequences to let the renderer know which rows a scroll can move.
+
+use std::ops::Range;
+
+const ESC: u8 = 0x1b;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+
+/// Scroll region as set by DECSTBM, with 1-based inclusive margins.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ScrollRegion {
+    pub top: usize,
+    /// `None` when the bottom margin was omitted, meaning the last line.
+    pub bottom: Option<usize>,
+}
+
+impl ScrollRegion {
+    /// Zero-based screen lines inside the region, clamped like alacritty does.
+    /// A region alacritty would have rejected covers the whole screen, and a
+    /// top margin of 0 means the first line, as in DECSTBM.
+    pub fn lines(&self, screen_lines: usize) -> Range<usize> {
+        let bottom = self.bottom.unwrap_or(screen_lines);
+        if self.top >= bottom {
+            return 0..screen_lines;
+        }
+        self.top.saturating_sub(1).min(screen_lines)..bottom.min(screen_lines)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    Csi,
+    /// A CSI sequence with a private marker or intermediate, which is never
+    /// DECSTBM.
+    CsiIgnore,
+}
+
+/// Incremental DECSTBM scanner that survives sequences split across reads.
+#[derive(Debug)]
+pub(crate) struct ScrollRegionScanner {
+    state: ScanState,
+    params: [usize; 2],
+    param_index: usize,
+}
+
+impl Default for ScrollRegionScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            params: [0; 2],
+            param_index: 0,
+        }
+    }
+}
+
+impl ScrollRegionScanner {
+    /// Feed raw PTY bytes, invoking `on_change` with every region set, or
+    /// `None` when a full reset (`ESC c`) restores the whole screen.
+    pub(crate) fn advance(
+        &mut self,
+        bytes: &[u8],
+        mut on_change: impl FnMut(Option<ScrollRegion>),
+    ) {
+        let mut index = 0;
+        while index < bytes.len() {
+            if self.state == ScanState::Ground {
+                match bytes[index..].iter().position(|&byte| byte == ESC) {
+                    Some(offset) => {
+                        index += offset + 1;
+                        self.state = ScanState::Escape;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            self.state = match (self.state, byte) {
+                (_, ESC) => ScanState::Escape,
+                (_, CAN | SUB) => ScanState::Ground,
+                (ScanState::Escape, b'[') => {
+                    self.params = [0; 2];
+                    self.param_index = 0;
+                    ScanState::Csi
+                }
+                (ScanState::Escape, b'c') => {
+                    on_change(None);
+                    ScanState::Ground
+                }
+                (ScanState::Escape, _) => ScanState::Ground,
+                (ScanState::Csi, b'0'..=b'9') => {
+                    if let Some(param) = self.params.get_mut(self.param_index) {
+                        *param = param
+                            .saturating_mul(10)
+                            .saturating_add((byte - b'0') as usize);
+                    }
+                    ScanState::Csi
+                }
+                (ScanState::Csi, b';') => {
+                    self.param_index += 1;
+                    ScanState::Csi
+                }
+                (ScanState::Csi, b'r') => {
+                    let top = self.params[0].max(1);
+                    let bottom =
+                        (self.param_index > 0 && self.params[1] != 0).then_some(self.params[1]);
+                    on_change(Some(ScrollRegion { top, bottom }));
+                    ScanState::Ground
+                }
+                // C0 controls inside a sequence are executed without ending it.
+                (ScanState::Csi | ScanState::CsiIgnore, 0x00..=0x1f) => self.state,
+                (ScanState::Csi | ScanState::CsiIgnore, 0x20..=0x3f) => ScanState::CsiIgnore,
+                (ScanState::Csi | ScanState::CsiIgnore, _) => ScanState::Ground,
+                (ScanState::Ground, _) => ScanState::Ground,
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ScrollRegion, ScrollRegionScanner};
+
+    fn changes(chunks: &[&[u8]]) -> Vec<Option<ScrollRegion>> {
+        let mut scanner = ScrollRegionScanner::default();
+        let mut found = Vec::new();
+        for chunk in chunks {
+            scanner.advance(chunk, |region| found.push(region));
+        }
+        found
+    }
+
+    #[test]
+    fn decstbm_sets_region_with_optional_bottom_margin() {
+        assert_eq!(
+            changes(&[b"\x1b[2;10r", b"\x1b[5r", b"\x1b[r"]),
+            vec![
+                Some(ScrollRegion {
+                    top: 2,
+                    bottom: Some(10)
+                }),
+                Some(ScrollRegion {
+                    top: 5,
+                    bottom: None
+                }),
+                Some(ScrollRegion {
+                    top: 1,
+                    bottom: None
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn region_survives_reads_split_mid_sequence() {
+        assert_eq!(
+            changes(&[b"text\x1b[", b"3;", b"8", b"r"]),
+            vec![Some(ScrollRegion {
+                top: 3,
+                bottom: Some(8)
+            })]
+        );
+    }
+
+    #[test]
+    fn private_modes_and_other_sequences_are_ignored() {
+        assert!(changes(&[b"\x1b[?1049h\x1b[?1r\x1b[2;5H\x1b[0m"]).is_empty());
+        assert_eq!(changes(&[b"\x1bc"]), vec![None]);
+    }
+
+    #[test]
+    fn region_lines_are_zero_based_and_clamped() {
+        let region = |top, bottom| ScrollRegion { top, bottom };
+
+        assert_eq!(region(2, Some(10)).lines(24), 1..10);
+        assert_eq!(region(5, None).lines(24), 4..24);
+        assert_eq!(region(2, Some(40)).lines(24), 1..24);
+        assert_eq!(region(10, Some(3)).lines(24), 0..24);
+        assert_eq!(region(0, Some(10)).lines(24), 0..10);
+    }
+}
@@ -0,0 +1,478 @@
+//! Shell integration marks (`OSC 133 ; A|B|C|D ST`).
+//!
+//! Shells that support semantic prompts report where each prompt, command and
+//! command output begins. The PTY reader records prompt, output and
+//! command-end marks as invisible zero-width marker characters, the same way
+//! as line sizes (see [`crate::line_size`]), so the marks scroll and leave the
+//! scrollback together with the rows they belong to.
+
+use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::term::Term;
+
+const ESC: u8 = 0x1b;
+const BEL: u8 = 0x07;
+
+const OSC133_PREFIX: &[u8] = b"133;";
+
+/// One `OSC 133` report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShellMark {
+    /// `A`: a prompt is about to be drawn.
+    PromptStart,
+    /// `B`: the prompt ended and the user is typing a command.
+    CommandStart,
+    /// `C`: the command was submitted and its output follows.
+    OutputStart,
+    /// `D[;<exit status>]`: the command finished.
+    CommandFinished(Option<i32>),
+}
+
+impl ShellMark {
+    /// Mark recorded on the grid for this report, if any.
+    fn kind(self) -> Option<MarkKind> {
+        match self {
+            ShellMark::PromptStart => Some(MarkKind::Prompt),
+            ShellMark::CommandStart => None,
+            ShellMark::OutputStart => Some(MarkKind::Output),
+            ShellMark::CommandFinished(_) => Some(MarkKind::CommandEnd),
+        }
+    }
+}
+
+/// Parse an OSC payload if it is a shell integration mark. Options after the
+/// mark, such as `aid=` or `cl=`, are ignored.
+pub(crate) fn osc133_mark(payload: &[u8]) -> Option<ShellMark> {
+    let rest = payload.strip_prefix(OSC133_PREFIX)?;
+    let mut params = rest.split(|&byte| byte == b';');
+    let mark = match params.next()? {
+        b"A" => ShellMark::PromptStart,
+        b"B" => ShellMark::CommandStart,
+        b"C" => ShellMark::OutputStart,
+        b"D" => ShellMark::CommandFinished(
+            params
+                .next()
+                .and_then(|status| std::str::from_utf8(status).ok())
+                .and_then(|status| status.trim().parse().ok()),
+        ),
+        _ => return None,
+    };
+    Some(mark)
+}
+
+/// Kind of a mark carried on the grid.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MarkKind {
+    /// First line of a prompt.
+    Prompt,
+    /// First line of a command's output.
+    Output,
+    /// Line on which a command's output ended.
+    CommandEnd,
+}
+
+impl MarkKind {
+    /// Tag letters are zero-width and never printed on their own.
+    const MARKERS: [(char, MarkKind); 3] = [
+        ('\u{E0041}', MarkKind::Prompt),
+        ('\u{E0043}', MarkKind::Output),
+        ('\u{E0044}', MarkKind::CommandEnd),
+    ];
+
+    /// Mark recorded by `c`, if it is a marker character.
+    pub fn from_marker(c: char) -> Option<Self> {
+        Self::MARKERS
+            .iter()
+            .find(|(marker, _)| *marker == c)
+            .map(|(_, kind)| *kind)
+    }
+
+    fn marker(self) -> char {
+        Self::MARKERS
+            .iter()
+            .find(|(_, kind)| *kind == self)
+            .map_or('\u{E0041}', |(marker, _)| *marker)
+    }
+}
+
+/// Bytes of the marker recording `kind`.
+pub(crate) fn mark_marker_bytes(kind: MarkKind) -> Vec<u8> {
+    let mut buf = [0; 4];
+    kind.marker().encode_utf8(&mut buf).as_bytes().to_vec()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    /// Inside a UTF-8 character with this many continuation bytes left.
+    Utf8(u8),
+    Escape,
+    Csi,
+    Osc,
+    OscEscape,
+}
+
+/// Places grid markers for the shell marks reported by the OSC scanner.
+///
+/// Like line size markers, a marker is inserted right after the sequence and
+/// again after the first character printed afterwards, because the cell before
+/// the cursor is often about to be overwritten. Line breaks, SGR, erase and
+/// mode sequences and other OSCs may come in between; anything else that
+/// moves the cursor drops the second marker.
+#[derive(Debug)]
+pub(crate) struct ShellMarkScanner {
+    state: ScanState,
+    pending: Vec<MarkKind>,
+}
+
+impl Default for ShellMarkScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl ShellMarkScanner {
+    /// Feed raw PTY bytes together with the marks found in them, as offsets
+    /// just past each sequence in ascending order. `on_marker` is invoked
+    /// with the offset at which a marker should be inserted.
+    pub(crate) fn advance(
+        &mut self,
+        bytes: &[u8],
+        marks: &[(usize, ShellMark)],
+        mut on_marker: impl FnMut(usize, MarkKind),
+    ) {
+        let mut marks = marks
+            .iter()
+            .filter_map(|(offset, mark)| mark.kind().map(|kind| (*offset, kind)))
+            .peekable();
+        let mut index = 0;
+        loop {
+            while let Some((offset, kind)) = marks.next_if(|(offset, _)| *offset <= index) {
+                on_marker(offset, kind);
+                if !self.pending.contains(&kind) {
+                    self.pending.push(kind);
+                }
+                self.state = ScanState::Ground;
+            }
+            if index >= bytes.len() {
+                return;
+            }
+            if self.pending.is_empty() {
+                match marks.peek() {
+                    Some((offset, _)) => {
+                        index = *offset;
+                        continue;
+                    }
+                    None => return,
+                }
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            let printed = match self.step(byte) {
+                Some(printed) => printed,
+                None => {
+                    self.pending.clear();
+                    continue;
+                }
+            };
+            if printed {
+                for kind in self.pending.drain(..) {
+                    on_marker(index, kind);
+                }
+                self.state = ScanState::Ground;
+            }
+        }
+    }
+
+    /// Advance the state machine by one byte. Returns whether a character was
+    /// printed, or `None` when the pending markers should be dropped.
+    fn step(&mut self, byte: u8) -> Option<bool> {
+        self.state = match (self.state, byte) {
+            (ScanState::Ground, 0x20..=0x7e) => return Some(true),
+            (ScanState::Ground, b'\r' | b'\n') => ScanState::Ground,
+            (ScanState::Ground, 0xc2..=0xdf) => ScanState::Utf8(1),
+            (ScanState::Ground, 0xe0..=0xef) => ScanState::Utf8(2),
+            (ScanState::Ground, 0xf0..=0xf4) => ScanState::Utf8(3),
+            (ScanState::Ground, ESC) => ScanState::Escape,
+            (ScanState::Utf8(1), 0x80..=0xbf) => return Some(true),
+            (ScanState::Utf8(remaining), 0x80..=0xbf) => ScanState::Utf8(remaining - 1),
+            (ScanState::Escape, b'[') => ScanState::Csi,
+            (ScanState::Escape, b']') => ScanState::Osc,
+            (ScanState::Csi, 0x20..=0x3f) => ScanState::Csi,
+            (ScanState::Csi, b'm' | b'K' | b'h' | b'l') => ScanState::Ground,
+            (ScanState::Osc | ScanState::OscEscape, BEL) => ScanState::Ground,
+            (ScanState::Osc | ScanState::OscEscape, ESC) => ScanState::OscEscape,
+            (ScanState::OscEscape, b'\\') => ScanState::Ground,
+            (ScanState::Osc, _) => ScanState::Osc,
+            _ => return None,
+        };
+        Some(false)
+    }
+}
+
+/// Grid cells carrying a `kind` marker, from the oldest scrollback line down.
+pub fn mark_points<T>(term: &Term<T>, kind: MarkKind) -> Vec<Point> {
+    let grid = term.grid();
+    let marker = kind.marker();
+    let columns = grid.columns();
+    (grid.topmost_line().0..=grid.bottommost_line().0)
+        .map(Line)
+        .flat_map(|line| {
+            let row = &grid[line];
+            (0..columns)
+                .map(Column)
+                .filter(move |column| {
+                    row[*column]
+                        .zerowidth()
+                        .is_some_and(|zerowidth| zerowidth.contains(&marker))
+                })
+                .map(move |column| Point::new(line, column))
+        })
+        .collect()
+}
+
+/// Grid lines carrying a `kind` marker, from the oldest scrollback line down.
+pub fn mark_lines<T>(term: &Term<T>, kind: MarkKind) -> Vec<Line> {
+    let mut lines: Vec<Line> = mark_points(term, kind)
+        .into_iter()
+        .map(|point| point.line)
+        .collect();
+    lines.dedup();
+    lines
+}
+
+/// Cells of the last finished command's output, from the start of the line
+/// its `C` mark is on to the cell before its `D` mark. `None` when no command
+/// finished or it printed nothing. Both lists must be in grid order.
+pub fn last_output_range(
+    output_starts: &[Point],
+    command_ends: &[Point],
+    last_column: Column,
+) -> Option<(Point, Point)> {
+    let end = *command_ends.last()?;
+    let start = output_starts.iter().rev().find(|start| **start <= end)?;
+    let start = Point::new(start.line, Column(0));
+    let end = match end.column.0 {
+        0 => Point::new(end.line - 1, last_column),
+        column => Point::new(end.line, Column(column - 1)),
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// Prompt to jump to from a viewport whose top row is `top`: the closest one
+/// above it, or below it when `up` is false. `prompts` must be sorted.
+pub fn adjacent_prompt(prompts: &[Line], top: Line, up: bool) -> Option<Line> {
+    if up {
+        prompts.iter().rev().find(|line| **line < top).copied()
+    } else {
+        prompts.iter().find(|line| **line > top).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        adjacent_prompt, last_output_range, mark_lines, mark_marker_bytes, mark_points,
+        osc133_mark, MarkKind, ShellMark, ShellMarkScanner,
+    };
+    use crate::osc_scanner::OscScanner;
+    use alacritty_terminal::event::VoidListener;
+    use alacritty_terminal::grid::Dimensions;
+    use alacritty_terminal::index::{Column, Line, Point};
+    use alacritty_terminal::term::{Config, Term};
+    use alacritty_terminal::vte::ansi::Processor;
+
+    struct Size;
+
+    impl Dimensions for Size {
+        fn total_lines(&self) -> usize {
+            3
+        }
+
+        fn screen_lines(&self) -> usize {
+            3
+        }
+
+        fn columns(&self) -> usize {
+            10
+        }
+    }
+
+    /// Markers the scanner places for `chunks`, as (chunk, offset, kind).
+    fn markers(chunks: &[&[u8]]) -> Vec<(usize, usize, MarkKind)> {
+        let mut osc = OscScanner::default();
+        let mut scanner = ShellMarkScanner::default();
+        let mut found = Vec::new();
+        for (chunk_index, chunk) in chunks.iter().enumerate() {
+            let mut marks = Vec::new();
+            osc.advance(chunk, |offset, payload| {
+                if let Some(mark) = osc133_mark(payload) {
+                    marks.push((offset, mark));
+                }
+            });
+            scanner.advance(chunk, &marks, |offset, kind| {
+                found.push((chunk_index, offset, kind))
+            });
+        }
+        found
+    }
+
+    /// Run `bytes` through the scanners and alacritty.
+    fn term_with_marks(bytes: &[u8]) -> Term<VoidListener> {
+        let mut marks = Vec::new();
+        OscScanner::default().advance(bytes, |offset, payload| {
+            if let Some(mark) = osc133_mark(payload) {
+                marks.push((offset, mark));
+            }
+        });
+        let mut inserted = Vec::new();
+        let mut copied = 0;
+        ShellMarkScanner::default().advance(bytes, &marks, |offset, kind| {
+            inserted.extend_from_slice(&bytes[copied..offset]);
+            inserted.extend(mark_marker_bytes(kind));
+            copied = offset;
+        });
+        inserted.extend_from_slice(&bytes[copied..]);
+
+        let config = Config {
+            scrolling_history: 10,
+            ..Config::default()
+        };
+        let mut term = Term::new(config, &Size, VoidListener);
+        Processor::<alacritty_terminal::vte::ansi::StdSyncHandler>::new()
+            .advance(&mut term, &inserted);
+        term
+    }
+
+    #[test]
+    fn osc133_payloads_parse_marks_and_exit_status() {
+        assert_eq!(osc133_mark(b"133;A"), Some(ShellMark::PromptStart));
+        assert_eq!(osc133_mark(b"133;A;aid=42"), Some(ShellMark::PromptStart));
+        assert_eq!(osc133_mark(b"133;B"), Some(ShellMark::CommandStart));
+        assert_eq!(osc133_mark(b"133;C"), Some(ShellMark::OutputStart));
+        assert_eq!(
+            osc133_mark(b"133;D;127"),
+            Some(ShellMark::CommandFinished(Some(127)))
+        );
+        assert_eq!(
+            osc133_mark(b"133;D"),
+            Some(ShellMark::CommandFinished(None))
+        );
+    }
+
+    #[test]
+    fn other_payloads_are_not_marks() {
+        assert_eq!(osc133_mark(b"133;Z"), None);
+        assert_eq!(osc133_mark(b"133"), None);
+        assert_eq!(osc133_mark(b"7;file:///tmp"), None);
+    }
+
+    #[test]
+    fn marker_follows_sequence_and_first_printed_character() {
+        let bytes = b"\x1b]133;A\x07\x1b[1m$ ";
+
+        assert_eq!(
+            markers(&[bytes]),
+            vec![(0, 8, MarkKind::Prompt), (0, 13, MarkKind::Prompt)]
+        );
+    }
+
+    #[test]
+    fn consecutive_marks_share_the_next_printed_character() {
+        let found = markers(&[b"\x1b]133;D;0\x1b\\\r\n\x1b]133;A\x07", b"\xc3\xa9"]);
+
+        assert_eq!(
+            found,
+            vec![
+                (0, 11, MarkKind::CommandEnd),
+                (0, 21, MarkKind::Prompt),
+                (1, 2, MarkKind::CommandEnd),
+                (1, 2, MarkKind::Prompt),
+            ]
+        );
+    }
+
+    #[test]
+    fn cursor_movement_drops_the_second_marker() {
+        assert_eq!(
+            markers(&[b"\x1b]133;C\x07\x1b[Hout"]),
+            vec![(0, 8, MarkKind::Output)]
+        );
+    }
+
+    #[test]
+    fn marks_survive_scrolling_into_history() {
+        let term = term_with_marks(
+            b"\x1b]133;A\x07$ ls\r\n\x1b]133;C\x07a\r\nb\r\n\x1b]133;D;0\x07\x1b]133;A\x07$ ",
+        );
+
+        assert_eq!(term.grid().history_size(), 1);
+        assert_eq!(mark_lines(&term, MarkKind::Prompt), vec![Line(-1), Line(2)]);
+        assert_eq!(mark_lines(&term, MarkKind::Output), vec![Line(0)]);
+        assert_eq!(mark_lines(&term, MarkKind::CommandEnd), vec![Line(2)]);
+    }
+
+    #[test]
+    fn adjacent_prompt_skips_the_prompt_at_the_top_row() {
+        let prompts = [Line(-40), Line(-12), Line(3)];
+
+        assert_eq!(adjacent_prompt(&prompts, Line(-12), true), Some(Line(-40)));
+        assert_eq!(adjacent_prompt(&prompts, Line(-12), false), Some(Line(3)));
+        assert_eq!(adjacent_prompt(&prompts, Line(-20), false), Some(Line(-12)));
+        assert_eq!(adjacent_prompt(&prompts, Line(-40), true), None);
+        assert_eq!(adjacent_prompt(&prompts, Line(3), false), None);
+        assert_eq!(adjacent_prompt(&[], Line(0), true), None);
+    }
+
+    #[test]
+    fn output_range_spans_last_command_output() {
+        let term = term_with_marks(b"\x1b]133;C\x07a\r\nb\r\n\x1b]133;D;0\x07\x1b]133;A\x07$ ");
+        let range = last_output_range(
+            &mark_points(&term, MarkKind::Output),
+            &mark_points(&term, MarkKind::CommandEnd),
+            Column(9),
+        );
+
+        assert_eq!(
+            range,
+            Some((
+                Point::new(Line(0), Column(0)),
+                Point::new(Line(1), Column(9))
+            ))
+        );
+    }
+
+    #[test]
+    fn output_range_uses_the_output_start_of_the_last_command() {
+        let at = |line, column| Point::new(Line(line), Column(column));
+        let starts = [at(-30, 0), at(-10, 0)];
+
+        assert_eq!(
+            last_output_range(&starts, &[at(-20, 0), at(-2, 4)], Column(79)),
+            Some((at(-10, 0), at(-2, 3)))
+        );
+        assert_eq!(
+            last_output_range(&starts, &[at(-20, 0)], Column(79)),
+            Some((at(-30, 0), at(-21, 79)))
+        );
+    }
+
+    #[test]
+    fn output_range_is_none_without_output() {
+        let at = |line, column| Point::new(Line(line), Column(column));
+
+        assert_eq!(last_output_range(&[], &[], Column(79)), None);
+        assert_eq!(
+            last_output_range(&[at(5, 0)], &[at(4, 0)], Column(79)),
+            None
+        );
+        assert_eq!(
+            last_output_range(&[at(4, 0)], &[at(4, 0)], Column(79)),
+            None
+        );
+    }
+}
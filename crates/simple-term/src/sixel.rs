@@ -0,0 +1,429 @@
+//! Sixel graphics capture and decoding.
+//!
+//! alacritty's parser drops DCS sequences, so Sixel images (`ESC P ... q`
+//! followed by sixel data and `ESC \`) are captured from the raw PTY stream
+//! next to the OSC scanner and decoded here into RGBA pixels.
+
+use crate::images::DecodedImage;
+
+const ESC: u8 = 0x1b;
+const CAN: u8 = 0x18;
+const SUB: u8 = 0x1a;
+
+/// Upper bound for buffered sixel data. Longer sequences are discarded.
+const MAX_SIXEL_BYTES: usize = 16 * 1024 * 1024;
+/// Pixels beyond this width or height are dropped while decoding.
+const MAX_SIXEL_DIMENSION: usize = 4096;
+const PALETTE_SIZE: usize = 256;
+
+/// VT340 default color registers, as RGB percentages.
+const VT340_PALETTE: [[u32; 3]; 16] = [
+    [0, 0, 0],
+    [20, 20, 80],
+    [80, 13, 13],
+    [20, 80, 20],
+    [80, 20, 80],
+    [20, 80, 80],
+    [80, 80, 20],
+    [53, 53, 53],
+    [26, 26, 26],
+    [33, 33, 60],
+    [60, 26, 26],
+    [33, 60, 33],
+    [60, 33, 60],
+    [33, 60, 60],
+    [60, 60, 33],
+    [80, 80, 80],
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ScanState {
+    Ground,
+    Escape,
+    DcsParams,
+    Data,
+    DataEscape,
+}
+
+/// Incremental DCS scanner that survives sixel sequences split across reads.
+#[derive(Debug)]
+pub(crate) struct SixelScanner {
+    state: ScanState,
+    params: Vec<u8>,
+    data: Vec<u8>,
+    overflowed: bool,
+}
+
+impl Default for SixelScanner {
+    fn default() -> Self {
+        Self {
+            state: ScanState::Ground,
+            params: Vec::new(),
+            data: Vec::new(),
+            overflowed: false,
+        }
+    }
+}
+
+impl SixelScanner {
+    /// Feed raw PTY bytes, invoking `on_image` with the offset just past the
+    /// terminator of every sixel sequence that decodes to an image.
+    pub(crate) fn advance(&mut self, bytes: &[u8], mut on_image: impl FnMut(usize, DecodedImage)) {
+        let mut index = 0;
+        while index < bytes.len() {
+            match self.state {
+                ScanState::Ground => {
+                    match bytes[index..].iter().position(|&byte| byte == ESC) {
+                        Some(offset) => {
+                            index += offset + 1;
+                            self.state = ScanState::Escape;
+                        }
+                        None => return,
+                    }
+                    continue;
+                }
+                ScanState::Data => {
+                    let rest = &bytes[index..];
+                    let end = rest
+                        .iter()
+                        .position(|&byte| matches!(byte, ESC | CAN | SUB))
+                        .unwrap_or(rest.len());
+                    self.push_data(&rest[..end]);
+                    index += end;
+                    if let Some(&byte) = rest.get(end) {
+                        index += 1;
+                        self.state = if byte == ESC {
+                            ScanState::DataEscape
+                        } else {
+                            ScanState::Ground
+                        };
+                    }
+                    continue;
+                }
+                _ => {}
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            match self.state {
+                ScanState::Ground | ScanState::Data => {}
+                ScanState::Escape => self.escape(byte),
+                ScanState::DcsParams => match byte {
+                    b'0'..=b'9' | b';' => self.params.push(byte),
+                    b'q' => self.state = ScanState::Data,
+                    ESC => self.state = ScanState::Escape,
+                    // Another DCS type; its payload is left to alacritty.
+                    _ => self.state = ScanState::Ground,
+                },
+                ScanState::DataEscape => {
+                    if byte == b'\\' {
+                        self.state = ScanState::Ground;
+                        if !self.overflowed {
+                            if let Some(image) = decode_sixel(&self.params, &self.data) {
+                                on_image(index, image);
+                            }
+                        }
+                        self.data = Vec::new();
+                    } else {
+                        self.escape(byte);
+                    }
+                }
+            }
+        }
+    }
+
+    fn escape(&mut self, byte: u8) {
+        self.state = match byte {
+            b'P' => {
+                self.params.clear();
+                self.data.clear();
+                self.overflowed = false;
+                ScanState::DcsParams
+            }
+            ESC => ScanState::Escape,
+            _ => ScanState::Ground,
+        };
+    }
+
+    fn push_data(&mut self, bytes: &[u8]) {
+        if self.data.len() + bytes.len() > MAX_SIXEL_BYTES {
+            self.overflowed = true;
+            return;
+        }
+        self.data.extend_from_slice(bytes);
+    }
+}
+
+/// Parse a run of `;`-separated decimal parameters starting at `index`.
+fn parse_params(data: &[u8], mut index: usize) -> (Vec<u32>, usize) {
+    let mut params = vec![0u32];
+    while let Some(&byte) = data.get(index) {
+        match byte {
+            b'0'..=b'9' => {
+                if let Some(last) = params.last_mut() {
+                    *last = last
+                        .saturating_mul(10)
+                        .saturating_add(u32::from(byte - b'0'));
+                }
+            }
+            b';' => params.push(0),
+            _ => break,
+        }
+        index += 1;
+    }
+    (params, index)
+}
+
+fn percent_to_channel(percent: u32) -> u8 {
+    (percent.min(100) * 255 / 100) as u8
+}
+
+/// Convert sixel HLS (hue 0° = blue, lightness and saturation in percent).
+fn hls_to_rgb(hue: u32, lightness: u32, saturation: u32) -> [u8; 3] {
+    let hue = ((hue % 360 + 240) % 360) as f32 / 360.0;
+    let lightness = lightness.min(100) as f32 / 100.0;
+    let saturation = saturation.min(100) as f32 / 100.0;
+    if saturation == 0.0 {
+        let value = (lightness * 255.0).round() as u8;
+        return [value; 3];
+    }
+
+    let q = if lightness < 0.5 {
+        lightness * (1.0 + saturation)
+    } else {
+        lightness + saturation - lightness * saturation
+    };
+    let p = 2.0 * lightness - q;
+    let channel = |t: f32| {
+        let t = t.rem_euclid(1.0);
+        let value = if t < 1.0 / 6.0 {
+            p + (q - p) * 6.0 * t
+        } else if t < 0.5 {
+            q
+        } else if t < 2.0 / 3.0 {
+            p + (q - p) * (2.0 / 3.0 - t) * 6.0
+        } else {
+            p
+        };
+        (value * 255.0).round() as u8
+    };
+    [
+        channel(hue + 1.0 / 3.0),
+        channel(hue),
+        channel(hue - 1.0 / 3.0),
+    ]
+}
+
+/// Decode the body of a sixel DCS sequence. `params` is the DCS parameter
+/// string before `q`; `data` is everything between `q` and the terminator.
+pub(crate) fn decode_sixel(params: &[u8], data: &[u8]) -> Option<DecodedImage> {
+    // P2 = 1 keeps unset pixels transparent; otherwise they take color 0.
+    let (dcs_params, _) = parse_params(params, 0);
+    let transparent_background = dcs_params.get(1) == Some(&1);
+
+    let mut palette = [[0u8; 3]; PALETTE_SIZE];
+    for (register, [r, g, b]) in palette.iter_mut().zip(VT340_PALETTE) {
+        *register = [
+            percent_to_channel(r),
+            percent_to_channel(g),
+            percent_to_channel(b),
+        ];
+    }
+
+    let mut rows: Vec<Vec<Option<[u8; 3]>>> = Vec::new();
+    let mut raster_width = 0usize;
+    let mut raster_height = 0usize;
+    let mut color = 0usize;
+    let mut x = 0usize;
+    let mut band_top = 0usize;
+    let mut index = 0;
+
+    while let Some(&byte) = data.get(index) {
+        index += 1;
+        let mut repeat = 1usize;
+        let sixel = match byte {
+            b'"' => {
+                let (raster, next) = parse_params(data, index);
+                index = next;
+                raster_width = raster.get(2).copied().unwrap_or(0) as usize;
+                raster_height = raster.get(3).copied().unwrap_or(0) as usize;
+                continue;
+            }
+            b'#' => {
+                let (values, next) = parse_params(data, index);
+                index = next;
+                color = values[0] as usize % PALETTE_SIZE;
+                if let [_, space, a, b, c] = values[..] {
+                    palette[color] = match space {
+                        1 => hls_to_rgb(a, b, c),
+                        _ => [
+                            percent_to_channel(a),
+                            percent_to_channel(b),
+                            percent_to_channel(c),
+                        ],
+                    };
+                }
+                continue;
+            }
+            b'!' => {
+                let (count, next) = parse_params(data, index);
+                index = next;
+                repeat = (count[0] as usize).clamp(1, MAX_SIXEL_DIMENSION);
+                match data.get(index) {
+                    Some(&sixel @ 0x3f..=0x7e) => {
+                        index += 1;
+                        sixel
+                    }
+                    _ => continue,
+                }
+            }
+            b'$' => {
+                x = 0;
+                continue;
+            }
+            b'-' => {
+                x = 0;
+                band_top += 6;
+                continue;
+            }
+            0x3f..=0x7e => byte,
+            _ => continue,
+        };
+
+        let bits = sixel - 0x3f;
+        for bit in 0..6 {
+            let y = band_top + bit;
+            if bits & (1 << bit) == 0 || y >= MAX_SIXEL_DIMENSION {
+                continue;
+            }
+            if rows.len() <= y {
+                rows.resize_with(y + 1, Vec::new);
+            }
+            let row = &mut rows[y];
+            let end = (x + repeat).min(MAX_SIXEL_DIMENSION);
+            if row.len() < end {
+                row.resize(end, None);
+            }
+            for pixel in row.iter_mut().take(end).skip(x) {
+                *pixel = Some(palette[color]);
+            }
+        }
+        x = (x + repeat).min(MAX_SIXEL_DIMENSION);
+    }
+
+    let width = rows
+        .iter()
+        .map(Vec::len)
+        .max()
+        .unwrap_or(0)
+        .max(raster_width)
+        .min(MAX_SIXEL_DIMENSION);
+    let height = rows.len().max(raster_height).min(MAX_SIXEL_DIMENSION);
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    let background = if transparent_background {
+        [0, 0, 0, 0]
+    } else {
+        let [r, g, b] = palette[0];
+        [r, g, b, 0xff]
+    };
+    let mut rgba = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let row = rows.get(y).map(Vec::as_slice).unwrap_or(&[]);
+        for x in 0..width {
+            match row.get(x).copied().flatten() {
+                Some([r, g, b]) => rgba.extend_from_slice(&[r, g, b, 0xff]),
+                None => rgba.extend_from_slice(&background),
+            }
+        }
+    }
+
+    Some(DecodedImage {
+        width: width as u32,
+        height: height as u32,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode_sixel, SixelScanner};
+    use crate::images::DecodedImage;
+
+    fn pixel(image: &DecodedImage, x: usize, y: usize) -> [u8; 4] {
+        let offset = (y * image.width as usize + x) * 4;
+        let mut rgba = [0; 4];
+        rgba.copy_from_slice(&image.rgba[offset..offset + 4]);
+        rgba
+    }
+
+    #[test]
+    fn decodes_tiny_palette_image_to_rgba() {
+        // Two columns: a full red column, then a green column covering only
+        // the top pixel. Unset pixels stay transparent (P2 = 1).
+        let image = decode_sixel(b"0;1", b"#1;2;100;0;0#2;2;0;100;0#1~#2@").expect("image");
+
+        assert_eq!((image.width, image.height), (2, 6));
+        for y in 0..6 {
+            assert_eq!(pixel(&image, 0, y), [255, 0, 0, 255]);
+        }
+        assert_eq!(pixel(&image, 1, 0), [0, 255, 0, 255]);
+        assert_eq!(pixel(&image, 1, 1), [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn decodes_repeats_bands_and_raster_size() {
+        let image = decode_sixel(b"", b"\"1;1;4;12#7!3~-#7@").expect("image");
+
+        assert_eq!((image.width, image.height), (4, 12));
+        let gray = [135, 135, 135, 255];
+        assert_eq!(pixel(&image, 2, 5), gray);
+        assert_eq!(pixel(&image, 0, 6), gray);
+        // Unset pixels take color register 0 without the P2 = 1 parameter.
+        assert_eq!(pixel(&image, 3, 0), [0, 0, 0, 255]);
+    }
+
+    #[test]
+    fn decodes_hls_colors_with_blue_at_zero_degrees() {
+        let image = decode_sixel(b"0;1", b"#3;1;0;50;100~").expect("image");
+        assert_eq!(pixel(&image, 0, 0), [0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn saturated_hls_hue_wraps_instead_of_overflowing() {
+        // 4294967295 is 255 more than a multiple of 360.
+        let saturated = decode_sixel(b"0;1", b"#1;1;4294967295;50;100~").expect("image");
+        let wrapped = decode_sixel(b"0;1", b"#1;1;255;50;100~").expect("image");
+        assert_eq!(pixel(&saturated, 0, 0), pixel(&wrapped, 0, 0));
+    }
+
+    #[test]
+    fn empty_sixel_data_produces_no_image() {
+        assert_eq!(decode_sixel(b"", b"#0;2;0;0;0"), None);
+    }
+
+    #[test]
+    fn scanner_reports_offset_after_terminator_across_reads() {
+        let mut scanner = SixelScanner::default();
+        let mut found = Vec::new();
+        scanner.advance(b"before\x1bPq#1~", |offset, image| {
+            found.push((offset, image.width))
+        });
+        assert!(found.is_empty());
+
+        scanner.advance(b"~\x1b\\after", |offset, image| {
+            found.push((offset, image.width))
+        });
+        assert_eq!(found, vec![(3, 2)]);
+    }
+
+    #[test]
+    fn scanner_ignores_other_dcs_and_aborted_sequences() {
+        let mut scanner = SixelScanner::default();
+        let mut count = 0;
+        scanner.advance(b"\x1bP+q544e\x1b\\\x1bPq~~\x18\x1b\\", |_, _| count += 1);
+        assert_eq!(count, 0);
+    }
+}
@@ -0,0 +1,83 @@
+//! Synchronized output (`CSI ? 2026 h` to `CSI ? 2026 l`).
+//!
+//! alacritty's parser holds back grid changes until a synchronized update
+//! ends, but images and scroll regions are scanned from the raw output as it
+//! arrives. The PTY reader follows the mode too, so the renderer can keep its
+//! previous frame until the update is complete.
+
+const ESC: u8 = 0x1b;
+
+/// Begin sequence; the end sequence differs only in its final byte. alacritty
+/// accepts exactly these bytes, without extra parameters.
+const BEGIN: &[u8] = b"\x1b[?2026h";
+
+/// Incremental matcher for the begin and end sequences that survives reads
+/// split mid-sequence.
+#[derive(Debug, Default)]
+pub(crate) struct SyncUpdateScanner {
+    matched: usize,
+}
+
+impl SyncUpdateScanner {
+    /// Feed raw PTY bytes, invoking `on_change` with `true` when an update
+    /// begins and `false` when it ends.
+    pub(crate) fn advance(&mut self, bytes: &[u8], mut on_change: impl FnMut(bool)) {
+        let prefix = &BEGIN[..BEGIN.len() - 1];
+        let mut index = 0;
+        while index < bytes.len() {
+            if self.matched == 0 {
+                match bytes[index..].iter().position(|&byte| byte == ESC) {
+                    Some(offset) => {
+                        index += offset + 1;
+                        self.matched = 1;
+                    }
+                    None => return,
+                }
+                continue;
+            }
+
+            let byte = bytes[index];
+            index += 1;
+            self.matched = if self.matched < prefix.len() && byte == prefix[self.matched] {
+                self.matched + 1
+            } else {
+                if self.matched == prefix.len() {
+                    match byte {
+                        b'h' => on_change(true),
+                        b'l' => on_change(false),
+                        _ => {}
+                    }
+                }
+                usize::from(byte == ESC)
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyncUpdateScanner;
+
+    fn changes(chunks: &[&[u8]]) -> Vec<bool> {
+        let mut scanner = SyncUpdateScanner::default();
+        let mut found = Vec::new();
+        for chunk in chunks {
+            scanner.advance(chunk, |active| found.push(active));
+        }
+        found
+    }
+
+    #[test]
+    fn begin_and_end_are_reported_across_split_reads() {
+        assert_eq!(
+            changes(&[b"\x1b[?2026hdraw\x1b[?20", b"26l"]),
+            vec![true, false]
+        );
+        assert_eq!(changes(&[b"\x1b\x1b[?2026h"]), vec![true]);
+    }
+
+    #[test]
+    fn other_private_modes_are_ignored() {
+        assert!(changes(&[b"\x1b[?2027h\x1b[?1049h\x1b[?2026;1h\x1b[2026h"]).is_empty());
+    }
+}
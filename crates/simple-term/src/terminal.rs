@@ -2,20 +2,51 @@
 
 use std::borrow::Cow;
 use std::collections::HashMap;
-use std::io;
+use std::io::{self, Read, Write};
+use std::ops::Range;
+use std::os::unix::net::UnixStream;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::thread::JoinHandle;
+use std::time::Instant;
 
-use alacritty_terminal::event::{Event as AlacEvent, EventListener, WindowSize};
+use alacritty_terminal::event::{Event as AlacEvent, EventListener, OnResize, WindowSize};
 use alacritty_terminal::event_loop::{EventLoop, EventLoopSender, Msg};
-use alacritty_terminal::grid::Dimensions;
+use alacritty_terminal::grid::{Dimensions, Scroll};
+use alacritty_terminal::index::{Column, Line, Point};
+use alacritty_terminal::selection::SelectionRange;
 use alacritty_terminal::sync::FairMutex;
-use alacritty_terminal::term::{self, Term};
-use alacritty_terminal::tty;
-use alacritty_terminal::vte::ansi::CursorStyle as AlacCursorStyle;
-
-use crate::Shell;
+use alacritty_terminal::term::cell::{Cell, Flags};
+use alacritty_terminal::term::color::Colors;
+use alacritty_terminal::term::{self, Term, TermMode};
+use alacritty_terminal::tty::{self, ChildEvent, EventedPty, EventedReadWrite};
+use alacritty_terminal::vte::ansi::{
+    Color, CursorShape, CursorStyle as AlacCursorStyle, NamedColor, Processor, StdSyncHandler,
+};
+use gpui::Keystroke;
+use parking_lot::Mutex;
+use polling::{Event as PollEvent, PollMode, Poller};
+use sysinfo::Pid;
+
+use crate::error::{ErrorKind, TerminalError};
+use crate::images::{display_size, placeholder_bytes, ImagePlacement, ImageStore, TerminalImage};
+use crate::iterm_images::inline_image_from_osc;
+use crate::kitty_graphics::{KittyGraphics, KittyOutput};
+use crate::line_size::{
+    is_grid_marker, marker_bytes, strip_grid_markers, LineSize, LineSizeScanner,
+};
+use crate::mappings::keys::to_esc_str;
+use crate::osc_scanner::{osc7_working_directory, OscScanner};
+use crate::pty_info::{foreground_process, ProcessIdGetter};
+use crate::scroll_region::{ScrollRegion, ScrollRegionScanner};
+use crate::shell_integration::{
+    mark_marker_bytes, osc133_mark, MarkKind, ShellMark, ShellMarkScanner,
+};
+use crate::sixel::SixelScanner;
+use crate::sync_update::SyncUpdateScanner;
+use crate::terminal_settings::default_word_separators;
+use crate::{Event, Shell};
 
 /// Events sent from the terminal backend to the UI layer.
 #[derive(Clone, Debug)]
@@ -23,6 +54,14 @@ pub enum TerminalEvent {
     Wakeup,
     Bell,
     TitleChanged(String),
+    /// The program dropped its title, such as by popping a title stack
+    /// entry (`CSI 23 t`) that was pushed before any title was set.
+    TitleReset,
+    /// The shell reported a new working directory via OSC 7.
+    CwdChanged(PathBuf),
+    /// A command finished, with the exit status the shell reported via
+    /// `OSC 133 ; D`.
+    CommandFinished(Option<i32>),
     Exit(i32),
 }
 
@@ -35,9 +74,11 @@ enum BackpressurePolicy {
 fn backpressure_policy(event: &TerminalEvent) -> BackpressurePolicy {
     match event {
         TerminalEvent::Wakeup | TerminalEvent::Bell => BackpressurePolicy::DropWhenFull,
-        TerminalEvent::TitleChanged(_) | TerminalEvent::Exit(_) => {
-            BackpressurePolicy::KeepLatestWhenFull
-        }
+        TerminalEvent::TitleChanged(_)
+        | TerminalEvent::TitleReset
+        | TerminalEvent::CwdChanged(_)
+        | TerminalEvent::CommandFinished(_)
+        | TerminalEvent::Exit(_) => BackpressurePolicy::KeepLatestWhenFull,
     }
 }
 
@@ -48,6 +89,7 @@ fn map_event(event: AlacEvent) -> Option<TerminalEvent> {
         AlacEvent::CursorBlinkingChange => Some(TerminalEvent::Wakeup),
         AlacEvent::Bell => Some(TerminalEvent::Bell),
         AlacEvent::Title(title) => Some(TerminalEvent::TitleChanged(title)),
+        AlacEvent::ResetTitle => Some(TerminalEvent::TitleReset),
         AlacEvent::ChildExit(code) => Some(TerminalEvent::Exit(code)),
         _ => None,
     }
@@ -80,18 +122,116 @@ fn terminal_dimensions(window_size: WindowSize) -> TerminalDimensions {
     }
 }
 
+/// Longest title kept by [`sanitize_title`], in characters.
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Title as shown to users: control characters other than tabs removed,
+/// trimmed, capped at 60 characters, and `shell` when nothing is left.
+pub fn sanitize_title(raw: &str) -> String {
+    let cleaned: String = raw
+        .chars()
+        .filter(|ch| *ch == '\t' || !ch.is_control())
+        .collect();
+    let trimmed = cleaned.trim();
+    if trimmed.is_empty() {
+        "shell".to_string()
+    } else {
+        trimmed.chars().take(MAX_TITLE_CHARS).collect()
+    }
+}
+
+type Listener = Arc<dyn Fn(&Event) + Send + Sync>;
+
+/// Callbacks registered with [`Terminal::subscribe`].
+#[derive(Clone, Default)]
+struct Subscribers {
+    listeners: Arc<Mutex<Vec<(u64, Listener)>>>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Subscribers {
+    fn add(&self, listener: Listener) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.listeners.lock().push((id, listener));
+        id
+    }
+
+    fn remove(&self, id: u64) {
+        self.listeners
+            .lock()
+            .retain(|(listener_id, _)| *listener_id != id);
+    }
+
+    /// Call every listener with `event`. The list is copied first, so a
+    /// listener may subscribe or unsubscribe while it runs.
+    fn notify(&self, event: Event) {
+        let listeners: Vec<Listener> = self
+            .listeners
+            .lock()
+            .iter()
+            .map(|(_, listener)| listener.clone())
+            .collect();
+        for listener in listeners {
+            listener(&event);
+        }
+    }
+}
+
+/// Command typed into the shell once, when its first output arrives.
+#[derive(Clone, Default)]
+struct InitialCommand {
+    command: Arc<Mutex<Option<String>>>,
+    /// Input channel of the event loop, set before the loop starts.
+    pty: Arc<OnceLock<EventLoopSender>>,
+}
+
+impl InitialCommand {
+    fn new(command: Option<String>) -> Self {
+        Self {
+            command: Arc::new(Mutex::new(command)),
+            pty: Arc::default(),
+        }
+    }
+
+    /// The command and a newline on the first wakeup, then nothing.
+    fn take_input(&self, event: &AlacEvent) -> Option<Vec<u8>> {
+        if !matches!(event, AlacEvent::Wakeup) {
+            return None;
+        }
+        let command = self.command.lock().take()?;
+        Some(format!("{command}\n").into_bytes())
+    }
+
+    fn send_on(&self, event: &AlacEvent) {
+        if let (Some(input), Some(pty)) = (self.take_input(event), self.pty.get()) {
+            let _ = pty.send(Msg::Input(input.into()));
+        }
+    }
+}
+
 /// Event proxy that forwards alacritty events to a channel.
 #[derive(Clone)]
 pub struct EventProxy {
     sender: smol::channel::Sender<TerminalEvent>,
+    initial_command: InitialCommand,
+    subscribers: Subscribers,
 }
 
-impl EventListener for EventProxy {
-    fn send_event(&self, event: AlacEvent) {
-        let Some(event) = map_event(event) else {
-            return;
-        };
+impl EventProxy {
+    fn new(sender: smol::channel::Sender<TerminalEvent>) -> Self {
+        Self {
+            sender,
+            initial_command: InitialCommand::default(),
+            subscribers: Subscribers::default(),
+        }
+    }
 
+    fn with_initial_command(mut self, command: Option<String>) -> Self {
+        self.initial_command = InitialCommand::new(command);
+        self
+    }
+
+    fn send_terminal_event(&self, event: TerminalEvent) {
         match self.sender.try_send(event) {
             Ok(()) => {}
             Err(smol::channel::TrySendError::Full(event)) => {
@@ -107,6 +247,325 @@ impl EventListener for EventProxy {
     }
 }
 
+impl EventListener for EventProxy {
+    fn send_event(&self, event: AlacEvent) {
+        self.initial_command.send_on(&event);
+        if let AlacEvent::Title(title) = &event {
+            self.subscribers
+                .notify(Event::TitleChanged(sanitize_title(title)));
+        }
+        if let Some(event) = map_event(event) {
+            self.send_terminal_event(event);
+        }
+    }
+}
+
+/// Cell size assumed for images when the UI has not reported one yet.
+const FALLBACK_CELL_WIDTH_PX: u16 = 8;
+const FALLBACK_CELL_HEIGHT_PX: u16 = 16;
+
+/// PTY wrapper that scans shell output for sequences alacritty ignores.
+///
+/// The event loop reads through this wrapper, so every byte is seen exactly
+/// once before it reaches the emulator. Sixel, Kitty and iTerm2 images are
+/// decoded here and followed by an anchor placeholder (see [`crate::images`]),
+/// and line size changes and shell integration marks are recorded as markers
+/// (see [`crate::line_size`] and [`crate::shell_integration`]).
+struct ScanningPty {
+    pty: tty::Pty,
+    scanner: OscScanner,
+    sixel_scanner: SixelScanner,
+    kitty_graphics: KittyGraphics,
+    line_size_scanner: LineSizeScanner,
+    shell_mark_scanner: ShellMarkScanner,
+    scroll_region_scanner: ScrollRegionScanner,
+    sync_update_scanner: SyncUpdateScanner,
+    /// When the synchronized update in progress began, shared with
+    /// [`Terminal`].
+    sync_update: Arc<Mutex<Option<Instant>>>,
+    /// Last DECSTBM region seen in the output, shared with [`Terminal`].
+    scroll_region: Arc<Mutex<Option<ScrollRegion>>>,
+    images: ImageStore,
+    /// Last reported size, with zero cell dimensions replaced by fallbacks.
+    window_size: WindowSize,
+    /// Scanned output (with placeholders) that did not fit the last read.
+    pending: Vec<u8>,
+    /// Keeps the event loop reading while `pending` is not empty.
+    pending_wake: PendingWake,
+    event_proxy: EventProxy,
+}
+
+/// Socket pair registered next to the PTY that is readable exactly while
+/// [`ScanningPty::pending`] holds output.
+///
+/// alacritty stops reading after a fixed number of bytes per lock and then
+/// waits until the PTY is readable again. Output left in `pending` at that
+/// point would sit there until the shell writes something else, so the
+/// readable wake socket brings the event loop back to drain it.
+struct PendingWake {
+    reader: UnixStream,
+    writer: UnixStream,
+    armed: bool,
+}
+
+impl PendingWake {
+    fn new() -> io::Result<Self> {
+        let (reader, writer) = UnixStream::pair()?;
+        reader.set_nonblocking(true)?;
+        writer.set_nonblocking(true)?;
+        Ok(Self {
+            reader,
+            writer,
+            armed: false,
+        })
+    }
+
+    /// Make the socket readable while `armed` is set, and drain it otherwise.
+    fn set(&mut self, armed: bool) {
+        if armed == self.armed {
+            return;
+        }
+        let result = if armed {
+            (&self.writer).write(&[0])
+        } else {
+            (&self.reader).read(&mut [0])
+        };
+        self.armed = armed && result.is_ok();
+    }
+}
+
+impl ScanningPty {
+    fn set_window_size(&mut self, window_size: WindowSize) {
+        self.window_size = WindowSize {
+            cell_width: match window_size.cell_width {
+                0 => FALLBACK_CELL_WIDTH_PX,
+                width => width,
+            },
+            cell_height: match window_size.cell_height {
+                0 => FALLBACK_CELL_HEIGHT_PX,
+                height => height,
+            },
+            ..window_size
+        };
+    }
+
+    /// Scan one read for side-channel sequences. Returns the bytes with image
+    /// anchors and line size markers inserted, or `None` when nothing was.
+    fn scan_output(&mut self, bytes: &[u8]) -> Option<Vec<u8>> {
+        let mut insertions = Vec::new();
+        let mut shell_marks = Vec::new();
+        let event_proxy = &self.event_proxy;
+        self.scanner.advance(bytes, |offset, payload| {
+            if let Some(cwd) = osc7_working_directory(payload) {
+                event_proxy
+                    .subscribers
+                    .notify(Event::BreadcrumbsChanged(cwd.clone()));
+                event_proxy.send_terminal_event(TerminalEvent::CwdChanged(cwd));
+            } else if let Some(mark) = osc133_mark(payload) {
+                if let ShellMark::CommandFinished(status) = mark {
+                    event_proxy.send_terminal_event(TerminalEvent::CommandFinished(status));
+                }
+                shell_marks.push((offset, mark));
+            } else if let Some((args, image)) = inline_image_from_osc(payload) {
+                let placement = ImagePlacement {
+                    image,
+                    width: args.width,
+                    height: args.height,
+                    preserve_aspect: args.preserve_aspect_ratio,
+                    move_cursor: true,
+                };
+                insertions.push((offset, Insertion::Image(placement)));
+            }
+        });
+        self.sixel_scanner.advance(bytes, |offset, image| {
+            insertions.push((offset, Insertion::Image(ImagePlacement::natural(image))));
+        });
+        let mut replies = Vec::new();
+        self.kitty_graphics.advance(bytes, |output| match output {
+            KittyOutput::Place { offset, placement } => {
+                insertions.push((offset, Insertion::Image(placement)))
+            }
+            KittyOutput::Reply(reply) => replies.extend(reply),
+        });
+        if !replies.is_empty() {
+            let _ = self.pty.writer().write_all(&replies);
+        }
+        self.line_size_scanner.advance(bytes, |offset, size| {
+            insertions.push((offset, Insertion::LineSize(size)));
+        });
+        self.shell_mark_scanner
+            .advance(bytes, &shell_marks, |offset, kind| {
+                insertions.push((offset, Insertion::ShellMark(kind)));
+            });
+        let scroll_region = &self.scroll_region;
+        self.scroll_region_scanner
+            .advance(bytes, |region| *scroll_region.lock() = region);
+        // Every begin restarts the clock, as alacritty's parser restarts its
+        // timeout, so an update that was never ended does not make the next
+        // one time out immediately.
+        let sync_update = &self.sync_update;
+        self.sync_update_scanner.advance(bytes, |active| {
+            *sync_update.lock() = active.then(Instant::now)
+        });
+
+        if insertions.is_empty() {
+            return None;
+        }
+        insertions.sort_by_key(|(offset, _)| *offset);
+        Some(splice_insertions(
+            &self.images,
+            self.window_size,
+            bytes,
+            insertions,
+        ))
+    }
+}
+
+/// Bytes injected into the PTY output for alacritty to carry on the grid.
+enum Insertion {
+    Image(ImagePlacement),
+    LineSize(LineSize),
+    ShellMark(MarkKind),
+}
+
+/// Store every placed image and return `bytes` with an anchor placeholder,
+/// line size marker or shell mark marker inserted at each offset. Offsets must
+/// be sorted.
+fn splice_insertions(
+    images: &ImageStore,
+    window_size: WindowSize,
+    bytes: &[u8],
+    insertions: Vec<(usize, Insertion)>,
+) -> Vec<u8> {
+    let mut output = Vec::with_capacity(bytes.len());
+    let mut copied = 0;
+    for (offset, insertion) in insertions {
+        output.extend_from_slice(&bytes[copied..offset]);
+        copied = offset;
+        let placement = match insertion {
+            Insertion::Image(placement) => placement,
+            Insertion::LineSize(size) => {
+                output.extend(marker_bytes(size));
+                continue;
+            }
+            Insertion::ShellMark(kind) => {
+                output.extend(mark_marker_bytes(kind));
+                continue;
+            }
+        };
+        let display = display_size(
+            (placement.image.width, placement.image.height),
+            (placement.width, placement.height),
+            placement.preserve_aspect,
+            window_size,
+        );
+        let image = TerminalImage::new(placement.image, display, window_size);
+        let lines = if placement.move_cursor {
+            image.lines()
+        } else {
+            1
+        };
+        let id = images.insert(image);
+        output.extend(placeholder_bytes(id, lines));
+    }
+    output.extend_from_slice(&bytes[copied..]);
+    output
+}
+
+fn drain_pending(pending: &mut Vec<u8>, buf: &mut [u8]) -> usize {
+    let len = pending.len().min(buf.len());
+    buf[..len].copy_from_slice(&pending[..len]);
+    pending.drain(..len);
+    len
+}
+
+impl Read for ScanningPty {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pending.is_empty() {
+            let read = self.pty.reader().read(buf)?;
+            match self.scan_output(&buf[..read]) {
+                Some(output) => self.pending = output,
+                None => return Ok(read),
+            }
+        }
+
+        let read = drain_pending(&mut self.pending, buf);
+        self.pending_wake.set(!self.pending.is_empty());
+        Ok(read)
+    }
+}
+
+impl Write for ScanningPty {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pty.writer().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.pty.writer().flush()
+    }
+}
+
+impl EventedReadWrite for ScanningPty {
+    type Reader = Self;
+    type Writer = Self;
+
+    unsafe fn register(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        // SAFETY: the wrapped PTY and the wake socket are owned by `self` and
+        // therefore outlive their registration for as long as the caller
+        // upholds the same contract.
+        unsafe {
+            self.pty.register(poll, interest, mode)?;
+            // The event loop reads the PTY on any readable event with the key
+            // it registered the PTY under.
+            poll.add_with_mode(
+                &self.pending_wake.reader,
+                PollEvent::readable(interest.key),
+                PollMode::Level,
+            )
+        }
+    }
+
+    fn reregister(
+        &mut self,
+        poll: &Arc<Poller>,
+        interest: PollEvent,
+        mode: PollMode,
+    ) -> io::Result<()> {
+        self.pty.reregister(poll, interest, mode)
+    }
+
+    fn deregister(&mut self, poll: &Arc<Poller>) -> io::Result<()> {
+        poll.delete(&self.pending_wake.reader)?;
+        self.pty.deregister(poll)
+    }
+
+    fn reader(&mut self) -> &mut Self {
+        self
+    }
+
+    fn writer(&mut self) -> &mut Self {
+        self
+    }
+}
+
+impl EventedPty for ScanningPty {
+    fn next_child_event(&mut self) -> Option<ChildEvent> {
+        self.pty.next_child_event()
+    }
+}
+
+impl OnResize for ScanningPty {
+    fn on_resize(&mut self, window_size: WindowSize) {
+        self.set_window_size(window_size);
+        self.pty.on_resize(window_size);
+    }
+}
+
 /// The terminal backend managing PTY, event loop, and terminal state.
 pub struct Terminal {
     /// Thread-safe access to the terminal state.
@@ -115,39 +574,267 @@ pub struct Terminal {
     sender: EventLoopSender,
     /// Receiver for terminal events (wakeup, bell, title changes, etc.).
     pub events: smol::channel::Receiver<TerminalEvent>,
+    /// Inline images received from the shell, anchored to grid cells.
+    pub images: ImageStore,
+    /// DECSTBM region last set by the running program.
+    scroll_region: Arc<Mutex<Option<ScrollRegion>>>,
+    /// Start of the synchronized update in progress.
+    sync_update: Arc<Mutex<Option<Instant>>>,
+    /// Emulator options, kept to re-apply them with a new scrollback size.
+    config: Mutex<term::Config>,
+    /// Looks up the PTY's foreground process group.
+    process_id: ProcessIdGetter,
+    /// Name of the last foreground process looked up, by process group.
+    foreground_process: Mutex<Option<(Pid, Option<String>)>>,
+    /// Callbacks registered with [`Terminal::subscribe`].
+    subscribers: Subscribers,
     /// Handle to the event loop thread.
     _event_loop_handle: JoinHandle<(
-        EventLoop<tty::Pty, EventProxy>,
+        EventLoop<ScanningPty, EventProxy>,
         alacritty_terminal::event_loop::State,
     )>,
 }
 
-impl Terminal {
-    /// Spawn a new terminal with the given shell and window size.
-    pub fn new(
-        shell: Shell,
-        working_directory: Option<PathBuf>,
-        window_size: WindowSize,
-        scrollback_lines: usize,
-        environment: HashMap<String, String>,
-        default_cursor_style: AlacCursorStyle,
-    ) -> io::Result<Self> {
+/// A cell of a [`GridSnapshot`] as the program wrote it, before inverse
+/// video or selection colors are applied.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridCell {
+    pub c: char,
+    pub fg: Color,
+    pub bg: Color,
+    pub flags: Flags,
+    /// Combining marks drawn over `c`, without line size or shell
+    /// integration markers.
+    pub zerowidth: Option<Arc<str>>,
+}
+
+impl GridCell {
+    pub fn from_cell(cell: &Cell) -> Self {
+        Self {
+            c: cell.c,
+            fg: cell.fg,
+            bg: cell.bg,
+            flags: cell.flags,
+            zerowidth: cell.zerowidth().and_then(combining_marks),
+        }
+    }
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self {
+            c: ' ',
+            fg: Color::Named(NamedColor::Foreground),
+            bg: Color::Named(NamedColor::Background),
+            flags: Flags::empty(),
+            zerowidth: None,
+        }
+    }
+}
+
+/// Zero-width characters of a cell without the grid markers. Every prompt
+/// row carries shell mark markers, so cells holding nothing else allocate
+/// nothing while the terminal lock is held.
+fn combining_marks(zerowidth: &[char]) -> Option<Arc<str>> {
+    if zerowidth.iter().all(|c| is_grid_marker(*c)) {
+        return None;
+    }
+    let marks: String = zerowidth.iter().filter(|c| !is_grid_marker(**c)).collect();
+    Some(Arc::from(marks))
+}
+
+/// Cursor of a [`GridSnapshot`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GridCursor {
+    /// Position in grid coordinates, like the selection.
+    pub point: Point,
+    /// Viewport row showing the cursor, or `None` while it is scrolled out
+    /// of view.
+    pub viewport_row: Option<usize>,
+    /// Style last set with DECSCUSR or the configured default; `Hidden`
+    /// while the program hides the cursor.
+    pub shape: CursorShape,
+    pub blinking: bool,
+}
+
+impl GridCursor {
+    pub fn from_term<T: EventListener>(term: &Term<T>) -> Self {
+        let cursor = term.renderable_content().cursor;
+        let row = cursor.point.line.0 + term.grid().display_offset() as i32;
+        Self {
+            point: cursor.point,
+            viewport_row: usize::try_from(row)
+                .ok()
+                .filter(|row| *row < term.screen_lines()),
+            shape: cursor.shape,
+            blinking: term.cursor_style().blinking,
+        }
+    }
+
+    /// Whether the cursor is shown and inside the viewport.
+    pub fn is_visible(&self) -> bool {
+        self.shape != CursorShape::Hidden && self.viewport_row.is_some()
+    }
+}
+
+/// Copy of the viewport taken while the terminal lock is held, so renderers
+/// can draw from it without holding the lock.
+#[derive(Clone)]
+pub struct GridSnapshot {
+    pub columns: usize,
+    pub screen_lines: usize,
+    pub history_size: usize,
+    pub display_offset: usize,
+    /// Viewport cells, row by row, `columns` per row.
+    pub cells: Vec<GridCell>,
+    /// DEC line size of each viewport row.
+    pub line_sizes: Vec<LineSize>,
+    pub cursor: GridCursor,
+    pub selection: Option<SelectionRange>,
+    /// Palette entries the program changed with OSC 4, 10 or 11; the others
+    /// are `None` and come from the theme.
+    pub colors: Colors,
+    pub mode: TermMode,
+}
+
+impl GridSnapshot {
+    pub fn from_term<T: EventListener>(term: &Term<T>) -> Self {
+        let columns = term.columns();
+        let screen_lines = term.screen_lines();
+        let display_offset = term.grid().display_offset();
+        let content = term.renderable_content();
+        let grid = term.grid();
+        let mut cells = Vec::with_capacity(columns * screen_lines);
+        let mut line_sizes = Vec::with_capacity(screen_lines);
+        for row in 0..screen_lines {
+            let grid_row = &grid[Line(row as i32 - display_offset as i32)];
+            let mut line_size = LineSize::Single;
+            for column in 0..columns {
+                let cell = &grid_row[Column(column)];
+                if let Some(size) = LineSize::from_cell(cell) {
+                    line_size = size;
+                }
+                cells.push(GridCell::from_cell(cell));
+            }
+            line_sizes.push(line_size);
+        }
+
+        GridSnapshot {
+            columns,
+            screen_lines,
+            history_size: term.history_size(),
+            display_offset,
+            cells,
+            line_sizes,
+            cursor: GridCursor::from_term(term),
+            selection: content.selection,
+            colors: *content.colors,
+            mode: content.mode,
+        }
+    }
+
+    /// Cells of viewport row `row`.
+    pub fn row(&self, row: usize) -> Option<&[GridCell]> {
+        self.cells.get(row * self.columns..(row + 1) * self.columns)
+    }
+
+    pub fn cell(&self, row: usize, column: usize) -> Option<&GridCell> {
+        self.row(row)?.get(column)
+    }
+}
+
+/// Options for spawning a [`Terminal`]. Unset options fall back to the
+/// system shell, the inherited working directory, the default scrollback and
+/// the default word separators.
+#[derive(Clone, Debug)]
+pub struct TerminalBuilder {
+    shell: Shell,
+    working_directory: Option<PathBuf>,
+    window_size: WindowSize,
+    scrollback_lines: usize,
+    environment: HashMap<String, String>,
+    default_cursor_style: AlacCursorStyle,
+    word_separators: String,
+    initial_command: Option<String>,
+}
+
+impl TerminalBuilder {
+    pub fn new(window_size: WindowSize) -> Self {
+        Self {
+            shell: Shell::System,
+            working_directory: None,
+            window_size,
+            scrollback_lines: crate::config::DEFAULT_SCROLL_HISTORY_LINES,
+            environment: HashMap::new(),
+            default_cursor_style: AlacCursorStyle::default(),
+            word_separators: default_word_separators(),
+            initial_command: None,
+        }
+    }
+
+    pub fn shell(mut self, shell: Shell) -> Self {
+        self.shell = shell;
+        self
+    }
+
+    pub fn working_directory(mut self, working_directory: Option<PathBuf>) -> Self {
+        self.working_directory = working_directory;
+        self
+    }
+
+    pub fn scrollback_lines(mut self, scrollback_lines: usize) -> Self {
+        self.scrollback_lines = scrollback_lines;
+        self
+    }
+
+    /// Variables added to the shell's environment, over `TERM` and
+    /// `COLORTERM`.
+    pub fn environment(mut self, environment: HashMap<String, String>) -> Self {
+        self.environment = environment;
+        self
+    }
+
+    /// Cursor style used until the program sets one with DECSCUSR.
+    pub fn default_cursor_style(mut self, default_cursor_style: AlacCursorStyle) -> Self {
+        self.default_cursor_style = default_cursor_style;
+        self
+    }
+
+    /// Characters that end a word for double-click selection.
+    pub fn word_separators(mut self, word_separators: &str) -> Self {
+        self.word_separators = word_separators.to_string();
+        self
+    }
+
+    /// Command typed into the shell, followed by a newline, once the shell
+    /// first prints output, such as `ssh server`.
+    pub fn initial_command(mut self, initial_command: Option<String>) -> Self {
+        self.initial_command = initial_command;
+        self
+    }
+
+    /// Spawn the shell and start reading its output.
+    pub fn build(self) -> Result<Terminal, TerminalError> {
+        let TerminalBuilder {
+            shell,
+            working_directory,
+            window_size,
+            scrollback_lines,
+            environment,
+            default_cursor_style,
+            word_separators,
+            initial_command,
+        } = self;
         let (event_sender, event_receiver) = smol::channel::bounded(256);
-        let event_proxy = EventProxy {
-            sender: event_sender,
-        };
+        let event_proxy = EventProxy::new(event_sender).with_initial_command(initial_command);
 
         // Configure the terminal emulator
-        let config = term::Config {
-            scrolling_history: scrollback_lines.min(crate::config::MAX_SCROLL_HISTORY_LINES),
-            default_cursor_style,
-            ..Default::default()
-        };
+        let config = term_config(scrollback_lines, default_cursor_style, &word_separators);
 
         let term_size = terminal_dimensions(window_size);
 
         // Create the terminal state
-        let term = Term::new(config, &term_size, event_proxy.clone());
+        let term = Term::new(config.clone(), &term_size, event_proxy.clone());
         let term = Arc::new(FairMutex::new(term));
 
         // Configure PTY options
@@ -169,21 +856,110 @@ impl Terminal {
         };
 
         // Spawn the PTY
-        let pty = tty::new(&pty_options, window_size, 0)?;
+        let images = ImageStore::default();
+        let scroll_region = Arc::new(Mutex::new(None));
+        let sync_update = Arc::new(Mutex::new(None));
+        let spawn_error = |kind, source| {
+            TerminalError::spawning(kind, &shell, pty_options.working_directory.clone(), source)
+        };
+        // alacritty ignores a missing working directory and starts the shell
+        // wherever this process runs.
+        if let Some(directory) = &pty_options.working_directory {
+            if !directory.is_dir() {
+                return Err(spawn_error(
+                    ErrorKind::WorkingDirMissing,
+                    io::Error::new(io::ErrorKind::NotFound, "working directory does not exist"),
+                ));
+            }
+        }
+        let child_pty = tty::new(&pty_options, window_size, 0).map_err(|error| {
+            let kind = spawn_error_kind(&error);
+            spawn_error(kind, error)
+        })?;
+        let process_id = ProcessIdGetter::new(&child_pty);
+        let mut pty = ScanningPty {
+            pty: child_pty,
+            scanner: OscScanner::default(),
+            sixel_scanner: SixelScanner::default(),
+            kitty_graphics: KittyGraphics::default(),
+            line_size_scanner: LineSizeScanner::default(),
+            shell_mark_scanner: ShellMarkScanner::default(),
+            scroll_region_scanner: ScrollRegionScanner::default(),
+            scroll_region: scroll_region.clone(),
+            sync_update_scanner: SyncUpdateScanner::default(),
+            sync_update: sync_update.clone(),
+            images: images.clone(),
+            window_size,
+            pending: Vec::new(),
+            pending_wake: PendingWake::new().map_err(|error| spawn_error(ErrorKind::Pty, error))?,
+            event_proxy: event_proxy.clone(),
+        };
+        pty.set_window_size(window_size);
 
         // Create and spawn the event loop
-        let event_loop = EventLoop::new(term.clone(), event_proxy, pty, false, false)?;
+        let initial_command = event_proxy.initial_command.clone();
+        let subscribers = event_proxy.subscribers.clone();
+        let event_loop = EventLoop::new(term.clone(), event_proxy, pty, false, false)
+            .map_err(|error| spawn_error(ErrorKind::Pty, error))?;
 
         let sender = event_loop.channel();
+        let _ = initial_command.pty.set(sender.clone());
         let handle = event_loop.spawn();
 
         Ok(Terminal {
             term,
             sender,
             events: event_receiver,
+            images,
+            scroll_region,
+            sync_update,
+            config: Mutex::new(config),
+            process_id,
+            foreground_process: Mutex::new(None),
+            subscribers,
             _event_loop_handle: handle,
         })
     }
+}
+
+impl Terminal {
+    /// Spawn a new terminal with the given shell and window size.
+    pub fn new(
+        shell: Shell,
+        working_directory: Option<PathBuf>,
+        window_size: WindowSize,
+        scrollback_lines: usize,
+        environment: HashMap<String, String>,
+        default_cursor_style: AlacCursorStyle,
+        word_separators: &str,
+    ) -> Result<Self, TerminalError> {
+        Self::builder(window_size)
+            .shell(shell)
+            .working_directory(working_directory)
+            .scrollback_lines(scrollback_lines)
+            .environment(environment)
+            .default_cursor_style(default_cursor_style)
+            .word_separators(word_separators)
+            .build()
+    }
+
+    /// Start configuring a terminal of `window_size`.
+    pub fn builder(window_size: WindowSize) -> TerminalBuilder {
+        TerminalBuilder::new(window_size)
+    }
+
+    /// Call `listener` with every [`Event::TitleChanged`] and
+    /// [`Event::BreadcrumbsChanged`] from now on. Listeners run on the PTY
+    /// reader thread, so they should hand work off rather than block.
+    /// Returns an id for [`Terminal::unsubscribe`].
+    pub fn subscribe(&self, listener: impl Fn(&Event) + Send + Sync + 'static) -> u64 {
+        self.subscribers.add(Arc::new(listener))
+    }
+
+    /// Stop calling the listener registered under `id`.
+    pub fn unsubscribe(&self, id: u64) {
+        self.subscribers.remove(id);
+    }
 
     /// Write bytes to the PTY.
     pub fn write(&self, data: impl Into<Cow<'static, [u8]>>) {
@@ -195,13 +971,142 @@ impl Terminal {
         self.write(s.as_bytes().to_vec());
     }
 
+    /// Type `text` as if it came from the keyboard.
+    pub fn send_text(&self, text: &str) {
+        self.write_str(text);
+    }
+
+    /// Send `keystroke` encoded for the current terminal mode. Returns `false`
+    /// when the keystroke produces no input, such as a bare modifier.
+    pub fn send_key(&self, keystroke: &Keystroke, option_as_meta: bool) -> bool {
+        let mode = *self.term.lock().mode();
+        match key_input(keystroke, &mode, option_as_meta) {
+            Some(input) => {
+                self.write_str(&input);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Paste `text`, bracketed when the running program enabled bracketed
+    /// paste mode.
+    pub fn paste(&self, text: &str) {
+        let mode = *self.term.lock().mode();
+        self.write(paste_input(text, &mode).into_bytes());
+    }
+
+    /// The writes [`Terminal::paste`] would make, split so each carries at
+    /// most `chunk_size` bytes of pasted text. Callers can spread them out to
+    /// keep a large paste from blocking the UI.
+    pub fn paste_chunks(&self, text: &str, chunk_size: usize) -> Vec<Vec<u8>> {
+        let mode = *self.term.lock().mode();
+        paste_chunks(text, &mode, chunk_size)
+    }
+
     /// Resize the terminal.
     pub fn resize(&self, window_size: WindowSize) {
         let term_size = terminal_dimensions(window_size);
         // Resize the PTY first via event loop
         let _ = self.sender.send(Msg::Resize(window_size));
-        // Then resize the terminal grid
+        // Then resize the terminal grid. alacritty rewraps soft-wrapped lines
+        // on the primary screen and in the scrollback; the alternate screen is
+        // left to the program to redraw.
         self.term.lock().resize(term_size);
+        // alacritty resets the scroll region on resize
+        *self.scroll_region.lock() = None;
+    }
+
+    /// Change how many lines of scrollback are kept. Shrinking drops the
+    /// oldest lines.
+    pub fn set_scrollback_lines(&self, lines: usize) {
+        apply_scrollback_lines(&mut self.term.lock(), &mut self.config.lock(), lines);
+    }
+
+    /// Scroll the viewport as little as possible to show grid `line`, such as
+    /// a search match or a prompt mark. Returns whether the viewport moved.
+    pub fn scroll_line_into_view(&self, line: Line) -> bool {
+        let mut term = self.term.lock();
+        let display_offset = term.grid().display_offset();
+        let target = display_offset_showing_line(
+            line,
+            display_offset,
+            term.screen_lines(),
+            term.history_size(),
+        );
+        if target == display_offset {
+            return false;
+        }
+        term.scroll_display(Scroll::Delta(target as i32 - display_offset as i32));
+        true
+    }
+
+    /// Text of the current selection, without the markers the PTY reader
+    /// leaves in grid cells.
+    pub fn current_selection_text(&self) -> Option<String> {
+        self.term
+            .lock()
+            .selection_to_string()
+            .map(|text| strip_grid_markers(&text).into_owned())
+    }
+
+    /// Write the whole scrollback and screen to `writer` as plain text,
+    /// without trailing blank lines. The terminal keeps running while a long
+    /// history is written.
+    pub fn export_scrollback(&self, writer: impl Write) -> io::Result<()> {
+        export_grid_text(&self.term, writer)
+    }
+
+    /// Print `text` on a new line as if the program had written it. Meant for
+    /// notices after the child has exited and nothing else writes to the grid.
+    pub fn show_notice(&self, text: &str) {
+        let mut term = self.term.lock();
+        Processor::<StdSyncHandler>::new().advance(&mut *term, format!("\r\n{text}").as_bytes());
+    }
+
+    /// Whether the running program switched to the alternate screen, which
+    /// has no scrollback.
+    pub fn is_alternate_screen(&self) -> bool {
+        self.term.lock().mode().contains(TermMode::ALT_SCREEN)
+    }
+
+    /// Copy the viewport, cursor and colors for drawing.
+    pub fn snapshot(&self) -> GridSnapshot {
+        GridSnapshot::from_term(&self.term.lock())
+    }
+
+    /// Screen lines the running program's scroll region covers, or `None`
+    /// when scrolling moves the whole screen.
+    pub fn scroll_region(&self, screen_lines: usize) -> Option<Range<usize>> {
+        let region = (*self.scroll_region.lock())?.lines(screen_lines);
+        (region != (0..screen_lines)).then_some(region)
+    }
+
+    /// When the running program began the synchronized update (DEC mode
+    /// 2026) it has not ended yet, or `None` outside of one.
+    pub fn synchronized_update_started(&self) -> Option<Instant> {
+        *self.sync_update.lock()
+    }
+
+    /// Name of the program in the foreground, such as the shell or `vim`.
+    /// The name is only looked up again when the foreground process group
+    /// changes, so this is cheap to poll.
+    pub fn foreground_process_name(&self) -> Option<String> {
+        let pid = self.process_id.pid()?;
+        let mut cached = self.foreground_process.lock();
+        match cached.as_ref() {
+            Some((cached_pid, name)) if *cached_pid == pid => name.clone(),
+            _ => {
+                let name = foreground_process(pid);
+                *cached = Some((pid, name.clone()));
+                name
+            }
+        }
+    }
+
+    /// Name of the shell the terminal was spawned with.
+    pub fn shell_process_name(&self) -> Option<String> {
+        foreground_process(self.process_id.fallback_pid())
     }
 
     /// Shutdown the terminal.
@@ -216,24 +1121,655 @@ impl Drop for Terminal {
     }
 }
 
+/// Emulator configuration for a new terminal. `word_separators` bound the
+/// words selected by a double click.
+fn term_config(
+    scrollback_lines: usize,
+    default_cursor_style: AlacCursorStyle,
+    word_separators: &str,
+) -> term::Config {
+    let config = term::Config {
+        default_cursor_style,
+        semantic_escape_chars: word_separators.to_string(),
+        ..Default::default()
+    };
+    scrollback_config(&config, scrollback_lines)
+}
+
+/// `config` keeping `lines` of scrollback, capped at
+/// [`crate::config::MAX_SCROLL_HISTORY_LINES`].
+fn scrollback_config(config: &term::Config, lines: usize) -> term::Config {
+    term::Config {
+        scrolling_history: lines.min(crate::config::MAX_SCROLL_HISTORY_LINES),
+        ..config.clone()
+    }
+}
+
+/// Resize the history of a running `term`, keeping `config` in sync.
+fn apply_scrollback_lines<T: EventListener>(
+    term: &mut Term<T>,
+    config: &mut term::Config,
+    lines: usize,
+) {
+    let updated = scrollback_config(config, lines);
+    if updated.scrolling_history == config.scrolling_history {
+        return;
+    }
+    *config = updated;
+    term.set_options(config.clone());
+}
+
+/// Grid lines copied per lock while exporting the scrollback.
+const EXPORT_CHUNK_LINES: i32 = 1000;
+
+const BRACKETED_PASTE_START: &str = "\x1b[200~";
+const BRACKETED_PASTE_END: &str = "\x1b[201~";
+
+/// Display offset closest to `display_offset` at which grid `line` is on
+/// screen. Lines above the viewport land on its top row and lines below on
+/// its bottom row.
+fn display_offset_showing_line(
+    line: Line,
+    display_offset: usize,
+    screen_lines: usize,
+    history_size: usize,
+) -> usize {
+    let line = line.0 as isize;
+    let current = display_offset as isize;
+    let target = if line + current < 0 {
+        -line
+    } else if line + current >= screen_lines as isize {
+        (screen_lines as isize - 1 - line).max(0)
+    } else {
+        current
+    };
+    (target.max(0) as usize).min(history_size)
+}
+
+/// Write the scrollback and screen as text, one grid line per output line
+/// with soft-wrapped lines joined. The lock is taken once per chunk of lines,
+/// and trailing blank lines are left out.
+fn export_grid_text<T: EventListener>(
+    term: &FairMutex<Term<T>>,
+    mut writer: impl Write,
+) -> io::Result<()> {
+    let (topmost, bottommost) = {
+        let term = term.lock();
+        (term.topmost_line().0, term.bottommost_line().0)
+    };
+    let mut blank_lines = 0;
+    let mut chunk_start = topmost;
+    while chunk_start <= bottommost {
+        let chunk_end = (chunk_start + EXPORT_CHUNK_LINES).min(bottommost + 1);
+        let lines: Vec<(String, bool)> = {
+            let term = term.lock();
+            // Output arriving between chunks can shrink the history.
+            let chunk_start = chunk_start.max(term.topmost_line().0);
+            (chunk_start..chunk_end)
+                .map(|line| grid_line_text(&term, Line(line)))
+                .collect()
+        };
+        for (text, wraps) in lines {
+            if !wraps && text.is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            for _ in 0..blank_lines {
+                writer.write_all(b"\n")?;
+            }
+            blank_lines = 0;
+            writer.write_all(text.as_bytes())?;
+            if !wraps {
+                writer.write_all(b"\n")?;
+            }
+        }
+        chunk_start = chunk_end;
+    }
+    writer.flush()
+}
+
+/// Text of grid `line` and whether it soft-wraps into the next line. Lines
+/// that end at a hard break lose their trailing spaces.
+fn grid_line_text<T>(term: &Term<T>, line: Line) -> (String, bool) {
+    let row = &term.grid()[line];
+    let wraps = row[term.last_column()].flags.contains(Flags::WRAPLINE);
+    let mut text = String::new();
+    for column in 0..term.columns() {
+        let cell = &row[Column(column)];
+        if cell
+            .flags
+            .intersects(Flags::WIDE_CHAR_SPACER | Flags::LEADING_WIDE_CHAR_SPACER)
+        {
+            continue;
+        }
+        text.push(cell.c);
+        if let Some(zerowidth) = cell.zerowidth() {
+            text.extend(zerowidth);
+        }
+    }
+    let mut text = strip_grid_markers(&text).into_owned();
+    if !wraps {
+        text.truncate(text.trim_end_matches(' ').len());
+    }
+    (text, wraps)
+}
+
+/// Bytes typed by `keystroke`: its escape sequence, or else its text.
+fn key_input(
+    keystroke: &Keystroke,
+    mode: &TermMode,
+    option_as_meta: bool,
+) -> Option<Cow<'static, str>> {
+    if let Some(esc) = to_esc_str(keystroke, mode, option_as_meta) {
+        return Some(esc);
+    }
+    if keystroke.modifiers.control || keystroke.modifiers.platform {
+        return None;
+    }
+    keystroke
+        .key_char
+        .clone()
+        .filter(|text| !text.is_empty())
+        .map(Cow::Owned)
+}
+
+/// Paste payload for the current mode. Without bracketed paste, newlines are
+/// sent as carriage returns like typed Enter keys.
+fn paste_input(text: &str, mode: &TermMode) -> String {
+    let body = paste_body(text, mode);
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        format!("{BRACKETED_PASTE_START}{body}{BRACKETED_PASTE_END}")
+    } else {
+        body
+    }
+}
+
+/// Pasted text as sent between the bracketed paste markers, if any.
+fn paste_body(text: &str, mode: &TermMode) -> String {
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        // An embedded end marker would let the rest of the payload run as
+        // typed commands. Removing one can join its neighbours into another,
+        // so repeat until none is left.
+        let mut text = text.to_string();
+        while text.contains(BRACKETED_PASTE_END) {
+            text = text.replace(BRACKETED_PASTE_END, "");
+        }
+        text
+    } else {
+        text.replace("\r\n", "\r").replace('\n', "\r")
+    }
+}
+
+/// `paste_input` split into writes of at most `chunk_size` bytes of text, cut
+/// between characters. The bracketed paste markers stay whole, at the start of
+/// the first write and the end of the last.
+fn paste_chunks(text: &str, mode: &TermMode, chunk_size: usize) -> Vec<Vec<u8>> {
+    let body = paste_body(text, mode);
+    let mut chunks = Vec::new();
+    let mut rest = body.as_str();
+    while !rest.is_empty() {
+        let mut end = chunk_size.clamp(1, rest.len());
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push(chunk.as_bytes().to_vec());
+        rest = tail;
+    }
+
+    if mode.contains(TermMode::BRACKETED_PASTE) {
+        if chunks.is_empty() {
+            chunks.push(Vec::new());
+        }
+        if let Some(first) = chunks.first_mut() {
+            first.splice(0..0, BRACKETED_PASTE_START.bytes());
+        }
+        if let Some(last) = chunks.last_mut() {
+            last.extend_from_slice(BRACKETED_PASTE_END.as_bytes());
+        }
+    }
+    chunks
+}
+
+/// Kind of a failure from opening the PTY and starting the shell. alacritty
+/// reports a missing program as `NotFound` and a program that cannot run as
+/// `PermissionDenied`; anything else comes from the PTY itself.
+fn spawn_error_kind(error: &io::Error) -> ErrorKind {
+    match error.kind() {
+        io::ErrorKind::NotFound => ErrorKind::ShellNotFound,
+        io::ErrorKind::PermissionDenied => ErrorKind::Io,
+        _ => ErrorKind::Pty,
+    }
+}
+
+/// Child environment: the terminal type and truecolor support advertised by
+/// default, with the user's `env` taking precedence.
 fn build_pty_env(extra_env: &HashMap<String, String>) -> HashMap<String, String> {
-    let mut env = extra_env.clone();
-    env.insert("TERM".to_string(), "xterm-256color".to_string());
+    let mut env = HashMap::from([
+        ("TERM".to_string(), "xterm-256color".to_string()),
+        ("COLORTERM".to_string(), "truecolor".to_string()),
+    ]);
+    env.extend(extra_env.clone());
     env
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        build_pty_env, terminal_dimensions, EventProxy, TerminalDimensions, TerminalEvent,
+        apply_scrollback_lines, build_pty_env, combining_marks, display_offset_showing_line,
+        drain_pending, export_grid_text, grid_line_text, key_input, paste_chunks, paste_input,
+        sanitize_title, scrollback_config, splice_insertions, term_config, terminal_dimensions,
+        EventProxy, GridSnapshot, InitialCommand, Insertion, PendingWake, Terminal,
+        TerminalDimensions, TerminalEvent,
     };
-    use alacritty_terminal::event::{Event as AlacEvent, EventListener};
+    use crate::error::ErrorKind;
+    use crate::images::{image_id_from_uri, DecodedImage, ImageExtent, ImagePlacement, ImageStore};
+    use crate::line_size::{marker_bytes, LineSize};
+    use crate::sixel::SixelScanner;
+    use crate::{Event, TerminalSettings};
+    use alacritty_terminal::event::{Event as AlacEvent, EventListener, VoidListener, WindowSize};
+    use alacritty_terminal::grid::{Dimensions, Scroll};
+    use alacritty_terminal::index::{Column, Line, Point, Side};
+    use alacritty_terminal::selection::{Selection, SelectionType};
+    use alacritty_terminal::sync::FairMutex;
+    use alacritty_terminal::term;
+    use alacritty_terminal::term::{Term, TermMode};
+    use alacritty_terminal::vte::ansi::{
+        CursorStyle as AlacCursorStyle, Processor, StdSyncHandler,
+    };
+    use gpui::Keystroke;
+    use parking_lot::Mutex;
+    use polling::{Event as PollEvent, Events, PollMode, Poller};
     use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Text a double click on column 5 of `text` selects.
+    fn double_click_word(text: &[u8], word_separators: &str) -> Option<String> {
+        let config = term_config(0, AlacCursorStyle::default(), word_separators);
+        let size = TerminalDimensions { cols: 20, lines: 2 };
+        let mut term = Term::new(config, &size, VoidListener);
+        Processor::<StdSyncHandler>::new().advance(&mut term, text);
+        let point = Point::new(Line(0), Column(5));
+        term.selection = Some(Selection::new(SelectionType::Semantic, point, Side::Left));
+        term.selection_to_string()
+    }
+
+    #[test]
+    fn scrollback_config_is_capped_and_keeps_other_options() {
+        let config = term_config(500, AlacCursorStyle::default(), "/");
+
+        assert_eq!(config.scrolling_history, 500);
+        let capped = scrollback_config(&config, usize::MAX);
+        assert_eq!(
+            capped.scrolling_history,
+            crate::config::MAX_SCROLL_HISTORY_LINES
+        );
+        assert_eq!(capped.semantic_escape_chars, "/");
+    }
+
+    #[test]
+    fn builder_spawns_the_same_terminal_as_the_positional_constructor() {
+        let shell = || crate::Shell::Program("/bin/sh".to_string());
+        let cursor_style = AlacCursorStyle {
+            shape: alacritty_terminal::vte::ansi::CursorShape::Beam,
+            blinking: true,
+        };
+        let environment = HashMap::from([("SIMPLE_TERM_TEST".to_string(), "1".to_string())]);
+        let positional = Terminal::new(
+            shell(),
+            None,
+            WINDOW,
+            500,
+            environment.clone(),
+            cursor_style,
+            "/",
+        )
+        .expect("spawn positional terminal");
+        let built = Terminal::builder(WINDOW)
+            .shell(shell())
+            .scrollback_lines(500)
+            .environment(environment)
+            .default_cursor_style(cursor_style)
+            .word_separators("/")
+            .build()
+            .expect("spawn built terminal");
+
+        assert_eq!(*built.config.lock(), *positional.config.lock());
+        let (built_term, positional_term) = (built.term.lock(), positional.term.lock());
+        assert_eq!(built_term.columns(), positional_term.columns());
+        assert_eq!(built_term.screen_lines(), positional_term.screen_lines());
+        assert_eq!(built_term.cursor_style(), positional_term.cursor_style());
+    }
+
+    #[test]
+    fn spawning_a_missing_shell_reports_shell_not_found() {
+        let error = Terminal::builder(WINDOW)
+            .shell(crate::Shell::Program(
+                "/nonexistent/simple-term-shell".to_string(),
+            ))
+            .build()
+            .err()
+            .expect("missing shell should not spawn");
+
+        assert_eq!(error.kind, ErrorKind::ShellNotFound);
+        assert_eq!(
+            error.program.as_deref(),
+            Some("/nonexistent/simple-term-shell")
+        );
+        assert!(error.to_string().contains("Shell command"));
+    }
+
+    #[test]
+    fn spawning_in_a_missing_directory_reports_working_dir_missing() {
+        let error = Terminal::builder(WINDOW)
+            .shell(crate::Shell::Program("/bin/sh".to_string()))
+            .working_directory(Some(PathBuf::from("/nonexistent/simple-term-dir")))
+            .build()
+            .err()
+            .expect("missing directory should not spawn");
+
+        assert_eq!(error.kind, ErrorKind::WorkingDirMissing);
+        assert_eq!(
+            error.directory,
+            Some(PathBuf::from("/nonexistent/simple-term-dir"))
+        );
+    }
+
+    #[test]
+    fn initial_command_is_queued_once_on_the_first_wakeup() {
+        let initial_command = InitialCommand::new(Some("ssh server".to_string()));
+
+        assert_eq!(initial_command.take_input(&AlacEvent::Bell), None);
+        assert_eq!(
+            initial_command.take_input(&AlacEvent::Wakeup),
+            Some(b"ssh server\n".to_vec())
+        );
+        assert_eq!(initial_command.take_input(&AlacEvent::Wakeup), None);
+        assert_eq!(
+            InitialCommand::default().take_input(&AlacEvent::Wakeup),
+            None
+        );
+    }
 
     #[test]
-    fn pty_env_includes_default_term() {
+    fn sanitize_title_removes_control_characters_and_limits_length() {
+        let sanitized = sanitize_title("  \u{0007}hello\tworld\u{001b}[31m  ");
+        assert_eq!(sanitized, "hello\tworld[31m");
+
+        let long = "x".repeat(120);
+        assert_eq!(sanitize_title(&long).len(), 60);
+        assert_eq!(sanitize_title("\u{0000}\u{0001}  "), "shell");
+    }
+
+    #[test]
+    fn setting_the_title_notifies_subscribers_with_the_sanitized_title() {
+        let (sender, _receiver) = smol::channel::unbounded();
+        let proxy = EventProxy::new(sender);
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let id = proxy.subscribers.add(Arc::new(move |event: &Event| {
+            sink.lock().push(event.clone())
+        }));
+        let size = TerminalDimensions { cols: 20, lines: 2 };
+        let mut term = Term::new(term::Config::default(), &size, proxy.clone());
+        let mut processor = Processor::<StdSyncHandler>::new();
+
+        processor.advance(&mut term, "\x1b]2;  vim \u{0085}notes.md  \x07".as_bytes());
+        proxy.subscribers.remove(id);
+        processor.advance(&mut term, b"\x1b]2;ignored\x07");
+
+        assert_eq!(
+            *received.lock(),
+            vec![Event::TitleChanged("vim notes.md".to_string())]
+        );
+    }
+
+    #[test]
+    fn osc7_output_notifies_subscribers_with_the_new_directory() {
+        let terminal = Terminal::builder(WINDOW)
+            .shell(crate::Shell::Program("/bin/sh".to_string()))
+            .build()
+            .expect("spawn terminal");
+        let (sender, receiver) = std::sync::mpsc::channel();
+        terminal.subscribe(move |event| {
+            if let Event::BreadcrumbsChanged(path) = event {
+                let _ = sender.send(path.clone());
+            }
+        });
+
+        terminal.write_str("printf '\\033]7;file://host/tmp/a%%20b\\007'\n");
+
+        assert_eq!(
+            receiver.recv_timeout(std::time::Duration::from_secs(10)),
+            Ok(PathBuf::from("/tmp/a b"))
+        );
+    }
+
+    #[test]
+    fn grid_snapshot_reports_dimensions_cells_and_cursor() {
+        let size = TerminalDimensions { cols: 8, lines: 3 };
+        let mut term = Term::new(
+            term_config(100, AlacCursorStyle::default(), " "),
+            &size,
+            VoidListener,
+        );
+        Processor::<StdSyncHandler>::new().advance(&mut term, b"one\r\ntwo\r\nthree\r\nfo");
+
+        let snapshot = GridSnapshot::from_term(&term);
+        assert_eq!((snapshot.columns, snapshot.screen_lines), (8, 3));
+        assert_eq!(snapshot.history_size, 1);
+        assert_eq!(snapshot.cells.len(), 24);
+        let first_row: String = snapshot
+            .row(0)
+            .expect("first row")
+            .iter()
+            .map(|cell| cell.c)
+            .collect();
+        assert_eq!(first_row.trim_end(), "two");
+        assert_eq!(snapshot.cell(2, 1).map(|cell| cell.c), Some('o'));
+        assert_eq!(snapshot.cursor.point, Point::new(Line(2), Column(2)));
+        assert_eq!(snapshot.cursor.viewport_row, Some(2));
+        assert!(snapshot.cursor.is_visible());
+        assert!(snapshot.row(3).is_none());
+
+        term.scroll_display(Scroll::Delta(1));
+        let scrolled = GridSnapshot::from_term(&term);
+        assert_eq!(scrolled.display_offset, 1);
+        assert_eq!(scrolled.cell(0, 0).map(|cell| cell.c), Some('o'));
+        assert_eq!(scrolled.cursor.viewport_row, None);
+        assert!(!scrolled.cursor.is_visible());
+    }
+
+    #[test]
+    fn terminal_snapshot_matches_the_spawned_window_size() {
+        let terminal = Terminal::builder(WINDOW)
+            .shell(crate::Shell::Program("/bin/sh".to_string()))
+            .build()
+            .expect("spawn terminal");
+
+        let snapshot = terminal.snapshot();
+        assert_eq!(snapshot.columns, WINDOW.num_cols as usize);
+        assert_eq!(snapshot.screen_lines, WINDOW.num_lines as usize);
+        assert_eq!(snapshot.line_sizes.len(), snapshot.screen_lines);
+    }
+
+    #[test]
+    fn combining_marks_leave_out_grid_markers() {
+        assert_eq!(combining_marks(&['\u{E0036}']), None);
+        assert_eq!(combining_marks(&['\u{E0041}', '\u{E0043}']), None);
+        assert_eq!(
+            combining_marks(&['\u{301}', '\u{E0036}']).as_deref(),
+            Some("\u{301}")
+        );
+    }
+
+    #[test]
+    fn builder_defaults_match_a_default_settings_file() {
+        let terminal = Terminal::builder(WINDOW)
+            .shell(crate::Shell::Program("/bin/sh".to_string()))
+            .build()
+            .expect("spawn terminal");
+        let config = terminal.config.lock();
+
+        let settings = TerminalSettings::default();
+        assert_eq!(
+            config.scrolling_history,
+            crate::config::DEFAULT_SCROLL_HISTORY_LINES
+        );
+        assert_eq!(config.semantic_escape_chars, settings.word_separators);
+    }
+
+    #[test]
+    fn selection_keeps_its_text_when_output_arrives() {
+        let config = term_config(100, AlacCursorStyle::default(), " ");
+        let size = TerminalDimensions { cols: 10, lines: 4 };
+        let mut term = Term::new(config, &size, VoidListener);
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let output: String = (0..20).map(|line| format!("line{line}\r\n")).collect();
+        processor.advance(&mut term, output.as_bytes());
+
+        for display_offset in [0, 5] {
+            // Selected while following output, and while scrolled back.
+            term.scroll_display(Scroll::Bottom);
+            term.scroll_display(Scroll::Delta(display_offset));
+            let top = Line(-display_offset);
+            let mut selection = Selection::new(
+                SelectionType::Simple,
+                Point::new(top, Column(0)),
+                Side::Left,
+            );
+            selection.update(Point::new(top + 1, Column(9)), Side::Right);
+            term.selection = Some(selection);
+            let before = term.selection_to_string();
+
+            processor.advance(&mut term, b"more\r\nand more\r\n");
+
+            assert!(before.is_some());
+            assert_eq!(term.selection_to_string(), before);
+        }
+    }
+
+    #[test]
+    fn shrinking_scrollback_drops_the_oldest_lines() {
+        let mut config = term_config(100, AlacCursorStyle::default(), " ");
+        let size = TerminalDimensions { cols: 10, lines: 2 };
+        let mut term = Term::new(config.clone(), &size, VoidListener);
+        let output: String = (0..50).map(|line| format!("line{line}\r\n")).collect();
+        Processor::<StdSyncHandler>::new().advance(&mut term, output.as_bytes());
+        assert_eq!(term.history_size(), 49);
+
+        apply_scrollback_lines(&mut term, &mut config, 10);
+
+        assert_eq!(config.scrolling_history, 10);
+        assert_eq!(term.history_size(), 10);
+        let oldest: String = (0..6)
+            .map(|column| term.grid()[Line(-10)][Column(column)].c)
+            .collect();
+        assert_eq!(oldest, "line39");
+    }
+
+    #[test]
+    fn line_above_the_viewport_is_scrolled_to_the_top_row() {
+        assert_eq!(display_offset_showing_line(Line(-30), 0, 24, 100), 30);
+        assert_eq!(display_offset_showing_line(Line(-30), 10, 24, 100), 30);
+        assert_eq!(display_offset_showing_line(Line(-500), 0, 24, 100), 100);
+    }
+
+    #[test]
+    fn line_below_the_viewport_is_scrolled_to_the_bottom_row() {
+        assert_eq!(display_offset_showing_line(Line(-5), 40, 24, 100), 28);
+        assert_eq!(display_offset_showing_line(Line(20), 40, 24, 100), 3);
+        assert_eq!(display_offset_showing_line(Line(23), 40, 24, 100), 0);
+    }
+
+    #[test]
+    fn visible_line_keeps_the_viewport_still() {
+        assert_eq!(display_offset_showing_line(Line(-10), 10, 24, 100), 10);
+        assert_eq!(display_offset_showing_line(Line(3), 10, 24, 100), 10);
+        assert_eq!(display_offset_showing_line(Line(23), 0, 24, 100), 0);
+    }
+
+    #[test]
+    fn export_joins_wrapped_lines_and_drops_trailing_blank_lines() {
+        let config = term_config(100, AlacCursorStyle::default(), "");
+        let size = TerminalDimensions { cols: 10, lines: 3 };
+        let mut term = Term::new(config, &size, VoidListener);
+        Processor::<StdSyncHandler>::new().advance(
+            &mut term,
+            b"one\r\n\r\ntwo   \r\n0123456789abc\r\n\xe4\xb8\xad\xe6\x96\x87\r\n\r\n",
+        );
+        assert!(term.history_size() > 0);
+
+        let mut exported = Vec::new();
+        export_grid_text(&FairMutex::new(term), &mut exported).unwrap();
+
+        assert_eq!(
+            String::from_utf8(exported).unwrap(),
+            "one\n\ntwo\n0123456789abc\n中文\n"
+        );
+    }
+
+    #[test]
+    fn narrowing_rewraps_long_lines_and_keeps_the_cursor_on_its_line() {
+        let config = term_config(100, AlacCursorStyle::default(), "");
+        let mut term = Term::new(
+            config,
+            &TerminalDimensions { cols: 20, lines: 4 },
+            VoidListener,
+        );
+        Processor::<StdSyncHandler>::new().advance(&mut term, b"0123456789abcdef\r\n$ ls");
+
+        term.resize(TerminalDimensions { cols: 10, lines: 4 });
+
+        // The extra row pushes the top of the screen into the scrollback.
+        assert_eq!(term.history_size(), 1);
+        let rows: Vec<_> = (-1..2)
+            .map(|line| grid_line_text(&term, Line(line)))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("0123456789".to_string(), true),
+                ("abcdef".to_string(), false),
+                ("$ ls".to_string(), false),
+            ]
+        );
+        assert_eq!(term.grid().cursor.point, Point::new(Line(1), Column(4)));
+
+        term.resize(TerminalDimensions { cols: 20, lines: 4 });
+
+        assert_eq!(
+            grid_line_text(&term, Line(0)),
+            ("0123456789abcdef".to_string(), false)
+        );
+        assert_eq!(term.grid().cursor.point, Point::new(Line(1), Column(4)));
+    }
+
+    #[test]
+    fn default_word_separators_split_on_brackets_and_whitespace() {
+        let separators = TerminalSettings::default().word_separators;
+
+        assert_eq!(
+            double_click_word(b"(usr/local) bin", &separators).as_deref(),
+            Some("usr/local")
+        );
+    }
+
+    #[test]
+    fn custom_word_separator_splits_double_click_selection() {
+        assert_eq!(
+            double_click_word(b"usr/local bin", "/").as_deref(),
+            Some("local bin")
+        );
+    }
+
+    #[test]
+    fn pty_env_includes_default_term_and_truecolor() {
         let env = build_pty_env(&HashMap::new());
         assert_eq!(env.get("TERM").map(String::as_str), Some("xterm-256color"));
+        assert_eq!(env.get("COLORTERM").map(String::as_str), Some("truecolor"));
     }
 
     #[test]
@@ -246,12 +1782,103 @@ mod tests {
     }
 
     #[test]
-    fn pty_env_keeps_terminal_type_consistent() {
+    fn pty_env_lets_user_env_override_defaults() {
         let mut extra = HashMap::new();
         extra.insert("TERM".to_string(), "vt100".to_string());
+        extra.insert("COLORTERM".to_string(), "24bit".to_string());
 
         let env = build_pty_env(&extra);
-        assert_eq!(env.get("TERM").map(String::as_str), Some("xterm-256color"));
+        assert_eq!(env.get("TERM").map(String::as_str), Some("vt100"));
+        assert_eq!(env.get("COLORTERM").map(String::as_str), Some("24bit"));
+    }
+
+    fn input_for(keystroke: &str, mode: TermMode) -> Option<String> {
+        let keystroke = Keystroke::parse(keystroke).expect("valid keystroke");
+        key_input(&keystroke, &mode, false).map(|input| input.into_owned())
+    }
+
+    #[test]
+    fn arrow_keys_follow_cursor_key_mode() {
+        assert_eq!(input_for("up", TermMode::NONE).as_deref(), Some("\x1b[A"));
+        assert_eq!(input_for("left", TermMode::NONE).as_deref(), Some("\x1b[D"));
+        assert_eq!(
+            input_for("up", TermMode::APP_CURSOR).as_deref(),
+            Some("\x1bOA")
+        );
+    }
+
+    #[test]
+    fn ctrl_c_sends_end_of_text_byte() {
+        assert_eq!(input_for("ctrl-c", TermMode::NONE).as_deref(), Some("\x03"));
+    }
+
+    #[test]
+    fn printable_keys_send_their_text_and_modifier_only_keys_send_nothing() {
+        let keystroke = Keystroke {
+            key_char: Some("é".to_string()),
+            ..Keystroke::parse("e").expect("valid keystroke")
+        };
+        assert_eq!(
+            key_input(&keystroke, &TermMode::NONE, false).as_deref(),
+            Some("é")
+        );
+        assert_eq!(input_for("shift", TermMode::NONE), None);
+    }
+
+    #[test]
+    fn paste_is_bracketed_only_when_mode_requests_it() {
+        assert_eq!(
+            paste_input("ls\n", &TermMode::BRACKETED_PASTE),
+            "\x1b[200~ls\n\x1b[201~"
+        );
+        assert_eq!(paste_input("a\r\nb\n", &TermMode::NONE), "a\rb\r");
+    }
+
+    #[test]
+    fn bracketed_paste_strips_embedded_end_markers() {
+        assert_eq!(
+            paste_input("echo hi\x1b[201~rm -rf ~\n", &TermMode::BRACKETED_PASTE),
+            "\x1b[200~echo hirm -rf ~\n\x1b[201~"
+        );
+        assert_eq!(
+            paste_input("a\x1b[20\x1b[201~1~b", &TermMode::BRACKETED_PASTE),
+            "\x1b[200~ab\x1b[201~"
+        );
+    }
+
+    #[test]
+    fn paste_chunks_keep_byte_order_and_whole_bracketed_markers() {
+        let text = "héllo wörld\n".repeat(1000);
+        let chunks = paste_chunks(&text, &TermMode::BRACKETED_PASTE, 4096);
+
+        assert!(chunks.len() > 1);
+        assert_eq!(
+            chunks.concat(),
+            paste_input(&text, &TermMode::BRACKETED_PASTE).into_bytes()
+        );
+        assert!(chunks[0].starts_with(b"\x1b[200~"));
+        assert!(chunks[chunks.len() - 1].ends_with(b"\x1b[201~"));
+        for chunk in &chunks {
+            assert!(std::str::from_utf8(chunk).is_ok());
+        }
+        for chunk in &chunks[1..chunks.len() - 1] {
+            assert!(!chunk.contains(&0x1b));
+        }
+
+        let unbracketed = paste_chunks("ab\r\né", &TermMode::NONE, 1);
+        assert_eq!(
+            unbracketed,
+            vec![
+                b"a".to_vec(),
+                b"b".to_vec(),
+                b"\r".to_vec(),
+                "é".as_bytes().to_vec()
+            ]
+        );
+        assert_eq!(
+            paste_chunks("", &TermMode::BRACKETED_PASTE, 4096),
+            vec![b"\x1b[200~\x1b[201~".to_vec()]
+        );
     }
 
     #[test]
@@ -276,7 +1903,7 @@ mod tests {
     #[test]
     fn event_proxy_maps_mouse_cursor_dirty_to_wakeup() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::MouseCursorDirty);
 
@@ -286,7 +1913,7 @@ mod tests {
     #[test]
     fn event_proxy_maps_cursor_blinking_change_to_wakeup() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::CursorBlinkingChange);
 
@@ -296,7 +1923,7 @@ mod tests {
     #[test]
     fn event_proxy_maps_wakeup_bell_title_and_exit_events() {
         let (sender, receiver) = smol::channel::bounded(8);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Wakeup);
         proxy.send_event(AlacEvent::Bell);
@@ -312,12 +1939,41 @@ mod tests {
         assert!(matches!(receiver.try_recv(), Ok(TerminalEvent::Exit(42))));
     }
 
+    #[test]
+    fn popping_the_title_stack_restores_the_pushed_title() {
+        let (sender, receiver) = smol::channel::unbounded();
+        let size = TerminalDimensions { cols: 20, lines: 2 };
+        let mut term = Term::new(term::Config::default(), &size, EventProxy::new(sender));
+        let mut processor = Processor::<StdSyncHandler>::new();
+        let titles = || {
+            std::iter::from_fn(|| receiver.try_recv().ok())
+                .map(|event| match event {
+                    TerminalEvent::TitleChanged(title) => Some(title),
+                    TerminalEvent::TitleReset => None,
+                    other => panic!("unexpected event {other:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        processor.advance(&mut term, b"\x1b[22t\x1b]2;zsh\x07\x1b[22t\x1b]2;vim\x07");
+        assert_eq!(
+            titles(),
+            vec![Some("zsh".to_string()), Some("vim".to_string())]
+        );
+
+        processor.advance(&mut term, b"\x1b[23t");
+        assert_eq!(titles(), vec![Some("zsh".to_string())]);
+
+        processor.advance(&mut term, b"\x1b[23t");
+        assert_eq!(titles(), vec![None]);
+    }
+
     #[test]
     fn event_proxy_ignores_unmapped_events() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
-        proxy.send_event(AlacEvent::ResetTitle);
+        proxy.send_event(AlacEvent::ColorRequest(0, Arc::new(|_| String::new())));
 
         assert!(receiver.try_recv().is_err());
     }
@@ -325,7 +1981,7 @@ mod tests {
     #[test]
     fn event_proxy_keeps_non_wakeup_events_when_channel_is_full() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Wakeup);
         proxy.send_event(AlacEvent::Title("shell".to_string()));
@@ -346,7 +2002,7 @@ mod tests {
     #[test]
     fn event_proxy_drops_wakeup_when_channel_is_full() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Title("sticky".to_string()));
         proxy.send_event(AlacEvent::Wakeup);
@@ -361,7 +2017,7 @@ mod tests {
     #[test]
     fn event_proxy_drops_bell_when_channel_is_full() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Wakeup);
         proxy.send_event(AlacEvent::Bell);
@@ -373,7 +2029,7 @@ mod tests {
     #[test]
     fn event_proxy_keeps_exit_when_channel_is_full() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Wakeup);
         proxy.send_event(AlacEvent::ChildExit(7));
@@ -394,7 +2050,7 @@ mod tests {
     #[test]
     fn event_proxy_keeps_latest_title_when_channel_is_full() {
         let (sender, receiver) = smol::channel::bounded(1);
-        let proxy = EventProxy { sender };
+        let proxy = EventProxy::new(sender);
 
         proxy.send_event(AlacEvent::Wakeup);
         proxy.send_event(AlacEvent::Title("first".to_string()));
@@ -406,4 +2062,132 @@ mod tests {
         ));
         assert!(receiver.try_recv().is_err());
     }
+
+    #[test]
+    fn event_proxy_keeps_cwd_change_when_channel_is_full() {
+        let (sender, receiver) = smol::channel::bounded(1);
+        let proxy = EventProxy::new(sender);
+
+        proxy.send_event(AlacEvent::Wakeup);
+        proxy.send_terminal_event(TerminalEvent::CwdChanged(PathBuf::from("/tmp")));
+
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(TerminalEvent::CwdChanged(path)) if path == std::path::Path::new("/tmp")
+        ));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    const WINDOW: WindowSize = WindowSize {
+        num_lines: 24,
+        num_cols: 80,
+        cell_width: 8,
+        cell_height: 4,
+    };
+
+    fn anchored_image_id(output: &[u8]) -> Option<u64> {
+        let output = std::str::from_utf8(output).ok()?;
+        let uri = output.split("\x1b]8;;").nth(1)?.split('\x1b').next()?;
+        image_id_from_uri(uri)
+    }
+
+    #[test]
+    fn image_anchor_is_inserted_after_the_image_sequence() {
+        let images = ImageStore::default();
+        let mut scanner = SixelScanner::default();
+        let bytes = b"a\x1bPq#1!4~-~\x1b\\b";
+        let mut placements = Vec::new();
+        scanner.advance(bytes, |offset, image| {
+            placements.push((offset, Insertion::Image(ImagePlacement::natural(image))))
+        });
+
+        let output = splice_insertions(&images, WINDOW, bytes, placements);
+        let image = anchored_image_id(&output)
+            .and_then(|id| images.get(id))
+            .expect("stored image");
+        assert_eq!((image.width, image.height), (4, 12));
+        assert_eq!(image.lines(), 3);
+        assert!(output.starts_with(b"a\x1bPq"));
+        assert!(output.ends_with(b"\x1b[D\n\nb"));
+    }
+
+    #[test]
+    fn image_anchor_respects_requested_size_and_cursor_mode() {
+        let images = ImageStore::default();
+        let placement = ImagePlacement {
+            width: ImageExtent::Cells(2),
+            move_cursor: false,
+            ..ImagePlacement::natural(DecodedImage {
+                width: 4,
+                height: 4,
+                rgba: vec![0; 64],
+            })
+        };
+
+        let output =
+            splice_insertions(&images, WINDOW, b"", vec![(0, Insertion::Image(placement))]);
+        let image = anchored_image_id(&output)
+            .and_then(|id| images.get(id))
+            .expect("stored image");
+        assert_eq!((image.display_width, image.display_height), (16, 16));
+        assert!(output.ends_with(b"\x1b[D"));
+    }
+
+    #[test]
+    fn line_size_markers_are_spliced_between_image_anchors() {
+        let images = ImageStore::default();
+        let placement = ImagePlacement::natural(DecodedImage {
+            width: 1,
+            height: 1,
+            rgba: vec![0; 4],
+        });
+        let insertions = vec![
+            (1, Insertion::LineSize(LineSize::DoubleWidth)),
+            (2, Insertion::Image(placement)),
+        ];
+
+        let output = splice_insertions(&images, WINDOW, b"abc", insertions);
+        let marker = marker_bytes(LineSize::DoubleWidth);
+        assert_eq!(&output[..1], b"a");
+        assert_eq!(&output[1..1 + marker.len()], marker.as_slice());
+        assert_eq!(output[1 + marker.len()], b'b');
+        assert!(output.ends_with(b"\x1b[Dc"));
+    }
+
+    #[test]
+    fn pending_output_drains_across_small_reads() {
+        let mut pending = b"abcde".to_vec();
+        let mut buf = [0u8; 3];
+
+        assert_eq!(drain_pending(&mut pending, &mut buf), 3);
+        assert_eq!(&buf, b"abc");
+        assert_eq!(drain_pending(&mut pending, &mut buf), 2);
+        assert_eq!(&buf[..2], b"de");
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    fn pending_wake_is_readable_only_while_armed() {
+        let mut wake = PendingWake::new().expect("socket pair");
+        let poller = Poller::new().expect("poller");
+        // SAFETY: the socket outlives its registration; it is removed below.
+        unsafe { poller.add_with_mode(&wake.reader, PollEvent::readable(0), PollMode::Level) }
+            .expect("register");
+        let readable = || {
+            let mut events = Events::new();
+            poller
+                .wait(&mut events, Some(Duration::ZERO))
+                .expect("poll");
+            !events.is_empty()
+        };
+
+        assert!(!readable());
+        wake.set(true);
+        wake.set(true);
+        assert!(readable());
+        assert!(readable());
+        wake.set(false);
+        assert!(!readable());
+        poller.delete(&wake.reader).expect("deregister");
+    }
 }
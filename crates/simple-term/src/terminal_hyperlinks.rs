@@ -21,9 +21,15 @@ use std::{
 };
 use url::Url;
 
+use crate::images::image_id_from_uri;
 use crate::PathStyle;
 
-const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`']+"#;
+/// Scheme URLs, plus bare `www.` domains, which are opened over https.
+const URL_REGEX: &str = r#"(ipfs:|ipns:|magnet:|mailto:|gemini://|gopher://|https://|http://|news:|file://|git://|ssh:|ftp://|www\.)[^\u{0000}-\u{001F}\u{007F}-\u{009F}<>"\s{-}\^⟨⟩`']+"#;
+/// Drive-letter (`C:\dir\file`) and UNC (`\\server\share`) paths, with
+/// an optional `:line:column` suffix. Either separator is accepted after the
+/// root.
+const WINDOWS_PATH_REGEX: &str = r#"(?P<link>(?P<path>(?:[A-Za-z]:|\\\\[^\s\\/:"<>|]+)[\\/][^\s:"<>|]*)(?::(?P<line>\d+)(?::(?P<column>\d+))?)?)"#;
 const WIDE_CHAR_SPACERS: Flags =
     Flags::from_bits(Flags::LEADING_WIDE_CHAR_SPACER.bits() | Flags::WIDE_CHAR_SPACER.bits())
         .unwrap();
@@ -31,6 +37,7 @@ const WIDE_CHAR_SPACERS: Flags =
 pub struct RegexSearches {
     url_regex: RegexSearch,
     path_hyperlink_regexes: Vec<Regex>,
+    windows_path_regex: Regex,
     path_hyperlink_timeout: Duration,
 }
 
@@ -39,6 +46,7 @@ impl Default for RegexSearches {
         Self {
             url_regex: RegexSearch::new(URL_REGEX).unwrap(),
             path_hyperlink_regexes: Vec::default(),
+            windows_path_regex: Regex::new(WINDOWS_PATH_REGEX).unwrap(),
             path_hyperlink_timeout: Duration::default(),
         }
     }
@@ -65,6 +73,7 @@ impl RegexSearches {
                         .ok()
                 })
                 .collect(),
+            windows_path_regex: Regex::new(WINDOWS_PATH_REGEX).unwrap(),
             path_hyperlink_timeout: Duration::from_millis(path_hyperlink_timeout_ms),
         }
     }
@@ -74,10 +83,14 @@ pub fn find_from_grid_point<T: EventListener>(
     term: &Term<T>,
     point: AlacPoint,
     regex_searches: &mut RegexSearches,
-    _path_style: PathStyle,
+    path_style: PathStyle,
 ) -> Option<(String, bool, Match)> {
     let grid = term.grid();
-    let link = grid.index(point).hyperlink();
+    // Image anchors are internal OSC 8 links and never navigable.
+    let link = grid
+        .index(point)
+        .hyperlink()
+        .filter(|link| image_id_from_uri(link.uri()).is_none());
     let found_word = if let Some(ref url) = link {
         let mut min_index = point;
         loop {
@@ -119,12 +132,22 @@ pub fn find_from_grid_point<T: EventListener>(
         }) {
             Some((url, true, url_match))
         } else {
+            // User patterns win; Windows paths fall back to the built-in pattern.
+            let style_regex = match path_style {
+                PathStyle::Windows => Some(&regex_searches.windows_path_regex),
+                PathStyle::Unix | PathStyle::Remote => None,
+            };
+            let path_regexes = regex_searches
+                .path_hyperlink_regexes
+                .iter()
+                .chain(style_regex)
+                .collect::<Vec<_>>();
             path_match(
                 term,
                 line_start,
                 line_end,
                 point,
-                &mut regex_searches.path_hyperlink_regexes,
+                &path_regexes,
                 regex_searches.path_hyperlink_timeout,
             )
             .map(|(path, path_match)| (path, false, path_match))
@@ -145,6 +168,8 @@ pub fn find_from_grid_point<T: EventListener>(
                     .strip_prefix("file://")
                     .unwrap_or(&maybe_url_or_path);
                 (path.to_string(), false, word_match)
+            } else if maybe_url_or_path.starts_with("www.") {
+                (format!("https://{maybe_url_or_path}"), true, word_match)
             } else {
                 (maybe_url_or_path, true, word_match)
             }
@@ -206,7 +231,7 @@ fn path_match<T>(
     line_start: AlacPoint,
     line_end: AlacPoint,
     hovered: AlacPoint,
-    path_hyperlink_regexes: &mut Vec<Regex>,
+    path_hyperlink_regexes: &[&Regex],
     path_hyperlink_timeout: Duration,
 ) -> Option<(String, Match)> {
     if path_hyperlink_regexes.is_empty() || path_hyperlink_timeout.as_millis() == 0 {
@@ -383,8 +408,11 @@ fn path_match<T>(
         }
 
         if let Some((timed_out_ms, timeout_ms)) = timed_out() {
-            warn!("Timed out processing path hyperlink regexes after {timed_out_ms}ms");
-            info!("{timeout_ms}ms time out specified in `terminal.path_hyperlink_timeout_ms`");
+            warn!(
+                "Aborted path hyperlink scan of a {}-byte line after {timed_out_ms}ms",
+                line.len()
+            );
+            info!("{timeout_ms}ms time out specified in `path_hyperlink_timeout_ms`");
             return None;
         }
     }
@@ -398,6 +426,7 @@ mod tests {
     use crate::PathStyle;
     use alacritty_terminal::index::{Column, Line, Point};
     use alacritty_terminal::term::test::mock_term;
+    use std::time::{Duration, Instant};
 
     #[test]
     fn trims_trailing_url_punctuation() {
@@ -481,6 +510,19 @@ mod tests {
         assert!(found.is_none());
     }
 
+    #[test]
+    fn tiny_timeout_returns_promptly_on_a_pathological_line() {
+        let term = mock_term(&"a/".repeat(2_000));
+        let mut searches = RegexSearches::new(vec![r"(?P<path>(?:a/)+b)"; 500], 1);
+        let point = Point::new(Line(0), Column(10));
+
+        let started = Instant::now();
+        let found = find_from_grid_point(&term, point, &mut searches, PathStyle::Unix);
+
+        assert!(found.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
     #[test]
     fn path_regex_match_supports_windows_drive_paths() {
         let term = mock_term(r"C:\Users\mt\project\main.rs:12:8");
@@ -515,6 +557,45 @@ mod tests {
         assert!(!is_url);
     }
 
+    #[test]
+    fn windows_path_style_detects_drive_paths_without_user_patterns() {
+        let term = mock_term(r"error at C:\Users\mt\src\main.rs:12:8 here");
+        let mut searches = RegexSearches::new(Vec::<String>::new(), 500);
+        let point = Point::new(Line(0), Column(14));
+
+        let (target, is_url, _) =
+            find_from_grid_point(&term, point, &mut searches, PathStyle::Windows)
+                .expect("windows path should match");
+
+        assert_eq!(target, r"C:\Users\mt\src\main.rs:12:8");
+        assert!(!is_url);
+        assert!(find_from_grid_point(&term, point, &mut searches, PathStyle::Unix).is_none());
+    }
+
+    #[test]
+    fn windows_path_style_detects_unc_and_forward_slash_paths() {
+        let term = mock_term(r"\\server\share\notes.txt D:/work/build.log:7");
+        let mut searches = RegexSearches::new(Vec::<String>::new(), 500);
+
+        let (unc, _, _) = find_from_grid_point(
+            &term,
+            Point::new(Line(0), Column(3)),
+            &mut searches,
+            PathStyle::Windows,
+        )
+        .expect("unc path should match");
+        let (drive, _, _) = find_from_grid_point(
+            &term,
+            Point::new(Line(0), Column(30)),
+            &mut searches,
+            PathStyle::Windows,
+        )
+        .expect("drive path should match");
+
+        assert_eq!(unc, r"\\server\share\notes.txt");
+        assert_eq!(drive, "D:/work/build.log:7");
+    }
+
     #[test]
     fn path_regex_match_supports_non_ascii_paths() {
         let term = mock_term("/tmp/你好.rs:9:1");
@@ -545,6 +626,69 @@ mod tests {
         assert!(is_url);
     }
 
+    /// URL under `column` of `text`, if one is found there.
+    fn url_at(text: &str, column: usize) -> Option<String> {
+        let term = mock_term(text);
+        let mut searches = RegexSearches::default();
+        let point = Point::new(Line(0), Column(column));
+        find_from_grid_point(&term, point, &mut searches, PathStyle::Unix)
+            .filter(|(_, is_url, _)| *is_url)
+            .map(|(target, _, _)| target)
+    }
+
+    #[test]
+    fn detects_ssh_git_mailto_and_ftp_urls() {
+        assert_eq!(
+            url_at("clone ssh://git@example.com/repo.git now", 8).as_deref(),
+            Some("ssh://git@example.com/repo.git")
+        );
+        assert_eq!(
+            url_at("git://example.com/project.git", 3).as_deref(),
+            Some("git://example.com/project.git")
+        );
+        assert_eq!(
+            url_at("mail mailto:dev@example.com", 8).as_deref(),
+            Some("mailto:dev@example.com")
+        );
+        assert_eq!(
+            url_at("ftp://ftp.example.com/pub/file.tar.gz", 0).as_deref(),
+            Some("ftp://ftp.example.com/pub/file.tar.gz")
+        );
+    }
+
+    #[test]
+    fn bare_www_domains_open_over_https() {
+        assert_eq!(
+            url_at("see www.example.com/docs for more", 6).as_deref(),
+            Some("https://www.example.com/docs")
+        );
+        assert_eq!(
+            url_at("https://www.example.com", 15).as_deref(),
+            Some("https://www.example.com")
+        );
+        assert_eq!(url_at("wwwexample.com", 2), None);
+    }
+
+    #[test]
+    fn trims_trailing_punctuation_from_every_scheme() {
+        assert_eq!(
+            url_at("(see ssh://host.example.com).", 8).as_deref(),
+            Some("ssh://host.example.com")
+        );
+        assert_eq!(
+            url_at("write to mailto:dev@example.com, please", 12).as_deref(),
+            Some("mailto:dev@example.com")
+        );
+        assert_eq!(
+            url_at("mirror ftp://example.com/pub.", 10).as_deref(),
+            Some("ftp://example.com/pub")
+        );
+        assert_eq!(
+            url_at("at www.example.com.", 5).as_deref(),
+            Some("https://www.example.com")
+        );
+    }
+
     #[test]
     fn file_urls_decode_percent_encoded_paths() {
         let term = mock_term("file:///tmp/hello%20world.rs");
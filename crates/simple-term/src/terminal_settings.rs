@@ -7,7 +7,10 @@ use alacritty_terminal::vte::ansi::{
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::PathStyle;
 
 /// Alternate scroll mode
 #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -21,7 +24,7 @@ pub enum AlternateScroll {
 }
 
 /// Terminal and chrome theme preset.
-#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 pub enum TerminalTheme {
     /// Atom One Dark-aligned dark palette.
@@ -38,6 +41,129 @@ pub enum TerminalTheme {
     Nord,
     /// Solarized dark palette.
     SolarizedDark,
+    /// Solarized light palette.
+    SolarizedLight,
+    /// GitHub-inspired light palette.
+    GithubLight,
+    /// Palette loaded from `~/.simple-term/themes/<name>.json`, keyed by file stem.
+    Custom(String),
+}
+
+/// Colors described by a custom theme file. Every color is a `#rrggbb` string.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct CustomThemeDefinition {
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub ui_bg: u32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub terminal_bg: u32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub cursor: u32,
+    /// The 16 ANSI colors, normal then bright.
+    #[serde(deserialize_with = "deserialize_ansi_colors")]
+    pub ansi_colors: [u32; 16],
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub foreground: u32,
+    #[serde(deserialize_with = "deserialize_hex_color")]
+    pub background: u32,
+}
+
+impl CustomThemeDefinition {
+    /// Parse one theme file body.
+    pub fn from_json(contents: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(contents)
+    }
+
+    /// Load every `*.json` theme in `dir`, sorted by name. Unreadable or
+    /// malformed files are logged and skipped.
+    pub fn load_dir(dir: &Path) -> Vec<(String, Self)> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return Vec::new();
+        };
+
+        let mut themes = Vec::new();
+        for path in entries.filter_map(|entry| entry.ok().map(|entry| entry.path())) {
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let parsed = std::fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|contents| Self::from_json(&contents).map_err(|err| err.to_string()));
+            match parsed {
+                Ok(theme) => themes.push((name.to_string(), theme)),
+                Err(err) => log::warn!("skipping custom theme {}: {err}", path.display()),
+            }
+        }
+
+        themes.sort_by(|(left, _), (right, _)| left.cmp(right));
+        themes
+    }
+}
+
+/// Parse `#rrggbb` (the leading `#` is optional) into `0xRRGGBB`.
+pub fn parse_hex_color(raw: &str) -> Option<u32> {
+    let hex = raw.trim();
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+fn deserialize_hex_color<'de, D>(deserializer: D) -> Result<u32, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    parse_hex_color(&raw).ok_or_else(|| serde::de::Error::custom(format!("invalid color '{raw}'")))
+}
+
+/// Optional `#rrggbb` setting. Invalid values are logged and ignored so one
+/// typo does not reset the whole settings file.
+fn deserialize_optional_hex_color<'de, D>(deserializer: D) -> Result<Option<u32>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let color = parse_hex_color(&raw);
+    if color.is_none() {
+        log::warn!("ignoring invalid color '{raw}'");
+    }
+    Ok(color)
+}
+
+fn serialize_optional_hex_color<S>(color: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: serde::Serializer,
+{
+    match color {
+        Some(color) => serializer.serialize_str(&format!("#{color:06x}")),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_ansi_colors<'de, D>(deserializer: D) -> Result<[u32; 16], D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw = Vec::<String>::deserialize(deserializer)?;
+    if raw.len() != 16 {
+        return Err(serde::de::Error::invalid_length(
+            raw.len(),
+            &"16 ANSI colors",
+        ));
+    }
+
+    let mut colors = [0; 16];
+    for (slot, value) in colors.iter_mut().zip(&raw) {
+        *slot = parse_hex_color(value)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid color '{value}'")))?;
+    }
+    Ok(colors)
 }
 
 /// Cursor shape
@@ -98,6 +224,65 @@ impl Blinking {
     }
 }
 
+/// What happens when the shell rings the bell (BEL)
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BellMode {
+    /// Ignore the bell
+    Off,
+    /// Briefly flash the terminal
+    #[default]
+    Visual,
+    /// Play the system beep
+    Audible,
+}
+
+/// Where the tab bar sits in the window
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TabBarPosition {
+    #[default]
+    Top,
+    Bottom,
+    /// No tab bar; the window keeps a single tab
+    Hidden,
+}
+
+/// What happens to a pane when its shell exits
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseOnExit {
+    /// Close the pane whatever the exit code
+    Always,
+    /// Close the pane when the shell exits with code 0
+    #[default]
+    OnSuccess,
+    /// Keep the pane open until Enter is pressed
+    Never,
+}
+
+impl CloseOnExit {
+    pub fn closes_after(self, exit_code: i32) -> bool {
+        match self {
+            CloseOnExit::Always => true,
+            CloseOnExit::OnSuccess => exit_code == 0,
+            CloseOnExit::Never => false,
+        }
+    }
+}
+
+/// Modifier held while clicking to open a hyperlink
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HyperlinkModifier {
+    /// A plain click opens links
+    None,
+    /// Command on macOS, the Super key elsewhere
+    Cmd,
+    Ctrl,
+    Alt,
+}
+
 /// Line height setting
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case", tag = "type")]
@@ -166,8 +351,20 @@ pub enum ShellConfig {
 }
 
 impl ShellConfig {
-    pub fn to_shell(&self) -> super::Shell {
-        match self {
+    /// Runtime shell, started as a login shell when `login_shell` is set. The
+    /// system shell is then looked up in `$SHELL` to have a program to pass
+    /// the flag to.
+    pub fn to_shell(&self, login_shell: bool) -> super::Shell {
+        self.to_shell_with_env(login_shell, super::Shell::from_env)
+    }
+
+    fn to_shell_with_env(
+        &self,
+        login_shell: bool,
+        system_shell: impl FnOnce() -> super::Shell,
+    ) -> super::Shell {
+        let shell = match self {
+            ShellConfig::System if login_shell => system_shell(),
             ShellConfig::System => super::Shell::System,
             ShellConfig::Program { program } => super::Shell::Program(program.clone()),
             ShellConfig::WithArguments { program, args } => super::Shell::WithArguments {
@@ -175,16 +372,57 @@ impl ShellConfig {
                 args: args.clone(),
                 title_override: None,
             },
+        };
+        if login_shell {
+            shell.into_login()
+        } else {
+            shell
         }
     }
 }
 
+/// Named shell setup offered by the new-tab profile picker.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ShellProfile {
+    /// Label shown in the picker
+    pub name: String,
+    /// Shell to launch
+    #[serde(default)]
+    pub shell: ShellConfig,
+    /// Working directory strategy; `None` uses the global strategy
+    #[serde(default)]
+    pub working_directory: Option<WorkingDirectory>,
+    /// Environment variables layered over the global `env`
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Command typed into the shell once it starts, e.g. `ssh server`
+    #[serde(default)]
+    pub initial_command: Option<String>,
+}
+
+impl ShellProfile {
+    /// The global environment with this profile's overrides applied on top.
+    pub fn effective_env(&self, base: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut env = base.clone();
+        env.extend(
+            self.env
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        env
+    }
+}
+
 /// Terminal settings
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct TerminalSettings {
     /// Shell configuration
     #[serde(default)]
     pub shell: ShellConfig,
+    /// Start `shell` as a login shell (`-l`) so it sources profile files;
+    /// profiles pass their own arguments
+    #[serde(default)]
+    pub login_shell: bool,
     /// Working directory strategy
     #[serde(default)]
     pub working_directory: WorkingDirectory,
@@ -197,6 +435,12 @@ pub struct TerminalSettings {
     /// Font fallback family names (monospace preferred)
     #[serde(default = "default_font_fallbacks")]
     pub font_fallbacks: Vec<String>,
+    /// Weight of regular text (100-900, 400 is normal)
+    #[serde(default = "default_font_weight")]
+    pub font_weight: u16,
+    /// Font family for bold text; bold uses the regular family when unset
+    #[serde(default)]
+    pub bold_font_family: Option<String>,
     /// Line height setting
     #[serde(default)]
     pub line_height: LineHeight,
@@ -206,12 +450,57 @@ pub struct TerminalSettings {
     /// Cursor shape
     #[serde(default)]
     pub cursor_shape: CursorShape,
+    /// Cursor color as `#rrggbb`; the theme's cursor color when unset
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_hex_color",
+        deserialize_with = "deserialize_optional_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cursor_color: Option<u32>,
+    /// Color of the glyph under a block cursor as `#rrggbb`; the terminal
+    /// background when unset
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_hex_color",
+        deserialize_with = "deserialize_optional_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub cursor_text_color: Option<u32>,
+    /// Background of selected cells as `#rrggbb`; a tint of the cursor color
+    /// blended into the cell background when unset
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_hex_color",
+        deserialize_with = "deserialize_optional_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub selection_background: Option<u32>,
+    /// Color of selected text as `#rrggbb`; the text keeps its own color
+    /// when unset
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_hex_color",
+        deserialize_with = "deserialize_optional_hex_color",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub selection_foreground: Option<u32>,
     /// Blinking behavior
     #[serde(default)]
     pub blinking: Blinking,
+    /// Time the cursor stays on or off while blinking, in milliseconds
+    #[serde(default = "default_cursor_blink_interval_ms")]
+    pub cursor_blink_interval_ms: u64,
+    /// How long the cursor holds still after input before blinking again, in
+    /// milliseconds; 0 blinks straight away
+    #[serde(default = "default_cursor_blink_suppress_ms")]
+    pub cursor_blink_suppress_ms: u64,
     /// Alternate scroll mode
     #[serde(default)]
     pub alternate_scroll: AlternateScroll,
+    /// Bell behavior
+    #[serde(default)]
+    pub bell_mode: BellMode,
     /// Treat option key as meta
     #[serde(default)]
     pub option_as_meta: bool,
@@ -221,9 +510,34 @@ pub struct TerminalSettings {
     /// Keep selection after copy
     #[serde(default = "default_true")]
     pub keep_selection_on_copy: bool,
+    /// Paste the last completed selection on middle click (X11 primary selection style)
+    #[serde(default = "default_middle_click_paste")]
+    pub middle_click_paste: bool,
+    /// Characters that end a word when double-clicking to select
+    #[serde(default = "default_word_separators")]
+    pub word_separators: String,
+    /// Ask before closing a tab whose foreground process is not the shell
+    #[serde(default = "default_true")]
+    pub confirm_close: bool,
+    /// Whether a pane closes when its shell exits
+    #[serde(default)]
+    pub close_on_exit: CloseOnExit,
+    /// Ask before pasting text with line breaks into a program that did not
+    /// enable bracketed paste, since each line would run as a command
+    #[serde(default = "default_true")]
+    pub paste_guard: bool,
     /// Show terminal button in status bar
     #[serde(default = "default_true")]
     pub button: bool,
+    /// Shortcut overrides keyed by action name (e.g. `"new_tab": "cmd+shift+n"`)
+    #[serde(default)]
+    pub keybindings: HashMap<String, String>,
+    /// Shell profiles offered when opening a tab from the "+" button
+    #[serde(default)]
+    pub profiles: Vec<ShellProfile>,
+    /// Show the shell-reported working directory (OSC 7) in tab titles
+    #[serde(default)]
+    pub show_cwd_in_tab_title: bool,
     /// Terminal and app-chrome theme preset
     #[serde(default)]
     pub theme: TerminalTheme,
@@ -239,27 +553,85 @@ pub struct TerminalSettings {
     /// Distance from menubar bottom to terminal panel top (pixels)
     #[serde(default = "default_panel_top_inset")]
     pub panel_top_inset: f32,
+    /// How long the panel takes to slide down from the top edge when shown
+    /// and back up when hidden, in milliseconds; 0 shows and hides instantly
+    #[serde(default)]
+    pub slide_animation_ms: u64,
+    /// Space between the window edges and the terminal grid, left and right (pixels)
+    #[serde(default)]
+    pub padding_x: f32,
+    /// Space between the tab bar or window bottom and the terminal grid (pixels)
+    #[serde(default)]
+    pub padding_y: f32,
+    /// Where the tab bar is drawn, or `hidden` for a single-tab window
+    #[serde(default)]
+    pub tab_bar_position: TabBarPosition,
+    /// Leave the tab bar out while only one tab is open
+    #[serde(default)]
+    pub hide_tab_bar_with_single_tab: bool,
     /// Default terminal width
     #[serde(default = "default_width")]
     pub default_width: u32,
     /// Default terminal height
     #[serde(default = "default_height")]
     pub default_height: u32,
+    /// Panel width as a fraction (0.0–1.0] of the monitor's work area;
+    /// `default_width` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub width_fraction: Option<f32>,
+    /// Panel height as a fraction (0.0–1.0] of the monitor's work area;
+    /// `default_height` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height_fraction: Option<f32>,
     /// Maximum scrollback lines
     #[serde(default = "default_scrollback")]
     pub max_scroll_history_lines: Option<usize>,
     /// Scroll multiplier
     #[serde(default = "default_scroll_multiplier")]
     pub scroll_multiplier: f32,
-    /// Minimum contrast ratio
+    /// Scroll multiplier for mouse wheels, which scroll by lines;
+    /// `scroll_multiplier` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse_scroll_multiplier: Option<f32>,
+    /// Scroll multiplier for trackpads, which scroll by pixels;
+    /// `scroll_multiplier` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trackpad_scroll_multiplier: Option<f32>,
+    /// Invert the wheel direction, both for scrollback and for wheel events
+    /// reported to programs
+    #[serde(default)]
+    pub natural_scroll: bool,
+    /// Jump back to the bottom when output arrives while scrolled into history,
+    /// unless the user scrolled moments ago
+    #[serde(default)]
+    pub scroll_on_output: bool,
+    /// Draw bold text in standard ANSI colors (0-7) with their bright variants (8-15)
+    #[serde(default)]
+    pub bold_is_bright: bool,
+    /// Minimum WCAG contrast ratio between text and its background, from 1 (off) to 21
     #[serde(default = "default_minimum_contrast")]
     pub minimum_contrast: f32,
+    /// How far text and cell colors fade toward the background while the
+    /// window is inactive, from 0 (off) to 0.8
+    #[serde(default)]
+    pub unfocused_dim: f32,
     /// Path hyperlink regex patterns
     #[serde(default)]
     pub path_hyperlink_regexes: Vec<String>,
     /// Path hyperlink timeout in milliseconds
     #[serde(default = "default_hyperlink_timeout")]
     pub path_hyperlink_timeout_ms: u64,
+    /// Modifier that turns a click on a hyperlink into opening it
+    #[serde(default = "default_hyperlink_modifier")]
+    pub hyperlink_modifier: HyperlinkModifier,
+    /// Path syntax recognized as hyperlinks; `windows` also matches drive and UNC paths
+    #[serde(default = "default_path_style")]
+    pub path_style: PathStyle,
+    /// URL opened for a clicked file link, with `{path}`, `{line}` and `{col}`
+    /// placeholders, e.g. `vscode://file/{path}:{line}:{col}`; unset opens the
+    /// file with the system handler
+    #[serde(default)]
+    pub file_open_template: Option<String>,
     /// Last known window placement per monitor key (macOS app shell).
     #[serde(default)]
     pub monitor_window_positions: HashMap<String, MonitorWindowPlacement>,
@@ -300,13 +672,33 @@ fn default_font_size() -> f32 {
     14.0
 }
 
+fn default_font_weight() -> u16 {
+    400
+}
+
 const MIN_FONT_SIZE: f32 = 6.0;
 const MAX_FONT_SIZE: f32 = 72.0;
+const MIN_FONT_WEIGHT: u16 = 100;
+const MAX_FONT_WEIGHT: u16 = 900;
+const MIN_CONTRAST_RATIO: f32 = 1.0;
+const MAX_CONTRAST_RATIO: f32 = 21.0;
+const MAX_UNFOCUSED_DIM: f32 = 0.8;
 const MIN_LINE_HEIGHT_RATIO: f32 = 0.5;
 const MAX_LINE_HEIGHT_RATIO: f32 = 3.0;
 const MAX_DEFAULT_WIDTH: u32 = 8192;
 const MAX_DEFAULT_HEIGHT: u32 = 4320;
 const MAX_PANEL_TOP_INSET: f32 = 64.0;
+const MAX_PADDING: f32 = 128.0;
+const MIN_CURSOR_BLINK_INTERVAL_MS: u64 = 100;
+const MAX_CURSOR_BLINK_INTERVAL_MS: u64 = 5_000;
+const MAX_CURSOR_BLINK_SUPPRESS_MS: u64 = 10_000;
+const MAX_SLIDE_ANIMATION_MS: u64 = 1_000;
+
+/// Fractions at or below zero are dropped so the pixel size applies; larger
+/// ones are capped at the whole work area.
+fn sanitize_screen_fraction(fraction: f32) -> Option<f32> {
+    (fraction.is_finite() && fraction > 0.0).then(|| fraction.min(1.0))
+}
 
 fn default_font_family() -> String {
     if cfg!(target_os = "macos") {
@@ -367,6 +759,14 @@ fn default_height() -> u32 {
     320
 }
 
+fn default_cursor_blink_interval_ms() -> u64 {
+    530
+}
+
+fn default_cursor_blink_suppress_ms() -> u64 {
+    800
+}
+
 fn default_scrollback() -> Option<usize> {
     Some(10_000)
 }
@@ -375,43 +775,101 @@ fn default_scroll_multiplier() -> f32 {
     3.0
 }
 
+fn default_middle_click_paste() -> bool {
+    cfg!(target_os = "linux")
+}
+
+pub(crate) fn default_word_separators() -> String {
+    "()[]{}<>\"' \t".to_string()
+}
+
 fn default_minimum_contrast() -> f32 {
-    45.0
+    MIN_CONTRAST_RATIO
 }
 
 fn default_hyperlink_timeout() -> u64 {
     500
 }
 
+fn default_hyperlink_modifier() -> HyperlinkModifier {
+    if cfg!(target_os = "macos") {
+        HyperlinkModifier::Cmd
+    } else {
+        HyperlinkModifier::Ctrl
+    }
+}
+
+fn default_path_style() -> PathStyle {
+    if cfg!(windows) {
+        PathStyle::Windows
+    } else {
+        PathStyle::Unix
+    }
+}
+
 impl Default for TerminalSettings {
     fn default() -> Self {
         Self {
             shell: ShellConfig::default(),
+            login_shell: false,
             working_directory: WorkingDirectory::default(),
             font_size: default_font_size(),
             font_family: default_font_family(),
             font_fallbacks: default_font_fallbacks(),
+            font_weight: default_font_weight(),
+            bold_font_family: None,
             line_height: LineHeight::default(),
             env: HashMap::new(),
             cursor_shape: CursorShape::default(),
+            cursor_color: None,
+            cursor_text_color: None,
+            selection_background: None,
+            selection_foreground: None,
             blinking: Blinking::default(),
+            cursor_blink_interval_ms: default_cursor_blink_interval_ms(),
+            cursor_blink_suppress_ms: default_cursor_blink_suppress_ms(),
             alternate_scroll: AlternateScroll::default(),
+            bell_mode: BellMode::default(),
             option_as_meta: false,
             copy_on_select: false,
             keep_selection_on_copy: true,
+            middle_click_paste: default_middle_click_paste(),
+            word_separators: default_word_separators(),
+            confirm_close: true,
+            close_on_exit: CloseOnExit::default(),
+            paste_guard: true,
             button: true,
+            keybindings: HashMap::new(),
+            profiles: Vec::new(),
+            show_cwd_in_tab_title: false,
             theme: TerminalTheme::default(),
             global_hotkey: default_global_hotkey(),
             pin_hotkey: default_pin_hotkey(),
             auto_hide_on_outside_click: true,
             panel_top_inset: default_panel_top_inset(),
+            slide_animation_ms: 0,
+            padding_x: 0.0,
+            padding_y: 0.0,
+            tab_bar_position: TabBarPosition::default(),
+            hide_tab_bar_with_single_tab: false,
             default_width: default_width(),
             default_height: default_height(),
+            width_fraction: None,
+            height_fraction: None,
             max_scroll_history_lines: default_scrollback(),
             scroll_multiplier: default_scroll_multiplier(),
+            mouse_scroll_multiplier: None,
+            trackpad_scroll_multiplier: None,
+            natural_scroll: false,
+            scroll_on_output: false,
+            bold_is_bright: false,
             minimum_contrast: default_minimum_contrast(),
+            unfocused_dim: 0.0,
             path_hyperlink_regexes: Vec::new(),
             path_hyperlink_timeout_ms: default_hyperlink_timeout(),
+            hyperlink_modifier: default_hyperlink_modifier(),
+            path_style: default_path_style(),
+            file_open_template: None,
             monitor_window_positions: HashMap::new(),
         }
     }
@@ -425,12 +883,65 @@ impl TerminalSettings {
         }
     }
 
+    pub fn cursor_blink_interval(&self) -> Duration {
+        Duration::from_millis(self.cursor_blink_interval_ms)
+    }
+
+    pub fn cursor_blink_suppression(&self) -> Duration {
+        Duration::from_millis(self.cursor_blink_suppress_ms)
+    }
+
+    pub fn slide_animation(&self) -> Duration {
+        Duration::from_millis(self.slide_animation_ms)
+    }
+
     fn sanitize(mut self) -> Self {
         if !self.font_size.is_finite() || self.font_size <= 0.0 {
             self.font_size = default_font_size();
         } else {
             self.font_size = self.font_size.clamp(MIN_FONT_SIZE, MAX_FONT_SIZE);
         }
+        self.font_weight = self.font_weight.clamp(MIN_FONT_WEIGHT, MAX_FONT_WEIGHT);
+        if self
+            .bold_font_family
+            .as_ref()
+            .is_some_and(|family| family.trim().is_empty())
+        {
+            self.bold_font_family = None;
+        }
+        if self
+            .file_open_template
+            .as_ref()
+            .is_some_and(|template| template.trim().is_empty())
+        {
+            self.file_open_template = None;
+        }
+
+        self.max_scroll_history_lines = self
+            .max_scroll_history_lines
+            .map(|lines| lines.min(crate::config::MAX_SCROLL_HISTORY_LINES));
+
+        self.cursor_blink_interval_ms = self
+            .cursor_blink_interval_ms
+            .clamp(MIN_CURSOR_BLINK_INTERVAL_MS, MAX_CURSOR_BLINK_INTERVAL_MS);
+        self.cursor_blink_suppress_ms = self
+            .cursor_blink_suppress_ms
+            .min(MAX_CURSOR_BLINK_SUPPRESS_MS);
+        self.slide_animation_ms = self.slide_animation_ms.min(MAX_SLIDE_ANIMATION_MS);
+
+        if !self.minimum_contrast.is_finite() {
+            self.minimum_contrast = default_minimum_contrast();
+        } else {
+            self.minimum_contrast = self
+                .minimum_contrast
+                .clamp(MIN_CONTRAST_RATIO, MAX_CONTRAST_RATIO);
+        }
+
+        self.unfocused_dim = if self.unfocused_dim.is_finite() {
+            self.unfocused_dim.clamp(0.0, MAX_UNFOCUSED_DIM)
+        } else {
+            0.0
+        };
 
         let line_height = std::mem::take(&mut self.line_height);
         self.line_height = match line_height {
@@ -454,6 +965,8 @@ impl TerminalSettings {
         } else {
             self.default_height = self.default_height.min(MAX_DEFAULT_HEIGHT);
         }
+        self.width_fraction = self.width_fraction.and_then(sanitize_screen_fraction);
+        self.height_fraction = self.height_fraction.and_then(sanitize_screen_fraction);
 
         if self.global_hotkey.trim().is_empty() {
             self.global_hotkey = default_global_hotkey();
@@ -468,6 +981,14 @@ impl TerminalSettings {
             self.panel_top_inset = self.panel_top_inset.min(MAX_PANEL_TOP_INSET);
         }
 
+        for padding in [&mut self.padding_x, &mut self.padding_y] {
+            *padding = if padding.is_finite() {
+                padding.clamp(0.0, MAX_PADDING)
+            } else {
+                0.0
+            };
+        }
+
         for placement in self.monitor_window_positions.values_mut() {
             if placement
                 .width
@@ -485,6 +1006,17 @@ impl TerminalSettings {
         self.monitor_window_positions.retain(|key, placement| {
             !key.trim().is_empty() && placement.x.is_finite() && placement.y.is_finite()
         });
+        self.profiles
+            .retain(|profile| !profile.name.trim().is_empty());
+        for profile in &mut self.profiles {
+            if profile
+                .initial_command
+                .as_ref()
+                .is_some_and(|command| command.trim().is_empty())
+            {
+                profile.initial_command = None;
+            }
+        }
 
         self
     }
@@ -508,6 +1040,26 @@ impl TerminalSettings {
         Self::default()
     }
 
+    /// Load settings from a JSON file, or `None` when it cannot be read or
+    /// parsed. Unlike [`Self::load`], a half-written file does not turn into
+    /// defaults.
+    pub fn try_load(config_path: &Path) -> Option<Self> {
+        let contents = match std::fs::read_to_string(config_path) {
+            Ok(contents) => contents,
+            Err(err) => {
+                log::warn!("failed to read {}: {err}", config_path.display());
+                return None;
+            }
+        };
+        match serde_json::from_str::<Self>(&contents) {
+            Ok(settings) => Some(settings.sanitize()),
+            Err(err) => {
+                log::warn!("failed to parse {}: {err}", config_path.display());
+                None
+            }
+        }
+    }
+
     /// Load settings from a JSON file and create a default file when missing.
     pub fn load_or_create(config_path: &PathBuf) -> Self {
         let settings = Self::load(config_path);
@@ -544,17 +1096,58 @@ impl TerminalSettings {
     pub fn config_path() -> PathBuf {
         Self::config_dir().join("settings.json")
     }
+
+    /// Directory scanned for custom `*.json` theme files.
+    pub fn themes_dir() -> PathBuf {
+        Self::config_dir().join("themes")
+    }
+}
+
+/// Reconcile settings edited outside the app with the running state.
+///
+/// `base` is the file content the app last loaded or saved. Fields whose value
+/// in `disk` differs from `base` were edited externally and win; every other
+/// field keeps its value from `current`, so runtime edits are not clobbered.
+pub fn merge_external_settings(
+    current: &TerminalSettings,
+    base: &TerminalSettings,
+    disk: &TerminalSettings,
+) -> TerminalSettings {
+    let (
+        Ok(serde_json::Value::Object(mut merged)),
+        Ok(serde_json::Value::Object(base)),
+        Ok(serde_json::Value::Object(disk)),
+    ) = (
+        serde_json::to_value(current),
+        serde_json::to_value(base),
+        serde_json::to_value(disk),
+    )
+    else {
+        return current.clone();
+    };
+
+    for (key, disk_value) in disk {
+        if base.get(&key) != Some(&disk_value) {
+            merged.insert(key, disk_value);
+        }
+    }
+
+    serde_json::from_value::<TerminalSettings>(serde_json::Value::Object(merged))
+        .map(TerminalSettings::sanitize)
+        .unwrap_or_else(|_| current.clone())
 }
 
 #[cfg(test)]
 mod tests {
     use super::{
-        default_font_fallbacks, default_font_family, Blinking, CursorShape, LineHeight,
-        MonitorWindowPlacement, ShellConfig, TerminalSettings, TerminalTheme,
+        default_font_fallbacks, default_font_family, merge_external_settings, parse_hex_color,
+        BellMode, Blinking, CloseOnExit, CursorShape, CustomThemeDefinition, LineHeight,
+        MonitorWindowPlacement, ShellConfig, ShellProfile, TerminalSettings, TerminalTheme,
+        WorkingDirectory,
     };
     use crate::Shell;
     use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     fn unique_temp_file(name: &str) -> PathBuf {
         let mut path = std::env::temp_dir();
@@ -626,12 +1219,12 @@ mod tests {
 
     #[test]
     fn shell_config_converts_to_runtime_shell_variants() {
-        assert!(matches!(ShellConfig::System.to_shell(), Shell::System));
+        assert!(matches!(ShellConfig::System.to_shell(false), Shell::System));
         assert!(matches!(
             ShellConfig::Program {
                 program: "/bin/zsh".to_string()
             }
-            .to_shell(),
+            .to_shell(false),
             Shell::Program(program) if program == "/bin/zsh"
         ));
         assert!(matches!(
@@ -639,7 +1232,7 @@ mod tests {
                 program: "/bin/bash".to_string(),
                 args: vec!["-l".to_string(), "-i".to_string()],
             }
-            .to_shell(),
+            .to_shell(false),
             Shell::WithArguments { program, args, title_override }
                 if program == "/bin/bash"
                     && args == vec!["-l".to_string(), "-i".to_string()]
@@ -647,6 +1240,53 @@ mod tests {
         ));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn login_shell_adds_login_flag_to_every_shell_config() {
+        let fish = || Shell::Program("/usr/bin/fish".to_string());
+        let login = |program: &str, args: &[&str]| Shell::WithArguments {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            title_override: None,
+        };
+
+        assert_eq!(
+            ShellConfig::System.to_shell_with_env(true, fish),
+            login("/usr/bin/fish", &["-l"])
+        );
+        assert_eq!(
+            ShellConfig::System.to_shell_with_env(true, || Shell::System),
+            Shell::System
+        );
+        assert_eq!(
+            ShellConfig::System.to_shell_with_env(false, fish),
+            Shell::System
+        );
+        let zsh = ShellConfig::Program {
+            program: "/bin/zsh".to_string(),
+        };
+        assert_eq!(
+            zsh.to_shell_with_env(true, fish),
+            login("/bin/zsh", &["-l"])
+        );
+        assert_eq!(
+            zsh.to_shell_with_env(false, fish),
+            Shell::Program("/bin/zsh".to_string())
+        );
+        let bash = ShellConfig::WithArguments {
+            program: "/bin/bash".to_string(),
+            args: vec!["-i".to_string()],
+        };
+        assert_eq!(
+            bash.to_shell_with_env(true, fish),
+            login("/bin/bash", &["-l", "-i"])
+        );
+        assert_eq!(
+            bash.to_shell_with_env(false, fish),
+            login("/bin/bash", &["-i"])
+        );
+    }
+
     #[test]
     fn load_returns_defaults_when_file_is_missing() {
         let path = unique_temp_file("missing");
@@ -662,6 +1302,8 @@ mod tests {
             "font_family": "JetBrains Mono",
             "env": {"FOO": "BAR"},
             "blinking": "off",
+            "bell_mode": "audible",
+            "bold_is_bright": true,
             "theme": "tokyo_night"
         }"#;
         std::fs::write(&path, json).expect("write test settings");
@@ -673,9 +1315,32 @@ mod tests {
         assert_eq!(settings.font_family, "JetBrains Mono");
         assert_eq!(settings.env.get("FOO").map(String::as_str), Some("BAR"));
         assert_eq!(settings.blinking, Blinking::Off);
+        assert_eq!(settings.bell_mode, BellMode::Audible);
+        assert!(settings.bold_is_bright);
         assert_eq!(settings.theme, TerminalTheme::TokyoNight);
     }
 
+    #[test]
+    fn close_on_exit_decides_by_setting_and_exit_code() {
+        for (setting, success, failure) in [
+            (CloseOnExit::Always, true, true),
+            (CloseOnExit::OnSuccess, true, false),
+            (CloseOnExit::Never, false, false),
+        ] {
+            assert_eq!(setting.closes_after(0), success, "{setting:?}");
+            assert_eq!(setting.closes_after(1), failure, "{setting:?}");
+            assert_eq!(setting.closes_after(130), failure, "{setting:?}");
+        }
+
+        let settings: TerminalSettings =
+            serde_json::from_str(r#"{"close_on_exit": "never"}"#).expect("settings");
+        assert_eq!(settings.close_on_exit, CloseOnExit::Never);
+        assert_eq!(
+            TerminalSettings::default().close_on_exit,
+            CloseOnExit::OnSuccess
+        );
+    }
+
     #[test]
     fn load_falls_back_to_defaults_for_invalid_json() {
         let path = unique_temp_file("invalid");
@@ -707,6 +1372,83 @@ mod tests {
         assert_eq!(settings.line_height, LineHeight::Comfortable);
     }
 
+    #[test]
+    fn load_clamps_cursor_blink_timing() {
+        let path = unique_temp_file("cursor-blink-timing");
+        let json = r#"{
+            "cursor_blink_interval_ms": 5,
+            "cursor_blink_suppress_ms": 60000
+        }"#;
+        std::fs::write(&path, json).expect("write test settings");
+
+        let settings = TerminalSettings::load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(settings.cursor_blink_interval(), Duration::from_millis(100));
+        assert_eq!(settings.cursor_blink_suppression(), Duration::from_secs(10));
+        assert_eq!(TerminalSettings::default().cursor_blink_interval_ms, 530);
+    }
+
+    #[test]
+    fn load_keeps_screen_fractions_within_the_work_area() {
+        let path = unique_temp_file("screen-fractions");
+        std::fs::write(
+            &path,
+            r#"{ "width_fraction": 1.5, "height_fraction": 0.0 }"#,
+        )
+        .expect("write test settings");
+
+        let settings = TerminalSettings::load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(settings.width_fraction, Some(1.0));
+        assert_eq!(settings.height_fraction, None);
+        assert_eq!(TerminalSettings::default().width_fraction, None);
+    }
+
+    #[test]
+    fn load_caps_slide_animation_and_defaults_to_instant() {
+        let path = unique_temp_file("slide-animation");
+        std::fs::write(&path, r#"{ "slide_animation_ms": 5000 }"#).expect("write test settings");
+
+        let settings = TerminalSettings::load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(settings.slide_animation(), Duration::from_secs(1));
+        assert_eq!(
+            TerminalSettings::default().slide_animation(),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn scrollback_lines_survive_a_save_and_are_capped_on_load() {
+        let path = unique_temp_file("scrollback-lines");
+        let settings = TerminalSettings {
+            max_scroll_history_lines: Some(25_000),
+            ..TerminalSettings::default()
+        };
+        settings.save(&path).expect("save settings");
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).expect("read settings"))
+                .expect("parse settings");
+        assert_eq!(saved["max_scroll_history_lines"], 25_000);
+        assert_eq!(
+            TerminalSettings::load(&path).max_scroll_history_lines,
+            Some(25_000)
+        );
+
+        std::fs::write(&path, r#"{"max_scroll_history_lines": 5000000}"#)
+            .expect("write test settings");
+        let loaded = TerminalSettings::load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(
+            loaded.max_scroll_history_lines,
+            Some(crate::config::MAX_SCROLL_HISTORY_LINES)
+        );
+    }
+
     #[test]
     fn load_sanitizes_zero_default_window_dimensions() {
         let path = unique_temp_file("invalid-window-size");
@@ -808,7 +1550,14 @@ mod tests {
             "line_height": {"type": "custom", "value": 100.0},
             "default_width": 50000,
             "default_height": 50000,
-            "panel_top_inset": 1000.0
+            "panel_top_inset": 1000.0,
+            "padding_x": 500.0,
+            "padding_y": -4.0,
+            "font_weight": 2000,
+            "bold_font_family": " ",
+            "file_open_template": "",
+            "minimum_contrast": 45.0,
+            "unfocused_dim": 3.0
         }"#;
         std::fs::write(&path, json).expect("write test settings");
 
@@ -816,10 +1565,17 @@ mod tests {
         std::fs::remove_file(path).ok();
 
         assert_eq!(settings.font_size, 72.0);
+        assert_eq!(settings.font_weight, 900);
+        assert_eq!(settings.bold_font_family, None);
+        assert_eq!(settings.file_open_template, None);
+        assert_eq!(settings.minimum_contrast, 21.0);
+        assert_eq!(settings.unfocused_dim, 0.8);
         assert_eq!(settings.line_height, LineHeight::Custom { value: 3.0 });
         assert_eq!(settings.default_width, 8192);
         assert_eq!(settings.default_height, 4320);
         assert_eq!(settings.panel_top_inset, 64.0);
+        assert_eq!(settings.padding_x, 128.0);
+        assert_eq!(settings.padding_y, 0.0);
     }
 
     #[test]
@@ -870,6 +1626,34 @@ mod tests {
         assert_eq!(loaded.font_family, "JetBrains Mono");
     }
 
+    #[test]
+    fn cursor_colors_round_trip_as_hex_strings() {
+        let settings = TerminalSettings {
+            cursor_color: Some(0xff8800),
+            ..TerminalSettings::default()
+        };
+
+        let value = serde_json::to_value(&settings).expect("serialize settings");
+        assert_eq!(value["cursor_color"], "#ff8800");
+        assert!(value.get("cursor_text_color").is_none());
+
+        let loaded: TerminalSettings = serde_json::from_value(value).expect("parse settings");
+        assert_eq!(loaded.cursor_color, Some(0xff8800));
+        assert_eq!(loaded.cursor_text_color, None);
+    }
+
+    #[test]
+    fn invalid_cursor_color_is_ignored_without_resetting_settings() {
+        let loaded: TerminalSettings = serde_json::from_str(
+            r##"{"font_size": 15.0, "cursor_color": "#ff", "cursor_text_color": "#000000"}"##,
+        )
+        .expect("parse settings");
+
+        assert_eq!(loaded.font_size, 15.0);
+        assert_eq!(loaded.cursor_color, None);
+        assert_eq!(loaded.cursor_text_color, Some(0x000000));
+    }
+
     #[test]
     fn save_creates_missing_parent_directories() {
         let mut path = std::env::temp_dir();
@@ -907,4 +1691,205 @@ mod tests {
             "dock_mode should not be serialized in settings.json"
         );
     }
+
+    const DRACULA_THEME_JSON: &str = r##"{
+        "ui_bg": "#191a21",
+        "terminal_bg": "#282a36",
+        "cursor": "#f8f8f2",
+        "ansi_colors": [
+            "#21222c", "#ff5555", "#50fa7b", "#f1fa8c",
+            "#bd93f9", "#ff79c6", "#8be9fd", "#f8f8f2",
+            "#6272a4", "#ff6e6e", "#69ff94", "#ffffa5",
+            "#d6acff", "#ff92df", "#a4ffff", "#ffffff"
+        ],
+        "foreground": "#f8f8f2",
+        "background": "#282a36"
+    }"##;
+
+    #[test]
+    fn custom_theme_json_parses_hex_colors() {
+        let theme = CustomThemeDefinition::from_json(DRACULA_THEME_JSON).expect("valid theme");
+
+        assert_eq!(theme.ui_bg, 0x191a21);
+        assert_eq!(theme.terminal_bg, 0x282a36);
+        assert_eq!(theme.cursor, 0xf8f8f2);
+        assert_eq!(theme.ansi_colors[1], 0xff5555);
+        assert_eq!(theme.ansi_colors[15], 0xffffff);
+        assert_eq!(theme.foreground, 0xf8f8f2);
+        assert_eq!(theme.background, 0x282a36);
+    }
+
+    #[test]
+    fn custom_theme_json_rejects_wrong_ansi_count_and_bad_colors() {
+        let short = DRACULA_THEME_JSON.replace(r##""#21222c", "##, "");
+        assert!(CustomThemeDefinition::from_json(&short).is_err());
+
+        let bad_color = DRACULA_THEME_JSON.replace("#191a21", "#19zz21");
+        assert!(CustomThemeDefinition::from_json(&bad_color).is_err());
+    }
+
+    #[test]
+    fn parse_hex_color_accepts_optional_hash_only_for_six_digits() {
+        assert_eq!(parse_hex_color("#A0b1C2"), Some(0xa0b1c2));
+        assert_eq!(parse_hex_color("a0b1c2"), Some(0xa0b1c2));
+        assert_eq!(parse_hex_color("#fff"), None);
+        assert_eq!(parse_hex_color("#a0b1c2ff"), None);
+    }
+
+    #[test]
+    fn load_dir_returns_valid_json_themes_sorted_by_file_stem() {
+        let dir = unique_temp_file("themes").with_extension("");
+        std::fs::create_dir_all(&dir).expect("create themes dir");
+        std::fs::write(dir.join("zenburn.json"), DRACULA_THEME_JSON).expect("write theme");
+        std::fs::write(dir.join("dracula.json"), DRACULA_THEME_JSON).expect("write theme");
+        std::fs::write(dir.join("broken.json"), "{").expect("write theme");
+        std::fs::write(dir.join("notes.txt"), "ignored").expect("write note");
+
+        let themes = CustomThemeDefinition::load_dir(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        let names: Vec<&str> = themes.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(names, ["dracula", "zenburn"]);
+    }
+
+    #[test]
+    fn custom_theme_setting_round_trips_by_name() {
+        let settings: TerminalSettings =
+            serde_json::from_str(r#"{ "theme": { "custom": "dracula" } }"#).expect("parse");
+        assert_eq!(settings.theme, TerminalTheme::Custom("dracula".to_string()));
+
+        let serialized = serde_json::to_string(&settings).expect("serialize");
+        assert!(serialized.contains(r#""theme":{"custom":"dracula"}"#));
+    }
+
+    #[test]
+    fn merge_external_settings_takes_disk_edits_and_keeps_runtime_edits() {
+        let base = TerminalSettings::default();
+
+        let mut current = base.clone();
+        current.theme = TerminalTheme::Nord;
+
+        let mut disk = base.clone();
+        disk.font_size = 20.0;
+        disk.keybindings
+            .insert("new_tab".to_string(), "ctrl+shift+n".to_string());
+
+        let merged = merge_external_settings(&current, &base, &disk);
+
+        assert_eq!(merged.font_size, 20.0);
+        assert_eq!(
+            merged.keybindings.get("new_tab").map(String::as_str),
+            Some("ctrl+shift+n")
+        );
+        assert_eq!(merged.theme, TerminalTheme::Nord);
+    }
+
+    #[test]
+    fn merge_external_settings_prefers_disk_when_both_sides_changed() {
+        let base = TerminalSettings::default();
+
+        let mut current = base.clone();
+        current.theme = TerminalTheme::Nord;
+
+        let mut disk = base.clone();
+        disk.theme = TerminalTheme::GithubLight;
+
+        let merged = merge_external_settings(&current, &base, &disk);
+        assert_eq!(merged.theme, TerminalTheme::GithubLight);
+    }
+
+    #[test]
+    fn merge_external_settings_is_a_no_op_for_the_apps_own_save() {
+        let current = TerminalSettings {
+            font_size: 16.0,
+            copy_on_select: true,
+            ..TerminalSettings::default()
+        };
+
+        let merged = merge_external_settings(&current, &current, &current);
+        assert_eq!(merged.font_size, 16.0);
+        assert!(merged.copy_on_select);
+    }
+
+    #[test]
+    fn try_load_returns_none_for_partial_writes() {
+        let path = unique_temp_file("partial-write");
+        std::fs::write(&path, r#"{ "font_size": 18"#).expect("write partial settings");
+
+        let loaded = TerminalSettings::try_load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn profile_effective_env_overrides_base_env() {
+        let base = std::collections::HashMap::from([
+            ("LANG".to_string(), "en_US.UTF-8".to_string()),
+            ("EDITOR".to_string(), "vim".to_string()),
+        ]);
+        let profile = ShellProfile {
+            name: "python".to_string(),
+            env: std::collections::HashMap::from([
+                ("EDITOR".to_string(), "nano".to_string()),
+                ("PYTHONSTARTUP".to_string(), "~/.pythonrc".to_string()),
+            ]),
+            ..ShellProfile::default()
+        };
+
+        let env = profile.effective_env(&base);
+
+        assert_eq!(env.get("LANG").map(String::as_str), Some("en_US.UTF-8"));
+        assert_eq!(env.get("EDITOR").map(String::as_str), Some("nano"));
+        assert_eq!(
+            env.get("PYTHONSTARTUP").map(String::as_str),
+            Some("~/.pythonrc")
+        );
+        assert_eq!(env.len(), 3);
+    }
+
+    #[test]
+    fn profiles_parse_from_settings_and_drop_unnamed_entries() {
+        let path = unique_temp_file("profiles");
+        let json = r#"{
+            "profiles": [
+                {
+                    "name": "python",
+                    "shell": { "type": "program", "program": "python3" },
+                    "working_directory": { "type": "always_home" },
+                    "env": { "PYTHONUNBUFFERED": "1" }
+                },
+                { "name": "  " },
+                { "name": "server", "initial_command": "ssh server" },
+                { "name": "blank", "initial_command": " " }
+            ]
+        }"#;
+        std::fs::write(&path, json).expect("write profile settings");
+
+        let settings = TerminalSettings::load(&path);
+        std::fs::remove_file(path).ok();
+
+        assert_eq!(settings.profiles.len(), 3);
+        assert_eq!(
+            settings.profiles[1].initial_command.as_deref(),
+            Some("ssh server")
+        );
+        assert_eq!(settings.profiles[2].initial_command, None);
+        let profile = &settings.profiles[0];
+        assert_eq!(profile.name, "python");
+        assert_eq!(
+            profile.shell,
+            ShellConfig::Program {
+                program: "python3".to_string()
+            }
+        );
+        assert_eq!(
+            profile.working_directory,
+            Some(WorkingDirectory::AlwaysHome)
+        );
+        assert_eq!(
+            profile.env.get("PYTHONUNBUFFERED").map(String::as_str),
+            Some("1")
+        );
+    }
 }
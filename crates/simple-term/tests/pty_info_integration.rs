@@ -6,7 +6,7 @@ use std::thread;
 use std::time::{Duration, Instant};
 
 use simple_term::alacritty_terminal::{event::WindowSize, tty};
-use simple_term::pty_info::{ProcessInfo, PtyProcessInfo};
+use simple_term::pty_info::{foreground_process, ProcessInfo, PtyProcessInfo};
 
 fn spawn_sleep_pty(seconds: u64) -> tty::Pty {
     let options = tty::Options {
@@ -212,3 +212,13 @@ fn kill_child_process_returns_false_after_process_is_gone() {
     assert!(process_info.update().is_none());
     assert!(!process_info.kill_child_process());
 }
+
+#[test]
+fn foreground_process_names_the_program_in_the_foreground() {
+    let pty = spawn_sleep_pty(30);
+    let process_info = PtyProcessInfo::new(&pty);
+    let pid = process_info.pid().expect("pid");
+
+    assert_eq!(foreground_process(pid).as_deref(), Some("sleep"));
+    assert!(process_info.kill_child_process());
+}
@@ -11,6 +11,7 @@ use simple_term::terminal::{Terminal, TerminalEvent};
 use simple_term::AlacCursorStyle;
 use simple_term::Dimensions;
 use simple_term::Shell;
+use simple_term::TerminalSettings;
 
 const POLL_INTERVAL: Duration = Duration::from_millis(20);
 
@@ -37,6 +38,7 @@ fn spawn_terminal_script(script: &str, size: WindowSize, scrollback_lines: usize
         scrollback_lines,
         HashMap::new(),
         AlacCursorStyle::default(),
+        &TerminalSettings::default().word_separators,
     )
     .expect("failed to spawn terminal")
 }
@@ -253,6 +255,24 @@ fn emits_title_changed_event_from_osc_sequence() {
     ));
 }
 
+#[test]
+fn emits_cwd_changed_event_from_osc7_sequence() {
+    let terminal = spawn_terminal_script(
+        "printf '\\033]7;file://localhost/tmp/osc%%207\\033\\\\'; sleep 0.05; exit 0",
+        window_size(24, 80),
+        128,
+    );
+
+    let cwd_event = wait_for_event(&terminal, Duration::from_secs(4), |event| {
+        matches!(event, TerminalEvent::CwdChanged(_))
+    });
+
+    assert!(matches!(
+        cwd_event,
+        Some(TerminalEvent::CwdChanged(path)) if path == std::path::Path::new("/tmp/osc 7")
+    ));
+}
+
 #[test]
 fn emits_exit_event_with_child_status() {
     let terminal = spawn_terminal_script("exit 17", window_size(24, 80), 64);
@@ -264,6 +284,40 @@ fn emits_exit_event_with_child_status() {
     assert!(matches!(exit_event, Some(TerminalEvent::Exit(17))));
 }
 
+#[test]
+fn unended_synchronized_update_times_out_and_a_second_begin_restarts_it() {
+    let terminal = spawn_terminal_script(
+        r"printf '\033[?2026hheld'; sleep 1; printf '\033[?2026hagain'; sleep 5",
+        window_size(24, 80),
+        64,
+    );
+
+    assert!(wait_until(Duration::from_secs(4), || {
+        terminal.synchronized_update_started().is_some()
+    }));
+    let first = terminal.synchronized_update_started();
+
+    // The program never ends the update, so alacritty flushes the grid once
+    // its own timeout passes while the begin stays recorded.
+    assert!(
+        wait_for_screen_contains(&terminal, "held", Duration::from_secs(4)),
+        "expected the held update to be flushed; screen:\n{}",
+        visible_screen_text(&terminal)
+    );
+    assert_eq!(terminal.synchronized_update_started(), first);
+
+    assert!(wait_until(Duration::from_secs(4), || {
+        terminal.synchronized_update_started() > first
+    }));
+    assert!(
+        wait_for_screen_contains(&terminal, "again", Duration::from_secs(4)),
+        "expected the second update to be flushed; screen:\n{}",
+        visible_screen_text(&terminal)
+    );
+
+    terminal.shutdown();
+}
+
 #[test]
 fn ctrl_c_produces_terminal_feedback_and_wakeup() {
     let terminal = spawn_terminal_script("cat", window_size(24, 80), 128);